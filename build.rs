@@ -0,0 +1,186 @@
+//! 根据 `src/server/tools_list.rs` 的工具定义生成客户端的调用类型（`client::tools`）。
+//!
+//! 每个工具生成一个结构体：必填参数由 `new` 传入，可选参数通过同名方法设置，
+//! 工具增删或参数变化时客户端随之更新，无需手工同步。
+
+#![recursion_limit = "256"]
+
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+#[allow(dead_code)]
+mod tools_list {
+    include!("src/server/tools_list.rs");
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in",
+    "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "async", "await", "dyn", "gen",
+];
+
+struct Param {
+    name: String,
+    ident: String,
+    ty: &'static str,
+    description: Option<String>,
+}
+
+impl Param {
+    /// `new` 与 setter 的参数类型；字符串接受 `impl Into<String>`
+    fn arg_ty(&self) -> &'static str {
+        if self.ty == "String" { "impl Into<String>" } else { self.ty }
+    }
+
+    fn arg_value(&self) -> &'static str {
+        if self.ty == "String" { "value.into()" } else { "value" }
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/server/tools_list.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut out = String::new();
+    let definitions = tools_list::get_tools_list();
+    for tool in definitions["tools"].as_array().into_iter().flatten() {
+        generate_tool(&mut out, tool);
+    }
+
+    let path = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR")).join("client_tools.rs");
+    std::fs::write(&path, out).expect("write client_tools.rs");
+}
+
+fn generate_tool(out: &mut String, tool: &Value) {
+    let name = tool["name"].as_str().expect("tool name");
+    let type_name = pascal_case(name);
+    let schema = &tool["inputSchema"];
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+
+    let mut required_params = Vec::new();
+    let mut optional_params = Vec::new();
+    for (prop, prop_schema) in schema["properties"].as_object().into_iter().flatten() {
+        let param = Param {
+            name: prop.clone(),
+            ident: if KEYWORDS.contains(&prop.as_str()) { format!("r#{}", prop) } else { prop.clone() },
+            ty: rust_type(prop_schema),
+            description: prop_schema["description"].as_str().map(str::to_string),
+        };
+        if required.contains(&prop.as_str()) {
+            required_params.push(param);
+        } else {
+            optional_params.push(param);
+        }
+    }
+    // 属性按字母序遍历，必填参数改按 `required` 的顺序出现在 `new` 中
+    required_params.sort_by_key(|p| required.iter().position(|r| *r == p.name));
+
+    write_doc(out, "", tool["description"].as_str().unwrap_or_default());
+    if required_params.is_empty() {
+        out.push_str("#[derive(Debug, Clone, Default)]\n");
+    } else {
+        out.push_str("#[derive(Debug, Clone)]\n");
+    }
+    let _ = writeln!(out, "pub struct {} {{", type_name);
+    for param in &required_params {
+        let _ = writeln!(out, "    {}: {},", param.ident, param.ty);
+    }
+    for param in &optional_params {
+        let _ = writeln!(out, "    {}: Option<{}>,", param.ident, param.ty);
+    }
+    out.push_str("}\n\n");
+
+    let _ = writeln!(out, "impl {} {{", type_name);
+    if !required_params.is_empty() {
+        let args: Vec<String> = required_params
+            .iter()
+            .map(|p| format!("{}: {}", p.ident, p.arg_ty()))
+            .collect();
+        if args.len() > 7 {
+            let _ = writeln!(out, "    #[allow(clippy::too_many_arguments)]");
+        }
+        let _ = writeln!(out, "    pub fn new({}) -> Self {{", args.join(", "));
+        out.push_str("        Self {\n");
+        for param in &required_params {
+            if param.ty == "String" {
+                let _ = writeln!(out, "            {}: {}.into(),", param.ident, param.ident);
+            } else {
+                let _ = writeln!(out, "            {},", param.ident);
+            }
+        }
+        for param in &optional_params {
+            let _ = writeln!(out, "            {}: None,", param.ident);
+        }
+        out.push_str("        }\n    }\n");
+    }
+    for param in &optional_params {
+        out.push('\n');
+        if let Some(description) = &param.description {
+            write_doc(out, "    ", description);
+        }
+        let _ = writeln!(out, "    pub fn {}(mut self, value: {}) -> Self {{", param.ident, param.arg_ty());
+        let _ = writeln!(out, "        self.{} = Some({});", param.ident, param.arg_value());
+        out.push_str("        self\n    }\n");
+    }
+    out.push_str("}\n\n");
+
+    let _ = writeln!(out, "impl ToolCall for {} {{", type_name);
+    let _ = writeln!(out, "    const NAME: &'static str = {:?};", name);
+    out.push('\n');
+    out.push_str("    fn arguments(&self) -> Value {\n");
+    if required_params.is_empty() && optional_params.is_empty() {
+        out.push_str("        Value::Object(Map::new())\n");
+    } else {
+        out.push_str("        let mut arguments = Map::new();\n");
+        for param in &required_params {
+            let _ = writeln!(out, "        arguments.insert({:?}.to_string(), json!(self.{}));", param.name, param.ident);
+        }
+        for param in &optional_params {
+            let _ = writeln!(out, "        if let Some(value) = &self.{} {{", param.ident);
+            let _ = writeln!(out, "            arguments.insert({:?}.to_string(), json!(value));", param.name);
+            out.push_str("        }\n");
+        }
+        out.push_str("        Value::Object(arguments)\n");
+    }
+    out.push_str("    }\n}\n\n");
+}
+
+/// 参数 schema 对应的 Rust 类型；数组、对象和多类型参数保留为 `Value`
+fn rust_type(schema: &Value) -> &'static str {
+    match schema["type"].as_str() {
+        Some("integer") => "i64",
+        Some("number") => "f64",
+        Some("boolean") => "bool",
+        Some("string") => "String",
+        _ => "Value",
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn write_doc(out: &mut String, indent: &str, text: &str) {
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            let _ = writeln!(out, "{}///", indent);
+        } else {
+            let _ = writeln!(out, "{}/// {}", indent, line);
+        }
+    }
+}
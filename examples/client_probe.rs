@@ -0,0 +1,20 @@
+use std::env;
+
+use iris_mcp::client::Client;
+
+fn main() {
+    let executable = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./target/debug/iris-mcp".to_string());
+
+    let mut client = Client::spawn(&executable).expect("failed to start iris-mcp");
+    client.initialize().expect("initialize failed");
+
+    let tools = client.list_tools().expect("tools/list failed");
+    println!("Server exposes {} tools.", tools.len());
+
+    match client.mouse_get_position() {
+        Ok(result) => println!("{}", result.text()),
+        Err(e) => println!("mouse_get_position failed: {}", e),
+    }
+}
@@ -0,0 +1,160 @@
+//! Benchmark for the monitor's input-hook hot path.
+//!
+//! The OS input hook must never block: on macOS a stalled CGEventTap callback
+//! freezes global input, and Windows silently unhooks slow low-level hooks. The
+//! monitor therefore hands events to a collector thread through a bounded,
+//! lock-free channel instead of pushing into the mutex-guarded event store.
+//!
+//! This compares both hand-off strategies on a simulated hook thread, delivering
+//! events at a steady rate while a reader thread contends the way
+//! `monitor_*_events` does (lock the store and clone a full buffer), and prints
+//! per-event latency percentiles of the hook-side work:
+//!
+//! ```text
+//! cargo run --release --example hook_latency_bench -- --events 20000 --rate 2000
+//! ```
+//!
+//! Options: `--events N` events per strategy (default 20000), `--rate N` events
+//! per second (default 2000), `--capacity N` store capacity cloned by the reader
+//! (default 100000).
+
+use std::collections::VecDeque;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Same bound as the monitor's hook → collector channel.
+const CHANNEL_CAPACITY: usize = 4096;
+
+#[derive(Clone)]
+struct Event {
+    _timestamp_micros: u128,
+    _x: i32,
+    _y: i32,
+}
+
+fn event(i: usize) -> Event {
+    Event { _timestamp_micros: i as u128, _x: i as i32, _y: -(i as i32) }
+}
+
+fn option(args: &[String], name: &str, default: usize) -> usize {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|v| v.parse().unwrap_or_else(|_| panic!("invalid value for {}", name)))
+        .unwrap_or(default)
+}
+
+/// Store already filled to capacity, so every reader clone copies a full buffer.
+fn full_store(capacity: usize) -> Arc<Mutex<VecDeque<Event>>> {
+    Arc::new(Mutex::new((0..capacity).map(event).collect()))
+}
+
+/// Reader that repeatedly locks the store and clones it, like a monitor read.
+fn spawn_reader(store: Arc<Mutex<VecDeque<Event>>>, stop: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            let snapshot: Vec<Event> = store.lock().unwrap().iter().cloned().collect();
+            std::hint::black_box(snapshot);
+            thread::yield_now();
+        }
+    })
+}
+
+fn push_bounded(store: &Mutex<VecDeque<Event>>, capacity: usize, event: Event) {
+    let mut store = store.lock().unwrap();
+    if store.len() >= capacity {
+        store.pop_front();
+    }
+    store.push_back(event);
+}
+
+/// Sleeps until the `i`-th event is due.
+fn pace(started: Instant, i: usize, rate: usize) {
+    let due = started + Duration::from_secs_f64(i as f64 / rate as f64);
+    if let Some(wait) = due.checked_duration_since(Instant::now()) {
+        thread::sleep(wait);
+    }
+}
+
+/// Hook thread pushes straight into the mutex-guarded store.
+fn bench_mutex(events: usize, rate: usize, capacity: usize) -> Vec<Duration> {
+    let store = full_store(capacity);
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader = spawn_reader(store.clone(), stop.clone());
+
+    let mut latencies = Vec::with_capacity(events);
+    let started = Instant::now();
+    for i in 0..events {
+        pace(started, i, rate);
+        let start = Instant::now();
+        push_bounded(&store, capacity, event(i));
+        latencies.push(start.elapsed());
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    reader.join().unwrap();
+    latencies
+}
+
+/// Hook thread only does a non-blocking send; a collector thread owns the store lock.
+fn bench_channel(events: usize, rate: usize, capacity: usize) -> (Vec<Duration>, usize) {
+    let store = full_store(capacity);
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader = spawn_reader(store.clone(), stop.clone());
+
+    let (sender, receiver) = mpsc::sync_channel::<Event>(CHANNEL_CAPACITY);
+    let collector_store = store.clone();
+    let collector = thread::spawn(move || {
+        for event in receiver {
+            push_bounded(&collector_store, capacity, event);
+        }
+    });
+
+    let mut latencies = Vec::with_capacity(events);
+    let mut dropped = 0;
+    let started = Instant::now();
+    for i in 0..events {
+        pace(started, i, rate);
+        let start = Instant::now();
+        if sender.try_send(event(i)).is_err() {
+            dropped += 1;
+        }
+        latencies.push(start.elapsed());
+    }
+
+    drop(sender);
+    collector.join().unwrap();
+    stop.store(true, Ordering::Relaxed);
+    reader.join().unwrap();
+    (latencies, dropped)
+}
+
+fn report(name: &str, mut latencies: Vec<Duration>) {
+    latencies.sort();
+    let at = |q: f64| latencies[((latencies.len() - 1) as f64 * q) as usize];
+    println!(
+        "{:<8} p50 {:>10.2?}  p99 {:>10.2?}  p99.9 {:>10.2?}  max {:>10.2?}",
+        name,
+        at(0.50),
+        at(0.99),
+        at(0.999),
+        latencies[latencies.len() - 1]
+    );
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let events = option(&args, "--events", 20_000).max(1);
+    let rate = option(&args, "--rate", 2_000).max(1);
+    let capacity = option(&args, "--capacity", 100_000).max(1);
+
+    println!("{} events per strategy at {}/s, store capacity {}", events, rate, capacity);
+    report("mutex", bench_mutex(events, rate, capacity));
+    let (latencies, dropped) = bench_channel(events, rate, capacity);
+    report("channel", latencies);
+    println!("channel dropped {} events (collector could not keep up)", dropped);
+}
@@ -0,0 +1,196 @@
+//! Soak test for the key/mouse monitor.
+//!
+//! Synthesizes high-rate input through enigo while another thread continuously
+//! drains the event store, and fails if draining stalls (deadlock on the queue
+//! locks), a drain returns more events than the store can hold, or resident
+//! memory keeps growing.
+//!
+//! Run it against a throwaway display, e.g. on Linux:
+//!
+//! ```text
+//! xvfb-run -a cargo run --release --example monitor_soak -- --duration-secs 14400 2>/dev/null
+//! ```
+//!
+//! Options: `--duration-secs N` (default 3600), `--rate N` synthesized events per
+//! second (default 500), `--max-rss-growth-mb N` (default 64).
+
+use std::env;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use enigo::{Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings};
+use iris_mcp::monitor::key_mouse;
+
+/// Upper bound of a single drain: the current keyboard + mouse store capacity,
+/// which can be changed through the environment or `configure_event_buffers`.
+fn max_events_per_drain() -> usize {
+    let report = key_mouse::buffer_report();
+    report.keyboard.capacity + report.mouse.capacity
+}
+
+/// A drain that has not completed for this long is treated as a deadlock.
+const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Memory is measured relative to the RSS after this warm-up period.
+const WARMUP: Duration = Duration::from_secs(30);
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+struct Options {
+    duration: Duration,
+    rate: u64,
+    max_rss_growth_kb: u64,
+}
+
+fn parse_options() -> Options {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let value = |name: &str, default: u64| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(|v| v.parse().unwrap_or_else(|_| fail(&format!("invalid value for {}", name))))
+            .unwrap_or(default)
+    };
+    Options {
+        duration: Duration::from_secs(value("--duration-secs", 3600)),
+        rate: value("--rate", 500).max(1),
+        max_rss_growth_kb: value("--max-rss-growth-mb", 64) * 1024,
+    }
+}
+
+fn fail(msg: &str) -> ! {
+    println!("FAIL: {}", msg);
+    process::exit(1);
+}
+
+fn now_millis(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Resident set size in KiB, if the platform exposes it.
+fn rss_kb() -> Option<u64> {
+    if let Ok(statm) = std::fs::read_to_string("/proc/self/statm") {
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        return Some(pages * 4);
+    }
+    let output = process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &process::id().to_string()])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+fn main() {
+    let options = parse_options();
+    let start = Instant::now();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    key_mouse::initialize();
+    println!(
+        "Soaking monitor for {:?} at {} events/s...",
+        options.duration, options.rate
+    );
+
+    // Input synthesizer: a small mouse circle plus Shift taps.
+    let synthesized = Arc::new(AtomicU64::new(0));
+    let synth = {
+        let stop = stop.clone();
+        let synthesized = synthesized.clone();
+        let interval = Duration::from_micros(1_000_000 / options.rate);
+        thread::spawn(move || {
+            let mut enigo = Enigo::new(&Settings::default()).unwrap_or_else(|e| fail(&format!("enigo init: {}", e)));
+            let mut step = 0u64;
+            while !stop.load(Ordering::Relaxed) {
+                let angle = (step % 360) as f64 * std::f64::consts::PI / 180.0;
+                let x = 200 + (angle.cos() * 50.0) as i32;
+                let y = 200 + (angle.sin() * 50.0) as i32;
+                let _ = enigo.move_mouse(x, y, Coordinate::Abs);
+                if step.is_multiple_of(10) {
+                    let direction = if step.is_multiple_of(20) { Direction::Press } else { Direction::Release };
+                    let _ = enigo.key(Key::Shift, direction);
+                }
+                synthesized.fetch_add(1, Ordering::Relaxed);
+                step += 1;
+                thread::sleep(interval);
+            }
+            let _ = enigo.key(Key::Shift, Direction::Release);
+        })
+    };
+
+    // Drainer: empties both queues continuously and publishes a heartbeat.
+    let heartbeat = Arc::new(AtomicU64::new(0));
+    let drained = Arc::new(AtomicU64::new(0));
+    let max_drain_micros = Arc::new(AtomicU64::new(0));
+    let drainer = {
+        let stop = stop.clone();
+        let heartbeat = heartbeat.clone();
+        let drained = drained.clone();
+        let max_drain_micros = max_drain_micros.clone();
+        thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                let t = Instant::now();
+                let batch = key_mouse::take_keyboard_events().len() + key_mouse::take_mouse_events().len();
+                max_drain_micros.fetch_max(t.elapsed().as_micros() as u64, Ordering::Relaxed);
+                let capacity = max_events_per_drain();
+                if batch > capacity {
+                    fail(&format!("drain returned {} events, store should hold at most {}", batch, capacity));
+                }
+                drained.fetch_add(batch as u64, Ordering::Relaxed);
+                heartbeat.store(now_millis(start), Ordering::Relaxed);
+                thread::sleep(Duration::from_millis(10));
+            }
+        })
+    };
+
+    // Watchdog: stalls, memory growth and periodic reports.
+    let mut baseline_rss = None;
+    let mut last_report = Instant::now();
+    while start.elapsed() < options.duration {
+        thread::sleep(Duration::from_secs(1));
+
+        let stalled_for = now_millis(start).saturating_sub(heartbeat.load(Ordering::Relaxed));
+        if stalled_for > STALL_TIMEOUT.as_millis() as u64 {
+            fail(&format!("drainer made no progress for {} ms (deadlock?)", stalled_for));
+        }
+
+        let rss = rss_kb();
+        if baseline_rss.is_none() && start.elapsed() >= WARMUP {
+            baseline_rss = rss;
+        }
+        if let (Some(base), Some(now)) = (baseline_rss, rss)
+            && now > base + options.max_rss_growth_kb
+        {
+            fail(&format!("RSS grew from {} KiB to {} KiB", base, now));
+        }
+
+        if last_report.elapsed() >= REPORT_INTERVAL {
+            last_report = Instant::now();
+            println!(
+                "[{:>6}s] synthesized={} drained={} max_drain={}us rss={}",
+                start.elapsed().as_secs(),
+                synthesized.load(Ordering::Relaxed),
+                drained.load(Ordering::Relaxed),
+                max_drain_micros.load(Ordering::Relaxed),
+                rss.map(|r| format!("{} KiB", r)).unwrap_or_else(|| "n/a".to_string()),
+            );
+        }
+    }
+
+    stop.store(true, Ordering::Relaxed);
+    let _ = synth.join();
+    let _ = drainer.join();
+
+    let drained = drained.load(Ordering::Relaxed);
+    if drained == 0 {
+        fail("no events were observed; is the listener running and permitted to capture input?");
+    }
+    println!(
+        "PASS: synthesized={} drained={} max_drain={}us",
+        synthesized.load(Ordering::Relaxed),
+        drained,
+        max_drain_micros.load(Ordering::Relaxed)
+    );
+}
@@ -0,0 +1,683 @@
+//! iris-mcp 的 Rust 类型化客户端。
+//!
+//! 封装 JSON-RPC 请求的构造与解析，调用方只需使用类型化方法：
+//!
+//! ```no_run
+//! use iris_mcp::client::{Button, Client};
+//!
+//! let mut client = Client::spawn("./target/release/iris-mcp").unwrap();
+//! client.initialize().unwrap();
+//! client.mouse_click(100, 200, Button::Left).unwrap();
+//! ```
+//!
+//! [`tools`] 模块为每个工具提供一个由工具定义生成的调用类型，覆盖全部工具与参数，
+//! 通过 [`Client::call`] 发送；`Client` 上手写的方法只是常用调用的简写。
+//!
+//! 方法为阻塞调用；本仓库不依赖异步运行时，需要异步时可在 `spawn_blocking` 中使用。
+
+pub mod tools;
+pub mod transport;
+
+use serde_json::{json, Value};
+use std::fmt;
+use tools::ToolCall;
+use transport::{HttpTransport, StdioTransport, Transport};
+
+#[derive(Debug)]
+pub enum ClientError {
+    Io(String),
+    Protocol(String),
+    Rpc { code: i32, message: String },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Io(msg) => write!(f, "client io error: {}", msg),
+            ClientError::Protocol(msg) => write!(f, "client protocol error: {}", msg),
+            ClientError::Rpc { code, message } => write!(f, "rpc error {}: {}", code, message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Left,
+    Middle,
+    Right,
+}
+
+impl Button {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Button::Left => "left",
+            Button::Middle => "middle",
+            Button::Right => "right",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Press,
+    Release,
+    Click,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Press => "press",
+            Direction::Release => "release",
+            Direction::Click => "click",
+        }
+    }
+}
+
+/// 工具调用结果（MCP `content` 数组及可选的 `structuredContent`）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResult {
+    pub content: Vec<Value>,
+    /// 结构见 `iris-mcp schema` 导出的 `<tool>.output.json`
+    pub structured_content: Option<Value>,
+}
+
+impl ToolResult {
+    /// 拼接所有 text 类型内容
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter(|c| c["type"] == "text")
+            .filter_map(|c| c["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub struct Client {
+    transport: Box<dyn Transport + Send>,
+    next_id: u64,
+}
+
+impl Client {
+    pub fn new(transport: Box<dyn Transport + Send>) -> Self {
+        Self { transport, next_id: 1 }
+    }
+
+    /// 启动本地 iris-mcp 进程并通过 stdio 通信
+    pub fn spawn(executable: &str) -> Result<Self, ClientError> {
+        Ok(Self::new(Box::new(StdioTransport::spawn(executable)?)))
+    }
+
+    /// 连接通过 HTTP 暴露的 iris-mcp 服务
+    pub fn http(url: &str) -> Result<Self, ClientError> {
+        Ok(Self::new(Box::new(HttpTransport::new(url)?)))
+    }
+
+    /// 连接启用了令牌认证的 HTTP 服务
+    pub fn http_with_token(url: &str, token: &str) -> Result<Self, ClientError> {
+        Ok(Self::new(Box::new(HttpTransport::new(url)?.with_token(token))))
+    }
+
+    /// 发送任意 JSON-RPC 方法并返回 `result`
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value, ClientError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let response = self.transport.send(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        }))?;
+
+        if let Some(err) = response.get("error") {
+            return Err(ClientError::Rpc {
+                code: err["code"].as_i64().unwrap_or(0) as i32,
+                message: err["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| ClientError::Protocol("response has no result".to_string()))
+    }
+
+    pub fn initialize(&mut self) -> Result<Value, ClientError> {
+        self.request("initialize", json!({}))
+    }
+
+    pub fn list_tools(&mut self) -> Result<Vec<Value>, ClientError> {
+        let result = self.request("tools/list", json!({}))?;
+        Ok(result["tools"].as_array().cloned().unwrap_or_default())
+    }
+
+    pub fn list_resources(&mut self) -> Result<Vec<Value>, ClientError> {
+        let result = self.request("resources/list", json!({}))?;
+        Ok(result["resources"].as_array().cloned().unwrap_or_default())
+    }
+
+    /// 读取资源，返回 `contents` 数组
+    pub fn read_resource(&mut self, uri: &str) -> Result<Vec<Value>, ClientError> {
+        let result = self.request("resources/read", json!({ "uri": uri }))?;
+        Ok(result["contents"].as_array().cloned().unwrap_or_default())
+    }
+
+    pub fn subscribe_resource(&mut self, uri: &str) -> Result<(), ClientError> {
+        self.request("resources/subscribe", json!({ "uri": uri })).map(|_| ())
+    }
+
+    pub fn unsubscribe_resource(&mut self, uri: &str) -> Result<(), ClientError> {
+        self.request("resources/unsubscribe", json!({ "uri": uri })).map(|_| ())
+    }
+
+    /// 按名称调用工具
+    pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<ToolResult, ClientError> {
+        let result = self.request("tools/call", json!({ "name": name, "arguments": arguments }))?;
+        Ok(ToolResult {
+            content: result["content"].as_array().cloned().unwrap_or_default(),
+            structured_content: result.get("structuredContent").cloned(),
+        })
+    }
+
+    /// 发送 [`tools`] 模块中生成的工具调用
+    pub fn call<T: ToolCall>(&mut self, call: T) -> Result<ToolResult, ClientError> {
+        self.call_tool(T::NAME, call.arguments())
+    }
+
+    // ---------------- 鼠标 ----------------
+
+    pub fn mouse_move(&mut self, x: i32, y: i32) -> Result<ToolResult, ClientError> {
+        self.call_tool("mouse_move", json!({ "x": x, "y": y }))
+    }
+
+    pub fn mouse_click(&mut self, x: i32, y: i32, button: Button) -> Result<ToolResult, ClientError> {
+        self.call_tool("mouse_click", json!({ "x": x, "y": y, "button": button.as_str() }))
+    }
+
+    pub fn mouse_multi_click(
+        &mut self,
+        x: i32,
+        y: i32,
+        button: Button,
+        clicks: u32,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "mouse_click",
+            json!({ "x": x, "y": y, "button": button.as_str(), "clicks": clicks }),
+        )
+    }
+
+    pub fn mouse_double_click(&mut self, x: i32, y: i32, button: Button) -> Result<ToolResult, ClientError> {
+        self.call_tool("mouse_double_click", json!({ "x": x, "y": y, "button": button.as_str() }))
+    }
+
+    pub fn mouse_scroll(&mut self, lines_x: i32, lines_y: i32) -> Result<ToolResult, ClientError> {
+        self.call_tool("mouse_scroll", json!({ "lines_x": lines_x, "lines_y": lines_y }))
+    }
+
+    /// 先将光标移动到 (x, y) 再按行滚动
+    pub fn mouse_scroll_at(&mut self, x: i32, y: i32, lines_x: i32, lines_y: i32) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "mouse_scroll",
+            json!({ "x": x, "y": y, "lines_x": lines_x, "lines_y": lines_y }),
+        )
+    }
+
+    /// 按像素平滑滚动，正数向下/向右
+    pub fn mouse_smooth_scroll(
+        &mut self,
+        distance_x: i32,
+        distance_y: i32,
+        duration_ms: u64,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "mouse_scroll",
+            json!({
+                "mode": "pixels",
+                "distance_x": distance_x,
+                "distance_y": distance_y,
+                "duration_ms": duration_ms,
+            }),
+        )
+    }
+
+    pub fn mouse_get_position(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("mouse_get_position", json!({}))
+    }
+
+    pub fn mouse_drag(&mut self, target_x: i32, target_y: i32, button: Button) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "mouse_drag",
+            json!({ "target_x": target_x, "target_y": target_y, "button": button.as_str() }),
+        )
+    }
+
+    pub fn mouse_button_control(&mut self, button: Button, direction: Direction) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "mouse_button_control",
+            json!({ "button": button.as_str(), "direction": direction.as_str() }),
+        )
+    }
+
+    pub fn mouse_move_path(&mut self, points: &[(i32, i32)], speed_ms: u64) -> Result<ToolResult, ClientError> {
+        let points: Vec<Value> = points.iter().map(|(x, y)| json!({ "x": x, "y": y })).collect();
+        self.call_tool("mouse_move_path", json!({ "points": points, "speed_ms": speed_ms }))
+    }
+
+    /// 带插值选项的路径移动，`options` 可包含 easing / max_step_px；点的第三项为该点的 delay_ms
+    pub fn mouse_move_path_with(
+        &mut self,
+        points: &[(i32, i32, u64)],
+        speed_ms: u64,
+        mut options: Value,
+    ) -> Result<ToolResult, ClientError> {
+        if !options.is_object() {
+            options = json!({});
+        }
+        let points: Vec<Value> = points
+            .iter()
+            .map(|(x, y, delay_ms)| json!({ "x": x, "y": y, "delay_ms": delay_ms }))
+            .collect();
+        options["points"] = json!(points);
+        options["speed_ms"] = json!(speed_ms);
+        self.call_tool("mouse_move_path", options)
+    }
+
+    /// `target` 为 smart_click 的目标描述对象（text/template/selector/x,y）
+    pub fn smart_click(&mut self, target: Value, button: Button) -> Result<ToolResult, ClientError> {
+        self.call_tool("smart_click", json!({ "target": target, "button": button.as_str() }))
+    }
+
+    /// `kind` 取 pinch_in / pinch_out / rotate / swipe，其余参数放在 `options` 中
+    pub fn gesture(&mut self, kind: &str, mut options: Value) -> Result<ToolResult, ClientError> {
+        if !options.is_object() {
+            options = json!({});
+        }
+        options["type"] = json!(kind);
+        self.call_tool("gesture", options)
+    }
+
+    // ---------------- 键盘 ----------------
+
+    pub fn type_text(&mut self, text: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("type_text", json!({ "text": text }))
+    }
+
+    /// 逐字符输入，字符间隔 `char_delay_ms` ± `variance_ms`
+    pub fn type_text_paced(&mut self, text: &str, char_delay_ms: u64, variance_ms: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "type_text",
+            json!({ "text": text, "char_delay_ms": char_delay_ms, "char_delay_variance_ms": variance_ms }),
+        )
+    }
+
+    /// 分块输入长文本，每块 `chunk_size` 个字符
+    pub fn type_text_chunked(&mut self, text: &str, chunk_size: u32) -> Result<ToolResult, ClientError> {
+        self.call_tool("type_text", json!({ "text": text, "chunk_size": chunk_size }))
+    }
+
+    /// 输入敏感文本（如密码），响应与服务器日志中不回显内容
+    pub fn type_secret(&mut self, text: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("type_text", json!({ "text": text, "sensitive": true }))
+    }
+
+    /// 经剪贴板粘贴文本（绕过输入法），完成后恢复原剪贴板
+    pub fn paste_text(&mut self, text: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("paste_text", json!({ "text": text }))
+    }
+
+    /// 经剪贴板粘贴文本，`restore_clipboard` 为 false 时保留粘贴内容在剪贴板中
+    pub fn paste_text_with(&mut self, text: &str, restore_clipboard: bool) -> Result<ToolResult, ClientError> {
+        self.call_tool("paste_text", json!({ "text": text, "restore_clipboard": restore_clipboard }))
+    }
+
+    pub fn system_command(&mut self, command: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("system_command", json!({ "command": command }))
+    }
+
+    /// 以指定主修饰键（"ctrl" / "cmd" / "alt"）执行系统快捷键
+    pub fn system_command_with_modifier(&mut self, command: &str, modifier: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("system_command", json!({ "command": command, "modifier": modifier }))
+    }
+
+    pub fn key_control(&mut self, key: &str, direction: Direction) -> Result<ToolResult, ClientError> {
+        self.call_tool("key_control", json!({ "key": key, "direction": direction.as_str() }))
+    }
+
+    /// 按住按键 `hold_ms` 毫秒后释放
+    pub fn key_hold(&mut self, key: &str, hold_ms: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("key_control", json!({ "key": key, "hold_ms": hold_ms }))
+    }
+
+    pub fn key_combo(&mut self, keys: &[&str], check_conflicts: bool) -> Result<ToolResult, ClientError> {
+        self.call_tool("key_combo", json!({ "keys": keys, "check_conflicts": check_conflicts }))
+    }
+
+    pub fn key_repeat(&mut self, key: &str, count: u32, interval_ms: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("key_repeat", json!({ "key": key, "count": count, "interval_ms": interval_ms }))
+    }
+
+    /// 按顺序执行按键步骤，每步为 `{"key", "direction", "delay_ms"}`
+    pub fn key_sequence(&mut self, steps: Value) -> Result<ToolResult, ClientError> {
+        self.call_tool("key_sequence", json!({ "steps": steps }))
+    }
+
+    /// 切换全局输入节奏预设（`fast` / `normal` / `human`）
+    pub fn set_input_profile(&mut self, profile: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("set_input_profile", json!({ "profile": profile }))
+    }
+
+    /// 设置全局延迟倍率，0 表示跳过等待
+    pub fn set_input_delay_scale(&mut self, delay_scale: f64) -> Result<ToolResult, ClientError> {
+        self.call_tool("set_input_profile", json!({ "delay_scale": delay_scale }))
+    }
+
+    /// 释放所有经服务器按下但尚未释放的键与鼠标按键
+    pub fn release_all_inputs(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("release_all_inputs", json!({}))
+    }
+
+    pub fn focus_next(&mut self, times: u32) -> Result<ToolResult, ClientError> {
+        self.call_tool("focus_next", json!({ "times": times }))
+    }
+
+    pub fn focus_prev(&mut self, times: u32) -> Result<ToolResult, ClientError> {
+        self.call_tool("focus_prev", json!({ "times": times }))
+    }
+
+    pub fn activate_focused(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("activate_focused", json!({}))
+    }
+
+    // ---------------- 监控 ----------------
+
+    pub fn monitor_screen_events(&mut self, reason: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_screen_events", json!({ "reason": reason }))
+    }
+
+    /// 截图并等比缩小到不超过给定尺寸（像素）
+    pub fn monitor_screen_events_fit(&mut self, reason: &str, max_width: u32, max_height: u32) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "monitor_screen_events",
+            json!({ "reason": reason, "max_width": max_width, "max_height": max_height }),
+        )
+    }
+
+    pub fn monitor_keyboard_events(&mut self, reason: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_keyboard_events", json!({ "reason": reason }))
+    }
+
+    pub fn monitor_mouse_events(&mut self, reason: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_mouse_events", json!({ "reason": reason }))
+    }
+
+    /// 获取鼠标事件，连续的移动合并为笔画（start / end / point_count / bounding_box）
+    pub fn monitor_mouse_strokes(&mut self, reason: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_mouse_events", json!({ "reason": reason, "coalesce_moves": true }))
+    }
+
+    /// 获取键盘事件，每个事件附带 UTC 的 ISO-8601 `timestamp` 字符串
+    pub fn monitor_keyboard_events_iso(&mut self, reason: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_keyboard_events", json!({ "reason": reason, "timestamp_format": "iso8601" }))
+    }
+
+    /// 识别鼠标事件缓冲区中的手势（单击、双击、拖拽、滑动）
+    pub fn monitor_gestures(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_gestures", json!({}))
+    }
+
+    /// 从游标开始读取键盘事件，不影响默认读取位置；下次使用响应中的 `next_cursor`
+    pub fn monitor_keyboard_events_since(&mut self, reason: &str, cursor: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_keyboard_events", json!({ "reason": reason, "cursor": cursor }))
+    }
+
+    /// 从游标开始读取鼠标事件，不影响默认读取位置；下次使用响应中的 `next_cursor`
+    pub fn monitor_mouse_events_since(&mut self, reason: &str, cursor: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_mouse_events", json!({ "reason": reason, "cursor": cursor }))
+    }
+
+    /// 以具名消费者读取事件（`device` 为 keyboard / mouse），读取不改变位置，处理后调用 `monitor_ack_events`
+    pub fn monitor_events_for(&mut self, device: &str, reason: &str, consumer: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            &format!("monitor_{}_events", device),
+            json!({ "reason": reason, "consumer": consumer }),
+        )
+    }
+
+    /// 确认具名消费者已处理到 `cursor` 为止的事件，`trim` 时裁剪所有消费者都已确认的事件
+    pub fn monitor_ack_events(
+        &mut self,
+        consumer: &str,
+        device: &str,
+        cursor: u64,
+        trim: bool,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "monitor_ack_events",
+            json!({ "consumer": consumer, "device": device, "cursor": cursor, "trim": trim }),
+        )
+    }
+
+    /// 注销具名消费者
+    pub fn monitor_release_consumer(&mut self, consumer: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_ack_events", json!({ "consumer": consumer, "release": true }))
+    }
+
+    /// 查询时间范围内（Unix 时间戳，微秒，两端包含）的键盘事件
+    pub fn monitor_keyboard_events_between(
+        &mut self,
+        reason: &str,
+        since_micros: Option<u64>,
+        until_micros: Option<u64>,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "monitor_keyboard_events",
+            json!({ "reason": reason, "since_micros": since_micros, "until_micros": until_micros }),
+        )
+    }
+
+    /// 查询时间范围内（Unix 时间戳，微秒，两端包含）的鼠标事件
+    pub fn monitor_mouse_events_between(
+        &mut self,
+        reason: &str,
+        since_micros: Option<u64>,
+        until_micros: Option<u64>,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "monitor_mouse_events",
+            json!({ "reason": reason, "since_micros": since_micros, "until_micros": until_micros }),
+        )
+    }
+
+    pub fn list_displays(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("list_displays", json!({}))
+    }
+
+    /// 查询持久化事件日志中时间范围内的事件；`device` 为 keyboard / mouse / all
+    pub fn query_event_log(
+        &mut self,
+        since_micros: Option<u64>,
+        until_micros: Option<u64>,
+        device: &str,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "query_event_log",
+            json!({ "since_micros": since_micros, "until_micros": until_micros, "device": device }),
+        )
+    }
+
+    /// 调整键鼠事件缓冲区容量，None 表示不变；返回容量与占用
+    pub fn configure_event_buffers(
+        &mut self,
+        keyboard_capacity: Option<usize>,
+        mouse_capacity: Option<usize>,
+    ) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "configure_event_buffers",
+            json!({ "keyboard_capacity": keyboard_capacity, "mouse_capacity": mouse_capacity }),
+        )
+    }
+
+    /// 控制键鼠监控（start / pause / resume / stop / status），可同时清空事件缓冲区
+    pub fn monitor_control(&mut self, action: &str, clear_buffers: bool) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_control", json!({ "action": action, "clear_buffers": clear_buffers }))
+    }
+
+    /// 查看或切换键盘监控隐私策略（full / mask / counts / disabled），None 时只查询
+    pub fn configure_keyboard_privacy(&mut self, mode: Option<&str>) -> Result<ToolResult, ClientError> {
+        self.call_tool("configure_keyboard_privacy", json!({ "mode": mode }))
+    }
+
+    /// 查询键鼠监控后端与平台输入捕获能力
+    pub fn monitor_capabilities(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_capabilities", json!({}))
+    }
+
+    /// 查询监听线程、收集线程、最近事件与监听器锁的健康状态
+    pub fn monitor_health(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_health", json!({}))
+    }
+
+    /// 订阅键鼠事件（参数同 monitor_subscribe 工具），返回订阅 ID
+    pub fn monitor_subscribe(&mut self, filter: Value) -> Result<u64, ClientError> {
+        let result = self.request("tools/call", json!({ "name": "monitor_subscribe", "arguments": filter }))?;
+        result["structuredContent"]["subscription_id"]
+            .as_u64()
+            .ok_or_else(|| ClientError::Protocol("response has no subscription_id".to_string()))
+    }
+
+    pub fn monitor_unsubscribe(&mut self, subscription_id: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("monitor_unsubscribe", json!({ "subscription_id": subscription_id }))
+    }
+
+    /// 识别屏幕上包含给定文本的行
+    pub fn screen_ocr(&mut self, contains: Option<&str>) -> Result<ToolResult, ClientError> {
+        self.call_tool("screen_ocr", json!({ "contains": contains }))
+    }
+
+    /// 截取所有显示器并按全局坐标拼接为一张图
+    pub fn capture_all_displays(&mut self, reason: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("capture_all_displays", json!({ "reason": reason }))
+    }
+
+    /// 开始录制屏幕（参数同 screen_record_start 工具）
+    pub fn screen_record_start(&mut self, options: Value) -> Result<ToolResult, ClientError> {
+        self.call_tool("screen_record_start", options)
+    }
+
+    pub fn screen_record_stop(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("screen_record_stop", json!({}))
+    }
+
+    /// 订阅屏幕帧（参数同 screen_subscribe 工具），返回 (订阅 ID, 资源 URI)
+    pub fn screen_subscribe(&mut self, options: Value) -> Result<(u64, String), ClientError> {
+        let result = self.request("tools/call", json!({ "name": "screen_subscribe", "arguments": options }))?;
+        let structured = &result["structuredContent"];
+        match (structured["subscription_id"].as_u64(), structured["uri"].as_str()) {
+            (Some(id), Some(uri)) => Ok((id, uri.to_string())),
+            _ => Err(ClientError::Protocol("response has no subscription_id or uri".to_string())),
+        }
+    }
+
+    pub fn screen_unsubscribe(&mut self, subscription_id: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("screen_unsubscribe", json!({ "subscription_id": subscription_id }))
+    }
+
+    pub fn get_keyboard_state(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("get_keyboard_state", json!({}))
+    }
+
+    pub fn get_idle_time(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("get_idle_time", json!({}))
+    }
+
+    pub fn get_input_snapshot(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("get_input_snapshot", json!({}))
+    }
+
+    pub fn input_source_info(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("input_source_info", json!({}))
+    }
+
+    // ---------------- 窗口 ----------------
+
+    pub fn list_windows(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("list_windows", json!({}))
+    }
+
+    /// 光标（或给定全局坐标）处的窗口
+    pub fn window_at_point(&mut self, point: Option<(i32, i32)>) -> Result<ToolResult, ClientError> {
+        let arguments = match point {
+            Some((x, y)) => json!({ "x": x, "y": y }),
+            None => json!({}),
+        };
+        self.call_tool("window_at_point", arguments)
+    }
+
+    /// 等待标题匹配 `title` 的窗口出现（`state` 为 appear）或消失（disappear）
+    pub fn wait_for_window(&mut self, title: &str, state: &str, timeout_ms: u64) -> Result<ToolResult, ClientError> {
+        self.call_tool("wait_for_window", json!({ "title": title, "state": state, "timeout_ms": timeout_ms }))
+    }
+
+    /// `position` 取 left-half / right-half / maximize-work-area / center-60
+    pub fn window_snap(&mut self, window_id: u64, position: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("window_snap", json!({ "window_id": window_id, "position": position }))
+    }
+
+    /// `action` 取 minimize / maximize / restore / close
+    pub fn window_control(&mut self, window_id: u64, action: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("window_control", json!({ "window_id": window_id, "action": action }))
+    }
+
+    // ---------------- 脚本 ----------------
+
+    /// `steps` 为步骤数组，每步包含 tool / arguments / 可选的 save_as
+    pub fn execute_script(&mut self, steps: Value, variables: Value) -> Result<ToolResult, ClientError> {
+        self.call_tool("execute_script", json!({ "steps": steps, "variables": variables }))
+    }
+
+    // ---------------- 宏 ----------------
+
+    pub fn macro_define(&mut self, name: &str, description: &str, steps: Value, overwrite: bool) -> Result<ToolResult, ClientError> {
+        self.call_tool(
+            "macro_define",
+            json!({ "name": name, "description": description, "steps": steps, "overwrite": overwrite }),
+        )
+    }
+
+    pub fn macro_list(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("macro_list", json!({}))
+    }
+
+    pub fn macro_run(&mut self, name: &str, variables: Value) -> Result<ToolResult, ClientError> {
+        self.call_tool("macro_run", json!({ "name": name, "variables": variables }))
+    }
+
+    pub fn macro_delete(&mut self, name: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("macro_delete", json!({ "name": name }))
+    }
+
+    // ---------------- 录制 ----------------
+
+    pub fn recording_start(&mut self, name: &str) -> Result<ToolResult, ClientError> {
+        self.call_tool("recording_start", json!({ "name": name }))
+    }
+
+    pub fn recording_stop(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("recording_stop", json!({}))
+    }
+
+    /// 回放录制，`speed` 为速度倍数（0.1–10）
+    pub fn recording_replay(&mut self, name: &str, speed: f64) -> Result<ToolResult, ClientError> {
+        self.call_tool("recording_replay", json!({ "name": name, "speed": speed }))
+    }
+
+    // ---------------- 维护 ----------------
+
+    /// 查询最新版本与更新日志；安装只能通过命令行 `iris-mcp check-update --install`
+    pub fn check_update(&mut self) -> Result<ToolResult, ClientError> {
+        self.call_tool("check_update", json!({}))
+    }
+}
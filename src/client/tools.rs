@@ -0,0 +1,22 @@
+//! 每个工具一个调用类型，由构建脚本根据 `server::tools_list` 的工具定义生成。
+//!
+//! 必填参数通过 `new` 传入，可选参数用同名方法设置，再交给 [`Client::call`](super::Client::call)：
+//!
+//! ```no_run
+//! use iris_mcp::client::{tools, Client};
+//!
+//! let mut client = Client::spawn("./target/release/iris-mcp").unwrap();
+//! client.initialize().unwrap();
+//! client.call(tools::MouseClick::new(100.0, 200.0, "left").clicks(2)).unwrap();
+//! ```
+
+use serde_json::{json, Map, Value};
+
+/// 一次工具调用：工具名与 `arguments`
+pub trait ToolCall {
+    const NAME: &'static str;
+
+    fn arguments(&self) -> Value;
+}
+
+include!(concat!(env!("OUT_DIR"), "/client_tools.rs"));
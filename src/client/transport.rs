@@ -0,0 +1,162 @@
+//! 客户端传输层：stdio（启动 iris-mcp 子进程）与 HTTP（POST JSON-RPC）。
+
+use super::ClientError;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// 发送一条 JSON-RPC 请求并返回对应的响应
+pub trait Transport {
+    fn send(&mut self, request: &Value) -> Result<Value, ClientError>;
+}
+
+/// 通过子进程 stdin/stdout 进行逐行 JSON-RPC 通信
+pub struct StdioTransport {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl StdioTransport {
+    /// 启动指定路径的 iris-mcp 可执行文件
+    pub fn spawn(executable: &str) -> Result<Self, ClientError> {
+        let mut child = Command::new(executable)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ClientError::Io(e.to_string()))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| ClientError::Io("child stdin unavailable".to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ClientError::Io("child stdout unavailable".to_string()))?;
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+}
+
+impl Transport for StdioTransport {
+    fn send(&mut self, request: &Value) -> Result<Value, ClientError> {
+        writeln!(self.stdin, "{}", request).map_err(|e| ClientError::Io(e.to_string()))?;
+        self.stdin.flush().map_err(|e| ClientError::Io(e.to_string()))?;
+
+        // 跳过服务器主动推送的通知（无 id），直到读到响应
+        loop {
+            let mut line = String::new();
+            let read = self
+                .stdout
+                .read_line(&mut line)
+                .map_err(|e| ClientError::Io(e.to_string()))?;
+            if read == 0 {
+                return Err(ClientError::Io("server closed stdout".to_string()));
+            }
+            let message: Value =
+                serde_json::from_str(&line).map_err(|e| ClientError::Protocol(e.to_string()))?;
+            if message.get("id").is_some() {
+                return Ok(message);
+            }
+        }
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 通过 HTTP/1.1 POST 发送 JSON-RPC 请求（仅支持 http://）
+pub struct HttpTransport {
+    host: String,
+    port: u16,
+    path: String,
+    token: Option<String>,
+}
+
+impl HttpTransport {
+    /// 解析形如 `http://host:port/path` 的地址
+    pub fn new(url: &str) -> Result<Self, ClientError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| ClientError::Protocol(format!("unsupported url: {}", url)))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((h, p)) => (
+                h,
+                p.parse::<u16>()
+                    .map_err(|_| ClientError::Protocol(format!("invalid port in url: {}", url)))?,
+            ),
+            None => (authority, 80),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+            token: None,
+        })
+    }
+
+    /// 携带 `Authorization: Bearer <令牌>`，对应服务端的 `--token`/`IRIS_HTTP_TOKEN`
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send(&mut self, request: &Value) -> Result<Value, ClientError> {
+        let body = request.to_string();
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))
+            .map_err(|e| ClientError::Io(e.to_string()))?;
+        let auth = self
+            .token
+            .as_ref()
+            .map(|t| format!("Authorization: Bearer {}\r\n", t))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            auth,
+            body.len(),
+            body
+        )
+        .map_err(|e| ClientError::Io(e.to_string()))?;
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(|e| ClientError::Io(e.to_string()))?;
+
+        let (head, payload) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| ClientError::Protocol("malformed HTTP response".to_string()))?;
+        let status_ok = head
+            .lines()
+            .next()
+            .map(|l| l.split_whitespace().nth(1) == Some("200"))
+            .unwrap_or(false);
+        if !status_ok {
+            return Err(ClientError::Protocol(format!(
+                "HTTP error: {}",
+                head.lines().next().unwrap_or_default()
+            )));
+        }
+        serde_json::from_str(payload).map_err(|e| ClientError::Protocol(e.to_string()))
+    }
+}
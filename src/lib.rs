@@ -1,3 +1,9 @@
+// 工具列表的 json! 宏嵌套较深，超出默认递归上限
+#![recursion_limit = "256"]
+
+pub mod client;
 pub mod monitor;
 pub mod operator;
 pub mod server;
+pub mod service;
+pub mod update;
@@ -1,6 +1,119 @@
-use iris_mcp::server;
+use iris_mcp::monitor::key_mouse;
+use iris_mcp::{server, service, update};
+use std::env;
 use std::io;
+use std::path::PathBuf;
 
 fn main() -> io::Result<()> {
-    server::run_server()
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // 事件缓冲区容量，对所有服务模式生效
+    let keyboard_buffer = usize_flag(&args, "--keyboard-buffer")?;
+    let mouse_buffer = usize_flag(&args, "--mouse-buffer")?;
+    if keyboard_buffer.is_some() || mouse_buffer.is_some() {
+        key_mouse::set_buffer_capacity(keyboard_buffer, mouse_buffer);
+    }
+    // 监听器锁被仍在运行的进程持有时强制接管
+    if args.iter().any(|a| a == "--force-monitor") {
+        key_mouse::set_force_monitor(true);
+    }
+    // 不自动启动键鼠监控，需要时通过 monitor_control 启动
+    if args.iter().any(|a| a == "--no-monitor") {
+        key_mouse::set_autostart(false);
+    }
+
+    match args.first().map(String::as_str) {
+        Some("schema") => {
+            let out = match args.iter().position(|a| a == "--out") {
+                Some(i) => args
+                    .get(i + 1)
+                    .map(PathBuf::from)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "--out requires a directory"))?,
+                None => PathBuf::from("schemas"),
+            };
+            let count = server::schema::export(&out)?;
+            eprintln!("已导出 {} 个工具的 schema 及 openrpc.json 到 {}", count, out.display());
+            Ok(())
+        }
+        Some("serve") => {
+            let addr = match args.iter().position(|a| a == "--listen") {
+                Some(i) => args
+                    .get(i + 1)
+                    .cloned()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "--listen requires an address"))?,
+                None => server::http::DEFAULT_LISTEN_ADDR.to_string(),
+            };
+            let token = server::http::resolve_token(flag_value(&args, "--token")?);
+            server::http::run_http_server(&addr, token)
+        }
+        Some("bridge-host") => {
+            let addr = match args.iter().position(|a| a == "--listen") {
+                Some(i) => args
+                    .get(i + 1)
+                    .cloned()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "--listen requires an address"))?,
+                None => server::bridge::DEFAULT_BRIDGE_ADDR.to_string(),
+            };
+            let token = server::bridge::resolve_token(flag_value(&args, "--token")?)?;
+            let allow_remote = args.iter().any(|a| a == "--allow-remote");
+            server::bridge::run_bridge_host(&addr, token, allow_remote)
+        }
+        Some("bridge") => {
+            let addr = flag_value(&args, "--connect")?
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "bridge requires --connect <host:port>"))?;
+            let token = server::bridge::resolve_token(flag_value(&args, "--token")?)?;
+            server::bridge::run_bridge_client(&addr, &token)
+        }
+        Some("install-service") => {
+            let config = service::ServiceConfig::from_args(&args[1..])?;
+            let path = service::install(&config)?;
+            eprintln!("已安装后台服务（HTTP {}），配置文件：{}", config.listen, path.display());
+            Ok(())
+        }
+        Some("uninstall-service") => {
+            let path = service::uninstall()?;
+            eprintln!("已卸载后台服务：{}", path.display());
+            Ok(())
+        }
+        Some("check-update") => {
+            let install = args.iter().any(|a| a == "--install");
+            let info = update::check_update(install).map_err(io::Error::other)?;
+            println!("当前版本: {}", info.current_version);
+            println!("最新版本: {}", info.latest_version);
+            if !info.changelog.is_empty() {
+                println!("\n{}\n", info.changelog.trim());
+            }
+            match (&info.installed_to, info.update_available) {
+                (Some(path), _) => println!("已更新: {}", path.display()),
+                (None, true) => println!("使用 --install 下载并替换当前可执行文件"),
+                (None, false) => println!("已是最新版本"),
+            }
+            Ok(())
+        }
+        _ => server::run_server(),
+    }
+}
+
+/// 读取 `--flag value` 形式的参数；flag 存在但缺少值时报错
+fn flag_value(args: &[String], flag: &str) -> io::Result<Option<String>> {
+    match args.iter().position(|a| a == flag) {
+        Some(i) => args
+            .get(i + 1)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} requires a value", flag))),
+        None => Ok(None),
+    }
+}
+
+/// 读取 `--flag <正整数>` 形式的参数
+fn usize_flag(args: &[String], flag: &str) -> io::Result<Option<usize>> {
+    flag_value(args, flag)?
+        .map(|v| {
+            v.parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("{} requires a positive integer", flag)))
+        })
+        .transpose()
 }
@@ -0,0 +1,603 @@
+//! 无障碍（Accessibility）查询：焦点元素信息、可滚动区域的滚动位置与内容尺寸、按选择器查找元素。
+//! macOS 基于 AXUIElement；Windows (UIA) 与 Linux (AT-SPI) 暂为桩实现。
+
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ElementInfo {
+    pub role: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub value: Option<String>,
+    pub position: Option<(f64, f64)>,
+    pub size: Option<(f64, f64)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ScrollMetrics {
+    /// 可滚动容器本身
+    pub container: ElementInfo,
+    /// 垂直滚动位置，0.0（顶部）– 1.0（底部）
+    pub vertical_position: Option<f64>,
+    /// 水平滚动位置，0.0（左）– 1.0（右）
+    pub horizontal_position: Option<f64>,
+    /// 可见区域尺寸
+    pub viewport_size: Option<(f64, f64)>,
+    /// 内容总尺寸
+    pub content_size: Option<(f64, f64)>,
+}
+
+#[derive(Debug)]
+pub enum AccessibilityError {
+    UnsupportedPlatform(&'static str),
+    PermissionDenied(&'static str),
+    NotImplemented(&'static str),
+    NotFound(String),
+}
+
+impl fmt::Display for AccessibilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessibilityError::UnsupportedPlatform(p) => write!(f, "accessibility unsupported on {}", p),
+            AccessibilityError::PermissionDenied(msg) => write!(f, "accessibility permission denied: {}", msg),
+            AccessibilityError::NotImplemented(msg) => write!(f, "accessibility not implemented: {}", msg),
+            AccessibilityError::NotFound(msg) => write!(f, "accessibility element not found: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AccessibilityError {}
+
+/// 选择器可以匹配的属性
+const SELECTOR_ATTRIBUTES: [&str; 4] = ["title", "description", "value", "identifier"];
+
+/// 选择器中的一个属性条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub attribute: String,
+    pub value: String,
+    /// `*=`：包含（不区分大小写）；否则为完全相等
+    pub contains: bool,
+}
+
+/// 无障碍元素选择器：`Role[attr=value][attr*=value]`，如 `AXButton[title=OK]`、`[description*=save]`。
+/// 角色可省略 `AX` 前缀且不区分大小写；属性为 title / description / value / identifier，
+/// 值可用双引号包含 `]` 等字符
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    pub role: Option<String>,
+    pub conditions: Vec<Condition>,
+}
+
+impl Selector {
+    pub fn parse(text: &str) -> Result<Selector, String> {
+        let text = text.trim();
+        let (role, mut rest) = match text.find('[') {
+            Some(i) => (text[..i].trim(), &text[i..]),
+            None => (text, ""),
+        };
+        let mut conditions = Vec::new();
+        while !rest.is_empty() {
+            let body = rest
+                .strip_prefix('[')
+                .ok_or_else(|| format!("expected '[' at {:?}", rest))?;
+            let op = body.find('=').ok_or_else(|| format!("missing '=' in {:?}", rest))?;
+            let (attribute, contains) = match body[..op].strip_suffix('*') {
+                Some(name) => (name.trim(), true),
+                None => (body[..op].trim(), false),
+            };
+            if !SELECTOR_ATTRIBUTES.contains(&attribute) {
+                return Err(format!(
+                    "unknown attribute {:?} (expected one of {})",
+                    attribute,
+                    SELECTOR_ATTRIBUTES.join(", ")
+                ));
+            }
+            let after = body[op + 1..].trim_start();
+            let (value, remainder) = match after.strip_prefix('"') {
+                Some(quoted) => {
+                    let end = quoted.find('"').ok_or_else(|| format!("unterminated quote in {:?}", rest))?;
+                    let remainder = quoted[end + 1..].trim_start();
+                    (&quoted[..end], remainder)
+                }
+                None => {
+                    let end = after.find(']').ok_or_else(|| format!("missing ']' in {:?}", rest))?;
+                    (after[..end].trim(), &after[end..])
+                }
+            };
+            rest = remainder
+                .strip_prefix(']')
+                .ok_or_else(|| format!("missing ']' in {:?}", rest))?
+                .trim_start();
+            conditions.push(Condition { attribute: attribute.to_string(), value: value.to_string(), contains });
+        }
+        if role.is_empty() && conditions.is_empty() {
+            return Err("empty selector".to_string());
+        }
+        Ok(Selector { role: (!role.is_empty()).then(|| role.to_string()), conditions })
+    }
+
+    /// 按属性读取函数判断元素是否匹配；`attr` 接收 "role" 与选择器属性名
+    pub fn matches(&self, attr: impl Fn(&str) -> Option<String>) -> bool {
+        if let Some(role) = &self.role {
+            let Some(actual) = attr("role") else { return false };
+            let bare = actual.strip_prefix("AX").unwrap_or(&actual);
+            let wanted = role.strip_prefix("AX").unwrap_or(role);
+            if !bare.eq_ignore_ascii_case(wanted) {
+                return false;
+            }
+        }
+        self.conditions.iter().all(|c| match attr(&c.attribute) {
+            Some(actual) if c.contains => actual.to_lowercase().contains(&c.value.to_lowercase()),
+            Some(actual) => actual == c.value,
+            None => false,
+        })
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.role.as_deref().unwrap_or(""))?;
+        for c in &self.conditions {
+            write!(f, "[{}{}={:?}]", c.attribute, if c.contains { "*" } else { "" }, c.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// 获取当前拥有键盘焦点的元素
+pub fn focused_element() -> Result<ElementInfo, AccessibilityError> {
+    platform::focused_element()
+}
+
+/// 按选择器查找第一个有位置与尺寸的元素（广度优先）。
+/// 给出 `pid` 时在该应用中查找，否则在前台应用中查找；给出 `window_id` 时只查找该窗口
+pub fn find_element(
+    selector: &Selector,
+    pid: Option<u32>,
+    window_id: Option<u64>,
+) -> Result<ElementInfo, AccessibilityError> {
+    platform::find_element(selector, pid, window_id)
+}
+
+/// 获取滚动容器的滚动位置与尺寸。
+/// `at` 为屏幕坐标时取该位置下的元素，否则取焦点元素，然后向上查找最近的滚动容器。
+pub fn scroll_metrics(at: Option<(f64, f64)>) -> Result<ScrollMetrics, AccessibilityError> {
+    platform::scroll_metrics(at)
+}
+
+/// macOS AXUIElement 的底层 FFI 与辅助函数，供本模块及窗口管理复用
+#[cfg(target_os = "macos")]
+pub(crate) mod ax {
+    use super::AccessibilityError;
+    use core_foundation::array::{CFArrayGetCount, CFArrayGetTypeID, CFArrayGetValueAtIndex};
+    use core_foundation::base::{CFGetTypeID, CFRelease, CFRetain, CFTypeRef, TCFType};
+    use core_foundation::boolean::{kCFBooleanFalse, kCFBooleanTrue, CFBooleanGetTypeID};
+    use core_foundation::number::{kCFNumberFloat64Type, CFNumberGetTypeID, CFNumberGetValue, CFNumberRef};
+    use core_foundation::string::{CFString, CFStringGetTypeID, CFStringRef};
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    pub type AXUIElementRef = CFTypeRef;
+    pub type AXError = i32;
+
+    pub const AX_ERROR_SUCCESS: AXError = 0;
+    const AX_VALUE_CG_POINT_TYPE: u32 = 1;
+    const AX_VALUE_CG_SIZE_TYPE: u32 = 2;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    unsafe extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+        fn AXUIElementCreateApplication(pid: i32) -> AXUIElementRef;
+        fn AXUIElementCopyAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> AXError;
+        fn AXUIElementSetAttributeValue(
+            element: AXUIElementRef,
+            attribute: CFStringRef,
+            value: CFTypeRef,
+        ) -> AXError;
+        fn AXUIElementCopyElementAtPosition(
+            application: AXUIElementRef,
+            x: f32,
+            y: f32,
+            element: *mut AXUIElementRef,
+        ) -> AXError;
+        fn AXUIElementPerformAction(element: AXUIElementRef, action: CFStringRef) -> AXError;
+        fn AXValueCreate(the_type: u32, value_ptr: *const c_void) -> CFTypeRef;
+        fn AXValueGetValue(value: CFTypeRef, the_type: u32, value_ptr: *mut c_void) -> bool;
+        /// 私有但长期稳定的 API：获取 AX 窗口对应的 CGWindowID
+        fn _AXUIElementGetWindow(element: AXUIElementRef, window_id: *mut u32) -> AXError;
+    }
+
+    /// 持有一个 CF 对象引用，离开作用域时释放
+    pub struct Owned(pub CFTypeRef);
+
+    impl Owned {
+        pub fn new(ptr: CFTypeRef) -> Option<Self> {
+            if ptr.is_null() { None } else { Some(Owned(ptr)) }
+        }
+    }
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            unsafe { CFRelease(self.0) };
+        }
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGSize {
+        width: f64,
+        height: f64,
+    }
+
+    pub fn ensure_trusted() -> Result<(), AccessibilityError> {
+        if unsafe { AXIsProcessTrusted() } {
+            Ok(())
+        } else {
+            Err(AccessibilityError::PermissionDenied(
+                "grant Accessibility access in System Settings → Privacy & Security",
+            ))
+        }
+    }
+
+    pub fn system_wide() -> Result<Owned, AccessibilityError> {
+        Owned::new(unsafe { AXUIElementCreateSystemWide() })
+            .ok_or_else(|| AccessibilityError::NotFound("system-wide element".to_string()))
+    }
+
+    pub fn application(pid: i32) -> Result<Owned, AccessibilityError> {
+        Owned::new(unsafe { AXUIElementCreateApplication(pid) })
+            .ok_or_else(|| AccessibilityError::NotFound(format!("application pid {}", pid)))
+    }
+
+    pub fn copy_attr(element: AXUIElementRef, name: &str) -> Option<Owned> {
+        let attr = CFString::new(name);
+        let mut value: CFTypeRef = ptr::null();
+        let err = unsafe {
+            AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value)
+        };
+        if err != AX_ERROR_SUCCESS {
+            return None;
+        }
+        Owned::new(value)
+    }
+
+    pub fn string_attr(element: AXUIElementRef, name: &str) -> Option<String> {
+        let value = copy_attr(element, name)?;
+        if unsafe { CFGetTypeID(value.0) } != unsafe { CFStringGetTypeID() } {
+            return None;
+        }
+        let s = unsafe { CFString::wrap_under_get_rule(value.0 as CFStringRef) };
+        Some(s.to_string())
+    }
+
+    pub fn number_attr(element: AXUIElementRef, name: &str) -> Option<f64> {
+        let value = copy_attr(element, name)?;
+        if unsafe { CFGetTypeID(value.0) } != unsafe { CFNumberGetTypeID() } {
+            return None;
+        }
+        let mut out = 0f64;
+        let ok = unsafe {
+            CFNumberGetValue(
+                value.0 as CFNumberRef,
+                kCFNumberFloat64Type,
+                &mut out as *mut f64 as *mut c_void,
+            )
+        };
+        ok.then_some(out)
+    }
+
+    pub fn bool_attr(element: AXUIElementRef, name: &str) -> Option<bool> {
+        let value = copy_attr(element, name)?;
+        if unsafe { CFGetTypeID(value.0) } != unsafe { CFBooleanGetTypeID() } {
+            return None;
+        }
+        Some(value.0 == unsafe { kCFBooleanTrue } as CFTypeRef)
+    }
+
+    pub fn set_bool_attr(element: AXUIElementRef, name: &str, value: bool) -> bool {
+        let attr = CFString::new(name);
+        let value = unsafe { if value { kCFBooleanTrue } else { kCFBooleanFalse } };
+        unsafe { AXUIElementSetAttributeValue(element, attr.as_concrete_TypeRef(), value as CFTypeRef) == AX_ERROR_SUCCESS }
+    }
+
+    /// 执行元素的 AX 动作（如 AXPress、AXRaise）
+    pub fn perform_action(element: AXUIElementRef, action: &str) -> bool {
+        let action = CFString::new(action);
+        unsafe { AXUIElementPerformAction(element, action.as_concrete_TypeRef()) == AX_ERROR_SUCCESS }
+    }
+
+    pub fn point_attr(element: AXUIElementRef, name: &str) -> Option<(f64, f64)> {
+        let value = copy_attr(element, name)?;
+        let mut point = CGPoint::default();
+        let ok = unsafe {
+            AXValueGetValue(value.0, AX_VALUE_CG_POINT_TYPE, &mut point as *mut CGPoint as *mut c_void)
+        };
+        ok.then_some((point.x, point.y))
+    }
+
+    pub fn size_attr(element: AXUIElementRef, name: &str) -> Option<(f64, f64)> {
+        let value = copy_attr(element, name)?;
+        let mut size = CGSize::default();
+        let ok = unsafe {
+            AXValueGetValue(value.0, AX_VALUE_CG_SIZE_TYPE, &mut size as *mut CGSize as *mut c_void)
+        };
+        ok.then_some((size.width, size.height))
+    }
+
+    pub fn set_point_attr(element: AXUIElementRef, name: &str, x: f64, y: f64) -> bool {
+        let point = CGPoint { x, y };
+        let Some(value) = Owned::new(unsafe {
+            AXValueCreate(AX_VALUE_CG_POINT_TYPE, &point as *const CGPoint as *const c_void)
+        }) else {
+            return false;
+        };
+        let attr = CFString::new(name);
+        unsafe { AXUIElementSetAttributeValue(element, attr.as_concrete_TypeRef(), value.0) == AX_ERROR_SUCCESS }
+    }
+
+    pub fn set_size_attr(element: AXUIElementRef, name: &str, width: f64, height: f64) -> bool {
+        let size = CGSize { width, height };
+        let Some(value) = Owned::new(unsafe {
+            AXValueCreate(AX_VALUE_CG_SIZE_TYPE, &size as *const CGSize as *const c_void)
+        }) else {
+            return false;
+        };
+        let attr = CFString::new(name);
+        unsafe { AXUIElementSetAttributeValue(element, attr.as_concrete_TypeRef(), value.0) == AX_ERROR_SUCCESS }
+    }
+
+    /// 取数组属性中的全部元素（各自保留引用）
+    pub fn children(element: AXUIElementRef, name: &str) -> Vec<Owned> {
+        let Some(array) = copy_attr(element, name) else {
+            return Vec::new();
+        };
+        if unsafe { CFGetTypeID(array.0) } != unsafe { CFArrayGetTypeID() } {
+            return Vec::new();
+        }
+        let count = unsafe { CFArrayGetCount(array.0 as _) };
+        (0..count)
+            .filter_map(|i| {
+                let child = unsafe { CFArrayGetValueAtIndex(array.0 as _, i) };
+                if child.is_null() {
+                    return None;
+                }
+                unsafe { CFRetain(child) };
+                Owned::new(child)
+            })
+            .collect()
+    }
+
+    /// 取数组属性的第一个元素（保留引用）
+    pub fn first_child(element: AXUIElementRef, name: &str) -> Option<Owned> {
+        children(element, name).into_iter().next()
+    }
+
+    pub fn element_at(x: f64, y: f64) -> Result<Owned, AccessibilityError> {
+        ensure_trusted()?;
+        let system = system_wide()?;
+        let mut element: AXUIElementRef = ptr::null();
+        let err = unsafe { AXUIElementCopyElementAtPosition(system.0, x as f32, y as f32, &mut element) };
+        if err != AX_ERROR_SUCCESS {
+            return Err(AccessibilityError::NotFound(format!("no element at ({}, {})", x, y)));
+        }
+        Owned::new(element)
+            .ok_or_else(|| AccessibilityError::NotFound(format!("no element at ({}, {})", x, y)))
+    }
+
+    /// AX 窗口元素对应的 CGWindowID
+    pub fn window_id(element: AXUIElementRef) -> Option<u32> {
+        let mut id = 0u32;
+        let err = unsafe { _AXUIElementGetWindow(element, &mut id) };
+        (err == AX_ERROR_SUCCESS).then_some(id)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::ax::{self, AXUIElementRef, Owned};
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// 向上查找滚动容器的最大层数
+    const MAX_PARENT_DEPTH: usize = 32;
+
+    /// 按选择器查找时最多访问的元素数，避免在超大的元素树中长时间遍历
+    const MAX_SEARCH_NODES: usize = 5000;
+
+    fn element_info(element: AXUIElementRef) -> ElementInfo {
+        ElementInfo {
+            role: ax::string_attr(element, "AXRole"),
+            title: ax::string_attr(element, "AXTitle"),
+            description: ax::string_attr(element, "AXDescription"),
+            value: ax::string_attr(element, "AXValue")
+                .or_else(|| ax::number_attr(element, "AXValue").map(|v| v.to_string())),
+            position: ax::point_attr(element, "AXPosition"),
+            size: ax::size_attr(element, "AXSize"),
+        }
+    }
+
+    fn focused() -> Result<Owned, AccessibilityError> {
+        ax::ensure_trusted()?;
+        let system = ax::system_wide()?;
+        ax::copy_attr(system.0, "AXFocusedUIElement")
+            .ok_or_else(|| AccessibilityError::NotFound("no focused element".to_string()))
+    }
+
+    pub fn focused_element() -> Result<ElementInfo, AccessibilityError> {
+        let element = focused()?;
+        Ok(element_info(element.0))
+    }
+
+    pub fn scroll_metrics(at: Option<(f64, f64)>) -> Result<ScrollMetrics, AccessibilityError> {
+        let mut current = match at {
+            Some((x, y)) => ax::element_at(x, y)?,
+            None => focused()?,
+        };
+
+        for _ in 0..MAX_PARENT_DEPTH {
+            if ax::string_attr(current.0, "AXRole").as_deref() == Some("AXScrollArea") {
+                let vertical = ax::copy_attr(current.0, "AXVerticalScrollBar")
+                    .and_then(|bar| ax::number_attr(bar.0, "AXValue"));
+                let horizontal = ax::copy_attr(current.0, "AXHorizontalScrollBar")
+                    .and_then(|bar| ax::number_attr(bar.0, "AXValue"));
+                let content_size = ax::first_child(current.0, "AXContents")
+                    .and_then(|content| ax::size_attr(content.0, "AXSize"));
+
+                return Ok(ScrollMetrics {
+                    container: element_info(current.0),
+                    vertical_position: vertical,
+                    horizontal_position: horizontal,
+                    viewport_size: ax::size_attr(current.0, "AXSize"),
+                    content_size,
+                });
+            }
+            current = match ax::copy_attr(current.0, "AXParent") {
+                Some(parent) => parent,
+                None => break,
+            };
+        }
+
+        Err(AccessibilityError::NotFound("no scrollable ancestor (AXScrollArea)".to_string()))
+    }
+
+    pub fn find_element(
+        selector: &Selector,
+        pid: Option<u32>,
+        window_id: Option<u64>,
+    ) -> Result<ElementInfo, AccessibilityError> {
+        ax::ensure_trusted()?;
+        let app = match pid {
+            Some(pid) => ax::application(pid as i32)?,
+            None => {
+                let system = ax::system_wide()?;
+                ax::copy_attr(system.0, "AXFocusedApplication")
+                    .ok_or_else(|| AccessibilityError::NotFound("no frontmost application".to_string()))?
+            }
+        };
+        let roots: Vec<Owned> = match window_id {
+            Some(id) => ax::children(app.0, "AXWindows")
+                .into_iter()
+                .filter(|w| ax::window_id(w.0).map(u64::from) == Some(id))
+                .collect(),
+            None => vec![app],
+        };
+        if let (Some(id), true) = (window_id, roots.is_empty()) {
+            return Err(AccessibilityError::NotFound(format!("window {} has no accessibility element", id)));
+        }
+
+        let attr = |element: AXUIElementRef, name: &str| match name {
+            "role" => ax::string_attr(element, "AXRole"),
+            "title" => ax::string_attr(element, "AXTitle"),
+            "description" => ax::string_attr(element, "AXDescription"),
+            "identifier" => ax::string_attr(element, "AXIdentifier"),
+            "value" => ax::string_attr(element, "AXValue")
+                .or_else(|| ax::number_attr(element, "AXValue").map(|v| v.to_string())),
+            _ => None,
+        };
+        let mut queue: VecDeque<Owned> = roots.into();
+        let mut visited = 0;
+        while let Some(element) = queue.pop_front() {
+            visited += 1;
+            if visited > MAX_SEARCH_NODES {
+                break;
+            }
+            if selector.matches(|name| attr(element.0, name)) {
+                let info = element_info(element.0);
+                if info.position.is_some() && info.size.is_some() {
+                    return Ok(info);
+                }
+            }
+            queue.extend(ax::children(element.0, "AXChildren"));
+        }
+        Err(AccessibilityError::NotFound(format!("no element matches {}", selector)))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+
+    pub fn focused_element() -> Result<ElementInfo, AccessibilityError> {
+        Err(AccessibilityError::NotImplemented(
+            "Windows: implement via UI Automation GetFocusedElement",
+        ))
+    }
+
+    pub fn scroll_metrics(_at: Option<(f64, f64)>) -> Result<ScrollMetrics, AccessibilityError> {
+        Err(AccessibilityError::NotImplemented(
+            "Windows: implement via UI Automation ScrollPattern",
+        ))
+    }
+
+    pub fn find_element(
+        _selector: &Selector,
+        _pid: Option<u32>,
+        _window_id: Option<u64>,
+    ) -> Result<ElementInfo, AccessibilityError> {
+        Err(AccessibilityError::NotImplemented(
+            "Windows: implement via UI Automation FindFirst",
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    pub fn focused_element() -> Result<ElementInfo, AccessibilityError> {
+        Err(AccessibilityError::NotImplemented(
+            "Linux: implement via AT-SPI focus tracking",
+        ))
+    }
+
+    pub fn scroll_metrics(_at: Option<(f64, f64)>) -> Result<ScrollMetrics, AccessibilityError> {
+        Err(AccessibilityError::NotImplemented(
+            "Linux: implement via AT-SPI Value interface on scroll bars",
+        ))
+    }
+
+    pub fn find_element(
+        _selector: &Selector,
+        _pid: Option<u32>,
+        _window_id: Option<u64>,
+    ) -> Result<ElementInfo, AccessibilityError> {
+        Err(AccessibilityError::NotImplemented(
+            "Linux: implement via AT-SPI Collection GetMatches",
+        ))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    pub fn focused_element() -> Result<ElementInfo, AccessibilityError> {
+        Err(AccessibilityError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn scroll_metrics(_at: Option<(f64, f64)>) -> Result<ScrollMetrics, AccessibilityError> {
+        Err(AccessibilityError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn find_element(
+        _selector: &Selector,
+        _pid: Option<u32>,
+        _window_id: Option<u64>,
+    ) -> Result<ElementInfo, AccessibilityError> {
+        Err(AccessibilityError::UnsupportedPlatform(std::env::consts::OS))
+    }
+}
@@ -0,0 +1,293 @@
+//! 截图标注：在截图上绘制十字准星、矩形框与文字标签，便于 Agent 把打算点击的位置展示给人确认。
+//!
+//! 坐标均为截图像素；线宽与字号随截图尺寸缩放，形状外侧带一圈白色描边，深色与浅色背景上都清晰可见。
+//! 标签使用内置的 5x7 点阵字体，只支持 ASCII 字母、数字与常用符号（小写显示为大写），其余字符显示为 `?`。
+//!
+//! 另可叠加带坐标标注的网格（[`draw_grid`]），没有无障碍信息时帮助视觉模型估读点击坐标。
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// 标注形状（截图像素坐标）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// 十字准星，中心在 (x, y)
+    Point { x: i64, y: i64 },
+    Rect { x: i64, y: i64, width: i64, height: i64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub shape: Shape,
+    pub label: Option<String>,
+    pub color: [u8; 3],
+}
+
+/// 未指定颜色时使用的红色
+pub const DEFAULT_COLOR: [u8; 3] = [0xff, 0x20, 0x20];
+
+/// 标签最多显示的字符数
+pub const MAX_LABEL_CHARS: usize = 32;
+
+const HALO: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const TEXT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// 依次绘制各标注，超出截图的部分被裁掉
+pub fn draw(img: &mut DynamicImage, annotations: &[Annotation]) {
+    if annotations.is_empty() {
+        return;
+    }
+    let mut rgba = img.to_rgba8();
+    // 1080p 上约 3 像素，小截图至少 2 像素
+    let unit = (rgba.width().min(rgba.height()) as i64 / 360).max(2);
+    for annotation in annotations {
+        let color = Rgba([annotation.color[0], annotation.color[1], annotation.color[2], 255]);
+        let anchor = match annotation.shape {
+            Shape::Point { x, y } => {
+                draw_crosshair(&mut rgba, x, y, unit, color);
+                // 标签默认在准星右上方，靠近截图边缘时换到另一侧，避免盖住目标
+                let (width, height) = label_size(annotation.label.as_deref().unwrap_or_default(), unit);
+                let right = x + 6 * unit + width <= rgba.width() as i64;
+                let above = y - 6 * unit - height >= 0;
+                (
+                    if right { x + 6 * unit } else { x - 6 * unit - width },
+                    if above { y - 6 * unit } else { y + 6 * unit + height },
+                )
+            }
+            Shape::Rect { x, y, width, height } => {
+                draw_rect(&mut rgba, x, y, width, height, unit, color);
+                (x, y - unit)
+            }
+        };
+        if let Some(label) = annotation.label.as_deref().filter(|l| !l.is_empty()) {
+            draw_label(&mut rgba, label, anchor, unit, color);
+        }
+    }
+    *img = DynamicImage::ImageRgba8(rgba);
+}
+
+/// 网格线颜色（与画面按一半透明度混合）
+const GRID_COLOR: Rgba<u8> = Rgba([255, 0, 200, 255]);
+
+/// 网格线在截图中的最小间距（像素），过密时间隔按倍数放大
+const MIN_GRID_PIXELS: f64 = 12.0;
+
+/// 叠加坐标网格：像素 (px, py) 对应的坐标为 `origin + (px, py) * units_per_pixel`，
+/// 每隔 `spacing` 个坐标单位画一条线，并在上边缘与左边缘标注坐标值。返回实际使用的间距
+pub fn draw_grid(img: &mut DynamicImage, spacing: u32, origin: (f64, f64), units_per_pixel: (f64, f64)) -> u32 {
+    let mut rgba = img.to_rgba8();
+    let (w, h) = (rgba.width() as i64, rgba.height() as i64);
+    let mut spacing = spacing.max(1) as f64;
+    while spacing / units_per_pixel.0.max(units_per_pixel.1) < MIN_GRID_PIXELS {
+        spacing *= 2.0;
+    }
+    let unit = (w.min(h) / 720).max(1);
+    let (_, label_height) = label_size("0", unit + 1);
+    let to_pixel = |value: f64, axis: usize| {
+        let (o, u) = if axis == 0 { (origin.0, units_per_pixel.0) } else { (origin.1, units_per_pixel.1) };
+        ((value - o) / u).round() as i64
+    };
+    let lines = |axis: usize, extent: i64| {
+        let (o, u) = if axis == 0 { (origin.0, units_per_pixel.0) } else { (origin.1, units_per_pixel.1) };
+        let first = (o / spacing).ceil() as i64;
+        let last = ((o + extent as f64 * u) / spacing).floor() as i64;
+        (first..=last).map(move |k| k as f64 * spacing)
+    };
+
+    for value in lines(0, w) {
+        let px = to_pixel(value, 0);
+        for py in 0..h {
+            blend(&mut rgba, px, py, GRID_COLOR);
+        }
+    }
+    for value in lines(1, h) {
+        let py = to_pixel(value, 1);
+        for px in 0..w {
+            blend(&mut rgba, px, py, GRID_COLOR);
+        }
+    }
+    // 标签放在线的右侧 / 下方；线太密放不下标签时每隔几条标注一次
+    let label_every = |axis: usize, label_extent: i64| {
+        let pixels = spacing / if axis == 0 { units_per_pixel.0 } else { units_per_pixel.1 };
+        ((label_extent + 4) as f64 / pixels).ceil().max(1.0) as i64
+    };
+    let widest = lines(0, w).map(|v| label_size(&v.to_string(), unit + 1).0).max().unwrap_or(0);
+    let every = label_every(0, widest);
+    for value in lines(0, w).filter(|v| (v / spacing) as i64 % every == 0) {
+        let px = to_pixel(value, 0);
+        draw_label(&mut rgba, &value.to_string(), (px + 2, label_height), unit + 1, GRID_COLOR);
+    }
+    let every = label_every(1, label_height);
+    for value in lines(1, h).filter(|v| (v / spacing) as i64 % every == 0 && to_pixel(*v, 1) > label_height) {
+        let py = to_pixel(value, 1);
+        draw_label(&mut rgba, &value.to_string(), (0, py + label_height + 1), unit + 1, GRID_COLOR);
+    }
+    *img = DynamicImage::ImageRgba8(rgba);
+    spacing as u32
+}
+
+/// 与画面按一半透明度混合，超出图像时忽略
+fn blend(img: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>) {
+    if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 {
+        return;
+    }
+    let pixel = img.get_pixel_mut(x as u32, y as u32);
+    for c in 0..3 {
+        pixel[c] = ((pixel[c] as u16 + color[c] as u16) / 2) as u8;
+    }
+}
+
+/// 十字准星：中心留空的四条臂加一个圆环
+fn draw_crosshair(img: &mut RgbaImage, x: i64, y: i64, unit: i64, color: Rgba<u8>) {
+    let (gap, arm, half) = (2 * unit, 9 * unit, unit / 2);
+    let arms = [
+        (x - arm, y - half, x - gap, y - half + unit),
+        (x + gap, y - half, x + arm, y - half + unit),
+        (x - half, y - arm, x - half + unit, y - gap),
+        (x - half, y + gap, x - half + unit, y + arm),
+    ];
+    for (x0, y0, x1, y1) in arms {
+        fill(img, x0 - 1, y0 - 1, x1 + 1, y1 + 1, HALO);
+    }
+    ring(img, x, y, 5 * unit, unit + 2, HALO);
+    for (x0, y0, x1, y1) in arms {
+        fill(img, x0, y0, x1, y1, color);
+    }
+    ring(img, x, y, 5 * unit, unit, color);
+    fill(img, x, y, x + 1, y + 1, color);
+}
+
+fn draw_rect(img: &mut RgbaImage, x: i64, y: i64, width: i64, height: i64, unit: i64, color: Rgba<u8>) {
+    let (right, bottom) = (x + width.max(1), y + height.max(1));
+    let edges = |grow: i64| {
+        [
+            (x - grow, y - grow, right + grow, y + unit + grow),
+            (x - grow, bottom - unit - grow, right + grow, bottom + grow),
+            (x - grow, y - grow, x + unit + grow, bottom + grow),
+            (right - unit - grow, y - grow, right + grow, bottom + grow),
+        ]
+    };
+    for (x0, y0, x1, y1) in edges(1) {
+        fill(img, x0, y0, x1, y1, HALO);
+    }
+    for (x0, y0, x1, y1) in edges(0) {
+        fill(img, x0, y0, x1, y1, color);
+    }
+}
+
+/// 标签：标注颜色的底色块上绘制白字，左下角在 `anchor`，整体移入截图范围内
+fn draw_label(img: &mut RgbaImage, label: &str, anchor: (i64, i64), unit: i64, color: Rgba<u8>) {
+    let glyphs: Vec<[u8; 7]> = label.chars().take(MAX_LABEL_CHARS).map(glyph).collect();
+    let (scale, pad) = label_metrics(unit);
+    let (width, height) = label_size(label, unit);
+    let x = anchor.0.min(img.width() as i64 - width).max(0);
+    let y = (anchor.1 - height).min(img.height() as i64 - height).max(0);
+
+    fill(img, x - 1, y - 1, x + width + 1, y + height + 1, HALO);
+    fill(img, x, y, x + width, y + height, color);
+    for (i, rows) in glyphs.iter().enumerate() {
+        let left = x + pad + i as i64 * 6 * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) != 0 {
+                    let (px, py) = (left + col * scale, y + pad + row as i64 * scale);
+                    fill(img, px, py, px + scale, py + scale, TEXT);
+                }
+            }
+        }
+    }
+}
+
+/// 标签的点阵放大倍数与内边距
+fn label_metrics(unit: i64) -> (i64, i64) {
+    let scale = unit.max(2) - 1;
+    (scale, scale)
+}
+
+/// 标签底色块的宽高
+fn label_size(label: &str, unit: i64) -> (i64, i64) {
+    let (scale, pad) = label_metrics(unit);
+    let chars = label.chars().take(MAX_LABEL_CHARS).count() as i64;
+    (chars * 6 * scale - scale + 2 * pad, 7 * scale + 2 * pad)
+}
+
+/// 填充 [x0, x1) × [y0, y1)，裁剪到图像范围
+fn fill(img: &mut RgbaImage, x0: i64, y0: i64, x1: i64, y1: i64, color: Rgba<u8>) {
+    let (w, h) = (img.width() as i64, img.height() as i64);
+    for py in y0.max(0)..y1.min(h) {
+        for px in x0.max(0)..x1.min(w) {
+            img.put_pixel(px as u32, py as u32, color);
+        }
+    }
+}
+
+/// 以 (cx, cy) 为圆心、半径 `radius`、线宽 `width` 的圆环
+fn ring(img: &mut RgbaImage, cx: i64, cy: i64, radius: i64, width: i64, color: Rgba<u8>) {
+    let outer = radius as f64 + width as f64 / 2.0;
+    let inner = (radius as f64 - width as f64 / 2.0).max(0.0);
+    let r = outer.ceil() as i64;
+    let (w, h) = (img.width() as i64, img.height() as i64);
+    for py in (cy - r).max(0)..(cy + r + 1).min(h) {
+        for px in (cx - r).max(0)..(cx + r + 1).min(w) {
+            let d = (((px - cx).pow(2) + (py - cy).pow(2)) as f64).sqrt();
+            if d >= inner && d <= outer {
+                img.put_pixel(px as u32, py as u32, color);
+            }
+        }
+    }
+}
+
+/// 5x7 点阵，每行低 5 位从左到右
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        ' ' => [0; 7],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '_' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111],
+        '+' => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+        '=' => [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        '#' => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+        '/' => [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+    }
+}
@@ -0,0 +1,444 @@
+//! 截图中的鼠标指针：`CGDisplay::image`、ScreenCaptureKit 与桌面复制得到的画面都不含指针，
+//! 这里读取当前指针图像与位置，按截图的像素比例缩放后合成到主显示器截图上。
+//!
+//! - macOS：`NSCursor.currentSystemCursor`（任意应用设置的当前指针），取不到时使用内置箭头
+//! - Windows：`GetCursorInfo` + `DrawIconEx`，指针隐藏时不合成
+//! - 其他平台：截图尚未实现，返回 NotImplemented
+
+use super::screen::MonitorError;
+use image::{imageops, DynamicImage, RgbaImage};
+
+/// 已按截图像素比例缩放、定位到截图坐标系中的指针
+#[derive(Debug, Clone)]
+pub struct CursorPlacement {
+    pub image: RgbaImage,
+    /// 热点在指针图像内的像素位置
+    pub hotspot_x: u32,
+    pub hotspot_y: u32,
+    /// 热点在截图中的像素位置
+    pub x: i32,
+    pub y: i32,
+}
+
+impl CursorPlacement {
+    /// 指针图像左上角在截图中的位置
+    pub fn origin(&self) -> (i64, i64) {
+        (self.x as i64 - self.hotspot_x as i64, self.y as i64 - self.hotspot_y as i64)
+    }
+}
+
+/// 读取当前指针并换算到 `frame_width`x`frame_height` 的主显示器截图中；
+/// 指针隐藏或不在主显示器上时返回 None
+pub fn locate(frame_width: u32, frame_height: u32) -> Result<Option<CursorPlacement>, MonitorError> {
+    platform::locate(frame_width, frame_height)
+}
+
+/// 以 alpha 混合将指针绘制到截图上，超出截图的部分被裁掉
+pub fn composite(img: &mut DynamicImage, cursor: &CursorPlacement) {
+    let (x, y) = cursor.origin();
+    let mut rgba = img.to_rgba8();
+    imageops::overlay(&mut rgba, &cursor.image, x, y);
+    *img = DynamicImage::ImageRgba8(rgba);
+}
+
+/// 按 `factor` 缩放指针图像与热点；比例接近 1 时原样返回
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn rescale(image: RgbaImage, hotspot: (u32, u32), factor: f64) -> (RgbaImage, (u32, u32)) {
+    if (factor - 1.0).abs() < 0.01 || factor <= 0.0 {
+        return (image, hotspot);
+    }
+    let width = ((image.width() as f64 * factor).round() as u32).max(1);
+    let height = ((image.height() as f64 * factor).round() as u32).max(1);
+    let scaled = imageops::resize(&image, width, height, imageops::FilterType::Triangle);
+    let hotspot = (
+        ((hotspot.0 as f64 * factor).round() as u32).min(width - 1),
+        ((hotspot.1 as f64 * factor).round() as u32).min(height - 1),
+    );
+    (scaled, hotspot)
+}
+
+/// 内置箭头：`#` 为黑色描边，`.` 为白色填充，热点在左上角
+#[cfg(target_os = "macos")]
+const ARROW: [&str; 19] = [
+    "#           ",
+    "##          ",
+    "#.#         ",
+    "#..#        ",
+    "#...#       ",
+    "#....#      ",
+    "#.....#     ",
+    "#......#    ",
+    "#.......#   ",
+    "#........#  ",
+    "#.........# ",
+    "#......#####",
+    "#...#..#    ",
+    "#..# #..#   ",
+    "#.#  #..#   ",
+    "##    #..#  ",
+    "#     #..#  ",
+    "       #..# ",
+    "       ###  ",
+];
+
+#[cfg(target_os = "macos")]
+fn builtin_arrow() -> RgbaImage {
+    let mut image = RgbaImage::new(ARROW[0].len() as u32, ARROW.len() as u32);
+    for (y, row) in ARROW.iter().enumerate() {
+        for (x, c) in row.bytes().enumerate() {
+            let pixel = match c {
+                b'#' => [0, 0, 0, 255],
+                b'.' => [255, 255, 255, 255],
+                _ => continue,
+            };
+            image.put_pixel(x as u32, y as u32, image::Rgba(pixel));
+        }
+    }
+    image
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use core_foundation::string::CFStringRef;
+    use core_graphics::color_space::kCGColorSpaceSRGB;
+    use core_graphics::display::CGDisplay;
+    use core_graphics::event::CGEvent;
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+    use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+    use std::ffi::{c_char, c_void};
+
+    type Id = *mut c_void;
+    type Sel = *mut c_void;
+
+    /// `kCGImageAlphaPremultipliedLast`：RGBA 字节顺序、预乘 alpha
+    const ALPHA_PREMULTIPLIED_LAST: u32 = 1;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct NSPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    unsafe extern "C" {}
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGImageGetWidth(image: *mut c_void) -> usize;
+        fn CGImageGetHeight(image: *mut c_void) -> usize;
+        fn CGColorSpaceCreateWithName(name: CFStringRef) -> *mut c_void;
+        fn CGColorSpaceRelease(space: *mut c_void);
+        fn CGBitmapContextCreate(
+            data: *mut c_void,
+            width: usize,
+            height: usize,
+            bits_per_component: usize,
+            bytes_per_row: usize,
+            space: *mut c_void,
+            bitmap_info: u32,
+        ) -> *mut c_void;
+        fn CGContextDrawImage(context: *mut c_void, rect: CGRect, image: *mut c_void);
+        fn CGContextRelease(context: *mut c_void);
+    }
+
+    #[link(name = "objc")]
+    unsafe extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Sel;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    unsafe fn sel(name: &[u8]) -> Sel {
+        unsafe { sel_registerName(name.as_ptr() as *const c_char) }
+    }
+
+    unsafe fn send_id(receiver: Id, selector: Sel) -> Id {
+        let f: unsafe extern "C" fn(Id, Sel) -> Id =
+            unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+        unsafe { f(receiver, selector) }
+    }
+
+    /// NSPoint 与 NSSize 布局相同，两个 f64 在 x86_64 与 arm64 上都通过寄存器返回
+    unsafe fn send_point(receiver: Id, selector: Sel) -> NSPoint {
+        let f: unsafe extern "C" fn(Id, Sel) -> NSPoint =
+            unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+        unsafe { f(receiver, selector) }
+    }
+
+    unsafe fn send_cg_image(receiver: Id, selector: Sel) -> *mut c_void {
+        // CGImageForProposedRect:context:hints:，三个参数均为空时选择最合适的表示
+        let f: unsafe extern "C" fn(Id, Sel, *mut c_void, Id, Id) -> *mut c_void =
+            unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+        unsafe { f(receiver, selector, std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut()) }
+    }
+
+    pub fn locate(frame_width: u32, frame_height: u32) -> Result<Option<CursorPlacement>, MonitorError> {
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| MonitorError::Io("CGEventSourceCreate failed".to_string()))?;
+        let location = CGEvent::new(source)
+            .map_err(|_| MonitorError::Io("CGEventCreate failed".to_string()))?
+            .location();
+
+        // 截图为主显示器的物理像素，主显示器左上角即全局坐标原点
+        let bounds = CGDisplay::main().bounds();
+        if bounds.size.width <= 0.0 || bounds.size.height <= 0.0 {
+            return Err(MonitorError::Io("main display has no size".to_string()));
+        }
+        let ratio = frame_width as f64 / bounds.size.width;
+        let x = ((location.x - bounds.origin.x) * frame_width as f64 / bounds.size.width).floor() as i32;
+        let y = ((location.y - bounds.origin.y) * frame_height as f64 / bounds.size.height).floor() as i32;
+        if x < 0 || y < 0 || x >= frame_width as i32 || y >= frame_height as i32 {
+            return Ok(None);
+        }
+
+        // 系统指针图像以点为单位描述尺寸，按截图每点的像素数缩放
+        let (image, hotspot) = match unsafe { system_cursor() } {
+            Some((image, hotspot, pixels_per_point)) => rescale(image, hotspot, ratio / pixels_per_point),
+            None => rescale(builtin_arrow(), (0, 0), ratio),
+        };
+        Ok(Some(CursorPlacement { image, hotspot_x: hotspot.0, hotspot_y: hotspot.1, x, y }))
+    }
+
+    /// 当前系统指针的 RGBA 图像、像素热点与每点像素数
+    unsafe fn system_cursor() -> Option<(RgbaImage, (u32, u32), f64)> {
+        unsafe {
+            let pool = objc_autoreleasePoolPush();
+            let result = (|| {
+                let class = objc_getClass(c"NSCursor".as_ptr());
+                if class.is_null() {
+                    return None;
+                }
+                let cursor = send_id(class, sel(b"currentSystemCursor\0"));
+                if cursor.is_null() {
+                    return None;
+                }
+                let ns_image = send_id(cursor, sel(b"image\0"));
+                if ns_image.is_null() {
+                    return None;
+                }
+                let size = send_point(ns_image, sel(b"size\0"));
+                // hotSpot 以点为单位，原点在图像左上角
+                let hotspot = send_point(cursor, sel(b"hotSpot\0"));
+                let cg_image = send_cg_image(ns_image, sel(b"CGImageForProposedRect:context:hints:\0"));
+                if cg_image.is_null() || size.x <= 0.0 {
+                    return None;
+                }
+                let image = draw_rgba(cg_image)?;
+                let pixels_per_point = image.width() as f64 / size.x;
+                let hotspot = (
+                    ((hotspot.x * pixels_per_point).round().max(0.0) as u32).min(image.width() - 1),
+                    ((hotspot.y * pixels_per_point).round().max(0.0) as u32).min(image.height() - 1),
+                );
+                Some((image, hotspot, pixels_per_point))
+            })();
+            objc_autoreleasePoolPop(pool);
+            result
+        }
+    }
+
+    /// 把 CGImage 绘制到 RGBA 位图中并还原预乘 alpha
+    unsafe fn draw_rgba(cg_image: *mut c_void) -> Option<RgbaImage> {
+        unsafe {
+            let width = CGImageGetWidth(cg_image);
+            let height = CGImageGetHeight(cg_image);
+            if width == 0 || height == 0 {
+                return None;
+            }
+            let mut pixels = vec![0u8; width * height * 4];
+            // 与截图一样输出 sRGB，合成时颜色一致
+            let space = CGColorSpaceCreateWithName(kCGColorSpaceSRGB);
+            let context = CGBitmapContextCreate(
+                pixels.as_mut_ptr().cast(),
+                width,
+                height,
+                8,
+                width * 4,
+                space,
+                ALPHA_PREMULTIPLIED_LAST,
+            );
+            CGColorSpaceRelease(space);
+            if context.is_null() {
+                return None;
+            }
+            let rect = CGRect::new(&CGPoint::new(0.0, 0.0), &CGSize::new(width as f64, height as f64));
+            CGContextDrawImage(context, rect, cg_image);
+            CGContextRelease(context);
+
+            for pixel in pixels.chunks_exact_mut(4) {
+                let alpha = pixel[3] as u32;
+                if alpha > 0 && alpha < 255 {
+                    for channel in &mut pixel[..3] {
+                        *channel = (*channel as u32 * 255 / alpha).min(255) as u8;
+                    }
+                }
+            }
+            RgbaImage::from_raw(width as u32, height as u32, pixels)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, GetObjectW, PatBlt,
+        ReleaseDC, SelectObject, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, BLACKNESS, DIB_RGB_COLORS, HBITMAP, HDC,
+        ROP_CODE, WHITENESS,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DrawIconEx, GetCursorInfo, GetIconInfo, GetSystemMetrics, CURSORINFO, CURSOR_SHOWING, DI_NORMAL, HICON,
+        ICONINFO, SM_CXSCREEN, SM_CYSCREEN,
+    };
+
+    pub fn locate(frame_width: u32, frame_height: u32) -> Result<Option<CursorPlacement>, MonitorError> {
+        let mut info = CURSORINFO { cbSize: std::mem::size_of::<CURSORINFO>() as u32, ..Default::default() };
+        unsafe { GetCursorInfo(&mut info) }.map_err(|e| MonitorError::Io(format!("GetCursorInfo: {}", e)))?;
+        if info.flags.0 & CURSOR_SHOWING.0 == 0 || info.hCursor.is_invalid() {
+            return Ok(None);
+        }
+
+        // 指针坐标与 SM_CXSCREEN 处于同一 DPI 感知空间，按其与截图尺寸之比换算到截图像素；
+        // 主显示器左上角即全局坐标原点
+        let (screen_width, screen_height) = unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
+        if screen_width <= 0 || screen_height <= 0 {
+            return Err(MonitorError::Io("GetSystemMetrics returned no screen size".to_string()));
+        }
+        let ratio = frame_width as f64 / screen_width as f64;
+        let x = (info.ptScreenPos.x as f64 * ratio).floor() as i32;
+        let y = (info.ptScreenPos.y as f64 * frame_height as f64 / screen_height as f64).floor() as i32;
+        if x < 0 || y < 0 || x >= frame_width as i32 || y >= frame_height as i32 {
+            return Ok(None);
+        }
+
+        let (image, hotspot) = unsafe { render(HICON(info.hCursor.0)) }?;
+        let (image, hotspot) = rescale(image, hotspot, ratio);
+        Ok(Some(CursorPlacement { image, hotspot_x: hotspot.0, hotspot_y: hotspot.1, x, y }))
+    }
+
+    /// 把指针分别绘制在黑底与白底上，由两次结果的差值还原 alpha；
+    /// 这样单色、彩色与带 alpha 的指针都能统一处理
+    unsafe fn render(icon: HICON) -> Result<(RgbaImage, (u32, u32)), MonitorError> {
+        unsafe {
+            let mut icon_info = ICONINFO::default();
+            GetIconInfo(icon, &mut icon_info).map_err(|e| MonitorError::Io(format!("GetIconInfo: {}", e)))?;
+            let monochrome = icon_info.hbmColor.is_invalid();
+            let mut bitmap = BITMAP::default();
+            let source = if monochrome { icon_info.hbmMask } else { icon_info.hbmColor };
+            let got = GetObjectW(
+                source.into(),
+                std::mem::size_of::<BITMAP>() as i32,
+                Some((&mut bitmap as *mut BITMAP).cast()),
+            );
+            if !icon_info.hbmColor.is_invalid() {
+                let _ = DeleteObject(icon_info.hbmColor.into());
+            }
+            if !icon_info.hbmMask.is_invalid() {
+                let _ = DeleteObject(icon_info.hbmMask.into());
+            }
+            if got == 0 {
+                return Err(MonitorError::Io("GetObject on cursor bitmap failed".to_string()));
+            }
+            let width = bitmap.bmWidth;
+            // 单色指针的掩码上下叠放 AND 与 XOR 两部分
+            let height = if monochrome { bitmap.bmHeight / 2 } else { bitmap.bmHeight };
+            if width <= 0 || height <= 0 {
+                return Err(MonitorError::Io("cursor bitmap is empty".to_string()));
+            }
+
+            let screen = GetDC(None);
+            let memory = CreateCompatibleDC(Some(screen));
+            let target = CreateCompatibleBitmap(screen, width, height);
+            let result = draw_on(memory, target, icon, width, height, BLACKNESS).and_then(|black| {
+                draw_on(memory, target, icon, width, height, WHITENESS).map(|white| (black, white))
+            });
+            let _ = DeleteObject(target.into());
+            let _ = DeleteDC(memory);
+            ReleaseDC(None, screen);
+            let (black, white) = result?;
+
+            let mut rgba = Vec::with_capacity(black.len());
+            for (b, w) in black.chunks_exact(4).zip(white.chunks_exact(4)) {
+                let diff = w[1] as i32 - b[1] as i32;
+                let pixel = if diff < 0 {
+                    // 反色像素（如文本插入符）在两种底色上互为反色，按不透明黑色绘制
+                    [0, 0, 0, 255]
+                } else {
+                    let alpha = (255 - diff) as u32;
+                    if alpha == 0 {
+                        [0, 0, 0, 0]
+                    } else {
+                        let channel = |v: u8| (v as u32 * 255 / alpha).min(255) as u8;
+                        [channel(b[2]), channel(b[1]), channel(b[0]), alpha as u8]
+                    }
+                };
+                rgba.extend_from_slice(&pixel);
+            }
+            let image = RgbaImage::from_raw(width as u32, height as u32, rgba)
+                .ok_or_else(|| MonitorError::Io("cursor bitmap size mismatch".to_string()))?;
+            let hotspot = (
+                icon_info.xHotspot.min(width as u32 - 1),
+                icon_info.yHotspot.min(height as u32 - 1),
+            );
+            Ok((image, hotspot))
+        }
+    }
+
+    /// 以 `background` 填充位图后绘制指针，返回自上而下的 BGRA 像素
+    unsafe fn draw_on(
+        memory: HDC,
+        target: HBITMAP,
+        icon: HICON,
+        width: i32,
+        height: i32,
+        background: ROP_CODE,
+    ) -> Result<Vec<u8>, MonitorError> {
+        unsafe {
+            let previous = SelectObject(memory, target.into());
+            let _ = PatBlt(memory, 0, 0, width, height, background);
+            let drawn = DrawIconEx(memory, 0, 0, icon, width, height, 0, None, DI_NORMAL);
+            // GetDIBits 要求位图未被选入设备上下文
+            SelectObject(memory, previous);
+            drawn.map_err(|e| MonitorError::Io(format!("DrawIconEx: {}", e)))?;
+
+            let mut info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    biHeight: -height,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut pixels = vec![0u8; width as usize * height as usize * 4];
+            let lines = GetDIBits(
+                memory,
+                target,
+                0,
+                height as u32,
+                Some(pixels.as_mut_ptr().cast()),
+                &mut info,
+                DIB_RGB_COLORS,
+            );
+            if lines == 0 {
+                return Err(MonitorError::Io("GetDIBits failed".to_string()));
+            }
+            Ok(pixels)
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::*;
+
+    pub fn locate(_frame_width: u32, _frame_height: u32) -> Result<Option<CursorPlacement>, MonitorError> {
+        Err(MonitorError::NotImplemented("Linux: implement XFixes cursor image capture"))
+    }
+}
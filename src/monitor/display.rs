@@ -0,0 +1,457 @@
+//! 显示器枚举：列出所有显示器在全局虚拟桌面中的位置与尺寸，
+//! 用于把"某显示器内的坐标"转换为鼠标操作使用的全局坐标。
+
+use super::screen::MonitorError;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayInfo {
+    /// 在 `list_displays()` 结果中的序号，主显示器恒为 0
+    pub index: usize,
+    /// 平台原生显示器 ID
+    pub id: u32,
+    /// 左上角在全局坐标系中的位置（与鼠标坐标同单位）
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// 物理像素 / 逻辑坐标的比例
+    pub scale: f64,
+    pub primary: bool,
+    /// 可用工作区（除去菜单栏/刘海、Dock、任务栏等保留区域），平台不支持时为 None
+    pub work_area: Option<Rect>,
+}
+
+/// 全局坐标系中的矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width as i32 && y < self.y + self.height as i32
+    }
+
+    /// 两个矩形的交集，不相交时返回 None
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width as i32).min(other.x + other.width as i32);
+        let bottom = (self.y + self.height as i32).min(other.y + other.height as i32);
+        (right > left && bottom > top).then(|| Rect {
+            x: left,
+            y: top,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        })
+    }
+
+    /// 将点限制到矩形内（含边界像素）
+    pub fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        let max_x = self.x + self.width.saturating_sub(1) as i32;
+        let max_y = self.y + self.height.saturating_sub(1) as i32;
+        (x.clamp(self.x, max_x.max(self.x)), y.clamp(self.y, max_y.max(self.y)))
+    }
+}
+
+impl DisplayInfo {
+    /// 显示器完整区域
+    pub fn bounds(&self) -> Rect {
+        Rect {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// 可用工作区；平台未提供时退化为完整区域
+    pub fn usable_area(&self) -> Rect {
+        self.work_area.unwrap_or_else(|| self.bounds())
+    }
+
+    /// 将显示器内坐标转换为全局坐标
+    pub fn to_global(&self, x: i32, y: i32) -> (i32, i32) {
+        (self.x + x, self.y + y)
+    }
+
+    /// 全局坐标是否落在该显示器内
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.bounds().contains(x, y)
+    }
+
+    /// 显示器在物理像素坐标系中的区域。
+    /// 物理坐标约定为：原点与逻辑坐标相同，显示器内偏移按 `scale` 放大。
+    pub fn physical_bounds(&self) -> Rect {
+        Rect {
+            x: self.x,
+            y: self.y,
+            width: (self.width as f64 * self.scale).round() as u32,
+            height: (self.height as f64 * self.scale).round() as u32,
+        }
+    }
+
+    /// 逻辑全局坐标 → 物理全局坐标
+    pub fn to_physical(&self, x: f64, y: f64) -> (i32, i32) {
+        (
+            self.x + ((x - self.x as f64) * self.scale).round() as i32,
+            self.y + ((y - self.y as f64) * self.scale).round() as i32,
+        )
+    }
+
+    /// 物理全局坐标 → 逻辑全局坐标
+    pub fn to_logical(&self, x: f64, y: f64) -> (i32, i32) {
+        let scale = if self.scale > 0.0 { self.scale } else { 1.0 };
+        (
+            self.x + ((x - self.x as f64) / scale).round() as i32,
+            self.y + ((y - self.y as f64) / scale).round() as i32,
+        )
+    }
+}
+
+/// 列出所有活动显示器，主显示器排在首位
+pub fn list_displays() -> Result<Vec<DisplayInfo>, MonitorError> {
+    let mut displays = platform::list_displays()?;
+    displays.sort_by_key(|d| (!d.primary, d.x, d.y));
+    for (i, d) in displays.iter_mut().enumerate() {
+        d.index = i;
+    }
+    Ok(displays)
+}
+
+/// 按序号获取显示器
+pub fn get_display(index: usize) -> Result<DisplayInfo, MonitorError> {
+    let displays = list_displays()?;
+    let count = displays.len();
+    displays
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| MonitorError::Io(format!("display {} not found ({} displays)", index, count)))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use core_graphics::display::CGDisplay;
+
+    pub fn list_displays() -> Result<Vec<DisplayInfo>, MonitorError> {
+        let ids = CGDisplay::active_displays()
+            .map_err(|e| MonitorError::Io(format!("CGGetActiveDisplayList failed: {}", e)))?;
+        let main_id = CGDisplay::main().id;
+        let work_areas = appkit::visible_frames();
+
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                let display = CGDisplay::new(id);
+                let bounds = display.bounds();
+                let scale = if bounds.size.width > 0.0 {
+                    display.pixels_wide() as f64 / bounds.size.width
+                } else {
+                    1.0
+                };
+                DisplayInfo {
+                    index: 0,
+                    id,
+                    x: bounds.origin.x as i32,
+                    y: bounds.origin.y as i32,
+                    width: bounds.size.width as u32,
+                    height: bounds.size.height as u32,
+                    scale,
+                    primary: id == main_id,
+                    work_area: work_areas.iter().find(|(sid, _)| *sid == id).map(|(_, r)| *r),
+                }
+            })
+            .collect())
+    }
+
+    /// 通过 Objective-C 运行时读取 NSScreen.visibleFrame。
+    /// visibleFrame 已排除菜单栏（刘海屏上菜单栏覆盖刘海高度）与 Dock。
+    mod appkit {
+        use super::Rect;
+        use core_foundation::base::TCFType;
+        use core_foundation::string::CFString;
+        use std::ffi::{c_char, c_void};
+
+        type Id = *mut c_void;
+        type Sel = *mut c_void;
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Default)]
+        struct NSRect {
+            x: f64,
+            y: f64,
+            width: f64,
+            height: f64,
+        }
+
+        #[link(name = "AppKit", kind = "framework")]
+        unsafe extern "C" {}
+
+        #[link(name = "objc")]
+        unsafe extern "C" {
+            fn objc_getClass(name: *const c_char) -> Id;
+            fn sel_registerName(name: *const c_char) -> Sel;
+            fn objc_msgSend();
+            #[cfg(target_arch = "x86_64")]
+            fn objc_msgSend_stret();
+            fn objc_autoreleasePoolPush() -> *mut c_void;
+            fn objc_autoreleasePoolPop(pool: *mut c_void);
+        }
+
+        unsafe fn sel(name: &[u8]) -> Sel {
+            unsafe { sel_registerName(name.as_ptr() as *const c_char) }
+        }
+
+        unsafe fn send_id(receiver: Id, selector: Sel) -> Id {
+            let f: unsafe extern "C" fn(Id, Sel) -> Id =
+                unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+            unsafe { f(receiver, selector) }
+        }
+
+        unsafe fn send_id_arg(receiver: Id, selector: Sel, arg: Id) -> Id {
+            let f: unsafe extern "C" fn(Id, Sel, Id) -> Id =
+                unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+            unsafe { f(receiver, selector, arg) }
+        }
+
+        unsafe fn send_index(receiver: Id, selector: Sel, index: usize) -> Id {
+            let f: unsafe extern "C" fn(Id, Sel, usize) -> Id =
+                unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+            unsafe { f(receiver, selector, index) }
+        }
+
+        unsafe fn send_usize(receiver: Id, selector: Sel) -> usize {
+            let f: unsafe extern "C" fn(Id, Sel) -> usize =
+                unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+            unsafe { f(receiver, selector) }
+        }
+
+        unsafe fn send_u32(receiver: Id, selector: Sel) -> u32 {
+            let f: unsafe extern "C" fn(Id, Sel) -> u32 =
+                unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+            unsafe { f(receiver, selector) }
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        unsafe fn send_rect(receiver: Id, selector: Sel) -> NSRect {
+            // x86_64 上大于 16 字节的结构体通过 stret 变体返回
+            let f: unsafe extern "C" fn(*mut NSRect, Id, Sel) =
+                unsafe { std::mem::transmute(objc_msgSend_stret as unsafe extern "C" fn()) };
+            let mut rect = NSRect::default();
+            unsafe { f(&mut rect, receiver, selector) };
+            rect
+        }
+
+        #[cfg(not(target_arch = "x86_64"))]
+        unsafe fn send_rect(receiver: Id, selector: Sel) -> NSRect {
+            let f: unsafe extern "C" fn(Id, Sel) -> NSRect =
+                unsafe { std::mem::transmute(objc_msgSend as unsafe extern "C" fn()) };
+            unsafe { f(receiver, selector) }
+        }
+
+        /// 返回 (CGDirectDisplayID, 全局坐标系中的 visibleFrame)
+        pub fn visible_frames() -> Vec<(u32, Rect)> {
+            let mut result = Vec::new();
+            unsafe {
+                let pool = objc_autoreleasePoolPush();
+                let class = objc_getClass(c"NSScreen".as_ptr());
+                if !class.is_null() {
+                    let screens = send_id(class, sel(b"screens\0"));
+                    let count = if screens.is_null() { 0 } else { send_usize(screens, sel(b"count\0")) };
+                    let key = CFString::new("NSScreenNumber");
+
+                    // Cocoa 坐标原点在主屏左下角，需翻转到左上角原点的全局坐标
+                    let mut primary_height = 0.0;
+                    for i in 0..count {
+                        let screen = send_index(screens, sel(b"objectAtIndex:\0"), i);
+                        let frame = send_rect(screen, sel(b"frame\0"));
+                        let visible = send_rect(screen, sel(b"visibleFrame\0"));
+                        if i == 0 {
+                            primary_height = frame.height;
+                        }
+                        let description = send_id(screen, sel(b"deviceDescription\0"));
+                        let number = send_id_arg(
+                            description,
+                            sel(b"objectForKey:\0"),
+                            key.as_concrete_TypeRef() as Id,
+                        );
+                        if number.is_null() {
+                            continue;
+                        }
+                        let display_id = send_u32(number, sel(b"unsignedIntValue\0"));
+                        result.push((
+                            display_id,
+                            Rect {
+                                x: visible.x.round() as i32,
+                                y: (primary_height - visible.y - visible.height).round() as i32,
+                                width: visible.width.round() as u32,
+                                height: visible.height.round() as u32,
+                            },
+                        ));
+                    }
+                }
+                objc_autoreleasePoolPop(pool);
+            }
+            result
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::randr::ConnectionExt as _;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _};
+
+    pub fn list_displays() -> Result<Vec<DisplayInfo>, MonitorError> {
+        let (conn, screen_num) = x11rb::connect(None)
+            .map_err(|e| MonitorError::Io(format!("X11 connect failed: {}", e)))?;
+        let root = conn.setup().roots[screen_num].root;
+        let reply = conn
+            .randr_get_monitors(root, true)
+            .map_err(|e| MonitorError::Io(e.to_string()))?
+            .reply()
+            .map_err(|e| MonitorError::Io(e.to_string()))?;
+        let work_area = net_work_area(&conn, root);
+
+        Ok(reply
+            .monitors
+            .iter()
+            .map(|m| {
+                let bounds = Rect {
+                    x: m.x as i32,
+                    y: m.y as i32,
+                    width: m.width as u32,
+                    height: m.height as u32,
+                };
+                DisplayInfo {
+                    index: 0,
+                    id: m.name,
+                    x: bounds.x,
+                    y: bounds.y,
+                    width: bounds.width,
+                    height: bounds.height,
+                    scale: 1.0,
+                    primary: m.primary,
+                    work_area: work_area.and_then(|w| w.intersect(&bounds)),
+                }
+            })
+            .collect())
+    }
+
+    /// 读取窗口管理器发布的 _NET_WORKAREA（当前桌面，覆盖整个虚拟屏幕）
+    fn net_work_area(conn: &impl Connection, root: u32) -> Option<Rect> {
+        let atom = conn
+            .intern_atom(false, b"_NET_WORKAREA")
+            .ok()?
+            .reply()
+            .ok()?
+            .atom;
+        let reply = conn
+            .get_property(false, root, atom, AtomEnum::CARDINAL, 0, 4)
+            .ok()?
+            .reply()
+            .ok()?;
+        let values: Vec<u32> = reply.value32()?.collect();
+        match values.as_slice() {
+            [x, y, w, h, ..] => Some(Rect {
+                x: *x as i32,
+                y: *y as i32,
+                width: *w,
+                height: *h,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::core::{BOOL, PCWSTR};
+    use windows::Win32::Foundation::{LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayMonitors, EnumDisplaySettingsW, GetMonitorInfoW, DEVMODEW, ENUM_CURRENT_SETTINGS, HDC, HMONITOR,
+        MONITORINFO, MONITORINFOEXW,
+    };
+
+    /// `MONITORINFOF_PRIMARY`
+    const MONITOR_PRIMARY: u32 = 1;
+
+    unsafe extern "system" fn collect(monitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, data: LPARAM) -> BOOL {
+        let monitors = unsafe { &mut *(data.0 as *mut Vec<HMONITOR>) };
+        monitors.push(monitor);
+        BOOL(1)
+    }
+
+    fn rect(r: &RECT) -> Rect {
+        Rect {
+            x: r.left,
+            y: r.top,
+            width: (r.right - r.left).max(0) as u32,
+            height: (r.bottom - r.top).max(0) as u32,
+        }
+    }
+
+    /// 坐标为本进程 DPI 感知上下文中的桌面坐标（与鼠标坐标一致）；
+    /// `scale` 取显示模式的实际像素宽度与该宽度之比
+    pub fn list_displays() -> Result<Vec<DisplayInfo>, MonitorError> {
+        let mut monitors: Vec<HMONITOR> = Vec::new();
+        let ok = unsafe { EnumDisplayMonitors(None, None, Some(collect), LPARAM(&mut monitors as *mut _ as isize)) };
+        if !ok.as_bool() {
+            return Err(MonitorError::Io("EnumDisplayMonitors failed".to_string()));
+        }
+
+        Ok(monitors
+            .into_iter()
+            .filter_map(|monitor| {
+                let mut info = MONITORINFOEXW::default();
+                info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+                if !unsafe { GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO) }.as_bool() {
+                    return None;
+                }
+                let bounds = rect(&info.monitorInfo.rcMonitor);
+                let mut mode = DEVMODEW { dmSize: std::mem::size_of::<DEVMODEW>() as u16, ..Default::default() };
+                let has_mode =
+                    unsafe { EnumDisplaySettingsW(PCWSTR(info.szDevice.as_ptr()), ENUM_CURRENT_SETTINGS, &mut mode) }
+                        .as_bool();
+                let scale = if has_mode && bounds.width > 0 && mode.dmPelsWidth > 0 {
+                    mode.dmPelsWidth as f64 / bounds.width as f64
+                } else {
+                    1.0
+                };
+                // 设备名形如 \\.\DISPLAY1，取末尾的编号作为 ID
+                let device = String::from_utf16_lossy(&info.szDevice);
+                let device = device.trim_end_matches('\0');
+                let digits = device.len() - device.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+                let id = device[device.len() - digits..].parse().unwrap_or(0);
+                Some(DisplayInfo {
+                    index: 0,
+                    id,
+                    x: bounds.x,
+                    y: bounds.y,
+                    width: bounds.width,
+                    height: bounds.height,
+                    scale,
+                    primary: info.monitorInfo.dwFlags & MONITOR_PRIMARY != 0,
+                    work_area: Some(rect(&info.monitorInfo.rcWork)),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    pub fn list_displays() -> Result<Vec<DisplayInfo>, MonitorError> {
+        Err(MonitorError::UnsupportedPlatform(std::env::consts::OS))
+    }
+}
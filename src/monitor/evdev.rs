@@ -0,0 +1,355 @@
+//! Linux evdev 输入监控后端：直接读取 `/dev/input/event*`，不依赖 X11。
+//!
+//! rdev 在 Linux 上通过 X11 RECORD 扩展监听，无 X11 的环境（Wayland 独占会话、服务器、
+//! 控制台）会失败，此时由本后端接管；设置 `IRIS_MONITOR_BACKEND=evdev` 可直接使用本后端。
+//!
+//! - 设备发现：按 `/sys/class/input/eventN/device/capabilities/ev` 选择带按键（EV_KEY）
+//!   或相对位移（EV_REL）能力的设备，每个设备一个读取线程
+//! - 权限诊断：读取设备需要属于 `input` 组（或 root），全部设备无权限时返回说明如何加入该组的错误
+//! - 热插拔：定期重新扫描设备目录，为新设备启动读取线程；设备移除时读取线程退出
+//!
+//! 事件转换为 rdev 事件后交给与 rdev 后端相同的回调，键名按 X11 键码（evdev 键码 + 8）映射，
+//! 与 rdev 后端输出一致。鼠标只上报相对位移，光标位置从 (0, 0) 起累加，仅反映相对移动轨迹。
+
+use super::key_mouse::MonitorError;
+use rdev::{Button, Event, EventType, Key};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// 重新扫描设备目录的间隔
+const HOTPLUG_SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+const INPUT_DIR: &str = "/dev/input";
+
+/// `struct input_event` 的大小：`struct timeval` + u16 type + u16 code + i32 value
+const INPUT_EVENT_SIZE: usize = 2 * std::mem::size_of::<std::os::raw::c_long>() + 8;
+
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const REL_HWHEEL: u16 = 0x06;
+const REL_WHEEL: u16 = 0x08;
+
+const BTN_LEFT: u16 = 0x110;
+const BTN_RIGHT: u16 = 0x111;
+const BTN_MIDDLE: u16 = 0x112;
+/// 鼠标与手柄按键的键码范围（BTN_MISC..KEY_OK）
+const BTN_RANGE: std::ops::Range<u16> = 0x100..0x160;
+
+/// 键盘按键 value：0 释放、1 按下、2 自动重复
+const KEY_RELEASE: i32 = 0;
+
+pub type Callback = Arc<dyn Fn(Event) + Send + Sync>;
+
+/// 监听所有输入设备并阻塞当前线程（负责热插拔扫描）。
+/// 首次扫描没有任何可读设备时返回错误。
+pub fn listen(callback: Callback) -> Result<(), MonitorError> {
+    let monitor = Arc::new(Monitor {
+        callback,
+        open: Mutex::new(HashSet::new()),
+        position: Mutex::new((0.0, 0.0)),
+    });
+
+    let scan = monitor.scan();
+    if scan.opened == 0 && monitor.open_count() == 0 {
+        return Err(if scan.denied > 0 {
+            MonitorError::PermissionDenied(permission_hint())
+        } else {
+            MonitorError::Io(format!("no keyboard or pointer devices found under {}", INPUT_DIR))
+        });
+    }
+    if scan.denied > 0 {
+        eprintln!("[evdev] {} device(s) not readable: {}", scan.denied, permission_hint());
+    }
+
+    loop {
+        thread::sleep(HOTPLUG_SCAN_INTERVAL);
+        monitor.scan();
+    }
+}
+
+struct Monitor {
+    callback: Callback,
+    /// 正在读取的设备路径
+    open: Mutex<HashSet<PathBuf>>,
+    /// 由相对位移累加的光标位置
+    position: Mutex<(f64, f64)>,
+}
+
+#[derive(Default)]
+struct ScanResult {
+    opened: usize,
+    denied: usize,
+}
+
+impl Monitor {
+    fn open_count(&self) -> usize {
+        self.open.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// 为尚未读取的输入设备启动读取线程
+    fn scan(self: &Arc<Self>) -> ScanResult {
+        let mut result = ScanResult::default();
+        let Ok(entries) = fs::read_dir(INPUT_DIR) else {
+            return result;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !name.starts_with("event") || !is_input_device(name) {
+                continue;
+            }
+            if self.open.lock().unwrap_or_else(|e| e.into_inner()).contains(&path) {
+                continue;
+            }
+            match File::open(&path) {
+                Ok(file) => {
+                    eprintln!("[evdev] reading {} ({})", path.display(), device_name(name));
+                    self.open.lock().unwrap_or_else(|e| e.into_inner()).insert(path.clone());
+                    let monitor = self.clone();
+                    let spawned = thread::Builder::new()
+                        .name(format!("evdev-{}", name))
+                        .spawn(move || monitor.read_device(path, file));
+                    if spawned.is_ok() {
+                        result.opened += 1;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => result.denied += 1,
+                Err(_) => {}
+            }
+        }
+        result
+    }
+
+    /// 读取设备事件直到设备移除或出错
+    fn read_device(&self, path: PathBuf, mut file: File) {
+        let mut buf = [0u8; INPUT_EVENT_SIZE];
+        loop {
+            if let Err(e) = file.read_exact(&mut buf) {
+                eprintln!("[evdev] {} closed: {}", path.display(), e);
+                break;
+            }
+            let header = INPUT_EVENT_SIZE - 8;
+            let kind = u16::from_ne_bytes([buf[header], buf[header + 1]]);
+            let code = u16::from_ne_bytes([buf[header + 2], buf[header + 3]]);
+            let value = i32::from_ne_bytes([buf[header + 4], buf[header + 5], buf[header + 6], buf[header + 7]]);
+            if let Some(event_type) = self.translate(kind, code, value) {
+                (self.callback)(Event {
+                    time: SystemTime::now(),
+                    name: None,
+                    event_type,
+                });
+            }
+        }
+        self.open.lock().unwrap_or_else(|e| e.into_inner()).remove(&path);
+    }
+
+    fn translate(&self, kind: u16, code: u16, value: i32) -> Option<EventType> {
+        match kind {
+            EV_KEY if BTN_RANGE.contains(&code) => {
+                let button = match code {
+                    BTN_LEFT => Button::Left,
+                    BTN_RIGHT => Button::Right,
+                    BTN_MIDDLE => Button::Middle,
+                    _ => Button::Unknown((code - BTN_RANGE.start) as u8),
+                };
+                Some(if value == KEY_RELEASE {
+                    EventType::ButtonRelease(button)
+                } else {
+                    EventType::ButtonPress(button)
+                })
+            }
+            EV_KEY => {
+                let key = key_from_code(code);
+                Some(if value == KEY_RELEASE {
+                    EventType::KeyRelease(key)
+                } else {
+                    EventType::KeyPress(key)
+                })
+            }
+            EV_REL => match code {
+                REL_X | REL_Y => {
+                    let mut position = self.position.lock().unwrap_or_else(|e| e.into_inner());
+                    if code == REL_X {
+                        position.0 = (position.0 + value as f64).max(0.0);
+                    } else {
+                        position.1 = (position.1 + value as f64).max(0.0);
+                    }
+                    Some(EventType::MouseMove { x: position.0, y: position.1 })
+                }
+                // 与 rdev 一致：向上/向右滚动为正
+                REL_WHEEL => Some(EventType::Wheel { delta_x: 0, delta_y: value as i64 }),
+                REL_HWHEEL => Some(EventType::Wheel { delta_x: value as i64, delta_y: 0 }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// 设备是否具有按键或相对位移能力
+fn is_input_device(event_name: &str) -> bool {
+    let path = Path::new("/sys/class/input").join(event_name).join("device/capabilities/ev");
+    let Ok(caps) = fs::read_to_string(path) else {
+        return false;
+    };
+    // 位掩码为以空格分隔的十六进制字，最低位的字在最后
+    let low = caps.split_whitespace().last().unwrap_or("0");
+    let bits = u64::from_str_radix(low, 16).unwrap_or(0);
+    bits & (1 << EV_KEY) != 0 || bits & (1 << EV_REL) != 0
+}
+
+fn device_name(event_name: &str) -> String {
+    fs::read_to_string(Path::new("/sys/class/input").join(event_name).join("device/name"))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// 无权限读取设备时的诊断说明
+fn permission_hint() -> &'static str {
+    if in_input_group() {
+        "user is in the `input` group but /dev/input/event* is not readable; log out and back in so the group membership takes effect"
+    } else {
+        "reading /dev/input/event* requires membership in the `input` group: run `sudo usermod -aG input $USER` and log in again"
+    }
+}
+
+/// 当前进程是否属于 `input` 组
+fn in_input_group() -> bool {
+    let Some(gid) = fs::read_to_string("/etc/group").ok().and_then(|groups| {
+        groups.lines().find_map(|line| {
+            let mut fields = line.split(':');
+            (fields.next() == Some("input"))
+                .then(|| fields.nth(1).and_then(|gid| gid.parse::<u32>().ok()))
+                .flatten()
+        })
+    }) else {
+        return false;
+    };
+    fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("Groups:").map(str::to_string))
+        })
+        .is_some_and(|groups| groups.split_whitespace().any(|g| g.parse() == Ok(gid)))
+}
+
+/// evdev 键码转换为 rdev 按键；与 rdev 的 X11 键码表一致（X11 键码 = evdev 键码 + 8）
+fn key_from_code(code: u16) -> Key {
+    match code {
+        1 => Key::Escape,
+        2 => Key::Num1,
+        3 => Key::Num2,
+        4 => Key::Num3,
+        5 => Key::Num4,
+        6 => Key::Num5,
+        7 => Key::Num6,
+        8 => Key::Num7,
+        9 => Key::Num8,
+        10 => Key::Num9,
+        11 => Key::Num0,
+        12 => Key::Minus,
+        13 => Key::Equal,
+        14 => Key::Backspace,
+        15 => Key::Tab,
+        16 => Key::KeyQ,
+        17 => Key::KeyW,
+        18 => Key::KeyE,
+        19 => Key::KeyR,
+        20 => Key::KeyT,
+        21 => Key::KeyY,
+        22 => Key::KeyU,
+        23 => Key::KeyI,
+        24 => Key::KeyO,
+        25 => Key::KeyP,
+        26 => Key::LeftBracket,
+        27 => Key::RightBracket,
+        28 => Key::Return,
+        29 => Key::ControlLeft,
+        30 => Key::KeyA,
+        31 => Key::KeyS,
+        32 => Key::KeyD,
+        33 => Key::KeyF,
+        34 => Key::KeyG,
+        35 => Key::KeyH,
+        36 => Key::KeyJ,
+        37 => Key::KeyK,
+        38 => Key::KeyL,
+        39 => Key::SemiColon,
+        40 => Key::Quote,
+        41 => Key::BackQuote,
+        42 => Key::ShiftLeft,
+        43 => Key::BackSlash,
+        44 => Key::KeyZ,
+        45 => Key::KeyX,
+        46 => Key::KeyC,
+        47 => Key::KeyV,
+        48 => Key::KeyB,
+        49 => Key::KeyN,
+        50 => Key::KeyM,
+        51 => Key::Comma,
+        52 => Key::Dot,
+        53 => Key::Slash,
+        54 => Key::ShiftRight,
+        55 => Key::KpMultiply,
+        56 => Key::Alt,
+        57 => Key::Space,
+        58 => Key::CapsLock,
+        59 => Key::F1,
+        60 => Key::F2,
+        61 => Key::F3,
+        62 => Key::F4,
+        63 => Key::F5,
+        64 => Key::F6,
+        65 => Key::F7,
+        66 => Key::F8,
+        67 => Key::F9,
+        68 => Key::F10,
+        69 => Key::NumLock,
+        70 => Key::ScrollLock,
+        71 => Key::Kp7,
+        72 => Key::Kp8,
+        73 => Key::Kp9,
+        74 => Key::KpMinus,
+        75 => Key::Kp4,
+        76 => Key::Kp5,
+        77 => Key::Kp6,
+        78 => Key::KpPlus,
+        79 => Key::Kp1,
+        80 => Key::Kp2,
+        81 => Key::Kp3,
+        82 => Key::Kp0,
+        83 => Key::KpDelete,
+        86 => Key::IntlBackslash,
+        87 => Key::F11,
+        88 => Key::F12,
+        96 => Key::KpReturn,
+        97 => Key::ControlRight,
+        98 => Key::KpDivide,
+        99 => Key::PrintScreen,
+        100 => Key::AltGr,
+        102 => Key::Home,
+        103 => Key::UpArrow,
+        104 => Key::PageUp,
+        105 => Key::LeftArrow,
+        106 => Key::RightArrow,
+        107 => Key::End,
+        108 => Key::DownArrow,
+        109 => Key::PageDown,
+        110 => Key::Insert,
+        111 => Key::Delete,
+        119 => Key::Pause,
+        125 => Key::MetaLeft,
+        _ => Key::Unknown(code as u32 + 8),
+    }
+}
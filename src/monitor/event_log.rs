@@ -0,0 +1,195 @@
+//! 键鼠事件的持久化 JSONL 日志，按文件大小轮转，供审计与历史查询。
+//!
+//! 通过环境变量开启（未设置 `IRIS_EVENT_LOG` 时不写日志）：
+//! - `IRIS_EVENT_LOG`：日志文件路径，如 `~/iris-events.jsonl`
+//! - `IRIS_EVENT_LOG_MAX_BYTES`：单个文件大小上限，默认 10 MiB
+//! - `IRIS_EVENT_LOG_MAX_FILES`：保留的轮转文件数（`<path>.1` 最新 … `<path>.N` 最旧），默认 5
+//!
+//! 每行一个事件：`{"device":"keyboard"|"mouse", ...事件字段}`。
+
+use super::key_mouse::{KeyEvent, MonitorError, MouseEvent, TimeRange};
+use serde::Serialize;
+use serde_json::Value;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 5;
+
+/// 日志中的一条事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "device", rename_all = "lowercase")]
+pub enum LogRecord {
+    Keyboard(KeyEvent),
+    Mouse(MouseEvent),
+}
+
+impl LogRecord {
+    /// 解析日志中的一行；内部标记枚举的派生反序列化不支持 u128 时间戳，因此按 `device` 字段手动分派
+    fn parse(line: &str) -> Option<Self> {
+        let mut value: Value = serde_json::from_str(line).ok()?;
+        let device = value.as_object_mut()?.remove("device")?;
+        match device.as_str()? {
+            "keyboard" => serde_json::from_value(value).ok().map(LogRecord::Keyboard),
+            "mouse" => serde_json::from_value(value).ok().map(LogRecord::Mouse),
+            _ => None,
+        }
+    }
+
+    pub fn timestamp_micros(&self) -> u128 {
+        match self {
+            LogRecord::Keyboard(e) => e.timestamp_micros,
+            LogRecord::Mouse(e) => e.timestamp_micros,
+        }
+    }
+}
+
+/// 写入时借用事件，避免复制
+#[derive(Serialize)]
+#[serde(tag = "device", rename_all = "lowercase")]
+pub(crate) enum LogRecordRef<'a> {
+    Keyboard(&'a KeyEvent),
+    Mouse(&'a MouseEvent),
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub path: PathBuf,
+    pub max_bytes: u64,
+    pub max_files: usize,
+}
+
+/// 环境变量中的日志配置；未开启时为 None
+pub fn config() -> Option<&'static LogConfig> {
+    static CONFIG: OnceLock<Option<LogConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let path = env::var_os("IRIS_EVENT_LOG").filter(|p| !p.is_empty())?;
+            let var = |name: &str| env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+            Some(LogConfig {
+                path: PathBuf::from(path),
+                max_bytes: var("IRIS_EVENT_LOG_MAX_BYTES").filter(|v| *v > 0).unwrap_or(DEFAULT_MAX_BYTES),
+                max_files: var("IRIS_EVENT_LOG_MAX_FILES").map_or(DEFAULT_MAX_FILES, |v| v as usize),
+            })
+        })
+        .as_ref()
+}
+
+/// 日志写入端，由监控的收集线程独占
+pub(crate) struct EventLog {
+    config: &'static LogConfig,
+    file: File,
+    size: u64,
+}
+
+impl EventLog {
+    /// 按配置打开日志；未开启或无法打开时返回 None（打开失败会记录到 stderr）
+    pub(crate) fn open() -> Option<Self> {
+        let config = config()?;
+        match open_append(config) {
+            Ok((file, size)) => {
+                eprintln!("[event_log] writing events to {}", config.path.display());
+                Some(EventLog { config, file, size })
+            }
+            Err(e) => {
+                eprintln!("[event_log] failed to open {}: {}", config.path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn append(&mut self, record: &LogRecordRef<'_>) {
+        let Ok(mut line) = serde_json::to_vec(record) else {
+            return;
+        };
+        line.push(b'\n');
+        if self.size > 0 && self.size + line.len() as u64 > self.config.max_bytes {
+            self.rotate();
+        }
+        match self.file.write_all(&line) {
+            Ok(()) => self.size += line.len() as u64,
+            Err(e) => eprintln!("[event_log] write failed: {}", e),
+        }
+    }
+
+    /// `<path>.N-1` → `<path>.N`，…，`<path>` → `<path>.1`，然后新建 `<path>`
+    fn rotate(&mut self) {
+        let config = self.config;
+        if config.max_files == 0 {
+            if let Err(e) = self.file.set_len(0) {
+                eprintln!("[event_log] truncate failed: {}", e);
+            }
+            self.size = 0;
+            return;
+        }
+        let _ = fs::remove_file(rotated_path(config, config.max_files));
+        for index in (1..config.max_files).rev() {
+            let _ = fs::rename(rotated_path(config, index), rotated_path(config, index + 1));
+        }
+        if let Err(e) = fs::rename(&config.path, rotated_path(config, 1)) {
+            eprintln!("[event_log] rotate failed: {}", e);
+        }
+        match open_append(config) {
+            Ok((file, size)) => {
+                self.file = file;
+                self.size = size;
+            }
+            Err(e) => eprintln!("[event_log] reopen failed: {}", e),
+        }
+    }
+}
+
+fn open_append(config: &LogConfig) -> std::io::Result<(File, u64)> {
+    if let Some(parent) = config.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&config.path)?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+fn rotated_path(config: &LogConfig, index: usize) -> PathBuf {
+    let mut name = config.path.clone().into_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// 按时间顺序（最旧的轮转文件在前）读取时间范围内且满足 `filter` 的事件，最多返回 `limit` 条。
+/// 返回的布尔值表示是否因达到上限而截断；无法解析的行（如正在写入的最后一行）被跳过。
+pub fn query(
+    range: TimeRange,
+    filter: impl Fn(&LogRecord) -> bool,
+    limit: usize,
+) -> Result<(Vec<LogRecord>, bool), MonitorError> {
+    let config = config().ok_or(MonitorError::NotImplemented(
+        "event log is disabled; set IRIS_EVENT_LOG to a file path to enable it",
+    ))?;
+
+    let mut records = Vec::new();
+    let files = (1..=config.max_files)
+        .rev()
+        .map(|index| rotated_path(config, index))
+        .chain(std::iter::once(config.path.clone()));
+    for path in files {
+        let Ok(file) = File::open(&path) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| MonitorError::Io(format!("{}: {}", path.display(), e)))?;
+            let Some(record) = LogRecord::parse(&line) else {
+                continue;
+            };
+            if !range.contains(record.timestamp_micros()) || !filter(&record) {
+                continue;
+            }
+            if records.len() >= limit {
+                return Ok((records, true));
+            }
+            records.push(record);
+        }
+    }
+    Ok((records, false))
+}
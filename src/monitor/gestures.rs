@@ -0,0 +1,251 @@
+//! 从原始鼠标事件识别高层手势：单击、双击、拖拽（起止点）与方向滑动。
+//!
+//! 按钮事件本身不带坐标，识别时以之前最近一次移动事件的位置作为按下/释放位置；
+//! 在此之前没有移动事件时位置未知，单击与拖拽的坐标为 None，无法判断拖拽距离时按单击处理。
+//! 滑动指未按住按钮时一段连续（相邻移动间隔不超过 `STROKE_GAP_MICROS`）、
+//! 快速且接近直线的移动。
+
+use super::key_mouse::{ButtonState, MouseButton, MouseEvent, MouseEventKind};
+use serde::Serialize;
+
+/// 按下与释放之间的位移超过该值（像素）时视为拖拽
+const DRAG_THRESHOLD_PX: f64 = 5.0;
+
+/// 两次单击间隔不超过该值（微秒）且位置接近时合并为双击
+const DOUBLE_CLICK_MICROS: u128 = 500_000;
+
+/// 双击两次单击之间允许的最大位移（像素）
+const DOUBLE_CLICK_DISTANCE_PX: f64 = 5.0;
+
+/// 相邻移动事件间隔超过该值（微秒）时开始新的笔画
+pub(crate) const STROKE_GAP_MICROS: u128 = 150_000;
+
+/// 滑动的最小位移（像素）
+const SWIPE_MIN_DISTANCE_PX: f64 = 200.0;
+
+/// 滑动的最长持续时间（微秒）
+const SWIPE_MAX_DURATION_MICROS: u128 = 500_000;
+
+/// 位移与路径长度之比不低于该值时视为直线
+const SWIPE_MIN_STRAIGHTNESS: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    fn distance(self, other: Point) -> f64 {
+        f64::from(other.x - self.x).hypot(f64::from(other.y - self.y))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// 识别出的手势；时间戳为 Unix 微秒
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Gesture {
+    Click {
+        button: MouseButton,
+        position: Option<Point>,
+        timestamp_micros: u128,
+        synthetic: bool,
+    },
+    DoubleClick {
+        button: MouseButton,
+        position: Option<Point>,
+        timestamp_micros: u128,
+        synthetic: bool,
+    },
+    Drag {
+        button: MouseButton,
+        start: Point,
+        end: Point,
+        start_micros: u128,
+        end_micros: u128,
+        synthetic: bool,
+    },
+    Swipe {
+        direction: SwipeDirection,
+        start: Point,
+        end: Point,
+        distance: f64,
+        start_micros: u128,
+        end_micros: u128,
+        synthetic: bool,
+    },
+}
+
+impl Gesture {
+    /// 手势结束的时间戳
+    pub fn end_micros(&self) -> u128 {
+        match self {
+            Gesture::Click { timestamp_micros, .. } | Gesture::DoubleClick { timestamp_micros, .. } => {
+                *timestamp_micros
+            }
+            Gesture::Drag { end_micros, .. } | Gesture::Swipe { end_micros, .. } => *end_micros,
+        }
+    }
+}
+
+/// 一个按住中的按钮
+struct PressedButton {
+    button: MouseButton,
+    position: Option<Point>,
+    timestamp_micros: u128,
+    synthetic: bool,
+}
+
+/// 一段连续的移动
+struct Stroke {
+    points: Vec<(Point, u128)>,
+    synthetic: bool,
+}
+
+impl Stroke {
+    /// 快速、足够长且接近直线的笔画识别为滑动
+    fn to_swipe(&self) -> Option<Gesture> {
+        let (&(start, start_micros), &(end, end_micros)) = (self.points.first()?, self.points.last()?);
+        let distance = start.distance(end);
+        if distance < SWIPE_MIN_DISTANCE_PX || end_micros.saturating_sub(start_micros) > SWIPE_MAX_DURATION_MICROS {
+            return None;
+        }
+        let path: f64 = self.points.windows(2).map(|w| w[0].0.distance(w[1].0)).sum();
+        if distance / path < SWIPE_MIN_STRAIGHTNESS {
+            return None;
+        }
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let direction = match (dx.abs() >= dy.abs(), dx > 0, dy > 0) {
+            (true, true, _) => SwipeDirection::Right,
+            (true, false, _) => SwipeDirection::Left,
+            (false, _, true) => SwipeDirection::Down,
+            (false, _, false) => SwipeDirection::Up,
+        };
+        Some(Gesture::Swipe {
+            direction,
+            start,
+            end,
+            distance: distance.round(),
+            start_micros,
+            end_micros,
+            synthetic: self.synthetic,
+        })
+    }
+}
+
+/// 按时间顺序识别事件中的手势，结果按结束时间排序。
+/// 序列末尾仍按住的按钮与未结束的笔画不产生手势。
+pub fn recognize<'a>(events: impl IntoIterator<Item = &'a MouseEvent>) -> Vec<Gesture> {
+    let mut gestures: Vec<Gesture> = Vec::new();
+    let mut position: Option<Point> = None;
+    let mut pressed: Vec<PressedButton> = Vec::new();
+    let mut stroke: Option<Stroke> = None;
+
+    for event in events {
+        match event.kind {
+            MouseEventKind::Move { x, y } => {
+                let point = Point { x, y };
+                position = Some(point);
+                if !pressed.is_empty() {
+                    continue;
+                }
+                match stroke.as_mut() {
+                    Some(s) if s.points.last().is_some_and(|(_, t)| event.timestamp_micros.saturating_sub(*t) <= STROKE_GAP_MICROS) => {
+                        s.points.push((point, event.timestamp_micros));
+                        s.synthetic &= event.synthetic;
+                    }
+                    _ => {
+                        if let Some(swipe) = stroke.take().and_then(|s| s.to_swipe()) {
+                            gestures.push(swipe);
+                        }
+                        stroke = Some(Stroke {
+                            points: vec![(point, event.timestamp_micros)],
+                            synthetic: event.synthetic,
+                        });
+                    }
+                }
+            }
+            MouseEventKind::Button { button, state: ButtonState::Press } => {
+                if let Some(swipe) = stroke.take().and_then(|s| s.to_swipe()) {
+                    gestures.push(swipe);
+                }
+                pressed.retain(|p| p.button != button);
+                pressed.push(PressedButton {
+                    button,
+                    position,
+                    timestamp_micros: event.timestamp_micros,
+                    synthetic: event.synthetic,
+                });
+            }
+            MouseEventKind::Button { button, state: ButtonState::Release } => {
+                let Some(index) = pressed.iter().position(|p| p.button == button) else {
+                    continue;
+                };
+                let press = pressed.remove(index);
+                let synthetic = press.synthetic && event.synthetic;
+                match (press.position, position) {
+                    (Some(start), Some(end)) if start.distance(end) > DRAG_THRESHOLD_PX => {
+                        gestures.push(Gesture::Drag {
+                            button,
+                            start,
+                            end,
+                            start_micros: press.timestamp_micros,
+                            end_micros: event.timestamp_micros,
+                            synthetic,
+                        });
+                    }
+                    _ => push_click(&mut gestures, button, press.position, event.timestamp_micros, synthetic),
+                }
+            }
+            MouseEventKind::Scroll { .. } => {}
+            // 缺失期间的释放事件与移动未知，丢弃进行中的按键与笔画
+            MouseEventKind::Gap { .. } => {
+                pressed.clear();
+                stroke = None;
+                position = None;
+            }
+        }
+    }
+    if let Some(swipe) = stroke.and_then(|s| s.to_swipe()) {
+        gestures.push(swipe);
+    }
+    gestures.sort_by_key(Gesture::end_micros);
+    gestures
+}
+
+/// 记录单击；与上一个同按钮的单击足够接近时合并为双击
+fn push_click(
+    gestures: &mut Vec<Gesture>,
+    button: MouseButton,
+    position: Option<Point>,
+    timestamp_micros: u128,
+    synthetic: bool,
+) {
+    if let Some(last) = gestures.last_mut()
+        && let Gesture::Click { button: last_button, position: last_position, timestamp_micros: last_micros, synthetic: last_synthetic } = *last
+        && last_button == button
+        && timestamp_micros.saturating_sub(last_micros) <= DOUBLE_CLICK_MICROS
+        && match (last_position, position) {
+            (Some(a), Some(b)) => a.distance(b) <= DOUBLE_CLICK_DISTANCE_PX,
+            _ => true,
+        }
+    {
+        *last = Gesture::DoubleClick {
+            button,
+            position: last_position.or(position),
+            timestamp_micros,
+            synthetic: last_synthetic && synthetic,
+        };
+        return;
+    }
+    gestures.push(Gesture::Click { button, position, timestamp_micros, synthetic });
+}
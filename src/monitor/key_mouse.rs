@@ -1,42 +1,88 @@
 //! 跨平台键盘和鼠标监控实现（使用 rdev 事件驱动）
 //! 基于操作系统原生事件机制，零 CPU 占用
 //! 
-//! 启动时自动开始监控，将事件存储在 FIFO 队列中。
-//! MCP 协议调用时返回存储的事件并清空队列。
+//! 启动时自动开始监控（`--no-monitor` 或 `IRIS_MONITOR_AUTOSTART=0` 关闭），将事件存储在 FIFO 队列中。
+//! MCP 协议调用时返回存储的事件并清空队列。可通过 `monitor_control` 暂停、恢复、停止与重新启动；
+//! rdev 无法卸载已安装的系统钩子，暂停与停止时监听线程保持运行但丢弃所有事件，停止时释放监听器锁。
+//!
+//! 监听回调只通过有界通道非阻塞地投递事件，由独立的收集线程写入队列，
+//! 因此请求处理中的锁竞争不会拖慢系统输入钩子（macOS 上阻塞钩子会冻结全局输入）。
+//!
+//! 各平台的监听后端由 rdev 提供：macOS 使用 CGEventTap，Linux 使用 X11 RECORD，
+//! Windows 使用 WH_KEYBOARD_LL / WH_MOUSE_LL 低级钩子，钩子安装在监听线程上并由该线程的消息循环驱动。
+//! 按住键时系统自动重复产生的按下事件（Windows 上为重复的 WM_KEYDOWN）记为 `Repeat`。
+//! 按住 Control / Alt / Meta（或按住 Shift 按非字符键）时按下其他键，会在该按下事件之后
+//! 额外记录一条 `Combo` 事件，`key` 为组合名称（如 `Control+C`、`Shift+Meta+4`）。
+//!
+//! 监听后端出错退出（如运行中被撤销辅助功能权限）时，监听线程按退避间隔重新启动后端，
+//! 恢复后在键盘与鼠标事件中各插入一条 `monitoring_gap` 标记，表示此前一段时间的事件缺失。
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fmt;
-use std::sync::{Arc, Mutex, OnceLock};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, OnceLock, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
 use std::thread;
 use std::env;
+use std::time::{Duration, Instant};
 use std::fs;
 use std::path::PathBuf;
+use super::event_log::{EventLog, LogRecordRef};
+use super::privacy;
+#[cfg(target_os = "windows")]
+use super::scaling;
+use crate::operator::window::{self, WindowError};
+use crate::operator::{keys, source};
 use rdev::{listen, Event, EventType};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 // ============================================================
 // 键盘事件类型定义
 // ============================================================
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KeyEventType {
     Press,
+    /// 按住期间系统自动重复产生的按下事件
+    Repeat,
     Release,
+    /// 由按住的修饰键与随后按下的键合成的组合键，`key` 为组合名称
+    Combo,
+    /// 监听器中断后恢复时插入的标记，`since_micros` 至本事件时间戳之间的事件缺失；`key` 为 `monitoring_gap`
+    Gap { since_micros: u128 },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// 事件发生时拥有焦点的窗口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowContext {
+    pub app: Option<String>,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct KeyEvent {
+    /// 物理按键名称，与布局和修饰键无关
     pub key: String,
+    /// 按当前键盘布局与修饰键状态解析出的输入字符（如 Shift+Semicolon 为 `:`）；
+    /// 释放事件、不产生字符的键以及 evdev 后端为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub char: Option<String>,
     pub event_type: KeyEventType,
     pub timestamp_micros: u128,
+    /// 单调时钟时间戳（自监控初始化起的微秒数），不受系统时间调整影响，用于计算事件间隔
+    #[serde(default)]
+    pub monotonic_micros: u64,
+    /// 是否发生在 iris-mcp 注入输入期间（见 `operator::source`）
+    pub synthetic: bool,
+    /// 事件发生时的前台窗口；未开启或无法查询时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowContext>,
 }
 
 // ============================================================
 // 鼠标事件类型定义
 // ============================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MouseButton {
     Left,
     Middle,
@@ -44,23 +90,57 @@ pub enum MouseButton {
     Other(u8),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ButtonState {
     Press,
     Release,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+/// 滚动增量的单位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollUnit {
+    /// 滚轮格数（Windows / Linux）
+    #[default]
+    Lines,
+    /// 像素（macOS）
+    Pixels,
+}
+
+/// macOS 像素滚动增量换算为行时每行的像素数
+pub const PIXELS_PER_SCROLL_LINE: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MouseEventKind {
     Move { x: i32, y: i32 },
     Button { button: MouseButton, state: ButtonState },
-    Scroll { delta_x: i32, delta_y: i32 },
+    /// 滚动增量统一换算为行（滚轮一格），向上 / 向右为正；后端报告的原始值保留在 `raw_*`
+    Scroll {
+        delta_x: f64,
+        delta_y: f64,
+        #[serde(default)]
+        raw_delta_x: i32,
+        #[serde(default)]
+        raw_delta_y: i32,
+        #[serde(default)]
+        raw_unit: ScrollUnit,
+    },
+    /// 监听器中断后恢复时插入的标记，`since_micros` 至本事件时间戳之间的事件缺失
+    Gap { since_micros: u128 },
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MouseEvent {
     pub kind: MouseEventKind,
     pub timestamp_micros: u128,
+    /// 单调时钟时间戳（自监控初始化起的微秒数），不受系统时间调整影响，用于计算事件间隔
+    #[serde(default)]
+    pub monotonic_micros: u64,
+    /// 是否发生在 iris-mcp 注入输入期间（见 `operator::source`）
+    pub synthetic: bool,
+    /// 事件发生时的前台窗口；未开启或无法查询时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowContext>,
 }
 
 // ============================================================
@@ -92,62 +172,663 @@ impl std::error::Error for MonitorError {}
 // 配置常量
 // ============================================================
 
-/// 最大存储的键盘事件数量
-const MAX_KEYBOARD_EVENTS: usize = 100;
+/// 默认存储的键盘事件数量（环境变量 IRIS_KEYBOARD_BUFFER_SIZE 覆盖）
+const DEFAULT_KEYBOARD_EVENTS: usize = 100;
+
+/// 默认存储的鼠标事件数量（环境变量 IRIS_MOUSE_BUFFER_SIZE 覆盖）
+const DEFAULT_MOUSE_EVENTS: usize = 200;
+
+/// 事件缓冲区容量上限
+pub const MAX_EVENT_BUFFER_CAPACITY: usize = 100_000;
 
-/// 最大存储的鼠标事件数量
-const MAX_MOUSE_EVENTS: usize = 200;
+/// 监听回调与收集线程之间通道的容量；通道满时新事件被丢弃而不是阻塞回调
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
 
-/// 鼠标移动采样默认间隔（微秒）。
+/// `fixed` 策略下鼠标移动的默认采样间隔（微秒）
 const DEFAULT_MOUSE_MOVE_INTERVAL_MICROS: u128 = 2_000; // 2ms
 
+/// `adaptive` 策略下未按住按钮时的默认采样间隔（微秒）
+const DEFAULT_MOUSE_HOVER_INTERVAL_MICROS: u128 = 16_000; // 16ms
+
+/// `adaptive` 策略下按住按钮拖拽时的默认采样间隔（微秒）
+const DEFAULT_MOUSE_DRAG_INTERVAL_MICROS: u128 = 1_000; // 1ms
+
+/// 监听后端退出后首次重启前的等待时间，之后每次失败翻倍
+const LISTENER_RESTART_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// 重启等待时间的上限
+const LISTENER_RESTART_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 后端连续运行超过该时长后退出，视为新的故障，退避重新从初始值开始
+const LISTENER_HEALTHY_RUN: Duration = Duration::from_secs(60);
+
+/// 缺失标记事件的键名
+pub const MONITORING_GAP_KEY: &str = "monitoring_gap";
+
 // ============================================================
 // 事件存储
 // ============================================================
 
+/// 收集线程写入存储的事件
+enum CapturedEvent {
+    Keyboard(KeyEvent),
+    Mouse(MouseEvent),
+    /// 监听器恢复后的首个回调，存储时展开为键盘与鼠标各一条缺失标记
+    Gap { since_micros: u128, timestamp_micros: u128, monotonic_micros: u64 },
+}
+
+/// 监听回调产生、经通道送往收集线程的事件。
+/// Windows 上的移动坐标换算需要读取带锁的显示器几何缓存（过期时还会重新枚举显示器），
+/// 因此回调只传递原始坐标，由收集线程换算，保证系统输入钩子上不获取任何锁
+enum HookEvent {
+    Captured(CapturedEvent),
+    Move { x: f64, y: f64, timestamp_micros: u128, monotonic_micros: u64, synthetic: bool },
+}
+
+impl HookEvent {
+    fn resolve(self) -> CapturedEvent {
+        match self {
+            HookEvent::Captured(event) => event,
+            HookEvent::Move { x, y, timestamp_micros, monotonic_micros, synthetic } => {
+                // Windows 的低级鼠标钩子总是报告物理像素，换算为鼠标操作使用的逻辑坐标，便于直接回放；
+                // macOS 报告的已是逻辑点，X11 与 evdev 不区分逻辑与物理坐标，均无需换算
+                #[cfg(target_os = "windows")]
+                let (x, y) = scaling::physical_to_logical(x, y);
+                #[cfg(not(target_os = "windows"))]
+                let (x, y) = (x.round() as i32, y.round() as i32);
+                CapturedEvent::Mouse(MouseEvent {
+                    kind: MouseEventKind::Move { x, y },
+                    timestamp_micros,
+                    monotonic_micros,
+                    synthetic,
+                    window: None,
+                })
+            }
+        }
+    }
+}
+
+/// 交给事件监听函数的事件
+#[derive(Debug, Clone, Copy)]
+pub enum MonitorEvent<'a> {
+    Keyboard(&'a KeyEvent),
+    Mouse(&'a MouseEvent),
+}
+
+type EventListener = Box<dyn Fn(MonitorEvent<'_>) + Send + Sync>;
+
+/// 事件监听函数，在收集线程上对每个存储的事件调用（不在系统输入钩子上运行）
+static EVENT_LISTENERS: RwLock<Vec<EventListener>> = RwLock::new(Vec::new());
+
+/// 注册事件监听函数（注册后不可移除，由调用方自行决定是否处理事件），并确保监听器已启动
+pub fn add_event_listener(listener: impl Fn(MonitorEvent<'_>) + Send + Sync + 'static) {
+    EVENT_LISTENERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Box::new(listener));
+    initialize();
+}
+
+/// 组合名称中修饰键的顺序
+const MODIFIER_ORDER: [&str; 4] = ["Control", "Alt", "Shift", "Meta"];
+
+/// 按键名称对应的修饰键（不区分左右）；AltGr 用于输入字符，不视为修饰键
+pub(crate) fn modifier_of(key: &str) -> Option<&'static str> {
+    match key {
+        "Control" | "LeftControl" | "RightControl" => Some("Control"),
+        "Alt" | "LeftAlt" | "RightAlt" => Some("Alt"),
+        "Shift" | "LeftShift" | "RightShift" => Some("Shift"),
+        "Meta" | "LeftMeta" | "RightMeta" => Some("Meta"),
+        _ => None,
+    }
+}
+
+/// 按下后产生字符的键；只按住 Shift 时按这些键属于正常输入，不记为组合键
+fn produces_text(key: &str) -> bool {
+    key.chars().count() == 1
+        || (key.starts_with("Numpad") && key != "NumpadEnter")
+        || matches!(
+            key,
+            "Space" | "Grave" | "Minus" | "Equal" | "LeftBracket" | "RightBracket" | "BackSlash"
+                | "Semicolon" | "Apostrophe" | "Comma" | "Dot" | "Slash"
+        )
+}
+
+/// 根据按住的键与新按下的键生成组合名称；不构成组合键时返回 None
+fn combo_for(held: &BTreeSet<String>, key: &str) -> Option<String> {
+    let modifiers: Vec<&str> = MODIFIER_ORDER
+        .into_iter()
+        .filter(|m| held.iter().any(|k| modifier_of(k) == Some(*m)))
+        .collect();
+    if modifiers.is_empty() || matches!(key, "AltGr" | "Fn") || (modifiers == ["Shift"] && produces_text(key)) {
+        return None;
+    }
+    Some(format!("{}+{}", modifiers.join("+"), key))
+}
+
+/// 把任意写法的组合名称（如 `ctrl+shift+4`、`cmd+c`）转换为 `Combo` 事件使用的形式
+pub fn canonical_combo(name: &str) -> Option<String> {
+    let mut modifiers = Vec::new();
+    let mut key = None;
+    for part in name.split('+').map(str::trim) {
+        if part.is_empty() {
+            return None;
+        }
+        let canonical = keys::canonical_name(part);
+        match canonical.and_then(modifier_of) {
+            Some(modifier) => modifiers.push(modifier),
+            None if key.is_none() => {
+                key = Some(match canonical {
+                    Some(canonical) => canonical.to_string(),
+                    None => part.to_uppercase(),
+                })
+            }
+            None => return None,
+        }
+    }
+    let key = key?;
+    let modifiers: Vec<&str> = MODIFIER_ORDER.into_iter().filter(|m| modifiers.contains(m)).collect();
+    if modifiers.is_empty() {
+        return None;
+    }
+    Some(format!("{}+{}", modifiers.join("+"), key))
+}
+
+/// 前台窗口缓存的有效期（毫秒）：查询需要一次窗口系统往返，不逐事件查询，
+/// 因此焦点切换后这段时间内的事件仍可能记为切换前的窗口
+const FOCUS_REFRESH_MS: u64 = 250;
+
+/// 收集线程为事件附加前台窗口。设置 `IRIS_MONITOR_WINDOW_CONTEXT=0` 关闭；
+/// 平台不支持查询时自动关闭
+struct FocusTracker {
+    enabled: bool,
+    fetched_at: Option<Instant>,
+    context: Option<WindowContext>,
+}
+
+impl FocusTracker {
+    fn new() -> Self {
+        let enabled = env::var("IRIS_MONITOR_WINDOW_CONTEXT")
+            .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "off"))
+            .unwrap_or(true);
+        FocusTracker { enabled, fetched_at: None, context: None }
+    }
+
+    fn current(&mut self) -> Option<WindowContext> {
+        if !self.enabled {
+            return None;
+        }
+        if self
+            .fetched_at
+            .is_none_or(|t| t.elapsed() >= Duration::from_millis(FOCUS_REFRESH_MS))
+        {
+            self.context = match window::focused_window() {
+                Ok(info) => Some(WindowContext { app: info.app, title: info.title }),
+                Err(WindowError::NotImplemented(_) | WindowError::UnsupportedPlatform(_)) => {
+                    self.enabled = false;
+                    None
+                }
+                Err(_) => None,
+            };
+            self.fetched_at = Some(Instant::now());
+        }
+        self.context.clone()
+    }
+}
+
+/// 获取锁；若持锁线程曾 panic，沿用其中的数据继续工作，避免一次 panic 让监控永久失效
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 读取事件的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadMode {
+    /// 返回默认读取位置之后的事件并前移读取位置（兼容旧的"读取即清空"语义）
+    Take,
+    /// 返回默认读取位置之后的事件，不前移读取位置
+    Peek,
+    /// 返回序号不小于给定游标的事件，不影响默认读取位置
+    From(u64),
+}
+
+/// 按事件时间戳（微秒）筛选，两端均包含；None 表示不限
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub since_micros: Option<u128>,
+    pub until_micros: Option<u128>,
+}
+
+impl TimeRange {
+    pub fn is_unbounded(&self) -> bool {
+        self.since_micros.is_none() && self.until_micros.is_none()
+    }
+
+    pub(crate) fn contains(&self, timestamp: u128) -> bool {
+        self.since_micros.is_none_or(|since| timestamp >= since)
+            && self.until_micros.is_none_or(|until| timestamp <= until)
+    }
+}
+
+/// 带时间戳的监控事件
+pub trait Timestamped {
+    fn timestamp_micros(&self) -> u128;
+}
+
+impl Timestamped for KeyEvent {
+    fn timestamp_micros(&self) -> u128 {
+        self.timestamp_micros
+    }
+}
+
+impl Timestamped for MouseEvent {
+    fn timestamp_micros(&self) -> u128 {
+        self.timestamp_micros
+    }
+}
+
+/// 缓冲区中事件覆盖的时间范围（Unix 微秒，含两端）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    pub since_micros: u128,
+    pub until_micros: u128,
+}
+
+/// 一次读取的结果
+#[derive(Debug, Clone)]
+pub struct EventBatch<T> {
+    /// 事件及其序号
+    pub events: Vec<(u64, T)>,
+    /// 下次增量读取使用的游标
+    pub next_cursor: u64,
+    /// 游标指向的事件已被环形缓冲区覆盖而丢失的数量
+    pub missed: u64,
+    /// 监控启动以来因缓冲区已满被覆盖（含缩小容量时丢弃）的事件总数
+    pub evicted_count: u64,
+    /// 监控启动以来因投递通道已满而丢弃的键鼠事件总数（键盘与鼠标共用）
+    pub dropped_count: u64,
+    /// 缓冲区当前覆盖的时间范围（最旧、最新事件的时间戳）；缓冲区为空时为 None
+    pub covered: Option<Coverage>,
+}
+
+/// 每个缓冲区最多登记的消费者数
+pub const MAX_EVENT_CONSUMERS: usize = 16;
+
+/// 持久环形缓冲区：读取不会删除事件，容量满时覆盖最旧的事件。
+/// 每个事件有单调递增的序号，供游标读取；`drained` 记录 `ReadMode::Take` 的读取位置。
+/// 具名消费者各自保存已确认的位置，确认后可裁剪所有消费者都已确认的事件。
+struct EventRing<T> {
+    events: VecDeque<T>,
+    capacity: usize,
+    next_seq: u64,
+    drained: u64,
+    /// 因容量已满被覆盖或缩小容量时丢弃的事件数
+    evicted: u64,
+    /// 消费者名称 → 已确认的位置（下一个未确认事件的序号）
+    consumers: BTreeMap<String, u64>,
+}
+
+impl<T: Clone + Timestamped> EventRing<T> {
+    fn new(capacity: usize) -> Self {
+        EventRing {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+            next_seq: 0,
+            drained: 0,
+            evicted: 0,
+            consumers: BTreeMap::new(),
+        }
+    }
+
+    /// 消费者的已确认位置；首次出现的消费者从缓冲区中最旧的事件开始。
+    /// 消费者数已达上限时返回 None
+    fn consumer_cursor(&mut self, name: &str) -> Option<u64> {
+        if let Some(cursor) = self.consumers.get(name) {
+            return Some(*cursor);
+        }
+        if self.consumers.len() >= MAX_EVENT_CONSUMERS {
+            return None;
+        }
+        let cursor = self.oldest_seq();
+        self.consumers.insert(name.to_string(), cursor);
+        Some(cursor)
+    }
+
+    /// 确认消费者已处理到 `cursor`（不含）为止的事件；位置只前进不后退
+    fn ack(&mut self, name: &str, cursor: u64) -> Option<u64> {
+        let oldest = self.oldest_seq();
+        let next_seq = self.next_seq;
+        if !self.consumers.contains_key(name) && self.consumers.len() >= MAX_EVENT_CONSUMERS {
+            return None;
+        }
+        let acked = self.consumers.entry(name.to_string()).or_insert(oldest);
+        *acked = (*acked).max(cursor.min(next_seq));
+        Some(*acked)
+    }
+
+    /// 丢弃所有消费者都已确认的事件，返回丢弃的数量
+    fn trim(&mut self) -> usize {
+        let Some(min_acked) = self.consumers.values().min().copied() else {
+            return 0;
+        };
+        let count = min_acked.saturating_sub(self.oldest_seq()).min(self.events.len() as u64) as usize;
+        self.events.drain(..count);
+        count
+    }
+
+    fn push(&mut self, event: T) {
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+            self.evicted += 1;
+        }
+        self.events.push_back(event);
+        self.next_seq += 1;
+    }
+
+    /// 丢弃全部事件；序号继续递增，默认读取位置与消费者位置移到末尾
+    fn clear(&mut self) -> usize {
+        let count = self.events.len();
+        self.events.clear();
+        self.drained = self.next_seq;
+        for cursor in self.consumers.values_mut() {
+            *cursor = self.next_seq;
+        }
+        count
+    }
+
+    /// 调整容量；缩小时丢弃最旧的事件
+    fn resize(&mut self, capacity: usize) {
+        while self.events.len() > capacity {
+            self.events.pop_front();
+            self.evicted += 1;
+        }
+        self.capacity = capacity;
+        self.events.shrink_to(capacity);
+    }
+
+    fn stats(&self) -> BufferStats {
+        BufferStats {
+            capacity: self.capacity,
+            occupancy: self.events.len(),
+            next_cursor: self.next_seq,
+            evicted_count: self.evicted,
+            covered: self.covered(),
+            consumers: self.consumers.clone(),
+        }
+    }
+
+    /// 缓冲区中最旧与最新事件的时间戳
+    fn covered(&self) -> Option<Coverage> {
+        Some(Coverage {
+            since_micros: self.events.front()?.timestamp_micros(),
+            until_micros: self.events.back()?.timestamp_micros(),
+        })
+    }
+
+    fn oldest_seq(&self) -> u64 {
+        self.next_seq - self.events.len() as u64
+    }
+
+    fn read(&mut self, mode: ReadMode, range: TimeRange) -> EventBatch<T> {
+        let from = match mode {
+            ReadMode::Take | ReadMode::Peek => self.drained,
+            ReadMode::From(cursor) => cursor.min(self.next_seq),
+        };
+        let start = from.max(self.oldest_seq());
+        let batch = EventBatch {
+            events: self
+                .events
+                .iter()
+                .zip(self.oldest_seq()..)
+                .skip((start - self.oldest_seq()) as usize)
+                .filter(|(event, _)| range.contains(event.timestamp_micros()))
+                .map(|(event, seq)| (seq, event.clone()))
+                .collect(),
+            next_cursor: self.next_seq,
+            missed: start - from,
+            evicted_count: self.evicted,
+            dropped_count: 0,
+            covered: self.covered(),
+        };
+        if mode == ReadMode::Take {
+            self.drained = self.next_seq;
+        }
+        batch
+    }
+}
+
+/// 单个事件缓冲区的容量与占用
+#[derive(Debug, Clone, Serialize)]
+pub struct BufferStats {
+    pub capacity: usize,
+    pub occupancy: usize,
+    /// 下一个事件的序号
+    pub next_cursor: u64,
+    /// 因缓冲区已满被覆盖的事件总数
+    pub evicted_count: u64,
+    /// 缓冲区当前覆盖的时间范围（最旧、最新事件的时间戳）
+    pub covered: Option<Coverage>,
+    /// 具名消费者的已确认位置
+    pub consumers: BTreeMap<String, u64>,
+}
+
+/// 键盘与鼠标事件缓冲区的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct BufferReport {
+    pub keyboard: BufferStats,
+    pub mouse: BufferStats,
+    /// 因投递通道已满而丢弃的键鼠事件总数
+    pub dropped_count: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferKind {
+    Keyboard,
+    Mouse,
+}
+
+/// 通过命令行或工具设置的容量，优先于环境变量
+static CAPACITY_OVERRIDES: Mutex<(Option<usize>, Option<usize>)> = Mutex::new((None, None));
+
+/// 缓冲区初始容量：命令行/工具设置 > 环境变量 > 默认值
+fn buffer_capacity(kind: BufferKind) -> usize {
+    let overrides = *lock(&CAPACITY_OVERRIDES);
+    let (configured, var, default) = match kind {
+        BufferKind::Keyboard => (overrides.0, "IRIS_KEYBOARD_BUFFER_SIZE", DEFAULT_KEYBOARD_EVENTS),
+        BufferKind::Mouse => (overrides.1, "IRIS_MOUSE_BUFFER_SIZE", DEFAULT_MOUSE_EVENTS),
+    };
+    configured
+        .or_else(|| env::var(var).ok().and_then(|v| v.trim().parse::<usize>().ok()))
+        .filter(|v| *v > 0)
+        .unwrap_or(default)
+        .min(MAX_EVENT_BUFFER_CAPACITY)
+}
+
+/// 事件队列仅由收集线程写入、请求处理线程读取；
+/// 系统输入回调线程从不接触这里的锁，锁竞争或中毒都不会阻塞系统输入。
 struct EventStorage {
-    keyboard_events: Arc<Mutex<VecDeque<KeyEvent>>>,
-    mouse_events: Arc<Mutex<VecDeque<MouseEvent>>>,
+    keyboard_events: Mutex<EventRing<KeyEvent>>,
+    mouse_events: Mutex<EventRing<MouseEvent>>,
+    /// 当前按下的键（按名称），由按下/释放事件维护
+    held_keys: Mutex<BTreeSet<String>>,
+    /// 当前按下的鼠标按键及按下时的事件，由按键事件维护
+    held_buttons: Mutex<Vec<HeldButton>>,
+    /// 最近一次鼠标移动的位置（逻辑坐标）；尚无移动事件时为 None
+    cursor: Mutex<Option<(i32, i32)>>,
+    /// 最近一次事件的时间戳（Unix 微秒）；尚无事件时为监控创建时间
+    last_event_micros: AtomicU64,
+    /// 最近一次非注入（真人）事件的时间戳
+    last_human_event_micros: AtomicU64,
+    /// 最近一次写入的键盘 / 鼠标事件的时间戳（Unix 微秒）；尚无事件时为 0
+    last_keyboard_micros: AtomicU64,
+    last_mouse_micros: AtomicU64,
 }
 
 impl EventStorage {
     fn new() -> Self {
         EventStorage {
-            keyboard_events: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_KEYBOARD_EVENTS))),
-            mouse_events: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_MOUSE_EVENTS))),
+            keyboard_events: Mutex::new(EventRing::new(buffer_capacity(BufferKind::Keyboard))),
+            mouse_events: Mutex::new(EventRing::new(buffer_capacity(BufferKind::Mouse))),
+            held_keys: Mutex::new(BTreeSet::new()),
+            held_buttons: Mutex::new(Vec::new()),
+            cursor: Mutex::new(None),
+            last_event_micros: AtomicU64::new(now_micros()),
+            last_human_event_micros: AtomicU64::new(now_micros()),
+            last_keyboard_micros: AtomicU64::new(0),
+            last_mouse_micros: AtomicU64::new(0),
         }
     }
-    
-    /// 添加键盘事件，超过容量时移除最旧的事件
-    fn push_keyboard_event(&self, event: KeyEvent) {
-        let mut queue = self.keyboard_events.lock().unwrap();
-        if queue.len() >= MAX_KEYBOARD_EVENTS {
-            queue.pop_front();
+
+    /// 记录输入活动时间，供空闲时间查询
+    fn touch(&self, timestamp_micros: u128, synthetic: bool) {
+        let timestamp = timestamp_micros as u64;
+        self.last_event_micros.fetch_max(timestamp, Ordering::Relaxed);
+        if !synthetic {
+            self.last_human_event_micros.fetch_max(timestamp, Ordering::Relaxed);
         }
-        queue.push_back(event);
     }
-    
-    /// 添加鼠标事件，超过容量时移除最旧的事件
-    fn push_mouse_event(&self, event: MouseEvent) {
-        let mut queue = self.mouse_events.lock().unwrap();
-        if queue.len() >= MAX_MOUSE_EVENTS {
-            queue.pop_front();
+
+    /// 存储事件；开启持久化日志时同时追加到日志，并交给已注册的事件监听函数
+    fn push(&self, event: CapturedEvent, mut log: Option<&mut EventLog>) {
+        let listeners = EVENT_LISTENERS.read().unwrap_or_else(|e| e.into_inner());
+        match event {
+            CapturedEvent::Keyboard(e) => {
+                if privacy::policy() == privacy::KeyboardPrivacy::Disabled {
+                    // 关闭期间看不到释放事件，清空按下的键以免重新开启后残留
+                    lock(&self.held_keys).clear();
+                    return;
+                }
+                self.touch(e.timestamp_micros, e.synthetic);
+                self.last_keyboard_micros.fetch_max(e.timestamp_micros as u64, Ordering::Relaxed);
+                let (e, combo) = self.classify_key_event(e);
+                for e in std::iter::once(e).chain(combo).filter_map(privacy::apply) {
+                    if let Some(log) = log.as_deref_mut() {
+                        log.append(&LogRecordRef::Keyboard(&e));
+                    }
+                    for listener in listeners.iter() {
+                        listener(MonitorEvent::Keyboard(&e));
+                    }
+                    self.push_keyboard_event(e);
+                }
+            }
+            CapturedEvent::Mouse(e) => {
+                self.touch(e.timestamp_micros, e.synthetic);
+                self.last_mouse_micros.fetch_max(e.timestamp_micros as u64, Ordering::Relaxed);
+                self.track_mouse_state(&e);
+                if let Some(log) = log {
+                    log.append(&LogRecordRef::Mouse(&e));
+                }
+                for listener in listeners.iter() {
+                    listener(MonitorEvent::Mouse(&e));
+                }
+                self.push_mouse_event(e);
+            }
+            CapturedEvent::Gap { since_micros, timestamp_micros, monotonic_micros } => {
+                // 中断期间看不到释放事件；标记不是输入活动，不更新空闲时间
+                self.clear_held();
+                if privacy::policy() != privacy::KeyboardPrivacy::Disabled {
+                    let e = KeyEvent {
+                        key: MONITORING_GAP_KEY.to_string(),
+                        char: None,
+                        event_type: KeyEventType::Gap { since_micros },
+                        timestamp_micros,
+                        monotonic_micros,
+                        synthetic: false,
+                        window: None,
+                    };
+                    if let Some(log) = log.as_deref_mut() {
+                        log.append(&LogRecordRef::Keyboard(&e));
+                    }
+                    for listener in listeners.iter() {
+                        listener(MonitorEvent::Keyboard(&e));
+                    }
+                    self.push_keyboard_event(e);
+                }
+                let e = MouseEvent {
+                    kind: MouseEventKind::Gap { since_micros },
+                    timestamp_micros,
+                    monotonic_micros,
+                    synthetic: false,
+                    window: None,
+                };
+                if let Some(log) = log {
+                    log.append(&LogRecordRef::Mouse(&e));
+                }
+                for listener in listeners.iter() {
+                    listener(MonitorEvent::Mouse(&e));
+                }
+                self.push_mouse_event(e);
+            }
         }
-        queue.push_back(event);
     }
     
-    /// 获取所有键盘事件并清空队列
-    fn take_keyboard_events(&self) -> Vec<KeyEvent> {
-        let mut queue = self.keyboard_events.lock().unwrap();
-        let events: Vec<KeyEvent> = queue.drain(..).collect();
-        events
+    /// 更新按下的键集合；已按下的键再次收到按下事件时记为自动重复。
+    /// 首次按下非修饰键且有修饰键按住时，同时返回合成的组合键事件
+    fn classify_key_event(&self, mut event: KeyEvent) -> (KeyEvent, Option<KeyEvent>) {
+        let mut held = lock(&self.held_keys);
+        let mut combo = None;
+        match event.event_type {
+            KeyEventType::Press | KeyEventType::Repeat => {
+                if !held.insert(event.key.clone()) {
+                    event.event_type = KeyEventType::Repeat;
+                } else if modifier_of(&event.key).is_none() {
+                    combo = combo_for(&held, &event.key).map(|key| KeyEvent {
+                        key,
+                        char: None,
+                        event_type: KeyEventType::Combo,
+                        timestamp_micros: event.timestamp_micros,
+                        monotonic_micros: event.monotonic_micros,
+                        synthetic: event.synthetic,
+                        window: event.window.clone(),
+                    });
+                }
+            }
+            KeyEventType::Release => {
+                held.remove(&event.key);
+            }
+            KeyEventType::Combo | KeyEventType::Gap { .. } => {}
+        }
+        (event, combo)
+    }
+
+    /// 清空按下的键与鼠标按键
+    fn clear_held(&self) {
+        lock(&self.held_keys).clear();
+        lock(&self.held_buttons).clear();
+    }
+
+    /// 更新按下的鼠标按键与光标位置
+    fn track_mouse_state(&self, event: &MouseEvent) {
+        match event.kind {
+            MouseEventKind::Move { x, y } => {
+                *lock(&self.cursor) = Some((x, y));
+            }
+            MouseEventKind::Button { button, state: ButtonState::Press } => {
+                let mut held = lock(&self.held_buttons);
+                if !held.iter().any(|h| h.button == button) {
+                    held.push(HeldButton {
+                        button,
+                        since_micros: event.timestamp_micros,
+                        press_position: *lock(&self.cursor),
+                        synthetic: event.synthetic,
+                    });
+                }
+            }
+            MouseEventKind::Button { button, state: ButtonState::Release } => {
+                lock(&self.held_buttons).retain(|h| h.button != button);
+            }
+            MouseEventKind::Scroll { .. } | MouseEventKind::Gap { .. } => {}
+        }
+    }
+
+    /// 添加键盘事件，超过容量时覆盖最旧的事件
+    fn push_keyboard_event(&self, event: KeyEvent) {
+        lock(&self.keyboard_events).push(event);
     }
     
-    /// 获取所有鼠标事件并清空队列
-    fn take_mouse_events(&self) -> Vec<MouseEvent> {
-        let mut queue = self.mouse_events.lock().unwrap();
-        let events: Vec<MouseEvent> = queue.drain(..).collect();
-        events
+    /// 添加鼠标事件，超过容量时覆盖最旧的事件
+    fn push_mouse_event(&self, event: MouseEvent) {
+        lock(&self.mouse_events).push(event);
     }
 }
 
@@ -157,152 +838,398 @@ impl EventStorage {
 
 struct UnifiedMonitor {
     storage: Arc<EventStorage>,
-    #[allow(dead_code)]
-    last_mouse_move_micros: Arc<Mutex<u128>>,
+    /// 监听线程是否在运行；后端出错退出后为 false，可重新启动
     started: Arc<AtomicBool>,
+    /// 采集状态（`MonitorState`）；监听线程运行但未处于 Running 时回调直接丢弃事件
+    state: Arc<AtomicU8>,
     event_count: Arc<AtomicU64>,
+    /// 因通道已满而丢弃的事件数
+    dropped_count: Arc<AtomicU64>,
+    /// 最近一次系统输入回调的时间（Unix 微秒，含暂停期间被丢弃的事件）；尚未回调时为 0
+    last_callback_micros: Arc<AtomicU64>,
+    /// 监听后端退出的时间（Unix 微秒）；恢复后的首个回调插入缺失标记并清零，未中断时为 0
+    gap_since_micros: Arc<AtomicU64>,
+    /// 监听后端退出后重新启动的次数
+    listener_restarts: Arc<AtomicU64>,
+    /// 回调向收集线程投递事件的通道
+    sender: mpsc::SyncSender<HookEvent>,
+    /// 收集线程与监听线程的句柄，用于健康检查
+    collector: thread::JoinHandle<()>,
+    listener: Mutex<Option<thread::JoinHandle<()>>>,
+    /// 串行化启动/停止等控制操作
+    control: Mutex<()>,
+}
+
+/// 键鼠监控的采集状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorState {
+    /// 正在采集事件
+    Running,
+    /// 暂停采集，保留监听器锁与缓冲区
+    Paused,
+    /// 停止采集并释放监听器锁，其他进程可以接管
+    Stopped,
+}
+
+impl MonitorState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => MonitorState::Running,
+            1 => MonitorState::Paused,
+            _ => MonitorState::Stopped,
+        }
+    }
 }
 
 static GLOBAL_MONITOR: OnceLock<UnifiedMonitor> = OnceLock::new();
 
+/// 通过 `--no-monitor` 关闭自动启动；为 None 时读取环境变量 `IRIS_MONITOR_AUTOSTART`
+static AUTOSTART_OVERRIDE: Mutex<Option<bool>> = Mutex::new(None);
+
+/// 首次访问监控时是否自动启动监听器：命令行设置 > `IRIS_MONITOR_AUTOSTART` > 默认开启
+fn autostart_enabled() -> bool {
+    lock(&AUTOSTART_OVERRIDE).unwrap_or_else(|| {
+        env::var("IRIS_MONITOR_AUTOSTART")
+            .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "off"))
+            .unwrap_or(true)
+    })
+}
+
 impl UnifiedMonitor {
-    /// 获取或初始化全局监听器
+    /// 获取或初始化全局监听器；开启自动启动时首次访问即启动监听
     fn global() -> &'static Self {
-        GLOBAL_MONITOR.get_or_init(|| {
-            let storage = Arc::new(EventStorage::new());
-            let last_mouse_move_micros = Arc::new(Mutex::new(0u128));
-            let started = Arc::new(AtomicBool::new(false));
-            let event_count = Arc::new(AtomicU64::new(0));
-            
-            let pid = std::process::id();
-            eprintln!("[monitor_key_mouse][PID:{}] Initializing event monitor...", pid);
-            
-            // 尝试获取全局锁
-            if !try_acquire_lock() {
-                eprintln!("[monitor_key_mouse][PID:{}] Another process is already monitoring. This process will not start a listener.", pid);
-                // 不启动监听器，但返回有效的结构
-                return UnifiedMonitor {
-                    storage,
-                    last_mouse_move_micros,
-                    started, // 保持 false
-                    event_count,
-                };
+        static AUTOSTART: std::sync::Once = std::sync::Once::new();
+        let monitor = GLOBAL_MONITOR.get_or_init(Self::new);
+        AUTOSTART.call_once(|| {
+            if autostart_enabled() {
+                if let Err(error) = monitor.start() {
+                    eprintln!("[monitor_key_mouse][PID:{}] {}. This process will not start a listener.", std::process::id(), error);
+                }
+            } else {
+                eprintln!("[monitor_key_mouse][PID:{}] Autostart disabled; use monitor_control to start", std::process::id());
             }
-            
-            let storage_clone = storage.clone();
-            let last_mouse_move_micros_clone = last_mouse_move_micros.clone();
-            let started_clone = started.clone();
-            let event_count_clone = event_count.clone();
-            
-            // 启动统一的事件监听线程
-            thread::Builder::new()
-                .name("key-mouse-monitor".to_string())
-                .spawn(move || {
-                    eprintln!("[monitor_key_mouse][PID:{}] Starting rdev listen...", pid);
-                    started_clone.store(true, Ordering::SeqCst);
-                    
-                    if let Err(error) = listen(move |event: Event| {
-                        event_count_clone.fetch_add(1, Ordering::Relaxed);
-                        Self::handle_event(
-                            event,
-                            storage_clone.clone(),
-                            last_mouse_move_micros_clone.clone(),
-                        );
-                    }) {
-                        eprintln!("[monitor_key_mouse][PID:{}] rdev listen error: {:?}", pid, error);
-                        started_clone.store(false, Ordering::SeqCst);
-                        release_lock();
+        });
+        monitor
+    }
+
+    /// 创建存储与收集线程，不启动监听
+    fn new() -> Self {
+        // 固定单调时钟起点，之后事件的 monotonic_micros 均相对于此时刻
+        monotonic_micros();
+        let storage = Arc::new(EventStorage::new());
+        let (sender, receiver) = mpsc::sync_channel::<HookEvent>(EVENT_CHANNEL_CAPACITY);
+
+        eprintln!("[monitor_key_mouse][PID:{}] Initializing event monitor...", std::process::id());
+
+        // 收集线程：把通道中的事件写入存储队列
+        let storage_clone = storage.clone();
+        let collector = thread::Builder::new()
+            .name("key-mouse-collector".to_string())
+            .spawn(move || {
+                let mut log = EventLog::open();
+                let mut focus = FocusTracker::new();
+                for event in receiver {
+                    let mut event = event.resolve();
+                    let window = focus.current();
+                    match &mut event {
+                        CapturedEvent::Keyboard(e) => e.window = window,
+                        CapturedEvent::Mouse(e) => e.window = window,
+                        CapturedEvent::Gap { .. } => {}
                     }
-                })
-                .expect("Failed to start key-mouse monitor thread");
-            
-            // 等待一小段时间确保线程启动
-            thread::sleep(std::time::Duration::from_millis(50));
-            eprintln!("[monitor_key_mouse][PID:{}] Monitor initialization complete", pid);
-            
-            UnifiedMonitor {
-                storage,
-                last_mouse_move_micros,
-                started,
-                event_count,
-            }
-        })
+                    storage_clone.push(event, log.as_mut());
+                }
+            })
+            .expect("Failed to start key-mouse collector thread");
+
+        UnifiedMonitor {
+            storage,
+            started: Arc::new(AtomicBool::new(false)),
+            state: Arc::new(AtomicU8::new(MonitorState::Stopped as u8)),
+            event_count: Arc::new(AtomicU64::new(0)),
+            dropped_count: Arc::new(AtomicU64::new(0)),
+            last_callback_micros: Arc::new(AtomicU64::new(0)),
+            gap_since_micros: Arc::new(AtomicU64::new(0)),
+            listener_restarts: Arc::new(AtomicU64::new(0)),
+            sender,
+            collector,
+            listener: Mutex::new(None),
+            control: Mutex::new(()),
+        }
     }
-    
-    /// 处理并存储事件
-    fn handle_event(
-        event: Event,
-        storage: Arc<EventStorage>,
-        last_mouse_move_micros: Arc<Mutex<u128>>,
-    ) {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_micros())
-            .unwrap_or(0);
-        
-        match event.event_type {
-            // 键盘事件
-            EventType::KeyPress(key) => {
-                storage.push_keyboard_event(KeyEvent {
-                    key: key_to_string(key),
-                    event_type: KeyEventType::Press,
-                    timestamp_micros: timestamp,
-                });
+
+    fn state(&self) -> MonitorState {
+        MonitorState::from_u8(self.state.load(Ordering::SeqCst))
+    }
+
+    /// 监听线程在运行且正在采集事件
+    fn capturing(&self) -> bool {
+        self.started.load(Ordering::SeqCst) && self.state() == MonitorState::Running
+    }
+
+    /// 获取监听器锁并开始采集；监听线程未运行时启动它
+    fn start(&self) -> Result<(), String> {
+        let _control = lock(&self.control);
+        let pid = std::process::id();
+        if self.state() == MonitorState::Stopped && !try_acquire_lock() {
+            return Err("Another process is already monitoring".to_string());
+        }
+        self.state.store(MonitorState::Running as u8, Ordering::SeqCst);
+        if self.started.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let state = self.state.clone();
+        let started = self.started.clone();
+        let event_count = self.event_count.clone();
+        let dropped_count = self.dropped_count.clone();
+        let last_callback_micros = self.last_callback_micros.clone();
+        let gap_since_micros = self.gap_since_micros.clone();
+        let sender = self.sender.clone();
+        let sampler = MoveSampler::new(mouse_sampling());
+
+        // 回调运行在系统输入钩子上：只做转换与非阻塞发送，不持有任何锁
+        let callback: Arc<dyn Fn(Event) + Send + Sync> = Arc::new(move |event: Event| {
+            last_callback_micros.store(now_micros(), Ordering::Relaxed);
+            if state.load(Ordering::Relaxed) != MonitorState::Running as u8 {
+                return;
             }
-            EventType::KeyRelease(key) => {
-                storage.push_keyboard_event(KeyEvent {
-                    key: key_to_string(key),
-                    event_type: KeyEventType::Release,
-                    timestamp_micros: timestamp,
-                });
+            event_count.fetch_add(1, Ordering::Relaxed);
+            // 监听器重启后的首个事件之前插入缺失标记
+            let since = gap_since_micros.swap(0, Ordering::Relaxed);
+            if since != 0 {
+                let gap = CapturedEvent::Gap {
+                    since_micros: since as u128,
+                    timestamp_micros: now_micros() as u128,
+                    monotonic_micros: monotonic_micros(),
+                };
+                if sender.try_send(HookEvent::Captured(gap)).is_err() {
+                    dropped_count.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            if let Some(captured) = Self::translate_event(event, &sampler)
+                && sender.try_send(captured).is_err()
+            {
+                dropped_count.fetch_add(1, Ordering::Relaxed);
             }
+        });
+
+        // 启动统一的事件监听线程；后端退出时按退避间隔重启，直到采集被停止
+        let state = self.state.clone();
+        let gap_since_micros = self.gap_since_micros.clone();
+        let listener_restarts = self.listener_restarts.clone();
+        let listener = thread::Builder::new()
+            .name("key-mouse-monitor".to_string())
+            .spawn(move || {
+                let stopped = || state.load(Ordering::SeqCst) == MonitorState::Stopped as u8;
+                let mut backoff = LISTENER_RESTART_INITIAL_BACKOFF;
+                loop {
+                    let run_started = Instant::now();
+                    let error = match run_listener(callback.clone(), pid) {
+                        Ok(()) => "listener exited".to_string(),
+                        Err(error) => error.to_string(),
+                    };
+                    if stopped() {
+                        break;
+                    }
+                    // 保留最早的中断时间：连续重启失败属于同一段缺失
+                    let _ = gap_since_micros.compare_exchange(0, now_micros(), Ordering::Relaxed, Ordering::Relaxed);
+                    if run_started.elapsed() >= LISTENER_HEALTHY_RUN {
+                        backoff = LISTENER_RESTART_INITIAL_BACKOFF;
+                    }
+                    eprintln!(
+                        "[monitor_key_mouse][PID:{}] listen error: {}; restarting in {:?}",
+                        pid, error, backoff
+                    );
+                    let deadline = Instant::now() + backoff;
+                    while Instant::now() < deadline && !stopped() {
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                    if stopped() {
+                        break;
+                    }
+                    listener_restarts.fetch_add(1, Ordering::Relaxed);
+                    backoff = (backoff * 2).min(LISTENER_RESTART_MAX_BACKOFF);
+                }
+                started.store(false, Ordering::SeqCst);
+                state.store(MonitorState::Stopped as u8, Ordering::SeqCst);
+                release_lock();
+            })
+            .expect("Failed to start key-mouse monitor thread");
+        *lock(&self.listener) = Some(listener);
+
+        // 等待一小段时间确保线程启动
+        thread::sleep(std::time::Duration::from_millis(50));
+        eprintln!("[monitor_key_mouse][PID:{}] Monitor started", pid);
+        Ok(())
+    }
+
+    /// 暂停或停止采集；停止时释放监听器锁。
+    /// 系统输入钩子无法卸载，监听线程保持运行但丢弃所有事件
+    fn halt(&self, target: MonitorState) {
+        let _control = lock(&self.control);
+        let previous = self.state();
+        if previous == MonitorState::Stopped {
+            return;
+        }
+        self.state.store(target as u8, Ordering::SeqCst);
+        // 暂停期间看不到释放事件，清空按下状态以免恢复后残留
+        self.storage.clear_held();
+        if target == MonitorState::Stopped {
+            release_lock();
+        }
+        eprintln!("[monitor_key_mouse][PID:{}] Monitor {:?}", std::process::id(), target);
+    }
+
+    /// 将 rdev 事件转换为存储事件；被采样丢弃的鼠标移动返回 None
+    fn translate_event(event: Event, sampler: &MoveSampler) -> Option<HookEvent> {
+        let timestamp = now_micros() as u128;
+        let monotonic = monotonic_micros();
+        let synthetic = source::is_synthetic_at(timestamp);
+        
+        let captured = match event.event_type {
+            // 键盘事件；rdev 在按下时按当前布局解析出字符，控制字符（回车、退格等）不算输入字符
+            EventType::KeyPress(key) => CapturedEvent::Keyboard(KeyEvent {
+                key: key_to_string(key),
+                char: event.name.filter(|name| !name.is_empty() && !name.chars().any(char::is_control)),
+                event_type: KeyEventType::Press,
+                timestamp_micros: timestamp,
+                monotonic_micros: monotonic,
+                synthetic,
+                window: None,
+            }),
+            EventType::KeyRelease(key) => CapturedEvent::Keyboard(KeyEvent {
+                key: key_to_string(key),
+                char: None,
+                event_type: KeyEventType::Release,
+                timestamp_micros: timestamp,
+                monotonic_micros: monotonic,
+                synthetic,
+                window: None,
+            }),
             
             // 鼠标事件
             EventType::MouseMove { x, y } => {
-                // 节流：仅在距离上次记录超过采样间隔时保存
-                let mut last = last_mouse_move_micros.lock().unwrap();
-                if timestamp.saturating_sub(*last) < mouse_move_interval_micros() {
-                    return;
+                if !sampler.sample(timestamp) {
+                    return None;
                 }
-                *last = timestamp;
-
-                storage.push_mouse_event(MouseEvent {
-                    kind: MouseEventKind::Move {
-                        x: x as i32,
-                        y: y as i32,
-                    },
+                return Some(HookEvent::Move {
+                    x,
+                    y,
                     timestamp_micros: timestamp,
+                    monotonic_micros: monotonic,
+                    synthetic,
                 });
             }
             EventType::ButtonPress(button) => {
-                storage.push_mouse_event(MouseEvent {
-                    kind: MouseEventKind::Button {
-                        button: map_button(button),
-                        state: ButtonState::Press,
-                    },
+                let button = map_button(button);
+                sampler.press(button);
+                CapturedEvent::Mouse(MouseEvent {
+                    kind: MouseEventKind::Button { button, state: ButtonState::Press },
                     timestamp_micros: timestamp,
-                });
+                    monotonic_micros: monotonic,
+                    synthetic,
+                    window: None,
+                })
             }
             EventType::ButtonRelease(button) => {
-                storage.push_mouse_event(MouseEvent {
-                    kind: MouseEventKind::Button {
-                        button: map_button(button),
-                        state: ButtonState::Release,
-                    },
-                    timestamp_micros: timestamp,
-                });
-            }
-            EventType::Wheel { delta_x, delta_y } => {
-                storage.push_mouse_event(MouseEvent {
-                    kind: MouseEventKind::Scroll {
-                        delta_x: delta_x as i32,
-                        delta_y: delta_y as i32,
-                    },
+                let button = map_button(button);
+                sampler.release(button);
+                CapturedEvent::Mouse(MouseEvent {
+                    kind: MouseEventKind::Button { button, state: ButtonState::Release },
                     timestamp_micros: timestamp,
-                });
+                    monotonic_micros: monotonic,
+                    synthetic,
+                    window: None,
+                })
             }
+            // rdev 在 Windows / Linux 上报告滚轮格数，macOS 上报告像素增量
+            EventType::Wheel { delta_x, delta_y } => CapturedEvent::Mouse(MouseEvent {
+                kind: MouseEventKind::Scroll {
+                    delta_x: scroll_lines(delta_x, WHEEL_UNIT),
+                    delta_y: scroll_lines(delta_y, WHEEL_UNIT),
+                    raw_delta_x: delta_x as i32,
+                    raw_delta_y: delta_y as i32,
+                    raw_unit: WHEEL_UNIT,
+                },
+                timestamp_micros: timestamp,
+                monotonic_micros: monotonic,
+                synthetic,
+                window: None,
+            }),
+        };
+        Some(HookEvent::Captured(captured))
+    }
+}
+
+/// 监听后端的运行状态，供 `monitor_capabilities` 报告
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListenerStatus {
+    /// 正在使用或最后尝试的后端（`rdev` / `evdev`）
+    pub backend: Option<&'static str>,
+    /// 后端退出时的错误
+    pub error: Option<String>,
+}
+
+static LISTENER_STATUS: Mutex<ListenerStatus> = Mutex::new(ListenerStatus {
+    backend: None,
+    error: None,
+});
+
+fn set_listener_status(backend: &'static str, error: Option<String>) {
+    *lock(&LISTENER_STATUS) = ListenerStatus {
+        backend: Some(backend),
+        error,
+    };
+}
+
+/// 运行监听后端并阻塞当前线程。
+/// Linux 上 rdev（X11）不可用、处于 Wayland 会话（rdev 只能看到 XWayland 窗口）
+/// 或 `IRIS_MONITOR_BACKEND=evdev` 时改用 evdev 后端；`IRIS_MONITOR_BACKEND=rdev` 强制使用 rdev。
+fn run_listener(callback: Arc<dyn Fn(Event) + Send + Sync>, pid: u32) -> Result<(), MonitorError> {
+    #[cfg(target_os = "linux")]
+    {
+        let requested = env::var("IRIS_MONITOR_BACKEND").unwrap_or_default().to_ascii_lowercase();
+        let wayland = super::wayland::is_wayland_session();
+        if requested == "evdev" || (wayland && requested != "rdev") {
+            eprintln!("[monitor_key_mouse][PID:{}] Starting evdev listen (wayland={})...", pid, wayland);
+            return run_evdev(callback);
         }
     }
+
+    eprintln!("[monitor_key_mouse][PID:{}] Starting rdev listen...", pid);
+    set_listener_status("rdev", None);
+    let rdev_callback = callback.clone();
+    let error = match listen(move |event: Event| rdev_callback(event)) {
+        Ok(()) => return Ok(()),
+        Err(error) => error,
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        eprintln!(
+            "[monitor_key_mouse][PID:{}] rdev listen error: {:?}, falling back to evdev",
+            pid, error
+        );
+        run_evdev(callback)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        drop(callback);
+        let error = format!("rdev listen error: {:?}", error);
+        set_listener_status("rdev", Some(error.clone()));
+        Err(MonitorError::Io(error))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn run_evdev(callback: Arc<dyn Fn(Event) + Send + Sync>) -> Result<(), MonitorError> {
+    set_listener_status("evdev", None);
+    let result = super::evdev::listen(callback);
+    if let Err(error) = &result {
+        set_listener_status("evdev", Some(error.to_string()));
+    }
+    result
 }
 
 // ============================================================
@@ -315,26 +1242,398 @@ pub fn initialize() {
     let _ = UnifiedMonitor::global();
 }
 
-/// 获取所有键盘事件并清空存储
-pub fn take_keyboard_events() -> Vec<KeyEvent> {
+/// 按读取方式获取键盘事件，只返回时间范围内的事件
+pub fn read_keyboard_events(mode: ReadMode, range: TimeRange) -> EventBatch<KeyEvent> {
+    let monitor = UnifiedMonitor::global();
+    let mut batch = lock(&monitor.storage.keyboard_events).read(mode, range);
+    batch.dropped_count = monitor.dropped_count.load(Ordering::Relaxed);
+    log_read("keyboard", monitor, &batch);
+    batch
+}
+
+/// 按读取方式获取鼠标事件，只返回时间范围内的事件
+pub fn read_mouse_events(mode: ReadMode, range: TimeRange) -> EventBatch<MouseEvent> {
+    let monitor = UnifiedMonitor::global();
+    let mut batch = lock(&monitor.storage.mouse_events).read(mode, range);
+    batch.dropped_count = monitor.dropped_count.load(Ordering::Relaxed);
+    log_read("mouse", monitor, &batch);
+    batch
+}
+
+/// 设置事件缓冲区容量（1..=`MAX_EVENT_BUFFER_CAPACITY`），None 表示不变。
+/// 监听器尚未初始化时只记录设置，初始化时生效；缩小容量会丢弃最旧的事件。
+pub fn set_buffer_capacity(keyboard: Option<usize>, mouse: Option<usize>) {
+    let keyboard = keyboard.map(|c| c.clamp(1, MAX_EVENT_BUFFER_CAPACITY));
+    let mouse = mouse.map(|c| c.clamp(1, MAX_EVENT_BUFFER_CAPACITY));
+    {
+        let mut overrides = lock(&CAPACITY_OVERRIDES);
+        overrides.0 = keyboard.or(overrides.0);
+        overrides.1 = mouse.or(overrides.1);
+    }
+    if let Some(monitor) = GLOBAL_MONITOR.get() {
+        if let Some(capacity) = keyboard {
+            lock(&monitor.storage.keyboard_events).resize(capacity);
+        }
+        if let Some(capacity) = mouse {
+            lock(&monitor.storage.mouse_events).resize(capacity);
+        }
+    }
+}
+
+/// 事件缓冲区的容量与占用
+pub fn buffer_report() -> BufferReport {
     let monitor = UnifiedMonitor::global();
-    let events = monitor.storage.take_keyboard_events();
-    let total_events = monitor.event_count.load(Ordering::Relaxed);
-    let started = monitor.started.load(Ordering::SeqCst);
-    eprintln!("[monitor_key_mouse][PID:{}] take_keyboard_events: returning {} events, started={}, total_processed={}", 
-        std::process::id(), events.len(), started, total_events);
-    events
+    BufferReport {
+        keyboard: lock(&monitor.storage.keyboard_events).stats(),
+        mouse: lock(&monitor.storage.mouse_events).stats(),
+        dropped_count: monitor.dropped_count.load(Ordering::Relaxed),
+    }
+}
+
+/// 具名消费者在缓冲区中的已确认位置，首次使用时登记；消费者数已达上限时返回 None
+pub fn consumer_cursor(kind: BufferKind, name: &str) -> Option<u64> {
+    let storage = &UnifiedMonitor::global().storage;
+    match kind {
+        BufferKind::Keyboard => lock(&storage.keyboard_events).consumer_cursor(name),
+        BufferKind::Mouse => lock(&storage.mouse_events).consumer_cursor(name),
+    }
+}
+
+/// 确认的结果
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AckReport {
+    /// 确认后的位置
+    pub cursor: u64,
+    /// 本次裁剪丢弃的事件数
+    pub trimmed: usize,
+}
+
+/// 确认具名消费者已处理到 `cursor` 为止的事件；`trim` 时丢弃所有消费者都已确认的事件。
+/// 消费者数已达上限时返回 None
+pub fn ack_events(kind: BufferKind, name: &str, cursor: u64, trim: bool) -> Option<AckReport> {
+    let storage = &UnifiedMonitor::global().storage;
+    let ack = |cursor, trimmed| AckReport { cursor, trimmed };
+    match kind {
+        BufferKind::Keyboard => {
+            let mut ring = lock(&storage.keyboard_events);
+            let cursor = ring.ack(name, cursor)?;
+            Some(ack(cursor, if trim { ring.trim() } else { 0 }))
+        }
+        BufferKind::Mouse => {
+            let mut ring = lock(&storage.mouse_events);
+            let cursor = ring.ack(name, cursor)?;
+            Some(ack(cursor, if trim { ring.trim() } else { 0 }))
+        }
+    }
+}
+
+/// 注销具名消费者（键盘与鼠标缓冲区），返回是否曾登记
+pub fn release_consumer(name: &str) -> bool {
+    let storage = &UnifiedMonitor::global().storage;
+    let keyboard = lock(&storage.keyboard_events).consumers.remove(name).is_some();
+    let mouse = lock(&storage.mouse_events).consumers.remove(name).is_some();
+    keyboard || mouse
+}
+
+/// 获取上次读取后的键盘事件，之后的默认读取不再返回它们
+pub fn take_keyboard_events() -> Vec<KeyEvent> {
+    read_keyboard_events(ReadMode::Take, TimeRange::default())
+        .events
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect()
 }
 
-/// 获取所有鼠标事件并清空存储
+/// 获取上次读取后的鼠标事件，之后的默认读取不再返回它们
 pub fn take_mouse_events() -> Vec<MouseEvent> {
+    read_mouse_events(ReadMode::Take, TimeRange::default())
+        .events
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect()
+}
+
+fn log_read<T>(kind: &str, monitor: &UnifiedMonitor, batch: &EventBatch<T>) {
+    eprintln!("[monitor_key_mouse][PID:{}] read {} events: returning {}, next_cursor={}, missed={}, started={}, total_processed={}, dropped={}",
+        std::process::id(), kind, batch.events.len(), batch.next_cursor, batch.missed,
+        monitor.started.load(Ordering::SeqCst),
+        monitor.event_count.load(Ordering::Relaxed),
+        monitor.dropped_count.load(Ordering::Relaxed));
+}
+
+/// 本进程的键鼠监听器是否在运行并采集事件
+pub fn is_running() -> bool {
+    UnifiedMonitor::global().capturing()
+}
+
+/// 设置首次访问监控时是否自动启动监听器；需在监控初始化前调用
+pub fn set_autostart(enabled: bool) {
+    *lock(&AUTOSTART_OVERRIDE) = Some(enabled);
+}
+
+/// 当前采集状态；监听后端出错退出时为 Stopped
+pub fn monitor_state() -> MonitorState {
     let monitor = UnifiedMonitor::global();
-    let events = monitor.storage.take_mouse_events();
-    let total_events = monitor.event_count.load(Ordering::Relaxed);
-    let started = monitor.started.load(Ordering::SeqCst);
-    eprintln!("[monitor_key_mouse][PID:{}] take_mouse_events: returning {} events, started={}, total_processed={}", 
-        std::process::id(), events.len(), started, total_events);
-    events
+    if monitor.started.load(Ordering::SeqCst) {
+        monitor.state()
+    } else {
+        MonitorState::Stopped
+    }
+}
+
+/// 开始采集（停止或暂停状态均可）；另一进程持有监听器锁时返回错误
+pub fn start_monitor() -> Result<(), String> {
+    UnifiedMonitor::global().start()
+}
+
+/// 恢复暂停的采集；已停止时返回错误（需重新启动以获取监听器锁）
+pub fn resume_monitor() -> Result<(), String> {
+    let monitor = UnifiedMonitor::global();
+    match monitor_state() {
+        MonitorState::Stopped => Err("Monitor is stopped; use start instead".to_string()),
+        _ => monitor.start(),
+    }
+}
+
+/// 暂停采集，保留监听器锁与缓冲区
+pub fn pause_monitor() {
+    UnifiedMonitor::global().halt(MonitorState::Paused);
+}
+
+/// 停止采集并释放监听器锁
+pub fn stop_monitor() {
+    UnifiedMonitor::global().halt(MonitorState::Stopped);
+}
+
+/// 清空键盘与鼠标事件缓冲区，返回丢弃的事件数（键盘、鼠标）
+pub fn clear_buffers() -> (usize, usize) {
+    let storage = &UnifiedMonitor::global().storage;
+    let keyboard = lock(&storage.keyboard_events).clear();
+    let mouse = lock(&storage.mouse_events).clear();
+    (keyboard, mouse)
+}
+
+/// 单调时钟读数：自进程内首次调用起的微秒数
+pub(crate) fn monotonic_micros() -> u64 {
+    static ANCHOR: OnceLock<Instant> = OnceLock::new();
+    ANCHOR.get_or_init(Instant::now).elapsed().as_micros() as u64
+}
+
+/// 当前 Unix 时间（微秒）
+pub(crate) fn now_micros() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+/// 输入空闲时间
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleTime {
+    /// 距最近一次键鼠事件（含 iris-mcp 注入的输入）的微秒数
+    pub idle_micros: u64,
+    /// 距最近一次真人（非注入）键鼠事件的微秒数
+    pub human_idle_micros: u64,
+    /// 最近一次事件的时间戳（Unix 微秒）；尚无事件时为监控启动时间
+    pub last_event_micros: u64,
+    pub last_human_event_micros: u64,
+}
+
+/// 距最近一次键鼠事件的空闲时间；本进程未运行监听器时返回 None
+pub fn idle_time() -> Option<IdleTime> {
+    let monitor = UnifiedMonitor::global();
+    if !monitor.capturing() {
+        return None;
+    }
+    let now = now_micros();
+    let last_event_micros = monitor.storage.last_event_micros.load(Ordering::Relaxed);
+    let last_human_event_micros = monitor.storage.last_human_event_micros.load(Ordering::Relaxed);
+    Some(IdleTime {
+        idle_micros: now.saturating_sub(last_event_micros),
+        human_idle_micros: now.saturating_sub(last_human_event_micros),
+        last_event_micros,
+        last_human_event_micros,
+    })
+}
+
+/// 监控记录的当前按下的键（按隐私策略脱敏）；本进程未运行监听器或键盘采集已关闭时返回 None
+pub fn held_keys() -> Option<Vec<String>> {
+    let monitor = UnifiedMonitor::global();
+    if !monitor.capturing() || privacy::policy() == privacy::KeyboardPrivacy::Disabled {
+        return None;
+    }
+    Some(privacy::mask_held_keys(lock(&monitor.storage.held_keys).iter().cloned().collect()))
+}
+
+/// 按住的鼠标按键
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeldButton {
+    pub button: MouseButton,
+    /// 按下时的时间戳（Unix 微秒）
+    pub since_micros: u128,
+    /// 按下时的光标位置（逻辑坐标）；按下前尚无移动事件时为 None
+    pub press_position: Option<(i32, i32)>,
+    /// 按下事件是否由 iris-mcp 注入
+    pub synthetic: bool,
+}
+
+/// 监控记录的当前输入状态
+#[derive(Debug, Clone, Serialize)]
+pub struct InputSnapshot {
+    /// 当前按下的键（按名称排序，按隐私策略脱敏；键盘采集关闭时为空）
+    pub keys: Vec<String>,
+    /// 当前按下的鼠标按键，按按下顺序排列
+    pub buttons: Vec<HeldButton>,
+    /// 最近一次鼠标移动的位置（逻辑坐标）
+    pub cursor: Option<(i32, i32)>,
+    /// 快照时间（Unix 微秒）
+    pub timestamp_micros: u64,
+}
+
+/// 监控记录的当前按下的键、鼠标按键与光标位置；本进程未运行监听器时返回 None。
+/// 状态由监控事件推算，监控启动前已按下的键或按键不会出现在快照中
+pub fn input_snapshot() -> Option<InputSnapshot> {
+    let monitor = UnifiedMonitor::global();
+    if !monitor.capturing() {
+        return None;
+    }
+    let storage = &monitor.storage;
+    Some(InputSnapshot {
+        keys: privacy::mask_held_keys(lock(&storage.held_keys).iter().cloned().collect()),
+        buttons: lock(&storage.held_buttons).clone(),
+        cursor: *lock(&storage.cursor),
+        timestamp_micros: now_micros(),
+    })
+}
+
+/// 监控后端能力报告
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorCapabilities {
+    /// 本进程的监听器是否在运行并采集事件（另一进程已在监控或已暂停/停止时为 false）
+    pub running: bool,
+    /// 采集状态
+    pub state: MonitorState,
+    /// 鼠标移动采样配置
+    pub mouse_sampling: SamplingConfig,
+    #[serde(flatten)]
+    pub listener: ListenerStatus,
+    /// 是否处于 Wayland 会话（此时使用 evdev 后端）
+    #[cfg(target_os = "linux")]
+    pub wayland_session: bool,
+}
+
+/// 报告监控后端与平台输入捕获能力
+pub fn capabilities() -> MonitorCapabilities {
+    let monitor = UnifiedMonitor::global();
+    MonitorCapabilities {
+        running: monitor.capturing(),
+        state: monitor_state(),
+        mouse_sampling: mouse_sampling(),
+        listener: lock(&LISTENER_STATUS).clone(),
+        #[cfg(target_os = "linux")]
+        wayland_session: super::wayland::is_wayland_session(),
+    }
+}
+
+/// 单个设备的事件流健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceHealth {
+    /// 该设备的事件是否正在被采集（键盘在隐私策略为 disabled 时为 false）
+    pub capturing: bool,
+    /// 最近一次写入缓冲区的事件时间戳（Unix 微秒）；尚无事件时为 None
+    pub last_event_micros: Option<u64>,
+    pub last_event_age_micros: Option<u64>,
+}
+
+/// 监听器锁文件的持有情况
+#[derive(Debug, Clone, Serialize)]
+pub struct LockOwnership {
+    pub path: String,
+    /// 锁文件记录的持有者 PID；没有锁文件或无法解析时为 None
+    pub holder_pid: Option<u32>,
+    /// 是否由本进程持有
+    pub held_by_self: bool,
+    /// 持有者进程是否仍在运行
+    pub holder_alive: Option<bool>,
+}
+
+/// 键鼠监控各线程的健康状态，用于区分“用户没有操作”与“输入钩子已失效”
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorHealth {
+    pub state: MonitorState,
+    /// 监听线程（系统输入钩子）是否在运行
+    pub listener_alive: bool,
+    #[serde(flatten)]
+    pub listener: ListenerStatus,
+    /// 收集线程是否在运行；退出后事件不再写入缓冲区
+    pub collector_alive: bool,
+    /// 最近一次系统输入回调的时间（Unix 微秒，含暂停期间被丢弃的事件）；尚未回调时为 None
+    pub last_callback_micros: Option<u64>,
+    pub last_callback_age_micros: Option<u64>,
+    /// 采集期间收到的系统输入回调数
+    pub event_count: u64,
+    /// 因通道已满而丢弃的事件数
+    pub dropped_count: u64,
+    /// 监听后端退出后重新启动的次数
+    pub listener_restarts: u64,
+    /// 监听后端退出且尚未恢复采集时为退出时间（Unix 微秒），此后的事件缺失
+    pub gap_since_micros: Option<u64>,
+    pub keyboard: DeviceHealth,
+    pub mouse: DeviceHealth,
+    pub lock: LockOwnership,
+    /// 检查时间（Unix 微秒）
+    pub timestamp_micros: u64,
+}
+
+/// 报告监听线程、收集线程、各设备最近事件与监听器锁的状态；不要求本进程正在采集
+pub fn health() -> MonitorHealth {
+    let monitor = UnifiedMonitor::global();
+    let now = now_micros();
+    let since = |micros: u64| (micros != 0).then_some(micros);
+    let device = |capturing: bool, last: &AtomicU64| {
+        let last_event_micros = since(last.load(Ordering::Relaxed));
+        DeviceHealth {
+            capturing,
+            last_event_micros,
+            last_event_age_micros: last_event_micros.map(|t| now.saturating_sub(t)),
+        }
+    };
+
+    let listener_alive = monitor.started.load(Ordering::SeqCst)
+        && lock(&monitor.listener).as_ref().is_some_and(|handle| !handle.is_finished());
+    let capturing = listener_alive && monitor.state() == MonitorState::Running;
+    let last_callback_micros = since(monitor.last_callback_micros.load(Ordering::Relaxed));
+
+    let lock_path = get_lock_file_path();
+    let holder_pid = fs::read_to_string(&lock_path)
+        .ok()
+        .and_then(|content| content.trim().parse::<u32>().ok());
+    let held_by_self = holder_pid == Some(std::process::id());
+
+    MonitorHealth {
+        state: monitor_state(),
+        listener_alive,
+        listener: lock(&LISTENER_STATUS).clone(),
+        collector_alive: !monitor.collector.is_finished(),
+        last_callback_micros,
+        last_callback_age_micros: last_callback_micros.map(|t| now.saturating_sub(t)),
+        event_count: monitor.event_count.load(Ordering::Relaxed),
+        dropped_count: monitor.dropped_count.load(Ordering::Relaxed),
+        listener_restarts: monitor.listener_restarts.load(Ordering::Relaxed),
+        gap_since_micros: since(monitor.gap_since_micros.load(Ordering::Relaxed)),
+        keyboard: device(
+            capturing && privacy::policy() != privacy::KeyboardPrivacy::Disabled,
+            &monitor.storage.last_keyboard_micros,
+        ),
+        mouse: device(capturing, &monitor.storage.last_mouse_micros),
+        lock: LockOwnership {
+            path: lock_path.display().to_string(),
+            holder_pid,
+            held_by_self,
+            holder_alive: holder_pid.map(|pid| held_by_self || process_alive(pid)),
+        },
+        timestamp_micros: now,
+    }
 }
 
 // ============================================================
@@ -371,96 +1670,20 @@ where
 // ============================================================
 
 fn key_to_string(key: rdev::Key) -> String {
-    use rdev::Key;
-    match key {
-        Key::Num0 => "0".to_string(),
-        Key::Num1 => "1".to_string(),
-        Key::Num2 => "2".to_string(),
-        Key::Num3 => "3".to_string(),
-        Key::Num4 => "4".to_string(),
-        Key::Num5 => "5".to_string(),
-        Key::Num6 => "6".to_string(),
-        Key::Num7 => "7".to_string(),
-        Key::Num8 => "8".to_string(),
-        Key::Num9 => "9".to_string(),
-        Key::KeyA => "A".to_string(),
-        Key::KeyB => "B".to_string(),
-        Key::KeyC => "C".to_string(),
-        Key::KeyD => "D".to_string(),
-        Key::KeyE => "E".to_string(),
-        Key::KeyF => "F".to_string(),
-        Key::KeyG => "G".to_string(),
-        Key::KeyH => "H".to_string(),
-        Key::KeyI => "I".to_string(),
-        Key::KeyJ => "J".to_string(),
-        Key::KeyK => "K".to_string(),
-        Key::KeyL => "L".to_string(),
-        Key::KeyM => "M".to_string(),
-        Key::KeyN => "N".to_string(),
-        Key::KeyO => "O".to_string(),
-        Key::KeyP => "P".to_string(),
-        Key::KeyQ => "Q".to_string(),
-        Key::KeyR => "R".to_string(),
-        Key::KeyS => "S".to_string(),
-        Key::KeyT => "T".to_string(),
-        Key::KeyU => "U".to_string(),
-        Key::KeyV => "V".to_string(),
-        Key::KeyW => "W".to_string(),
-        Key::KeyX => "X".to_string(),
-        Key::KeyY => "Y".to_string(),
-        Key::KeyZ => "Z".to_string(),
-        Key::F1 => "F1".to_string(),
-        Key::F2 => "F2".to_string(),
-        Key::F3 => "F3".to_string(),
-        Key::F4 => "F4".to_string(),
-        Key::F5 => "F5".to_string(),
-        Key::F6 => "F6".to_string(),
-        Key::F7 => "F7".to_string(),
-        Key::F8 => "F8".to_string(),
-        Key::F9 => "F9".to_string(),
-        Key::F10 => "F10".to_string(),
-        Key::F11 => "F11".to_string(),
-        Key::F12 => "F12".to_string(),
-        Key::Escape => "Escape".to_string(),
-        Key::Space => "Space".to_string(),
-        Key::ControlLeft => "LeftControl".to_string(),
-        Key::ControlRight => "RightControl".to_string(),
-        Key::ShiftLeft => "LeftShift".to_string(),
-        Key::ShiftRight => "RightShift".to_string(),
-        Key::Alt => "Alt".to_string(),
-        Key::AltGr => "AltGr".to_string(),
-        Key::MetaLeft => "LeftMeta".to_string(),
-        Key::MetaRight => "RightMeta".to_string(),
-        Key::Return => "Enter".to_string(),
-        Key::UpArrow => "Up".to_string(),
-        Key::DownArrow => "Down".to_string(),
-        Key::LeftArrow => "Left".to_string(),
-        Key::RightArrow => "Right".to_string(),
-        Key::Backspace => "Backspace".to_string(),
-        Key::CapsLock => "CapsLock".to_string(),
-        Key::Tab => "Tab".to_string(),
-        Key::Home => "Home".to_string(),
-        Key::End => "End".to_string(),
-        Key::PageUp => "PageUp".to_string(),
-        Key::PageDown => "PageDown".to_string(),
-        Key::Insert => "Insert".to_string(),
-        Key::Delete => "Delete".to_string(),
-        Key::KpMinus => "NumpadSubtract".to_string(),
-        Key::KpPlus => "NumpadAdd".to_string(),
-        Key::KpDivide => "NumpadDivide".to_string(),
-        Key::KpMultiply => "NumpadMultiply".to_string(),
-        Key::BackQuote => "Grave".to_string(),
-        Key::Minus => "Minus".to_string(),
-        Key::Equal => "Equal".to_string(),
-        Key::LeftBracket => "LeftBracket".to_string(),
-        Key::RightBracket => "RightBracket".to_string(),
-        Key::BackSlash => "BackSlash".to_string(),
-        Key::SemiColon => "Semicolon".to_string(),
-        Key::Quote => "Apostrophe".to_string(),
-        Key::Comma => "Comma".to_string(),
-        Key::Dot => "Dot".to_string(),
-        Key::Slash => "Slash".to_string(),
-        _ => format!("{:?}", key),
+    keys::name_of(key)
+}
+
+/// 监听后端报告的滚动增量单位
+#[cfg(target_os = "macos")]
+const WHEEL_UNIT: ScrollUnit = ScrollUnit::Pixels;
+#[cfg(not(target_os = "macos"))]
+const WHEEL_UNIT: ScrollUnit = ScrollUnit::Lines;
+
+/// 原始滚动增量换算为行
+fn scroll_lines(raw: i64, unit: ScrollUnit) -> f64 {
+    match unit {
+        ScrollUnit::Lines => raw as f64,
+        ScrollUnit::Pixels => raw as f64 / PIXELS_PER_SCROLL_LINE,
     }
 }
 
@@ -474,19 +1697,105 @@ fn map_button(button: rdev::Button) -> MouseButton {
     }
 }
 
-/// 获取鼠标移动采样间隔（微秒）。
-/// 优先读取环境变量 IRIS_MOUSE_MOVE_INTERVAL_US，值需为正整数。
-fn mouse_move_interval_micros() -> u128 {
-    static INTERVAL: OnceLock<u128> = OnceLock::new();
-    *INTERVAL.get_or_init(|| {
-        env::var("IRIS_MOUSE_MOVE_INTERVAL_US")
-            .ok()
-            .and_then(|v| v.parse::<u128>().ok())
-            .filter(|v| *v > 0)
-            .unwrap_or(DEFAULT_MOUSE_MOVE_INTERVAL_MICROS)
+/// 鼠标移动的采样策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SamplingStrategy {
+    /// 按住按钮时使用细粒度间隔以保留拖拽轨迹，普通移动使用粗粒度间隔
+    Adaptive,
+    /// 始终使用同一间隔
+    Fixed,
+    /// 记录每一个移动事件
+    All,
+}
+
+/// 鼠标移动采样配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SamplingConfig {
+    pub strategy: SamplingStrategy,
+    /// 未按住按钮时的采样间隔（微秒）；`fixed` 策略下用于所有移动
+    pub move_interval_micros: u128,
+    /// 按住按钮时的采样间隔（微秒），仅 `adaptive` 策略使用
+    pub drag_interval_micros: u128,
+}
+
+/// 读取鼠标移动采样配置：`IRIS_MOUSE_SAMPLING` 选择策略（adaptive / fixed / all，默认 adaptive），
+/// `IRIS_MOUSE_MOVE_INTERVAL_US` 与 `IRIS_MOUSE_DRAG_INTERVAL_US` 覆盖间隔，值需为正整数
+pub fn mouse_sampling() -> SamplingConfig {
+    static CONFIG: OnceLock<SamplingConfig> = OnceLock::new();
+    *CONFIG.get_or_init(|| {
+        let interval = |var: &str, default: u128| {
+            env::var(var)
+                .ok()
+                .and_then(|v| v.trim().parse::<u128>().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(default)
+        };
+        let strategy = match env::var("IRIS_MOUSE_SAMPLING").unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+            "fixed" => SamplingStrategy::Fixed,
+            "all" => SamplingStrategy::All,
+            _ => SamplingStrategy::Adaptive,
+        };
+        let default_move = match strategy {
+            SamplingStrategy::Fixed => DEFAULT_MOUSE_MOVE_INTERVAL_MICROS,
+            _ => DEFAULT_MOUSE_HOVER_INTERVAL_MICROS,
+        };
+        SamplingConfig {
+            strategy,
+            move_interval_micros: interval("IRIS_MOUSE_MOVE_INTERVAL_US", default_move),
+            drag_interval_micros: interval("IRIS_MOUSE_DRAG_INTERVAL_US", DEFAULT_MOUSE_DRAG_INTERVAL_MICROS),
+        }
     })
 }
 
+/// 在监听回调中决定是否记录鼠标移动；按下的按钮以位掩码跟踪，不持有锁
+struct MoveSampler {
+    config: SamplingConfig,
+    last_move_micros: AtomicU64,
+    buttons_down: AtomicU32,
+}
+
+impl MoveSampler {
+    fn new(config: SamplingConfig) -> Self {
+        MoveSampler { config, last_move_micros: AtomicU64::new(0), buttons_down: AtomicU32::new(0) }
+    }
+
+    fn bit(button: MouseButton) -> u32 {
+        match button {
+            MouseButton::Left => 1,
+            MouseButton::Right => 1 << 1,
+            MouseButton::Middle => 1 << 2,
+            MouseButton::Other(n) => 1 << (3 + u32::from(n) % 29),
+        }
+    }
+
+    fn press(&self, button: MouseButton) {
+        self.buttons_down.fetch_or(Self::bit(button), Ordering::Relaxed);
+    }
+
+    fn release(&self, button: MouseButton) {
+        self.buttons_down.fetch_and(!Self::bit(button), Ordering::Relaxed);
+    }
+
+    /// 距上次记录的移动超过当前采样间隔时记录
+    fn sample(&self, timestamp: u128) -> bool {
+        let interval = match self.config.strategy {
+            SamplingStrategy::All => return true,
+            SamplingStrategy::Fixed => self.config.move_interval_micros,
+            SamplingStrategy::Adaptive if self.buttons_down.load(Ordering::Relaxed) != 0 => {
+                self.config.drag_interval_micros
+            }
+            SamplingStrategy::Adaptive => self.config.move_interval_micros,
+        };
+        let last = self.last_move_micros.load(Ordering::Relaxed) as u128;
+        if timestamp.saturating_sub(last) < interval {
+            return false;
+        }
+        self.last_move_micros.store(timestamp as u64, Ordering::Relaxed);
+        true
+    }
+}
+
 /// 获取监听器锁文件路径
 fn get_lock_file_path() -> PathBuf {
     let mut path = env::temp_dir();
@@ -494,41 +1803,234 @@ fn get_lock_file_path() -> PathBuf {
     path
 }
 
-/// 尝试获取监听器锁，返回是否成功
+/// 新建的锁文件在该时间内内容为空视为持有者尚未写入 PID，不当作陈旧锁回收
+const LOCK_WRITE_GRACE: Duration = Duration::from_secs(2);
+
+/// 通过 `--force-monitor` 设置：锁被存活的进程持有时也接管
+static FORCE_MONITOR: AtomicBool = AtomicBool::new(false);
+
+/// 设置是否强制接管监听器锁；需在监控初始化前调用
+pub fn set_force_monitor(force: bool) {
+    FORCE_MONITOR.store(force, Ordering::SeqCst);
+}
+
+/// 尝试获取监听器锁，返回是否成功。
+/// 锁文件记录持有者 PID；持有者已退出（崩溃后遗留）或内容无法解析时视为陈旧锁并回收，
+/// 设置了 `--force-monitor` 时即使持有者仍在运行也接管
 fn try_acquire_lock() -> bool {
     let lock_path = get_lock_file_path();
     let pid = std::process::id();
-    
-    // 检查锁文件是否存在
-    if lock_path.exists() {
-        // 读取锁文件中的 PID
-        if let Ok(content) = fs::read_to_string(&lock_path) {
-            if let Ok(locked_pid) = content.trim().parse::<u32>() {
-                // 检查该进程是否还活着（简单检查：如果是自己的 PID 就认为已锁定）
-                if locked_pid == pid {
-                    return true; // 已经是自己持有锁
+
+    // 回收陈旧锁后重试；create_new 保证并发启动的进程中只有一个能创建锁文件
+    for _ in 0..3 {
+        match fs::OpenOptions::new().write(true).create_new(true).open(&lock_path) {
+            Ok(mut file) => {
+                use std::io::Write;
+                if let Err(e) = write!(file, "{}", pid) {
+                    eprintln!("[monitor_key_mouse][PID:{}] Failed to write lock: {}", pid, e);
+                    let _ = fs::remove_file(&lock_path);
+                    return false;
+                }
+                eprintln!("[monitor_key_mouse][PID:{}] Acquired lock at {:?}", pid, lock_path);
+                return true;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let read_pid = || {
+                    fs::read_to_string(&lock_path)
+                        .ok()
+                        .and_then(|content| content.trim().parse::<u32>().ok())
+                };
+                let locked_pid = read_pid();
+                match locked_pid {
+                    Some(locked_pid) if locked_pid == pid => return true, // 已经是自己持有锁
+                    Some(locked_pid) if process_alive(locked_pid) => {
+                        if !FORCE_MONITOR.load(Ordering::SeqCst) {
+                            eprintln!(
+                                "[monitor_key_mouse][PID:{}] Lock file exists with live PID:{}; pass --force-monitor to take over",
+                                pid, locked_pid
+                            );
+                            return false;
+                        }
+                        eprintln!("[monitor_key_mouse][PID:{}] Taking over lock from live PID:{} (--force-monitor)", pid, locked_pid);
+                    }
+                    Some(locked_pid) => {
+                        eprintln!("[monitor_key_mouse][PID:{}] Reclaiming stale lock left by PID:{}", pid, locked_pid);
+                    }
+                    None => {
+                        // 其他进程刚创建锁文件、尚未写入 PID 时内容同样为空
+                        let fresh = fs::metadata(&lock_path)
+                            .and_then(|m| m.modified())
+                            .is_ok_and(|t| t.elapsed().is_ok_and(|age| age < LOCK_WRITE_GRACE));
+                        if fresh {
+                            eprintln!("[monitor_key_mouse][PID:{}] Lock file is being written by another process", pid);
+                            return false;
+                        }
+                        eprintln!("[monitor_key_mouse][PID:{}] Reclaiming unreadable lock file", pid);
+                    }
+                }
+                // 删除前再读一次：其间另一个进程可能已回收并写入了新的锁，此时重新判断而不是删掉它
+                if read_pid() != locked_pid {
+                    continue;
                 }
-                eprintln!("[monitor_key_mouse][PID:{}] Lock file exists with PID:{}", pid, locked_pid);
+                let _ = fs::remove_file(&lock_path);
+            }
+            Err(e) => {
+                eprintln!("[monitor_key_mouse][PID:{}] Failed to acquire lock: {}", pid, e);
                 return false;
             }
         }
     }
-    
-    // 尝试创建锁文件
-    match fs::write(&lock_path, pid.to_string()) {
-        Ok(_) => {
-            eprintln!("[monitor_key_mouse][PID:{}] Acquired lock at {:?}", pid, lock_path);
-            true
-        }
-        Err(e) => {
-            eprintln!("[monitor_key_mouse][PID:{}] Failed to acquire lock: {}", pid, e);
-            false
-        }
-    }
+    eprintln!("[monitor_key_mouse][PID:{}] Lost the race for the monitor lock", pid);
+    false
 }
 
-/// 释放监听器锁
+/// 释放监听器锁；锁已被其他进程接管时保留
 fn release_lock() {
     let lock_path = get_lock_file_path();
-    let _ = fs::remove_file(&lock_path);
+    let owned = fs::read_to_string(&lock_path)
+        .ok()
+        .and_then(|content| content.trim().parse::<u32>().ok())
+        == Some(std::process::id());
+    if owned {
+        let _ = fs::remove_file(&lock_path);
+    }
+}
+
+/// 进程是否仍在运行
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    unsafe extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    // 信号 0 只做存在性与权限检查；EPERM 表示进程存在但属于其他用户
+    const EPERM: i32 = 1;
+    let result = unsafe { kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(EPERM)
+}
+
+/// 进程是否仍在运行
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const STILL_ACTIVE: u32 = 259;
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut std::ffi::c_void;
+        fn GetExitCodeProcess(process: *mut std::ffi::c_void, code: *mut u32) -> i32;
+        fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+    }
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            // 拒绝访问说明进程存在（如以管理员身份运行）
+            return std::io::Error::last_os_error().raw_os_error() == Some(ERROR_ACCESS_DENIED);
+        }
+        let mut code = 0;
+        let alive = GetExitCodeProcess(handle, &mut code) != 0 && code == STILL_ACTIVE;
+        CloseHandle(handle);
+        alive
+    }
+}
+
+/// 无法判断时按存活处理，不回收锁
+#[cfg(not(any(unix, windows)))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 仅带时间戳的测试事件，时间戳取推入顺序
+    #[derive(Debug, Clone, PartialEq)]
+    struct Ev(u128);
+
+    impl Timestamped for Ev {
+        fn timestamp_micros(&self) -> u128 {
+            self.0
+        }
+    }
+
+    fn ring(capacity: usize, count: u128) -> EventRing<Ev> {
+        let mut ring = EventRing::new(capacity);
+        for t in 0..count {
+            ring.push(Ev(t));
+        }
+        ring
+    }
+
+    fn seqs(batch: &EventBatch<Ev>) -> Vec<u64> {
+        batch.events.iter().map(|(seq, _)| *seq).collect()
+    }
+
+    #[test]
+    fn reads_in_order_after_wraparound() {
+        let mut ring = ring(4, 10);
+        let batch = ring.read(ReadMode::Peek, TimeRange::default());
+        assert_eq!(seqs(&batch), vec![6, 7, 8, 9]);
+        assert_eq!(batch.events.iter().map(|(_, e)| e.0).collect::<Vec<_>>(), vec![6, 7, 8, 9]);
+        assert_eq!(batch.next_cursor, 10);
+        assert_eq!(batch.evicted_count, 6);
+        assert_eq!(batch.covered, Some(Coverage { since_micros: 6, until_micros: 9 }));
+
+        // 游标位于缓冲区内时只返回之后的事件，不计丢失
+        let batch = ring.read(ReadMode::From(8), TimeRange::default());
+        assert_eq!(seqs(&batch), vec![8, 9]);
+        assert_eq!(batch.missed, 0);
+
+        // 时间筛选作用于覆盖后仍在缓冲区中的事件
+        let range = TimeRange { since_micros: Some(3), until_micros: Some(7) };
+        assert_eq!(seqs(&ring.read(ReadMode::Peek, range)), vec![6, 7]);
+    }
+
+    #[test]
+    fn slow_consumer_reports_missed_events() {
+        let mut ring = ring(4, 3);
+        let first = ring.read(ReadMode::From(0), TimeRange::default());
+        assert_eq!(seqs(&first), vec![0, 1, 2]);
+        assert_eq!(first.missed, 0);
+
+        // 消费者停在游标 3 时又写入 7 个事件，其中 3..6 已被覆盖
+        for t in 3..10 {
+            ring.push(Ev(t));
+        }
+        let batch = ring.read(ReadMode::From(first.next_cursor), TimeRange::default());
+        assert_eq!(seqs(&batch), vec![6, 7, 8, 9]);
+        assert_eq!(batch.missed, 3);
+        assert_eq!(batch.evicted_count, 6);
+        assert_eq!(batch.next_cursor, 10);
+
+        // 跟上之后不再报告丢失
+        let caught_up = ring.read(ReadMode::From(batch.next_cursor), TimeRange::default());
+        assert!(caught_up.events.is_empty());
+        assert_eq!(caught_up.missed, 0);
+    }
+
+    #[test]
+    fn take_mode_loses_events_overwritten_between_reads() {
+        let mut ring = ring(2, 2);
+        assert_eq!(seqs(&ring.read(ReadMode::Take, TimeRange::default())), vec![0, 1]);
+        for t in 2..7 {
+            ring.push(Ev(t));
+        }
+        let batch = ring.read(ReadMode::Take, TimeRange::default());
+        assert_eq!(seqs(&batch), vec![5, 6]);
+        assert_eq!(batch.missed, 3);
+        assert!(ring.read(ReadMode::Take, TimeRange::default()).events.is_empty());
+    }
+
+    #[test]
+    fn shrinking_counts_discarded_events_as_evicted() {
+        let mut ring = ring(8, 6);
+        ring.resize(2);
+        let batch = ring.read(ReadMode::From(0), TimeRange::default());
+        assert_eq!(seqs(&batch), vec![4, 5]);
+        assert_eq!(batch.missed, 4);
+        assert_eq!(ring.stats().evicted_count, 4);
+    }
 }
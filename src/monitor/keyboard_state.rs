@@ -0,0 +1,95 @@
+//! 修饰键与锁定键状态：修饰键取自键鼠监控记录的按下/释放事件，
+//! CapsLock / NumLock 的锁定状态向系统查询（监控只能看到切换，无法得知初始状态）。
+
+use super::key_mouse;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyboardState {
+    /// 键鼠监控是否在本进程运行且采集键盘；为 false 时修饰键状态未知
+    pub monitoring: bool,
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub meta: bool,
+    /// 当前按下的全部键（名称同键盘监控）
+    pub held_keys: Vec<String>,
+    /// 大写锁定；系统不支持查询时为 None
+    pub caps_lock: Option<bool>,
+    /// 数字锁定；系统不支持查询时为 None
+    pub num_lock: Option<bool>,
+}
+
+impl KeyboardState {
+    /// 是否有修饰键按下
+    pub fn any_modifier(&self) -> bool {
+        self.shift || self.control || self.alt || self.meta
+    }
+}
+
+pub fn keyboard_state() -> KeyboardState {
+    let held = key_mouse::held_keys();
+    let monitoring = held.is_some();
+    let held_keys = held.unwrap_or_default();
+    let down = |names: &[&str]| held_keys.iter().any(|k| names.contains(&k.as_str()));
+    let (caps_lock, num_lock) = platform::lock_state();
+
+    KeyboardState {
+        monitoring,
+        shift: down(&["Shift", "LeftShift", "RightShift"]),
+        control: down(&["Control", "LeftControl", "RightControl"]),
+        alt: down(&["Alt", "LeftAlt", "RightAlt", "AltGr"]),
+        meta: down(&["Meta", "LeftMeta", "RightMeta"]),
+        caps_lock,
+        num_lock,
+        held_keys,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    /// kCGEventSourceStateHIDSystemState
+    const HID_SYSTEM_STATE: i32 = 1;
+    /// kCGEventFlagMaskAlphaShift
+    const FLAG_ALPHA_SHIFT: u64 = 0x0001_0000;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventSourceFlagsState(state_id: i32) -> u64;
+    }
+
+    /// macOS 键盘没有数字锁定
+    pub fn lock_state() -> (Option<bool>, Option<bool>) {
+        let flags = unsafe { CGEventSourceFlagsState(HID_SYSTEM_STATE) };
+        (Some(flags & FLAG_ALPHA_SHIFT != 0), None)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt as _, KeyButMask};
+
+    /// 通过 X11 指针查询的修饰掩码读取：Lock 为大写锁定，Mod2 通常映射为数字锁定
+    pub fn lock_state() -> (Option<bool>, Option<bool>) {
+        let Ok((conn, screen_num)) = x11rb::connect(None) else {
+            return (None, None);
+        };
+        let root = conn.setup().roots[screen_num].root;
+        match conn.query_pointer(root).ok().and_then(|c| c.reply().ok()) {
+            Some(reply) => (
+                Some(reply.mask.contains(KeyButMask::LOCK)),
+                Some(reply.mask.contains(KeyButMask::MOD2)),
+            ),
+            None => (None, None),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+mod platform {
+    /// Windows: 待实现 GetKeyState(VK_CAPITAL / VK_NUMLOCK)
+    pub fn lock_state() -> (Option<bool>, Option<bool>) {
+        (None, None)
+    }
+}
@@ -1,3 +1,23 @@
+pub mod accessibility;
+pub mod annotate;
+pub mod cursor;
+pub mod display;
+pub mod event_log;
+#[cfg(target_os = "linux")]
+pub mod evdev;
+pub mod gestures;
 pub mod key_mouse;
+pub mod keyboard_state;
+pub mod ocr;
+pub mod postprocess;
+pub mod privacy;
+pub mod recorder;
+pub mod scaling;
 pub mod screen;
+pub mod screen_recording;
+#[cfg(target_os = "macos")]
+pub mod screencapturekit;
+pub mod strokes;
+#[cfg(target_os = "linux")]
+pub mod wayland;
 // pub mod state;  // 已废弃：事件存储现在直接在 key_mouse 模块中处理
@@ -0,0 +1,129 @@
+//! 屏幕文字识别：把截图交给 tesseract 识别，返回按行分组的文字及其边界框（截图像素坐标）。
+//!
+//! tesseract 在 PATH 中查找，或由环境变量 `IRIS_TESSERACT` 指定；识别语言默认取 `IRIS_OCR_LANG`，
+//! 未设置时为 `eng`（多种语言用 `+` 连接，如 `chi_sim+eng`，需安装对应的语言数据）。
+
+use super::screen::MonitorError;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 截图像素坐标系中的矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TextBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextBox {
+    /// 同时包含两个矩形的最小矩形
+    pub fn union(self, other: TextBox) -> TextBox {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        TextBox { x, y, width: right - x, height: bottom - y }
+    }
+
+    /// 中心点
+    pub fn center(&self) -> (f64, f64) {
+        (self.x as f64 + self.width as f64 / 2.0, self.y as f64 + self.height as f64 / 2.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OcrWord {
+    pub text: String,
+    /// 置信度 0–100
+    pub confidence: f64,
+    pub bbox: TextBox,
+}
+
+/// 一行文字；`text` 为各词以空格连接，`confidence` 为各词的平均值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OcrLine {
+    pub text: String,
+    pub confidence: f64,
+    pub bbox: TextBox,
+    pub words: Vec<OcrWord>,
+}
+
+/// 默认识别语言
+pub fn default_language() -> String {
+    env::var("IRIS_OCR_LANG").ok().filter(|l| !l.trim().is_empty()).unwrap_or_else(|| "eng".to_string())
+}
+
+/// 识别 PNG 图像中的文字，丢弃置信度低于 `min_confidence` 的词
+pub fn recognize(png: &[u8], language: &str, min_confidence: f64) -> Result<Vec<OcrLine>, MonitorError> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let input = env::temp_dir().join(format!(
+        "iris-ocr-{}-{}.png",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&input, png).map_err(|e| MonitorError::Io(format!("write {}: {}", input.display(), e)))?;
+
+    let tesseract = env::var("IRIS_TESSERACT").unwrap_or_else(|_| "tesseract".to_string());
+    let output = Command::new(&tesseract).arg(&input).args(["stdout", "-l", language, "tsv"]).output();
+    let _ = fs::remove_file(&input);
+    let output = output.map_err(|e| {
+        MonitorError::Io(format!("OCR requires tesseract ({}: {}); install it or set IRIS_TESSERACT", tesseract, e))
+    })?;
+    if !output.status.success() {
+        return Err(MonitorError::Io(format!(
+            "tesseract exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout), min_confidence))
+}
+
+/// 解析 tesseract 的 TSV 输出：
+/// `level page_num block_num par_num line_num word_num left top width height conf text`，
+/// 只取词级（level 5）记录，按 (block, par, line) 分组为行
+fn parse_tsv(tsv: &str, min_confidence: f64) -> Vec<OcrLine> {
+    let mut lines: Vec<((u32, u32, u32), OcrLine)> = Vec::new();
+    for row in tsv.lines().skip(1) {
+        let fields: Vec<&str> = row.splitn(12, '\t').collect();
+        if fields.len() < 12 || fields[0] != "5" {
+            continue;
+        }
+        let text = fields[11].trim();
+        let number = |i: usize| fields[i].trim().parse::<u32>().ok();
+        let confidence = fields[10].trim().parse::<f64>().unwrap_or(-1.0);
+        if text.is_empty() || confidence < min_confidence {
+            continue;
+        }
+        let (Some(block), Some(par), Some(line), Some(x), Some(y), Some(width), Some(height)) =
+            (number(2), number(3), number(4), number(6), number(7), number(8), number(9))
+        else {
+            continue;
+        };
+        let word = OcrWord { text: text.to_string(), confidence, bbox: TextBox { x, y, width, height } };
+        let key = (block, par, line);
+        match lines.last_mut() {
+            Some((last, current)) if *last == key => {
+                current.text.push(' ');
+                current.text.push_str(&word.text);
+                current.bbox = current.bbox.union(word.bbox);
+                current.words.push(word);
+            }
+            _ => lines.push((
+                key,
+                OcrLine { text: word.text.clone(), confidence: 0.0, bbox: word.bbox, words: vec![word] },
+            )),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(_, mut line)| {
+            line.confidence = line.words.iter().map(|w| w.confidence).sum::<f64>() / line.words.len() as f64;
+            line
+        })
+        .collect()
+}
@@ -0,0 +1,341 @@
+//! 截图后处理：裁剪、高对比度增强、放大与缩小、灰度与调色板输出等，作用于 `capture_frame()` 返回的 PNG 数据。
+
+use super::screen::MonitorError;
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::{imageops, DynamicImage, GrayImage};
+use std::env;
+use std::sync::OnceLock;
+
+/// 高对比度模式拉伸时两端各忽略的像素比例，避免少量极值像素影响拉伸效果
+const CONTRAST_CLIP_FRACTION: f64 = 0.01;
+
+/// 允许的最大放大倍数
+pub const MAX_UPSCALE: u32 = 4;
+
+/// 放大后图像的最大像素数（约 4K 的 4 倍），避免超大截图放大时耗尽内存
+pub const MAX_UPSCALED_PIXELS: u64 = 36_000_000;
+
+/// 调色板输出的颜色数范围与默认值
+pub const MIN_PALETTE_COLORS: usize = 2;
+pub const MAX_PALETTE_COLORS: usize = 256;
+pub const DEFAULT_PALETTE_COLORS: usize = 16;
+
+/// 量化直方图每个通道保留的位数
+const QUANT_BITS: u32 = 5;
+
+/// 画面指纹（缩小后的灰度图）长边的像素数
+const FINGERPRINT_SIZE: u32 = 160;
+
+/// 指纹像素灰度差超过该值才计为变化，忽略缩放与抗锯齿带来的细微差异
+const FINGERPRINT_TOLERANCE: u8 = 12;
+
+/// 图像像素坐标系中的矩形区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub fn decode(data: &[u8]) -> Result<DynamicImage, MonitorError> {
+    image::load_from_memory(data).map_err(|e| MonitorError::Io(format!("decode failed: {}", e)))
+}
+
+/// PNG 编码档位，由环境变量 `IRIS_PNG_COMPRESSION` 选择（`fast` / `balanced` / `best`）。
+/// 高分辨率截图的编码耗时远超截图本身，默认 `fast`：固定 Up 滤波加快速 DEFLATE，
+/// 体积略大但编码快数倍；`balanced` / `best` 逐行自适应选择滤波并提高压缩级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngCompression {
+    Fast,
+    Balanced,
+    Best,
+}
+
+pub fn png_compression() -> PngCompression {
+    static COMPRESSION: OnceLock<PngCompression> = OnceLock::new();
+    *COMPRESSION.get_or_init(|| match env::var("IRIS_PNG_COMPRESSION").as_deref().map(str::trim) {
+        Ok("balanced") => PngCompression::Balanced,
+        Ok("best") => PngCompression::Best,
+        Ok("fast") | Err(_) => PngCompression::Fast,
+        Ok(other) => {
+            eprintln!("[screen] unknown IRIS_PNG_COMPRESSION {:?}, using fast", other);
+            PngCompression::Fast
+        }
+    })
+}
+
+/// 编码为 PNG；完全不透明的 RGBA 图像（桌面截图）去掉 alpha 通道后编码，少处理四分之一的数据
+pub fn encode_png(img: &DynamicImage) -> Result<Vec<u8>, MonitorError> {
+    let (compression, filter) = match png_compression() {
+        PngCompression::Fast => (CompressionType::Fast, PngFilter::Up),
+        PngCompression::Balanced => (CompressionType::Default, PngFilter::Adaptive),
+        PngCompression::Best => (CompressionType::Best, PngFilter::Adaptive),
+    };
+    let opaque;
+    let img = match img {
+        DynamicImage::ImageRgba8(rgba) if rgba.pixels().all(|p| p[3] == u8::MAX) => {
+            opaque = DynamicImage::ImageRgb8(img.to_rgb8());
+            &opaque
+        }
+        _ => img,
+    };
+    let mut data = Vec::new();
+    img.write_with_encoder(PngEncoder::new_with_quality(&mut data, compression, filter))
+        .map_err(|e| MonitorError::Io(format!("encode failed: {}", e)))?;
+    Ok(data)
+}
+
+/// 裁剪到指定区域（超出图像部分会被截断）
+pub fn crop(img: &DynamicImage, region: Region) -> Result<DynamicImage, MonitorError> {
+    if region.x >= img.width() || region.y >= img.height() || region.width == 0 || region.height == 0 {
+        return Err(MonitorError::Io(format!(
+            "region {:?} outside image {}x{}",
+            region,
+            img.width(),
+            img.height()
+        )));
+    }
+    let width = region.width.min(img.width() - region.x);
+    let height = region.height.min(img.height() - region.y);
+    Ok(img.crop_imm(region.x, region.y, width, height))
+}
+
+/// 灰度化 + 对比度拉伸 + 放大，提升小字号界面文本的 OCR 识别率
+pub fn high_contrast(img: &DynamicImage, upscale: u32) -> Result<DynamicImage, MonitorError> {
+    let mut gray = img.to_luma8();
+    stretch_contrast(&mut gray);
+    upscale_by(&DynamicImage::ImageLuma8(gray), upscale)
+}
+
+/// 按整数倍放大（倍数钳制在 1–MAX_UPSCALE）；放大后超过 MAX_UPSCALED_PIXELS 时返回错误
+pub fn upscale_by(img: &DynamicImage, upscale: u32) -> Result<DynamicImage, MonitorError> {
+    let factor = upscale.clamp(1, MAX_UPSCALE);
+    if factor == 1 {
+        return Ok(img.clone());
+    }
+    let (width, height) = (img.width() * factor, img.height() * factor);
+    if width as u64 * height as u64 > MAX_UPSCALED_PIXELS {
+        return Err(MonitorError::Io(format!(
+            "upscaled image {}x{} exceeds {} pixels; crop a region or lower upscale",
+            width, height, MAX_UPSCALED_PIXELS
+        )));
+    }
+    Ok(img.resize_exact(width, height, imageops::FilterType::CatmullRom))
+}
+
+/// 等比缩小到不超过 `max_width` × `max_height`（任一为 None 表示该方向不限）；已满足时返回 None
+pub fn fit_within(img: &DynamicImage, max_width: Option<u32>, max_height: Option<u32>) -> Option<DynamicImage> {
+    let factor = [
+        max_width.map(|w| w as f64 / img.width() as f64),
+        max_height.map(|h| h as f64 / img.height() as f64),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(1.0, f64::min);
+    if factor >= 1.0 {
+        return None;
+    }
+    Some(downscale(img, factor))
+}
+
+/// 按比例缩小（0 < factor < 1），每边至少保留 1 像素
+pub fn downscale(img: &DynamicImage, factor: f64) -> DynamicImage {
+    let width = ((img.width() as f64 * factor).round() as u32).max(1);
+    let height = ((img.height() as f64 * factor).round() as u32).max(1);
+    img.resize_exact(width, height, imageops::FilterType::Triangle)
+}
+
+/// 画面指纹：缩小到长边不超过 `FINGERPRINT_SIZE` 的灰度图，用于近似比较两帧是否相同
+pub fn fingerprint(img: &DynamicImage) -> GrayImage {
+    let factor = FINGERPRINT_SIZE as f64 / img.width().max(img.height()) as f64;
+    let small = if factor < 1.0 { downscale(img, factor) } else { img.clone() };
+    small.to_luma8()
+}
+
+/// 两个指纹中发生变化的像素比例（0–1）；尺寸不同时返回 None
+pub fn difference_ratio(a: &GrayImage, b: &GrayImage) -> Option<f64> {
+    if a.dimensions() != b.dimensions() || a.is_empty() {
+        return None;
+    }
+    let changed = a.pixels().zip(b.pixels()).filter(|(p, q)| p[0].abs_diff(q[0]) > FINGERPRINT_TOLERANCE).count();
+    Some(changed as f64 / (a.width() * a.height()) as f64)
+}
+
+/// 编码为灰度 PNG（8 位单通道）
+pub fn encode_grayscale_png(img: &DynamicImage) -> Result<Vec<u8>, MonitorError> {
+    encode_png(&DynamicImage::ImageLuma8(img.to_luma8()))
+}
+
+/// 量化为最多 `colors` 种颜色并编码为索引色 PNG；颜色数不超过 16 时按 1/2/4 位打包像素。
+/// 文本为主的界面颜色很少，压缩率远高于真彩色
+pub fn encode_palette_png(img: &DynamicImage, colors: usize) -> Result<Vec<u8>, MonitorError> {
+    let rgb = img.to_rgb8();
+    let (palette, lookup) = median_cut(&rgb, colors.clamp(MIN_PALETTE_COLORS, MAX_PALETTE_COLORS));
+
+    let depth = match palette.len() {
+        0..=2 => png::BitDepth::One,
+        3..=4 => png::BitDepth::Two,
+        5..=16 => png::BitDepth::Four,
+        _ => png::BitDepth::Eight,
+    };
+    let bits = depth as usize;
+    let (width, height) = rgb.dimensions();
+    let row_bytes = (width as usize * bits).div_ceil(8);
+    let mut packed = vec![0u8; row_bytes * height as usize];
+    for (y, row) in rgb.rows().enumerate() {
+        let out = &mut packed[y * row_bytes..(y + 1) * row_bytes];
+        for (x, pixel) in row.enumerate() {
+            let index = lookup[quant_key(pixel.0)];
+            let bit = x * bits;
+            out[bit / 8] |= index << (8 - bits - bit % 8);
+        }
+    }
+
+    let to_io = |e: png::EncodingError| MonitorError::Io(format!("encode failed: {}", e));
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(depth);
+    encoder.set_palette(palette.concat());
+    encoder.set_compression(match png_compression() {
+        PngCompression::Fast => png::Compression::Fast,
+        PngCompression::Balanced => png::Compression::Balanced,
+        PngCompression::Best => png::Compression::High,
+    });
+    let mut writer = encoder.write_header().map_err(to_io)?;
+    writer.write_image_data(&packed).map_err(to_io)?;
+    writer.finish().map_err(to_io)?;
+    Ok(data)
+}
+
+/// 每通道取高 `QUANT_BITS` 位拼成直方图下标
+fn quant_key([r, g, b]: [u8; 3]) -> usize {
+    let shift = 8 - QUANT_BITS;
+    ((r as usize >> shift) << (2 * QUANT_BITS)) | ((g as usize >> shift) << QUANT_BITS) | (b as usize >> shift)
+}
+
+/// 在降低精度的颜色直方图上做中位切分，返回调色板与“直方图下标 → 调色板索引”查找表
+fn median_cut(img: &image::RgbImage, colors: usize) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let shift = 8 - QUANT_BITS;
+    let mut histogram = vec![0u32; 1 << (3 * QUANT_BITS)];
+    for pixel in img.pixels() {
+        histogram[quant_key(pixel.0)] += 1;
+    }
+    let entries: Vec<([u8; 3], u32)> = histogram
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(key, count)| {
+            let channel = |offset: u32| (((key >> offset) & ((1 << QUANT_BITS) - 1)) << shift) as u8;
+            ([channel(2 * QUANT_BITS), channel(QUANT_BITS), channel(0)], *count)
+        })
+        .collect();
+
+    // 反复切分通道跨度最大的盒子，直到达到颜色数或无法再分
+    let mut boxes = vec![entries];
+    while boxes.len() < colors {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (i, widest_channel(b)))
+            .max_by_key(|(_, (_, range))| *range);
+        let Some((index, (channel, _))) = widest else {
+            break;
+        };
+        let mut entries = boxes.swap_remove(index);
+        entries.sort_unstable_by_key(|(color, _)| color[channel]);
+        let total: u64 = entries.iter().map(|(_, count)| *count as u64).sum();
+        let mut acc = 0;
+        let split = entries
+            .iter()
+            .position(|(_, count)| {
+                acc += *count as u64;
+                acc * 2 >= total
+            })
+            .map_or(1, |i| (i + 1).clamp(1, entries.len() - 1));
+        let upper = entries.split_off(split);
+        boxes.push(entries);
+        boxes.push(upper);
+    }
+
+    // 盒内按像素数加权平均作为调色板颜色（补回被截去的低位中点）
+    let half = (1u64 << shift) / 2;
+    let palette: Vec<[u8; 3]> = boxes
+        .iter()
+        .map(|entries| {
+            let total: u64 = entries.iter().map(|(_, count)| *count as u64).sum::<u64>().max(1);
+            let mean = |c: usize| {
+                let sum: u64 = entries.iter().map(|(color, count)| color[c] as u64 * *count as u64).sum();
+                (sum / total + half).min(255) as u8
+            };
+            [mean(0), mean(1), mean(2)]
+        })
+        .collect();
+
+    let mut lookup = vec![0u8; histogram.len()];
+    for (index, entries) in boxes.iter().enumerate() {
+        for (color, _) in entries {
+            lookup[quant_key(*color)] = index as u8;
+        }
+    }
+    (palette, lookup)
+}
+
+/// 返回跨度最大的通道及其跨度
+fn widest_channel(entries: &[([u8; 3], u32)]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let (min, max) = entries
+                .iter()
+                .fold((u8::MAX, u8::MIN), |(min, max), (color, _)| (min.min(color[c]), max.max(color[c])));
+            (c, max - min)
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap_or((0, 0))
+}
+
+/// 基于直方图分位数的线性对比度拉伸
+fn stretch_contrast(img: &mut GrayImage) {
+    let mut histogram = [0u64; 256];
+    for p in img.pixels() {
+        histogram[p[0] as usize] += 1;
+    }
+
+    let total: u64 = histogram.iter().sum();
+    let clip = (total as f64 * CONTRAST_CLIP_FRACTION) as u64;
+    let low = percentile(&histogram, clip);
+    let high = 255 - percentile_rev(&histogram, clip);
+    if high <= low {
+        return;
+    }
+
+    let range = (high - low) as f32;
+    for p in img.pixels_mut() {
+        let v = (p[0].saturating_sub(low as u8)) as f32 / range;
+        p[0] = (v.min(1.0) * 255.0).round() as u8;
+    }
+}
+
+fn percentile(histogram: &[u64; 256], clip: u64) -> usize {
+    let mut acc = 0;
+    for (i, count) in histogram.iter().enumerate() {
+        acc += count;
+        if acc > clip {
+            return i;
+        }
+    }
+    255
+}
+
+fn percentile_rev(histogram: &[u64; 256], clip: u64) -> usize {
+    let mut acc = 0;
+    for (i, count) in histogram.iter().rev().enumerate() {
+        acc += count;
+        if acc > clip {
+            return i;
+        }
+    }
+    255
+}
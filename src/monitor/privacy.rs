@@ -0,0 +1,188 @@
+//! 键盘监控的隐私策略：在事件进入缓冲区、持久化日志与订阅推送之前脱敏。
+//!
+//! - `full`：记录完整按键（默认）
+//! - `mask`：字母与数字键（含小键盘数字）记为 `*`，组合键中的字母数字部分同样替换
+//! - `counts`：所有按键名记为 `*`，不记录输入字符与组合键，只保留事件数量与时间
+//! - `disabled`：不采集键盘事件，鼠标监控不受影响
+//!
+//! 初始策略取自环境变量 `IRIS_KEYBOARD_PRIVACY`，运行时可通过 `set_policy` 切换。
+//! 切换只影响之后的事件，缓冲区中已有的事件保持原样。
+//!
+//! 注入敏感文本（`type_text` 的 `sensitive: true`）期间由 `sensitive_scope` 临时收紧到
+//! `counts`，使注入的按键不以明文进入缓冲区、日志与订阅；已是 `counts`/`disabled` 时不变。
+
+use super::key_mouse::{self, KeyEvent, KeyEventType};
+use serde::Serialize;
+use std::env;
+use std::sync::atomic::{AtomicU8, AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardPrivacy {
+    Full,
+    Mask,
+    Counts,
+    Disabled,
+}
+
+impl KeyboardPrivacy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "full" => Some(KeyboardPrivacy::Full),
+            "mask" => Some(KeyboardPrivacy::Mask),
+            "counts" => Some(KeyboardPrivacy::Counts),
+            "disabled" => Some(KeyboardPrivacy::Disabled),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeyboardPrivacy::Full => "full",
+            KeyboardPrivacy::Mask => "mask",
+            KeyboardPrivacy::Counts => "counts",
+            KeyboardPrivacy::Disabled => "disabled",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => KeyboardPrivacy::Mask,
+            2 => KeyboardPrivacy::Counts,
+            3 => KeyboardPrivacy::Disabled,
+            _ => KeyboardPrivacy::Full,
+        }
+    }
+}
+
+/// 脱敏后的占位符
+const MASK: &str = "*";
+
+/// 尚未初始化时的哨兵值
+const UNSET: u8 = u8::MAX;
+
+static POLICY: AtomicU8 = AtomicU8::new(UNSET);
+
+/// 当前策略；首次调用时读取 `IRIS_KEYBOARD_PRIVACY`，无法识别的值按 `full` 处理
+pub fn policy() -> KeyboardPrivacy {
+    match POLICY.load(Ordering::Relaxed) {
+        UNSET => {
+            let initial = env::var("IRIS_KEYBOARD_PRIVACY")
+                .ok()
+                .and_then(|v| KeyboardPrivacy::parse(&v))
+                .unwrap_or(KeyboardPrivacy::Full);
+            // 与并发的 set_policy 竞争时以已设置的值为准
+            let _ = POLICY.compare_exchange(UNSET, initial as u8, Ordering::Relaxed, Ordering::Relaxed);
+            KeyboardPrivacy::from_u8(POLICY.load(Ordering::Relaxed))
+        }
+        value => KeyboardPrivacy::from_u8(value),
+    }
+}
+
+pub fn set_policy(policy: KeyboardPrivacy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// 敏感输入结束后继续脱敏的时长：钩子线程处理注入事件略晚于注入本身
+const SENSITIVE_SETTLE: Duration = Duration::from_millis(500);
+
+/// 进行中的敏感输入数量
+static SENSITIVE_SCOPES: AtomicUsize = AtomicUsize::new(0);
+
+/// 最近一次敏感输入结束后的脱敏截止时刻（相对 `epoch()` 的毫秒数）
+static SENSITIVE_UNTIL_MS: AtomicU64 = AtomicU64::new(0);
+
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+/// 敏感输入期间的策略覆盖；释放后再保持 `SENSITIVE_SETTLE` 以覆盖尚未处理完的事件
+pub struct SensitiveScope(());
+
+/// 开始一段敏感输入：在 guard 释放前把键盘事件按 `counts` 脱敏
+pub fn sensitive_scope() -> SensitiveScope {
+    epoch();
+    SENSITIVE_SCOPES.fetch_add(1, Ordering::SeqCst);
+    SensitiveScope(())
+}
+
+impl Drop for SensitiveScope {
+    fn drop(&mut self) {
+        let until = (epoch().elapsed() + SENSITIVE_SETTLE).as_millis() as u64;
+        SENSITIVE_UNTIL_MS.fetch_max(until, Ordering::SeqCst);
+        SENSITIVE_SCOPES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn sensitive_active() -> bool {
+    SENSITIVE_SCOPES.load(Ordering::SeqCst) > 0
+        || epoch().elapsed().as_millis() < u128::from(SENSITIVE_UNTIL_MS.load(Ordering::SeqCst))
+}
+
+/// 实际用于脱敏的策略：敏感输入期间 `full`/`mask` 收紧为 `counts`
+fn effective_policy() -> KeyboardPrivacy {
+    match policy() {
+        KeyboardPrivacy::Full | KeyboardPrivacy::Mask if sensitive_active() => KeyboardPrivacy::Counts,
+        policy => policy,
+    }
+}
+
+/// 按当前策略（含敏感输入期间的覆盖）脱敏键盘事件；`disabled` 或 `counts` 下的组合键事件返回 None
+pub fn apply(mut event: KeyEvent) -> Option<KeyEvent> {
+    match effective_policy() {
+        KeyboardPrivacy::Full => {}
+        KeyboardPrivacy::Mask => {
+            event.key = mask_combo(&event.key);
+            if event.char.as_deref().is_some_and(|c| c.chars().any(char::is_alphanumeric)) {
+                event.char = Some(MASK.to_string());
+            }
+        }
+        KeyboardPrivacy::Counts => {
+            if event.event_type == KeyEventType::Combo {
+                return None;
+            }
+            event.key = MASK.to_string();
+            event.char = None;
+        }
+        KeyboardPrivacy::Disabled => return None,
+    }
+    Some(event)
+}
+
+/// 按当前策略脱敏按下的键名列表（供状态快照使用）。
+/// `counts` 下保留修饰键名，以便仍能发现卡住的修饰键；`disabled` 下返回空列表
+pub fn mask_held_keys(keys: Vec<String>) -> Vec<String> {
+    match effective_policy() {
+        KeyboardPrivacy::Full => keys,
+        KeyboardPrivacy::Mask => keys.iter().map(|key| mask_key(key)).collect(),
+        KeyboardPrivacy::Counts => keys
+            .into_iter()
+            .map(|key| if key_mouse::modifier_of(&key).is_some() { key } else { MASK.to_string() })
+            .collect(),
+        KeyboardPrivacy::Disabled => Vec::new(),
+    }
+}
+
+/// 是否为采集期间会被替换的字母数字键（`A`–`Z`、`0`–`9`、`Numpad0`–`Numpad9`）
+fn is_alphanumeric_key(key: &str) -> bool {
+    match key.strip_prefix("Numpad") {
+        Some(digit) => digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()),
+        None => key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
+
+fn mask_key(key: &str) -> String {
+    if is_alphanumeric_key(key) {
+        MASK.to_string()
+    } else {
+        key.to_string()
+    }
+}
+
+/// 逐段脱敏 `Control+C` 形式的组合键名；单个键名同样适用
+fn mask_combo(key: &str) -> String {
+    key.split('+').map(mask_key).collect::<Vec<_>>().join("+")
+}
@@ -0,0 +1,167 @@
+//! 键鼠录制：开始录制后，收集线程把监控到的事件连同相对录制开始的时间偏移记入当前录制，
+//! 停止时返回完整的录制，由调用方持久化；回放见 `operator::replay`。
+//!
+//! 自动重复（`Repeat`）与合成的组合键（`Combo`）事件不录制：回放按住与释放的时序即可重现它们。
+//! 默认不录制 iris-mcp 自身注入的输入，避免回放时把上一次回放也录进去。
+
+use super::key_mouse::{self, ButtonState, KeyEventType, MonitorEvent, MouseButton, MouseEventKind};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard, Once};
+
+/// 单个录制的事件数上限，超过后停止记录并标记为截断
+pub const MAX_RECORDED_EVENTS: usize = 100_000;
+
+/// 录制的一个输入动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedAction {
+    KeyPress { key: String },
+    KeyRelease { key: String },
+    MouseMove { x: i32, y: i32 },
+    ButtonPress { button: MouseButton },
+    ButtonRelease { button: MouseButton },
+    /// 滚动增量（行），向上 / 向右为正（同键鼠监控）
+    Scroll { delta_x: i32, delta_y: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// 相对录制开始的偏移（微秒）
+    pub offset_micros: u64,
+    #[serde(flatten)]
+    pub action: RecordedAction,
+}
+
+/// 一次完整的录制
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// 录制开始时间（Unix 微秒）
+    pub started_micros: u64,
+    /// 录制时长（微秒）
+    pub duration_micros: u64,
+    /// 是否因达到 `MAX_RECORDED_EVENTS` 而截断
+    pub truncated: bool,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// 录制哪些事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordOptions {
+    pub keyboard: bool,
+    pub mouse: bool,
+    /// 是否录制鼠标移动；不录制时回放的点击发生在回放时光标所在位置
+    pub mouse_moves: bool,
+    /// 是否录制 iris-mcp 注入的输入
+    pub include_synthetic: bool,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        RecordOptions { keyboard: true, mouse: true, mouse_moves: true, include_synthetic: false }
+    }
+}
+
+struct ActiveRecording {
+    name: String,
+    options: RecordOptions,
+    recording: Recording,
+    /// 尚未凑满一行的滚动量（行），macOS 触控板的细小像素滚动累积后再记录
+    scroll_residual: (f64, f64),
+}
+
+impl ActiveRecording {
+    fn record(&mut self, event: MonitorEvent<'_>) {
+        let (timestamp, synthetic, action) = match event {
+            MonitorEvent::Keyboard(e) if self.options.keyboard => {
+                let action = match e.event_type {
+                    KeyEventType::Press => RecordedAction::KeyPress { key: e.key.clone() },
+                    KeyEventType::Release => RecordedAction::KeyRelease { key: e.key.clone() },
+                    KeyEventType::Repeat | KeyEventType::Combo | KeyEventType::Gap { .. } => return,
+                };
+                (e.timestamp_micros, e.synthetic, action)
+            }
+            MonitorEvent::Mouse(e) if self.options.mouse => {
+                let action = match e.kind {
+                    MouseEventKind::Move { x, y } if self.options.mouse_moves => RecordedAction::MouseMove { x, y },
+                    MouseEventKind::Move { .. } => return,
+                    MouseEventKind::Button { button, state: ButtonState::Press } => RecordedAction::ButtonPress { button },
+                    MouseEventKind::Button { button, state: ButtonState::Release } => {
+                        RecordedAction::ButtonRelease { button }
+                    }
+                    MouseEventKind::Gap { .. } => return,
+                    MouseEventKind::Scroll { .. } if e.synthetic && !self.options.include_synthetic => return,
+                    MouseEventKind::Scroll { delta_x, delta_y, .. } => {
+                        let (x, y) = (self.scroll_residual.0 + delta_x, self.scroll_residual.1 + delta_y);
+                        let (whole_x, whole_y) = (x.trunc(), y.trunc());
+                        self.scroll_residual = (x - whole_x, y - whole_y);
+                        if whole_x == 0.0 && whole_y == 0.0 {
+                            return;
+                        }
+                        RecordedAction::Scroll { delta_x: whole_x as i32, delta_y: whole_y as i32 }
+                    }
+                };
+                (e.timestamp_micros, e.synthetic, action)
+            }
+            _ => return,
+        };
+        if synthetic && !self.options.include_synthetic {
+            return;
+        }
+        let recording = &mut self.recording;
+        // 开始录制前已在通道中的事件不计入
+        let Some(offset_micros) = (timestamp as u64).checked_sub(recording.started_micros) else {
+            return;
+        };
+        if recording.events.len() >= MAX_RECORDED_EVENTS {
+            recording.truncated = true;
+            return;
+        }
+        recording.events.push(RecordedEvent { offset_micros, action });
+    }
+}
+
+fn active() -> MutexGuard<'static, Option<ActiveRecording>> {
+    static ACTIVE: Mutex<Option<ActiveRecording>> = Mutex::new(None);
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 开始录制；已有录制进行中时返回其名称
+pub fn start(name: &str, options: RecordOptions) -> Result<(), String> {
+    static LISTENER: Once = Once::new();
+    LISTENER.call_once(|| {
+        key_mouse::add_event_listener(|event| {
+            if let Some(active) = active().as_mut() {
+                active.record(event);
+            }
+        })
+    });
+
+    let mut active = active();
+    if let Some(current) = active.as_ref() {
+        return Err(current.name.clone());
+    }
+    *active = Some(ActiveRecording {
+        name: name.to_string(),
+        options,
+        recording: Recording {
+            started_micros: key_mouse::now_micros(),
+            duration_micros: 0,
+            truncated: false,
+            events: Vec::new(),
+        },
+        scroll_residual: (0.0, 0.0),
+    });
+    Ok(())
+}
+
+/// 停止录制，返回录制名称与内容；没有进行中的录制时返回 None
+pub fn stop() -> Option<(String, Recording)> {
+    let ActiveRecording { name, mut recording, .. } = active().take()?;
+    recording.duration_micros = key_mouse::now_micros().saturating_sub(recording.started_micros);
+    Some((name, recording))
+}
+
+/// 进行中的录制名称
+pub fn current() -> Option<String> {
+    active().as_ref().map(|a| a.name.clone())
+}
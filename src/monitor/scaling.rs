@@ -0,0 +1,68 @@
+//! 物理像素与逻辑坐标之间的换算。
+//!
+//! Hi-DPI（如 Retina）显示器上，截图（以及 Windows 上的输入监控）使用物理像素，而鼠标操作使用逻辑坐标。
+//! 这里按各显示器的 backing scale factor 在两个方向上统一换算，物理坐标的约定见
+//! [`DisplayInfo::physical_bounds`]。显示器列表短时缓存，避免在事件热路径上反复枚举。
+
+use super::display::{self, DisplayInfo};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 显示器几何缓存的有效期
+const DISPLAY_CACHE_TTL: Duration = Duration::from_secs(2);
+
+struct Snapshot {
+    taken_at: Instant,
+    displays: Vec<DisplayInfo>,
+}
+
+fn cache() -> &'static Mutex<Option<Snapshot>> {
+    static CACHE: OnceLock<Mutex<Option<Snapshot>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// 当前显示器列表（缓存），枚举失败时为空
+fn displays() -> Vec<DisplayInfo> {
+    let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(snapshot) = cache.as_ref()
+        && snapshot.taken_at.elapsed() < DISPLAY_CACHE_TTL
+    {
+        return snapshot.displays.clone();
+    }
+    let displays = display::list_displays().unwrap_or_default();
+    *cache = Some(Snapshot {
+        taken_at: Instant::now(),
+        displays: displays.clone(),
+    });
+    displays
+}
+
+/// 丢弃缓存的显示器几何；显示器拓扑监控发现增减或缩放变化时自动调用
+pub fn invalidate() {
+    *cache().lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// 物理全局坐标 → 逻辑全局坐标。
+/// 按物理区域查找所在显示器，找不到时按主显示器换算；无法枚举显示器时原样返回。
+pub fn physical_to_logical(x: f64, y: f64) -> (i32, i32) {
+    let displays = displays();
+    let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+    match displays
+        .iter()
+        .find(|d| d.physical_bounds().contains(xi, yi))
+        .or_else(|| displays.first())
+    {
+        Some(d) => d.to_logical(x, y),
+        None => (x.round() as i32, y.round() as i32),
+    }
+}
+
+/// 逻辑全局坐标 → 物理全局坐标，与 [`physical_to_logical`] 互逆
+pub fn logical_to_physical(x: f64, y: f64) -> (i32, i32) {
+    let displays = displays();
+    let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+    match displays.iter().find(|d| d.contains(xi, yi)).or_else(|| displays.first()) {
+        Some(d) => d.to_physical(x, y),
+        None => (x.round() as i32, y.round() as i32),
+    }
+}
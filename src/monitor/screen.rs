@@ -1,17 +1,35 @@
-//! Cross-platform screen monitoring design (stubs per platform).
+//! Cross-platform screen monitoring design.
 //! Goals: detect display topology/geometry changes or periodic frame capture events.
-//! Current state: per-platform stubs returning NotImplemented but compiling everywhere.
-
+//! Current state: on-demand frame capture on macOS (ScreenCaptureKit stream with a CGDisplay
+//! fallback) and Windows (DXGI output duplication kept open between captures, with a GDI
+//! fallback); both capture sessions are rebuilt when the display geometry changes. Display topology
+//! changes are reported on macOS (CGDisplayRegisterReconfigurationCallback), Windows
+//! (WM_DISPLAYCHANGE) and Linux/X11 (RandR notifications), each backed by periodic polling.
+//! Other platforms return NotImplemented but compile everywhere.
+
+use super::display::{self, DisplayInfo};
+use super::scaling;
+use crate::operator::locate;
 use serde::Serialize;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
 use std::thread;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 拓扑轮询间隔：平台通知丢失或不可用时仍能发现变化
+const TOPOLOGY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 收到通知后等待重配置完成的时间，合并同一次变更的多条通知
+const TOPOLOGY_SETTLE: Duration = Duration::from_millis(250);
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ScreenEventKind {
-    GeometryChanged { width: u32, height: u32, scale: f32 },
-    DisplayAdded,
-    DisplayRemoved,
+    /// 显示器的位置、尺寸（逻辑坐标，同 `list_displays`）或缩放比例变化
+    GeometryChanged { display_id: u32, x: i32, y: i32, width: u32, height: u32, scale: f32 },
+    DisplayAdded { display_id: u32 },
+    DisplayRemoved { display_id: u32 },
     FrameCaptured { 
         width: u32, 
         height: u32, 
@@ -53,25 +71,100 @@ impl fmt::Display for MonitorError {
  
 impl std::error::Error for MonitorError {}
 
+/// 显示器拓扑监控；释放时停止比较线程（平台通知源常驻进程）
 pub struct MonitorHandle {
-    #[cfg(target_os = "macos")]
+    stop: Arc<AtomicBool>,
+    wake: Sender<()>,
     thread: Option<thread::JoinHandle<()>>,
 }
 
 impl Drop for MonitorHandle {
     fn drop(&mut self) {
-        #[cfg(target_os = "macos")]
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = self.wake.send(());
         if let Some(handle) = self.thread.take() {
             let _ = handle.join();
         }
     }
 }
 
+/// 开始监控显示器拓扑：显示器增减或几何变化时以 `DisplayAdded` / `DisplayRemoved` /
+/// `GeometryChanged` 事件回调。平台通知触发立即比较，另按 `TOPOLOGY_POLL_INTERVAL` 轮询兜底
 pub fn start_monitor<F>(on_event: F) -> Result<MonitorHandle, MonitorError>
 where
     F: Fn(ScreenEvent) + Send + Sync + 'static,
 {
-    platform::start(on_event)
+    let initial = display::list_displays()?;
+    let (wake, wakeups) = mpsc::channel();
+    if let Err(e) = platform::watch_reconfiguration(wake.clone()) {
+        eprintln!("[screen] display change notifications unavailable ({}), polling only", e);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let thread = thread::Builder::new()
+        .name("screen-topology".to_string())
+        .spawn(move || {
+            let mut previous = initial;
+            while !thread_stop.load(Ordering::SeqCst) {
+                match wakeups.recv_timeout(TOPOLOGY_POLL_INTERVAL) {
+                    Ok(()) => {
+                        thread::sleep(TOPOLOGY_SETTLE);
+                        while wakeups.try_recv().is_ok() {}
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // 句柄持有发送端，不会断开；保险起见按轮询继续
+                    Err(RecvTimeoutError::Disconnected) => thread::sleep(TOPOLOGY_POLL_INTERVAL),
+                }
+                if thread_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(current) = display::list_displays() else { continue };
+                let changes = topology_changes(&previous, &current);
+                if !changes.is_empty() {
+                    invalidate_capture_session();
+                    scaling::invalidate();
+                    locate::invalidate_locations();
+                }
+                for kind in changes {
+                    on_event(ScreenEvent { kind, timestamp_micros: now_micros() });
+                }
+                previous = current;
+            }
+        })
+        .map_err(|e| MonitorError::Io(e.to_string()))?;
+
+    Ok(MonitorHandle { stop, wake, thread: Some(thread) })
+}
+
+/// 比较两次显示器列表，按显示器 ID 给出新增、移除与几何变化
+fn topology_changes(previous: &[DisplayInfo], current: &[DisplayInfo]) -> Vec<ScreenEventKind> {
+    let mut changes = Vec::new();
+    for old in previous {
+        if !current.iter().any(|d| d.id == old.id) {
+            changes.push(ScreenEventKind::DisplayRemoved { display_id: old.id });
+        }
+    }
+    for new in current {
+        match previous.iter().find(|d| d.id == new.id) {
+            None => changes.push(ScreenEventKind::DisplayAdded { display_id: new.id }),
+            Some(old) if (old.x, old.y, old.width, old.height) == (new.x, new.y, new.width, new.height)
+                && (old.scale - new.scale).abs() < 1e-3 => {}
+            Some(_) => changes.push(ScreenEventKind::GeometryChanged {
+                display_id: new.id,
+                x: new.x,
+                y: new.y,
+                width: new.width,
+                height: new.height,
+                scale: new.scale as f32,
+            }),
+        }
+    }
+    changes
+}
+
+fn now_micros() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_micros()).unwrap_or(0)
 }
 
 /// 按需捕获一帧屏幕截图（不启动持续监控）
@@ -79,34 +172,169 @@ pub fn capture_frame() -> Result<ScreenEvent, MonitorError> {
     platform::capture_frame()
 }
 
+/// 丢弃缓存的截图会话（macOS 的 ScreenCaptureKit 流、Windows 的 DXGI 桌面复制），
+/// 下次截图时按当前显示器几何重新建立；显示器拓扑监控发现变化时自动调用
+pub fn invalidate_capture_session() {
+    platform::invalidate_capture_session();
+}
+
+/// 截取单个显示器的画面
+pub fn capture_display(display: &DisplayInfo) -> Result<image::RgbImage, MonitorError> {
+    platform::capture_display(display)
+}
+
+/// 拼接后的虚拟桌面截图：像素 (px, py) 对应全局逻辑坐标 (origin_x + px, origin_y + py)
+pub struct VirtualDesktopCapture {
+    pub image: image::RgbImage,
+    pub origin_x: i32,
+    pub origin_y: i32,
+    pub displays: Vec<DisplayInfo>,
+    /// 截图失败的显示器 ID 与原因，其区域保持黑色
+    pub failed: Vec<(u32, String)>,
+}
+
+/// 虚拟桌面截图的最大边长（逻辑像素）
+const MAX_VIRTUAL_DESKTOP_SIZE: u32 = 16_384;
+
+/// 截取所有显示器并按全局逻辑坐标拼接为一张图；Hi-DPI 显示器缩小到逻辑分辨率，
+/// 使各显示器在图中的位置与鼠标坐标一一对应。显示器之间的空隙为黑色
+pub fn capture_all_displays() -> Result<VirtualDesktopCapture, MonitorError> {
+    use image::imageops;
+
+    let displays = display::list_displays()?;
+    let (Some(left), Some(top), Some(right), Some(bottom)) = (
+        displays.iter().map(|d| d.x).min(),
+        displays.iter().map(|d| d.y).min(),
+        displays.iter().map(|d| d.x + d.width as i32).max(),
+        displays.iter().map(|d| d.y + d.height as i32).max(),
+    ) else {
+        return Err(MonitorError::Io("no displays found".to_string()));
+    };
+    let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+    if width == 0 || height == 0 || width > MAX_VIRTUAL_DESKTOP_SIZE || height > MAX_VIRTUAL_DESKTOP_SIZE {
+        return Err(MonitorError::Io(format!("virtual desktop size {}x{} out of range", width, height)));
+    }
+
+    let mut image = image::RgbImage::new(width, height);
+    let mut failed = Vec::new();
+    for d in &displays {
+        match capture_display(d) {
+            Ok(frame) => {
+                let frame = if frame.dimensions() == (d.width, d.height) {
+                    frame
+                } else {
+                    imageops::resize(&frame, d.width, d.height, imageops::FilterType::Triangle)
+                };
+                imageops::replace(&mut image, &frame, (d.x - left) as i64, (d.y - top) as i64);
+            }
+            Err(e) => failed.push((d.id, e.to_string())),
+        }
+    }
+    if failed.len() == displays.len() {
+        let (_, reason) = failed.swap_remove(0);
+        return Err(MonitorError::Io(reason));
+    }
+    Ok(VirtualDesktopCapture { image, origin_x: left, origin_y: top, displays, failed })
+}
+
+/// 读取全局坐标处单个像素的 RGB 颜色
+pub fn pixel_color(x: i32, y: i32) -> Result<[u8; 3], MonitorError> {
+    platform::pixel_color(x, y)
+}
+
+/// 一帧 BGRA 像素，行紧密排列
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub(crate) struct BgraFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// BGRA 像素转为 RGB 图像；桌面图像不透明，丢弃 alpha
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn bgra_to_rgb(frame: &BgraFrame) -> Result<image::RgbImage, MonitorError> {
+    let rgb: Vec<u8> = frame.pixels.chunks_exact(4).flat_map(|px| [px[2], px[1], px[0]]).collect();
+    image::RgbImage::from_raw(frame.width, frame.height, rgb)
+        .ok_or_else(|| MonitorError::Io("frame buffer size mismatch".to_string()))
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn rgb_to_png(rgb: image::RgbImage) -> Result<Vec<u8>, MonitorError> {
+    super::postprocess::encode_png(&image::DynamicImage::ImageRgb8(rgb))
+}
+
 #[cfg(target_os = "macos")]
 mod platform {
     use super::*;
+    use crate::monitor::screencapturekit;
+    use core_graphics::color_space::{kCGColorSpaceSRGB, CGColorSpace};
     use core_graphics::display::CGDisplay;
     use core_graphics::image::CGImage;
+    use std::ffi::c_void;
 
-    pub fn start<F>(_on_event: F) -> Result<MonitorHandle, MonitorError>
-    where
-        F: Fn(ScreenEvent) + Send + Sync + 'static,
-    {
-        // 屏幕监控不再持续运行，而是按需调用 capture_frame()
-        // 这里保留接口但立即返回
-        let handle = thread::Builder::new()
-            .name("screen-monitor-macos".to_string())
-            .spawn(move || {
-                // 空线程，立即完成
-            })
-            .map_err(|e| MonitorError::Io(e.to_string()))?;
+    /// `kCGDisplayBeginConfigurationFlag`
+    const BEGIN_CONFIGURATION: u32 = 1;
+
+    type ReconfigurationCallback = extern "C" fn(display: u32, flags: u32, user_info: *mut c_void);
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGDisplayRegisterReconfigurationCallback(callback: ReconfigurationCallback, user_info: *mut c_void) -> i32;
+    }
 
-        Ok(MonitorHandle { thread: Some(handle) })
+    extern "C" fn on_reconfiguration(_display: u32, flags: u32, user_info: *mut c_void) {
+        // 每次重配置先收到一次开始通知，完成后每个受影响的显示器再各收到一次
+        if flags & BEGIN_CONFIGURATION != 0 {
+            return;
+        }
+        let wake = unsafe { &*(user_info as *const Sender<()>) };
+        let _ = wake.send(());
+    }
+
+    /// 注册显示器重配置回调；回调在窗口服务器通知到达时调用，发送端常驻进程
+    pub fn watch_reconfiguration(wake: Sender<()>) -> Result<(), MonitorError> {
+        let user_info = Box::into_raw(Box::new(wake));
+        let error = unsafe { CGDisplayRegisterReconfigurationCallback(on_reconfiguration, user_info.cast()) };
+        if error != 0 {
+            drop(unsafe { Box::from_raw(user_info) });
+            return Err(MonitorError::Io(format!("CGDisplayRegisterReconfigurationCallback failed: {}", error)));
+        }
+        Ok(())
     }
 
-    /// 按需捕获主显示器的一帧截图
+    /// 按需捕获主显示器的一帧截图：优先从 ScreenCaptureKit 捕获流取最新帧，
+    /// 不可用或设置 `IRIS_SCREEN_BACKEND=cgdisplay` 时使用 `CGDisplay::image`
     pub fn capture_frame() -> Result<ScreenEvent, MonitorError> {
+        let legacy = std::env::var("IRIS_SCREEN_BACKEND").is_ok_and(|v| v.eq_ignore_ascii_case("cgdisplay"));
+        if !legacy && screencapturekit::available() {
+            match screencapturekit::capture_main_display() {
+                Ok(frame) => return frame_event(&frame),
+                Err(error) => eprintln!("[screen] ScreenCaptureKit unavailable ({}), falling back to CGDisplay", error),
+            }
+        }
         capture_main_display_frame()
             .ok_or_else(|| MonitorError::Io("Failed to capture screen frame".to_string()))
     }
 
+    pub fn invalidate_capture_session() {
+        screencapturekit::invalidate();
+    }
+
+    fn frame_event(frame: &BgraFrame) -> Result<ScreenEvent, MonitorError> {
+        Ok(ScreenEvent {
+            kind: ScreenEventKind::FrameCaptured {
+                width: frame.width,
+                height: frame.height,
+                format: FrameFormat::Bgra8,
+                image_data: Some(rgb_to_png(bgra_to_rgb(frame)?)?),
+            },
+            timestamp_micros: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_micros())
+                .unwrap_or(0),
+        })
+    }
+
     fn capture_main_display_frame() -> Option<ScreenEvent> {
         let main = CGDisplay::main();
         let cg_image: CGImage = main.image()?;
@@ -131,12 +359,31 @@ mod platform {
         })
     }
 
+    /// sRGB 色彩空间，截图与取色统一输出 sRGB；系统不提供时退回设备 RGB
+    fn srgb_color_space() -> CGColorSpace {
+        unsafe { CGColorSpace::create_with_name(kCGColorSpaceSRGB) }.unwrap_or_else(CGColorSpace::create_device_rgb)
+    }
+
+    /// 截取指定显示器（物理像素）
+    pub fn capture_display(display: &DisplayInfo) -> Result<image::RgbImage, MonitorError> {
+        let cg_image = CGDisplay::new(display.id)
+            .image()
+            .ok_or_else(|| MonitorError::Io(format!("CGDisplayCreateImage failed for display {}", display.id)))?;
+        let rgba = cgimage_to_rgba(&cg_image, cg_image.width() as u32, cg_image.height() as u32)
+            .ok_or_else(|| MonitorError::Io("bitmap conversion failed".to_string()))?;
+        Ok(image::DynamicImage::ImageRgba8(rgba).to_rgb8())
+    }
+
     /// 将 CGImage 转换为 PNG 字节
     fn cgimage_to_png(cg_image: &CGImage, width: u32, height: u32) -> Option<Vec<u8>> {
-        use image::{ImageBuffer, RgbaImage, ImageFormat};
-        use std::io::Cursor;
+        let rgba_image = cgimage_to_rgba(cg_image, width, height)?;
+        crate::monitor::postprocess::encode_png(&image::DynamicImage::ImageRgba8(rgba_image)).ok()
+    }
+
+    /// 将 CGImage 绘制为 sRGB 的 RGBA 像素
+    fn cgimage_to_rgba(cg_image: &CGImage, width: u32, height: u32) -> Option<image::RgbaImage> {
+        use image::{ImageBuffer, RgbaImage};
         use std::os::raw::c_void;
-        use core_graphics::color_space::CGColorSpace;
         use core_graphics::context::CGContext;
         use core_graphics::geometry::CGRect;
 
@@ -146,8 +393,9 @@ mod platform {
         let buffer_size = bytes_per_row * height as usize;
         let mut buffer: Vec<u8> = vec![0; buffer_size];
 
-        // 使用 Core Graphics 创建位图上下文并绘制图像
-        let color_space = CGColorSpace::create_device_rgb();
+        // 绘制到 sRGB 位图上下文：CGDisplay::image 带有显示器的色彩配置，绘制时由 ColorSync
+        // 转换到 sRGB，广色域（P3）与 HDR 显示器上的颜色不会发灰或偏色
+        let color_space = srgb_color_space();
         let bitmap_info = core_graphics::base::kCGImageAlphaPremultipliedLast 
             | core_graphics::base::kCGBitmapByteOrder32Big;
 
@@ -168,36 +416,431 @@ mod platform {
 
         context.draw_image(rect, cg_image);
 
-        // 现在 buffer 包含 RGBA 数据，转换为 PNG
+        // 现在 buffer 包含 RGBA 数据
         let rgba_image: RgbaImage = ImageBuffer::from_raw(width, height, buffer)?;
-
-        // 编码为 PNG
-        let mut png_data = Vec::new();
-        let mut cursor = Cursor::new(&mut png_data);
-        rgba_image.write_to(&mut cursor, ImageFormat::Png).ok()?;
-        
-        Some(png_data)
+        Some(rgba_image)
     }
 
+    pub fn pixel_color(x: i32, y: i32) -> Result<[u8; 3], MonitorError> {
+        use core_graphics::context::CGContext;
+        use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+        use core_graphics::window::{kCGNullWindowID, kCGWindowImageDefault, kCGWindowListOptionOnScreenOnly};
+        use std::os::raw::c_void;
+
+        let bounds = CGRect::new(&CGPoint::new(x as f64, y as f64), &CGSize::new(1.0, 1.0));
+        let image = CGDisplay::screenshot(
+            bounds,
+            kCGWindowListOptionOnScreenOnly,
+            kCGNullWindowID,
+            kCGWindowImageDefault,
+        )
+        .ok_or_else(|| MonitorError::Io(format!("Failed to capture pixel at ({}, {})", x, y)))?;
+
+        // 绘制到 1x1 RGBA 位图（Retina 下的 2x2 像素会被平均）
+        let mut pixel = [0u8; 4];
+        let color_space = srgb_color_space();
+        let context = CGContext::create_bitmap_context(
+            Some(pixel.as_mut_ptr() as *mut c_void),
+            1,
+            1,
+            8,
+            4,
+            &color_space,
+            core_graphics::base::kCGImageAlphaPremultipliedLast | core_graphics::base::kCGBitmapByteOrder32Big,
+        );
+        context.draw_image(CGRect::new(&CGPoint::new(0.0, 0.0), &CGSize::new(1.0, 1.0)), &image);
+        drop(context);
+
+        Ok([pixel[0], pixel[1], pixel[2]])
+    }
 }
 
 #[cfg(target_os = "windows")]
 mod platform {
     use super::*;
+    use std::sync::{Mutex, OnceLock};
+    use windows::core::{w, Interface};
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+    use windows::Win32::Graphics::Direct3D11::{
+        D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D, D3D11_CPU_ACCESS_READ,
+        D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_MAPPED_SUBRESOURCE, D3D11_MAP_READ, D3D11_SDK_VERSION,
+        D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING,
+    };
+    use windows::Win32::Graphics::Dxgi::Common::{
+        DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_MODE_ROTATION_IDENTITY, DXGI_MODE_ROTATION_UNSPECIFIED, DXGI_SAMPLE_DESC,
+    };
+    use windows::Win32::Graphics::Dxgi::{
+        IDXGIDevice, IDXGIOutput1, IDXGIOutputDuplication, IDXGIResource, DXGI_ERROR_WAIT_TIMEOUT,
+        DXGI_OUTDUPL_FRAME_INFO,
+    };
+    use windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, GetDeviceCaps,
+        ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CAPTUREBLT, DESKTOPHORZRES, DESKTOPVERTRES,
+        DIB_RGB_COLORS, SRCCOPY,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, MSG, WINDOW_EX_STYLE,
+        WINDOW_STYLE, WM_DISPLAYCHANGE, WM_DPICHANGED, WM_SETTINGCHANGE, WNDCLASSW,
+    };
+
+    /// 等待桌面复制返回首帧的时间；新建的复制会话会立即提供当前桌面图像
+    const ACQUIRE_TIMEOUT_MS: u32 = 500;
+
+    /// 显示器变化通知的接收端，由隐藏窗口的窗口过程使用
+    static WAKE: OnceLock<Sender<()>> = OnceLock::new();
+
+    unsafe extern "system" fn window_proc(hwnd: HWND, message: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if (message == WM_DISPLAYCHANGE || message == WM_DPICHANGED || message == WM_SETTINGCHANGE)
+            && let Some(wake) = WAKE.get()
+        {
+            let _ = wake.send(());
+        }
+        unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+    }
 
-    pub fn start<F>(_on_event: F) -> Result<MonitorHandle, MonitorError>
-    where
-        F: Fn(ScreenEvent) + Send + Sync + 'static,
-    {
-        Err(MonitorError::NotImplemented(
-            "Windows: implement DXGI output duplication or display change notifications",
-        ))
+    /// 创建不可见的顶层窗口接收 WM_DISPLAYCHANGE 广播（仅消息窗口收不到广播），在专用线程上运行消息循环
+    pub fn watch_reconfiguration(wake: Sender<()>) -> Result<(), MonitorError> {
+        if WAKE.set(wake).is_err() {
+            return Ok(());
+        }
+        thread::Builder::new()
+            .name("screen-display-change".to_string())
+            .spawn(|| unsafe {
+                let class_name = w!("IrisDisplayChangeWindow");
+                let class = WNDCLASSW {
+                    lpfnWndProc: Some(window_proc),
+                    lpszClassName: class_name,
+                    ..Default::default()
+                };
+                RegisterClassW(&class);
+                let window = CreateWindowExW(
+                    WINDOW_EX_STYLE(0),
+                    class_name,
+                    w!(""),
+                    WINDOW_STYLE(0),
+                    0,
+                    0,
+                    0,
+                    0,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+                if let Err(e) = window {
+                    eprintln!("[screen] CreateWindowEx for display notifications failed: {}", e);
+                    return;
+                }
+                let mut message = MSG::default();
+                while GetMessageW(&mut message, None, 0, 0).as_bool() {
+                    DispatchMessageW(&message);
+                }
+            })
+            .map_err(|e| MonitorError::Io(e.to_string()))?;
+        Ok(())
     }
 
+    /// 按需捕获主显示器的一帧截图：优先使用 DXGI 桌面复制，
+    /// 不可用时（远程桌面会话、旋转的显示器、无 D3D11 硬件等）改用 GDI BitBlt
     pub fn capture_frame() -> Result<ScreenEvent, MonitorError> {
-        Err(MonitorError::NotImplemented(
-            "Windows: implement screenshot capture",
-        ))
+        // 会话锁内只做像素转换，PNG 编码在锁外进行，不阻塞其他截图（订阅、录制）
+        let rgb = match with_dxgi_frame(bgra_to_rgb) {
+            Ok(rgb) => rgb?,
+            Err(error) => {
+                eprintln!("[screen] DXGI duplication unavailable ({}), falling back to GDI", error);
+                bgra_to_rgb(&capture_gdi()?)?
+            }
+        };
+        let (width, height) = rgb.dimensions();
+        let image_data = rgb_to_png(rgb)?;
+
+        Ok(ScreenEvent {
+            kind: ScreenEventKind::FrameCaptured {
+                width,
+                height,
+                format: FrameFormat::Bgra8,
+                image_data: Some(image_data),
+            },
+            timestamp_micros: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_micros())
+                .unwrap_or(0),
+        })
+    }
+
+    /// 丢弃缓存的桌面复制会话，下次截图时重新打开
+    pub fn invalidate_capture_session() {
+        *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn dxgi_error(context: &str, error: windows::core::Error) -> MonitorError {
+        MonitorError::Io(format!("{}: {}", context, error))
+    }
+
+    /// 缓存的 DXGI 桌面复制会话：设备、复制对象、暂存纹理与像素缓冲在多次截图间复用，
+    /// 桌面未更新时直接返回上一帧
+    struct DxgiSession {
+        device: ID3D11Device,
+        context: ID3D11DeviceContext,
+        duplication: IDXGIOutputDuplication,
+        staging: Option<ID3D11Texture2D>,
+        frame: BgraFrame,
+        has_frame: bool,
+    }
+
+    // SAFETY: D3D11 设备可跨线程使用；立即上下文与复制对象只在持有 SESSION 锁时访问
+    unsafe impl Send for DxgiSession {}
+
+    static SESSION: Mutex<Option<DxgiSession>> = Mutex::new(None);
+
+    /// 用缓存会话取得最新一帧并交给 `f`；缓存的会话失效（分辨率变化、切换到安全桌面等返回
+    /// `DXGI_ERROR_ACCESS_LOST`）时重新打开一次
+    fn with_dxgi_frame<R>(f: impl FnOnce(&BgraFrame) -> R) -> Result<R, MonitorError> {
+        let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+        let mut reused = session.is_some();
+        loop {
+            let current = match session.as_mut() {
+                Some(current) => current,
+                None => session.insert(unsafe { DxgiSession::open()? }),
+            };
+            match unsafe { current.next_frame() } {
+                Ok(()) => return Ok(f(&current.frame)),
+                Err(error) => {
+                    *session = None;
+                    if !reused {
+                        return Err(error);
+                    }
+                    reused = false;
+                }
+            }
+        }
+    }
+
+    impl DxgiSession {
+        /// 打开主显示器（桌面坐标原点所在的输出）的桌面复制
+        unsafe fn open() -> Result<Self, MonitorError> {
+            unsafe {
+                let mut device: Option<ID3D11Device> = None;
+                let mut context: Option<ID3D11DeviceContext> = None;
+                D3D11CreateDevice(
+                    None,
+                    D3D_DRIVER_TYPE_HARDWARE,
+                    Default::default(),
+                    D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                    None,
+                    D3D11_SDK_VERSION,
+                    Some(&mut device),
+                    None,
+                    Some(&mut context),
+                )
+                .map_err(|e| dxgi_error("D3D11CreateDevice", e))?;
+                let (Some(device), Some(context)) = (device, context) else {
+                    return Err(MonitorError::Io("D3D11CreateDevice returned no device".to_string()));
+                };
+
+                let adapter = device
+                    .cast::<IDXGIDevice>()
+                    .and_then(|dxgi| dxgi.GetAdapter())
+                    .map_err(|e| dxgi_error("GetAdapter", e))?;
+                let mut primary = None;
+                let mut index = 0;
+                while let Ok(output) = adapter.EnumOutputs(index) {
+                    index += 1;
+                    let desc = output.GetDesc().map_err(|e| dxgi_error("IDXGIOutput::GetDesc", e))?;
+                    if desc.DesktopCoordinates.left == 0 && desc.DesktopCoordinates.top == 0 {
+                        primary = Some(output);
+                        break;
+                    }
+                    primary.get_or_insert(output);
+                }
+                let output = primary
+                    .ok_or_else(|| MonitorError::Io("adapter has no outputs attached".to_string()))?
+                    .cast::<IDXGIOutput1>()
+                    .map_err(|e| dxgi_error("IDXGIOutput1", e))?;
+
+                let duplication = output.DuplicateOutput(&device).map_err(|e| dxgi_error("DuplicateOutput", e))?;
+                // 旋转显示器的复制图像为未旋转方向，交给 GDI 处理
+                let rotation = duplication.GetDesc().Rotation;
+                if rotation != DXGI_MODE_ROTATION_IDENTITY && rotation != DXGI_MODE_ROTATION_UNSPECIFIED {
+                    return Err(MonitorError::Io("rotated output".to_string()));
+                }
+
+                Ok(DxgiSession {
+                    device,
+                    context,
+                    duplication,
+                    staging: None,
+                    frame: BgraFrame { width: 0, height: 0, pixels: Vec::new() },
+                    has_frame: false,
+                })
+            }
+        }
+
+        /// 取得下一帧；已有帧时不等待，桌面自上次截图后未更新则保留上一帧
+        unsafe fn next_frame(&mut self) -> Result<(), MonitorError> {
+            unsafe {
+                let timeout = if self.has_frame { 0 } else { ACQUIRE_TIMEOUT_MS };
+                let mut info = DXGI_OUTDUPL_FRAME_INFO::default();
+                let mut resource: Option<IDXGIResource> = None;
+                match self.duplication.AcquireNextFrame(timeout, &mut info, &mut resource) {
+                    Ok(()) => {}
+                    Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT && self.has_frame => return Ok(()),
+                    Err(e) => return Err(dxgi_error("AcquireNextFrame", e)),
+                }
+                // 只有指针移动时 LastPresentTime 为 0，桌面图像没有变化
+                let result = if self.has_frame && info.LastPresentTime == 0 {
+                    Ok(())
+                } else {
+                    resource
+                        .ok_or_else(|| MonitorError::Io("AcquireNextFrame returned no resource".to_string()))
+                        .and_then(|resource| {
+                            let texture =
+                                resource.cast::<ID3D11Texture2D>().map_err(|e| dxgi_error("ID3D11Texture2D", e))?;
+                            self.read_texture(&texture)
+                        })
+                };
+                let _ = self.duplication.ReleaseFrame();
+                result
+            }
+        }
+
+        /// 将 GPU 上的桌面纹理复制到 CPU 可读的暂存纹理并逐行读入像素缓冲
+        unsafe fn read_texture(&mut self, texture: &ID3D11Texture2D) -> Result<(), MonitorError> {
+            unsafe {
+                let mut desc = D3D11_TEXTURE2D_DESC::default();
+                texture.GetDesc(&mut desc);
+                if desc.Format != DXGI_FORMAT_B8G8R8A8_UNORM {
+                    return Err(MonitorError::Io(format!("unsupported desktop format {:?}", desc.Format)));
+                }
+                let staging = match &self.staging {
+                    Some(staging) if (self.frame.width, self.frame.height) == (desc.Width, desc.Height) => {
+                        staging.clone()
+                    }
+                    _ => {
+                        let staging_desc = D3D11_TEXTURE2D_DESC {
+                            Width: desc.Width,
+                            Height: desc.Height,
+                            MipLevels: 1,
+                            ArraySize: 1,
+                            Format: desc.Format,
+                            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+                            Usage: D3D11_USAGE_STAGING,
+                            BindFlags: 0,
+                            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+                            MiscFlags: 0,
+                        };
+                        let mut staging: Option<ID3D11Texture2D> = None;
+                        self.device
+                            .CreateTexture2D(&staging_desc, None, Some(&mut staging))
+                            .map_err(|e| dxgi_error("CreateTexture2D", e))?;
+                        let staging = staging
+                            .ok_or_else(|| MonitorError::Io("CreateTexture2D returned no texture".to_string()))?;
+                        self.staging = Some(staging.clone());
+                        staging
+                    }
+                };
+                self.context.CopyResource(&staging, texture);
+
+                let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+                self.context
+                    .Map(&staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+                    .map_err(|e| dxgi_error("Map", e))?;
+                let row_bytes = desc.Width as usize * 4;
+                let pixels = &mut self.frame.pixels;
+                pixels.clear();
+                pixels.reserve(row_bytes * desc.Height as usize);
+                for row in 0..desc.Height as usize {
+                    let src = (mapped.pData as *const u8).add(row * mapped.RowPitch as usize);
+                    pixels.extend_from_slice(std::slice::from_raw_parts(src, row_bytes));
+                }
+                self.context.Unmap(&staging, 0);
+
+                self.frame.width = desc.Width;
+                self.frame.height = desc.Height;
+                self.has_frame = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// 通过 GDI 从屏幕 DC 复制主显示器（物理像素），适用于桌面复制不可用的环境
+    fn capture_gdi() -> Result<BgraFrame, MonitorError> {
+        let (width, height) = unsafe {
+            let screen = GetDC(None);
+            if screen.is_invalid() {
+                return Err(MonitorError::Io("GetDC failed".to_string()));
+            }
+            let size = (GetDeviceCaps(Some(screen), DESKTOPHORZRES), GetDeviceCaps(Some(screen), DESKTOPVERTRES));
+            ReleaseDC(None, screen);
+            size
+        };
+        capture_gdi_rect(0, 0, width, height)
+    }
+
+    /// 截取指定显示器：按其桌面坐标区域从屏幕 DC 复制
+    pub fn capture_display(display: &DisplayInfo) -> Result<image::RgbImage, MonitorError> {
+        let frame = capture_gdi_rect(display.x, display.y, display.width as i32, display.height as i32)?;
+        bgra_to_rgb(&frame)
+    }
+
+    /// 从屏幕 DC 复制桌面坐标中的矩形区域
+    fn capture_gdi_rect(x: i32, y: i32, width: i32, height: i32) -> Result<BgraFrame, MonitorError> {
+        if width <= 0 || height <= 0 {
+            return Err(MonitorError::Io(format!("empty capture area {}x{}", width, height)));
+        }
+        unsafe {
+            let screen = GetDC(None);
+            if screen.is_invalid() {
+                return Err(MonitorError::Io("GetDC failed".to_string()));
+            }
+            let memory = CreateCompatibleDC(Some(screen));
+            let bitmap = CreateCompatibleBitmap(screen, width, height);
+            let previous = SelectObject(memory, bitmap.into());
+
+            // CAPTUREBLT 同时捕获分层（半透明）窗口
+            let result = BitBlt(memory, 0, 0, width, height, Some(screen), x, y, SRCCOPY | CAPTUREBLT)
+                .map_err(|e| MonitorError::Io(format!("BitBlt: {}", e)))
+                .and_then(|()| {
+                    // 负高度表示自上而下的行顺序
+                    let mut info = BITMAPINFO {
+                        bmiHeader: BITMAPINFOHEADER {
+                            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                            biWidth: width,
+                            biHeight: -height,
+                            biPlanes: 1,
+                            biBitCount: 32,
+                            biCompression: BI_RGB.0,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    };
+                    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+                    let lines = GetDIBits(
+                        memory,
+                        bitmap,
+                        0,
+                        height as u32,
+                        Some(pixels.as_mut_ptr().cast()),
+                        &mut info,
+                        DIB_RGB_COLORS,
+                    );
+                    if lines == 0 {
+                        return Err(MonitorError::Io("GetDIBits failed".to_string()));
+                    }
+                    Ok(BgraFrame { width: width as u32, height: height as u32, pixels })
+                });
+
+            SelectObject(memory, previous);
+            let _ = DeleteObject(bitmap.into());
+            let _ = DeleteDC(memory);
+            ReleaseDC(None, screen);
+            result
+        }
+    }
+
+    pub fn pixel_color(_x: i32, _y: i32) -> Result<[u8; 3], MonitorError> {
+        Err(MonitorError::NotImplemented("Windows: implement GetPixel on the screen DC"))
     }
 }
 
@@ -205,13 +848,36 @@ mod platform {
 mod platform {
     use super::*;
 
-    pub fn start<F>(_on_event: F) -> Result<MonitorHandle, MonitorError>
-    where
-        F: Fn(ScreenEvent) + Send + 'static,
-    {
-        Err(MonitorError::NotImplemented(
-            "Linux: implement DRM/GBM or X11 RandR events; Wayland likely restricted",
-        ))
+    /// 订阅根窗口的 RandR 屏幕、输出与 CRTC 变化通知，在专用线程上等待事件
+    pub fn watch_reconfiguration(wake: Sender<()>) -> Result<(), MonitorError> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::randr::{ConnectionExt as _, NotifyMask};
+
+        let (conn, screen_num) = x11rb::connect(None)
+            .map_err(|e| MonitorError::Io(format!("X11 connect failed: {}", e)))?;
+        let root = conn.setup().roots[screen_num].root;
+        conn.randr_select_input(root, NotifyMask::SCREEN_CHANGE | NotifyMask::OUTPUT_CHANGE | NotifyMask::CRTC_CHANGE)
+            .map_err(|e| MonitorError::Io(e.to_string()))?
+            .check()
+            .map_err(|e| MonitorError::Io(e.to_string()))?;
+
+        thread::Builder::new()
+            .name("screen-randr".to_string())
+            .spawn(move || loop {
+                match conn.wait_for_event() {
+                    Ok(_) => {
+                        if wake.send(()).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("[screen] RandR event connection closed: {}", e);
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| MonitorError::Io(e.to_string()))?;
+        Ok(())
     }
 
     pub fn capture_frame() -> Result<ScreenEvent, MonitorError> {
@@ -219,20 +885,53 @@ mod platform {
             "Linux: implement screenshot capture",
         ))
     }
+
+    pub fn invalidate_capture_session() {}
+
+    pub fn capture_display(_display: &DisplayInfo) -> Result<image::RgbImage, MonitorError> {
+        Err(MonitorError::NotImplemented("Linux: implement screenshot capture"))
+    }
+
+    pub fn pixel_color(x: i32, y: i32) -> Result<[u8; 3], MonitorError> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::{ConnectionExt as _, ImageFormat};
+
+        let (conn, screen_num) = x11rb::connect(None)
+            .map_err(|e| MonitorError::Io(format!("X11 connect failed: {}", e)))?;
+        let root = conn.setup().roots[screen_num].root;
+        let image = conn
+            .get_image(ImageFormat::Z_PIXMAP, root, x as i16, y as i16, 1, 1, !0)
+            .map_err(|e| MonitorError::Io(e.to_string()))?
+            .reply()
+            .map_err(|e| MonitorError::Io(e.to_string()))?;
+
+        // 24/32 位深的 ZPixmap 在小端序下为 BGRX
+        match image.data.as_slice() {
+            [b, g, r, ..] if image.depth >= 24 => Ok([*r, *g, *b]),
+            _ => Err(MonitorError::NotImplemented("Linux: only 24/32-bit X11 visuals are supported")),
+        }
+    }
 }
 
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
 mod platform {
     use super::*;
 
-    pub fn start<F>(_on_event: F) -> Result<MonitorHandle, MonitorError>
-    where
-        F: Fn(ScreenEvent) + Send + 'static,
-    {
+    pub fn watch_reconfiguration(_wake: Sender<()>) -> Result<(), MonitorError> {
         Err(MonitorError::UnsupportedPlatform(std::env::consts::OS))
     }
 
     pub fn capture_frame() -> Result<ScreenEvent, MonitorError> {
         Err(MonitorError::UnsupportedPlatform(std::env::consts::OS))
     }
+
+    pub fn invalidate_capture_session() {}
+
+    pub fn capture_display(_display: &DisplayInfo) -> Result<image::RgbImage, MonitorError> {
+        Err(MonitorError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn pixel_color(_x: i32, _y: i32) -> Result<[u8; 3], MonitorError> {
+        Err(MonitorError::UnsupportedPlatform(std::env::consts::OS))
+    }
 }
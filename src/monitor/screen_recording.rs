@@ -0,0 +1,359 @@
+//! 屏幕录制：按固定帧率截取主显示器（或其中的区域），编码为 GIF 或 MP4 文件，
+//! 便于把代理的操作过程交给人回看。
+//!
+//! - GIF 由 `image` 编码，每帧延时取实际截图间隔，画面未变化的帧合并为一帧；体积较大，适合短片段
+//! - MP4 通过管道把原始帧交给 ffmpeg（在 PATH 中查找，或由环境变量 `IRIS_FFMPEG` 指定）编码为 H.264；
+//!   截图慢于目标帧率时重复上一帧，保证播放速度与实际一致
+//!
+//! 同一时间只能进行一个录制；达到最长时长后自动停止，结果在 `stop` 时返回。
+
+use super::postprocess::{self, Region};
+use super::screen::{self, MonitorError, ScreenEventKind};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use serde::Serialize;
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 默认帧率与允许的帧率范围
+pub const DEFAULT_FPS: u32 = 5;
+pub const MAX_FPS: u32 = 30;
+
+/// 默认与最长录制时长
+pub const DEFAULT_MAX_DURATION: Duration = Duration::from_secs(60);
+pub const MAX_DURATION_LIMIT: Duration = Duration::from_secs(600);
+
+/// GIF 未指定尺寸限制时的默认最大宽度：全分辨率 GIF 编码很慢且体积巨大
+pub const DEFAULT_GIF_MAX_WIDTH: u32 = 1280;
+
+/// GIF 颜色量化速度（1–30，越大越快、质量越低）
+const GIF_SPEED: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoFormat {
+    Gif,
+    Mp4,
+}
+
+impl VideoFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gif" => Some(VideoFormat::Gif),
+            "mp4" => Some(VideoFormat::Mp4),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            VideoFormat::Gif => "gif",
+            VideoFormat::Mp4 => "mp4",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenRecordOptions {
+    pub path: PathBuf,
+    pub format: VideoFormat,
+    pub fps: u32,
+    /// 只录制该区域（截图像素坐标）
+    pub region: Option<Region>,
+    /// 等比缩小到不超过的尺寸
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    pub max_duration: Duration,
+}
+
+/// 录制停止的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    Requested,
+    MaxDuration,
+}
+
+/// 录制完成后的文件信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenRecordingInfo {
+    pub path: String,
+    pub format: VideoFormat,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    /// 截图次数
+    pub captured_frames: u64,
+    /// 写入文件的帧数（GIF 合并未变化的帧，MP4 为补帧后的帧数）
+    pub encoded_frames: u64,
+    pub duration_ms: u64,
+    pub bytes: u64,
+    pub stopped_by: StopReason,
+}
+
+struct Active {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<ScreenRecordingInfo, MonitorError>>,
+}
+
+fn active() -> MutexGuard<'static, Option<Active>> {
+    static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 正在进行的录制的输出路径
+pub fn current() -> Option<PathBuf> {
+    active().as_ref().filter(|a| !a.thread.is_finished()).map(|a| a.path.clone())
+}
+
+/// 截取一帧并按选项裁剪、缩小
+fn capture(options: &ScreenRecordOptions) -> Result<RgbaImage, MonitorError> {
+    let event = screen::capture_frame()?;
+    let ScreenEventKind::FrameCaptured { image_data: Some(data), .. } = event.kind else {
+        return Err(MonitorError::Io("frame has no image data".to_string()));
+    };
+    let mut img = postprocess::decode(&data)?;
+    if let Some(region) = options.region {
+        img = postprocess::crop(&img, region)?;
+    }
+    if let Some(fitted) = postprocess::fit_within(&img, options.max_width, options.max_height) {
+        img = fitted;
+    }
+    Ok(img.to_rgba8())
+}
+
+enum Encoder {
+    Gif(Box<GifEncoder<BufWriter<File>>>),
+    Mp4 { child: Child, stdin: ChildStdin },
+}
+
+impl Encoder {
+    fn create(options: &ScreenRecordOptions, width: u32, height: u32) -> Result<Self, MonitorError> {
+        if let Some(parent) = options.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| MonitorError::Io(format!("create {}: {}", parent.display(), e)))?;
+        }
+        match options.format {
+            VideoFormat::Gif => {
+                // create_new：不覆盖已有文件
+                let file = File::options()
+                    .write(true)
+                    .create_new(true)
+                    .open(&options.path)
+                    .map_err(|e| MonitorError::Io(format!("create {}: {}", options.path.display(), e)))?;
+                let mut encoder = GifEncoder::new_with_speed(BufWriter::new(file), GIF_SPEED);
+                encoder
+                    .set_repeat(Repeat::Infinite)
+                    .map_err(|e| MonitorError::Io(format!("gif encoder: {}", e)))?;
+                Ok(Encoder::Gif(Box::new(encoder)))
+            }
+            VideoFormat::Mp4 => {
+                let ffmpeg = env::var("IRIS_FFMPEG").unwrap_or_else(|_| "ffmpeg".to_string());
+                let mut child = Command::new(&ffmpeg)
+                    .args(["-hide_banner", "-loglevel", "error", "-n", "-f", "rawvideo", "-pix_fmt", "rgba"])
+                    .args(["-s", &format!("{}x{}", width, height), "-framerate", &options.fps.to_string()])
+                    .args(["-i", "-", "-c:v", "libx264", "-pix_fmt", "yuv420p"])
+                    // yuv420p 要求宽高为偶数
+                    .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2", "-movflags", "+faststart"])
+                    .arg(&options.path)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .map_err(|e| {
+                        MonitorError::Io(format!(
+                            "mp4 recording requires ffmpeg ({}: {}); install it, set IRIS_FFMPEG, or use format gif",
+                            ffmpeg, e
+                        ))
+                    })?;
+                let stdin = child.stdin.take().ok_or_else(|| MonitorError::Io("ffmpeg stdin unavailable".to_string()))?;
+                Ok(Encoder::Mp4 { child, stdin })
+            }
+        }
+    }
+
+    /// 写入一帧，在画面中持续 `delay`（GIF）或重复 `repeat` 次（MP4）
+    fn write(&mut self, frame: &RgbaImage, delay: Duration, repeat: u64) -> Result<(), MonitorError> {
+        match self {
+            Encoder::Gif(encoder) => encoder
+                .encode_frame(Frame::from_parts(frame.clone(), 0, 0, Delay::from_saturating_duration(delay)))
+                .map_err(|e| MonitorError::Io(format!("gif encode: {}", e))),
+            Encoder::Mp4 { stdin, .. } => {
+                for _ in 0..repeat {
+                    stdin.write_all(frame.as_raw()).map_err(|e| MonitorError::Io(format!("ffmpeg write: {}", e)))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), MonitorError> {
+        match self {
+            // GifEncoder 在释放时写入文件尾
+            Encoder::Gif(encoder) => {
+                drop(encoder);
+                Ok(())
+            }
+            Encoder::Mp4 { child, stdin } => {
+                drop(stdin);
+                let output = child.wait_with_output().map_err(|e| MonitorError::Io(format!("ffmpeg: {}", e)))?;
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    Err(MonitorError::Io(format!(
+                        "ffmpeg exited with {}: {}",
+                        output.status,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// 开始录制；先同步截取首帧，截图或编码器不可用时直接返回错误
+pub fn start(options: ScreenRecordOptions) -> Result<(), MonitorError> {
+    let mut slot = active();
+    if let Some(current) = slot.as_ref().filter(|a| !a.thread.is_finished()) {
+        return Err(MonitorError::Io(format!(
+            "screen recording to {} is in progress; stop it first",
+            current.path.display()
+        )));
+    }
+
+    let first = capture(&options)?;
+    let encoder = Encoder::create(&options, first.width(), first.height())?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let path = options.path.clone();
+    let thread = thread::Builder::new()
+        .name("screen-recording".to_string())
+        .spawn(move || record(options, encoder, first, thread_stop))
+        .map_err(|e| MonitorError::Io(e.to_string()))?;
+
+    // 上一个已自动停止但未取回结果的录制被丢弃，其文件保留在磁盘上
+    *slot = Some(Active { path, stop, thread });
+    Ok(())
+}
+
+/// 停止录制并等待文件写完
+pub fn stop() -> Result<ScreenRecordingInfo, MonitorError> {
+    let current = active().take().ok_or_else(|| MonitorError::Io("no screen recording in progress".to_string()))?;
+    current.stop.store(true, Ordering::SeqCst);
+    current
+        .thread
+        .join()
+        .map_err(|_| MonitorError::Io("screen recording thread panicked".to_string()))?
+}
+
+/// 录制线程：按帧率截图并写入编码器，直到收到停止请求或达到最长时长
+fn record(
+    options: ScreenRecordOptions,
+    mut encoder: Encoder,
+    first: RgbaImage,
+    stop: Arc<AtomicBool>,
+) -> Result<ScreenRecordingInfo, MonitorError> {
+    let (width, height) = first.dimensions();
+    let interval = Duration::from_secs_f64(1.0 / options.fps as f64);
+    let started = Instant::now();
+    let mut captured_frames = 1u64;
+    let mut encoded_frames = 0u64;
+    // 尚未写入的帧及其开始显示的时间（相对录制开始）
+    let mut pending = first;
+    let mut pending_at = Duration::ZERO;
+    let mut stopped_by = StopReason::Requested;
+    let mut result = Ok(());
+
+    loop {
+        // 下一个帧间隔边界；截图慢于帧率时跳过已错过的边界
+        let tick = (started.elapsed().as_secs_f64() / interval.as_secs_f64()) as u32 + 1;
+        if let Some(wait) = (interval * tick).checked_sub(started.elapsed()) {
+            // 分段等待以便及时响应停止请求
+            let deadline = Instant::now() + wait;
+            while !stop.load(Ordering::SeqCst) && Instant::now() < deadline {
+                thread::sleep(deadline.saturating_duration_since(Instant::now()).min(Duration::from_millis(20)));
+            }
+        }
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        if started.elapsed() >= options.max_duration {
+            stopped_by = StopReason::MaxDuration;
+            break;
+        }
+
+        let frame = match capture(&options) {
+            // 显示器分辨率变化时缩放到首帧尺寸，编码器要求尺寸一致
+            Ok(frame) if frame.dimensions() != (width, height) => {
+                image::imageops::resize(&frame, width, height, image::imageops::FilterType::Triangle)
+            }
+            Ok(frame) => frame,
+            Err(e) => {
+                eprintln!("[screen-recording] capture failed: {}", e);
+                continue;
+            }
+        };
+        captured_frames += 1;
+        let now = started.elapsed();
+
+        match options.format {
+            // 画面未变化时延长上一帧的显示时间
+            VideoFormat::Gif if frame == pending => continue,
+            VideoFormat::Gif => {
+                if let Err(e) = encoder.write(&pending, now - pending_at, 1) {
+                    result = Err(e);
+                    break;
+                }
+                encoded_frames += 1;
+            }
+            VideoFormat::Mp4 => {
+                // 补齐到当前时间应有的帧数
+                let target = (now.as_secs_f64() * options.fps as f64) as u64;
+                let repeat = target.saturating_sub(encoded_frames).max(1);
+                if let Err(e) = encoder.write(&pending, interval, repeat) {
+                    result = Err(e);
+                    break;
+                }
+                encoded_frames += repeat;
+            }
+        }
+        pending = frame;
+        pending_at = now;
+    }
+
+    let duration = started.elapsed().min(options.max_duration);
+    if result.is_ok() {
+        let repeat = match options.format {
+            VideoFormat::Gif => 1,
+            VideoFormat::Mp4 => ((duration.as_secs_f64() * options.fps as f64).ceil() as u64)
+                .saturating_sub(encoded_frames)
+                .max(1),
+        };
+        result = encoder.write(&pending, duration.saturating_sub(pending_at).max(interval), repeat);
+        encoded_frames += repeat;
+    }
+    let finished = encoder.finish();
+    result?;
+    finished?;
+
+    let bytes = fs::metadata(&options.path).map(|m| m.len()).unwrap_or(0);
+    Ok(ScreenRecordingInfo {
+        path: options.path.display().to_string(),
+        format: options.format,
+        width,
+        height,
+        fps: options.fps,
+        captured_frames,
+        encoded_frames,
+        duration_ms: duration.as_millis() as u64,
+        bytes,
+        stopped_by,
+    })
+}
@@ -0,0 +1,490 @@
+//! macOS ScreenCaptureKit 截图后端：为主显示器维持一个持续运行的捕获流，按需返回最新一帧。
+//!
+//! `CGDisplay::image` 已被弃用，且每次调用都要完整合成一次桌面，连续截图时很慢；
+//! ScreenCaptureKit 的流在画面变化时推送帧，截图只需复制已缓存的最新帧。
+//! 流按 sRGB 色彩空间输出 BGRA 像素，HDR 显示器上的内容会被色调映射，颜色与屏幕显示一致。
+//!
+//! - 首次截图时启动捕获流，之后保持运行；流被系统停止（如撤销屏幕录制权限）、主显示器或其分辨率变化后
+//!   在下次截图时重建
+//! - 启动失败后 `RETRY_AFTER` 内不再重试，由调用方回退到 `CGDisplay::image`
+//! - 需要 macOS 12.3 及以上；运行时找不到 `SCStream` 类时视为不可用
+//!
+//! 通过 Objective-C 运行时直接调用，完成回调使用手工构造的 block。
+
+use super::screen::BgraFrame;
+use core_foundation::base::{CFRelease, CFRetain};
+use core_graphics::color_space::kCGColorSpaceSRGB;
+use core_graphics::display::CGDisplay;
+use std::ffi::{c_char, c_void, CStr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+type Id = *mut c_void;
+type Sel = *mut c_void;
+
+/// 等待完成回调与首帧的时间
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 启动失败后暂不重试的时间，避免每次截图都等待超时
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// 捕获流的最高帧率；画面静止时系统不推送新帧
+const MAX_FRAMES_PER_SECOND: i32 = 30;
+
+/// `kCVPixelFormatType_32BGRA`
+const PIXEL_FORMAT_BGRA: u32 = u32::from_be_bytes(*b"BGRA");
+
+/// `SCStreamOutputTypeScreen`
+const OUTPUT_TYPE_SCREEN: isize = 0;
+
+/// `kCVPixelBufferLock_ReadOnly`
+const LOCK_READ_ONLY: u64 = 1;
+
+#[link(name = "ScreenCaptureKit", kind = "framework")]
+unsafe extern "C" {}
+
+#[link(name = "CoreMedia", kind = "framework")]
+unsafe extern "C" {
+    fn CMSampleBufferGetImageBuffer(buffer: *mut c_void) -> *mut c_void;
+}
+
+#[link(name = "CoreVideo", kind = "framework")]
+unsafe extern "C" {
+    fn CVPixelBufferLockBaseAddress(buffer: *mut c_void, flags: u64) -> i32;
+    fn CVPixelBufferUnlockBaseAddress(buffer: *mut c_void, flags: u64) -> i32;
+    fn CVPixelBufferGetBaseAddress(buffer: *mut c_void) -> *mut c_void;
+    fn CVPixelBufferGetBytesPerRow(buffer: *mut c_void) -> usize;
+    fn CVPixelBufferGetWidth(buffer: *mut c_void) -> usize;
+    fn CVPixelBufferGetHeight(buffer: *mut c_void) -> usize;
+}
+
+#[link(name = "objc")]
+unsafe extern "C" {
+    fn objc_getClass(name: *const c_char) -> Id;
+    fn objc_getProtocol(name: *const c_char) -> Id;
+    fn objc_allocateClassPair(superclass: Id, name: *const c_char, extra: usize) -> Id;
+    fn objc_registerClassPair(class: Id);
+    fn class_addMethod(class: Id, selector: Sel, imp: *const c_void, types: *const c_char) -> bool;
+    fn class_addProtocol(class: Id, protocol: Id) -> bool;
+    fn sel_registerName(name: *const c_char) -> Sel;
+    fn objc_msgSend();
+    fn objc_release(object: Id);
+    fn objc_retain(object: Id) -> Id;
+    fn objc_autoreleasePoolPush() -> *mut c_void;
+    fn objc_autoreleasePoolPop(pool: *mut c_void);
+}
+
+unsafe extern "C" {
+    static _NSConcreteStackBlock: *const c_void;
+    fn dispatch_queue_create(label: *const c_char, attr: *mut c_void) -> *mut c_void;
+}
+
+/// 以给定的参数与返回类型调用 `objc_msgSend`
+macro_rules! send {
+    ($ret:ty; $receiver:expr, $selector:literal $(, $arg:expr => $ty:ty)*) => {{
+        let f: unsafe extern "C" fn(Id, Sel $(, $ty)*) -> $ret =
+            std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+        f($receiver, sel($selector) $(, $arg)*)
+    }};
+}
+
+unsafe fn sel(name: &[u8]) -> Sel {
+    unsafe { sel_registerName(name.as_ptr() as *const c_char) }
+}
+
+unsafe fn class(name: &[u8]) -> Id {
+    unsafe { objc_getClass(name.as_ptr() as *const c_char) }
+}
+
+/// `CMTime`，用于 `minimumFrameInterval`
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CMTime {
+    value: i64,
+    timescale: i32,
+    flags: u32,
+    epoch: i64,
+}
+
+/// `kCMTimeFlags_Valid`
+const CMTIME_VALID: u32 = 1;
+
+// ============================================================
+// 完成回调
+// ============================================================
+
+#[repr(C)]
+struct BlockDescriptor {
+    reserved: usize,
+    size: usize,
+}
+
+/// 不捕获 Objective-C 对象的 block；`context` 为 `Arc<Completion>` 的裸指针。
+/// 异步接口会把栈上的 block 复制到堆上，复制按字节进行，因此 context 只能是普通指针
+#[repr(C)]
+struct Block {
+    isa: *const c_void,
+    flags: i32,
+    reserved: i32,
+    invoke: *const c_void,
+    descriptor: *const BlockDescriptor,
+    context: *const Completion,
+}
+
+static BLOCK_DESCRIPTOR: BlockDescriptor = BlockDescriptor { reserved: 0, size: std::mem::size_of::<Block>() };
+
+impl Block {
+    /// `invoke` 的第一个参数为 block 自身，其余参数与 Objective-C 声明一致
+    fn new(invoke: *const c_void, completion: &Arc<Completion>) -> Self {
+        Block {
+            isa: &raw const _NSConcreteStackBlock as *const c_void,
+            flags: 0,
+            reserved: 0,
+            invoke,
+            descriptor: &BLOCK_DESCRIPTOR,
+            context: Arc::into_raw(completion.clone()),
+        }
+    }
+}
+
+/// 完成回调的结果：成功时为保留（retain）过的对象指针
+#[derive(Default)]
+struct Completion {
+    result: Mutex<Option<Result<usize, String>>>,
+    ready: Condvar,
+}
+
+impl Completion {
+    fn finish(&self, result: Result<usize, String>) {
+        *lock(&self.result) = Some(result);
+        self.ready.notify_all();
+    }
+
+    fn wait(&self, what: &str) -> Result<usize, String> {
+        let guard = lock(&self.result);
+        let (mut guard, _) = self
+            .ready
+            .wait_timeout_while(guard, STARTUP_TIMEOUT, |result| result.is_none())
+            .unwrap_or_else(|e| e.into_inner());
+        guard.take().unwrap_or_else(|| Err(format!("{} timed out", what)))
+    }
+}
+
+/// 取回 block 持有的 Completion 引用（每个 block 只调用一次）
+unsafe fn take_completion(block: *mut Block) -> Arc<Completion> {
+    unsafe { Arc::from_raw((*block).context) }
+}
+
+/// `NSError.localizedDescription`
+unsafe fn error_description(error: Id) -> String {
+    unsafe {
+        let description: Id = send!(Id; error, b"localizedDescription\0");
+        if description.is_null() {
+            return "unknown error".to_string();
+        }
+        let utf8: *const c_char = send!(*const c_char; description, b"UTF8String\0");
+        if utf8.is_null() {
+            "unknown error".to_string()
+        } else {
+            CStr::from_ptr(utf8).to_string_lossy().into_owned()
+        }
+    }
+}
+
+/// `getShareableContentWithCompletionHandler:` 的回调：`(SCShareableContent *, NSError *)`
+unsafe extern "C" fn shareable_content_done(block: *mut Block, content: Id, error: Id) {
+    unsafe {
+        let completion = take_completion(block);
+        completion.finish(if content.is_null() {
+            Err(format!("SCShareableContent: {}", error_description(error)))
+        } else {
+            Ok(objc_retain(content) as usize)
+        });
+    }
+}
+
+/// `startCaptureWithCompletionHandler:` 的回调：`(NSError *)`
+unsafe extern "C" fn capture_started(block: *mut Block, error: Id) {
+    unsafe {
+        let completion = take_completion(block);
+        completion.finish(if error.is_null() {
+            Ok(0)
+        } else {
+            Err(format!("startCapture: {}", error_description(error)))
+        });
+    }
+}
+
+// ============================================================
+// 帧输出
+// ============================================================
+
+/// 最新一帧的 CVPixelBuffer（已 CFRetain）；尚未收到帧时为 0
+static LATEST: Mutex<usize> = Mutex::new(0);
+static FRAME_READY: Condvar = Condvar::new();
+
+/// 捕获流已被系统停止或被标记为失效，下次截图时重建
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `-[SCStreamOutput stream:didOutputSampleBuffer:ofType:]`，在捕获队列上调用
+unsafe extern "C" fn did_output_sample_buffer(_this: Id, _cmd: Sel, _stream: Id, sample: *mut c_void, kind: isize) {
+    if kind != OUTPUT_TYPE_SCREEN || sample.is_null() {
+        return;
+    }
+    unsafe {
+        // 画面未变化时系统推送的空闲帧不带像素
+        let pixels = CMSampleBufferGetImageBuffer(sample);
+        if pixels.is_null() {
+            return;
+        }
+        CFRetain(pixels as _);
+        let previous = std::mem::replace(&mut *lock(&LATEST), pixels as usize);
+        if previous != 0 {
+            CFRelease(previous as _);
+        }
+    }
+    FRAME_READY.notify_all();
+}
+
+/// `-[SCStreamDelegate stream:didStopWithError:]`
+unsafe extern "C" fn did_stop(_this: Id, _cmd: Sel, _stream: Id, error: Id) {
+    let reason = unsafe { error_description(error) };
+    eprintln!("[screen] ScreenCaptureKit stream stopped: {}", reason);
+    STOPPED.store(true, Ordering::SeqCst);
+}
+
+/// 运行时注册同时实现 SCStreamOutput 与 SCStreamDelegate 的输出类
+fn output_class() -> Id {
+    static CLASS: OnceLock<usize> = OnceLock::new();
+    *CLASS.get_or_init(|| unsafe {
+        let class = objc_allocateClassPair(class(b"NSObject\0"), c"IrisScreenCaptureOutput".as_ptr(), 0);
+        class_addMethod(
+            class,
+            sel(b"stream:didOutputSampleBuffer:ofType:\0"),
+            did_output_sample_buffer as *const c_void,
+            c"v@:@^{opaqueCMSampleBuffer=}q".as_ptr(),
+        );
+        class_addMethod(
+            class,
+            sel(b"stream:didStopWithError:\0"),
+            did_stop as *const c_void,
+            c"v@:@@".as_ptr(),
+        );
+        for protocol in [&b"SCStreamOutput\0"[..], &b"SCStreamDelegate\0"[..]] {
+            let protocol = objc_getProtocol(protocol.as_ptr() as *const c_char);
+            if !protocol.is_null() {
+                class_addProtocol(class, protocol);
+            }
+        }
+        objc_registerClassPair(class);
+        class as usize
+    }) as Id
+}
+
+// ============================================================
+// 捕获流
+// ============================================================
+
+/// 运行中的捕获流及其输出对象（均已保留）
+struct Session {
+    stream: usize,
+    output: usize,
+    /// 捕获的显示器与输出尺寸（物理像素），主显示器或分辨率变化后需重建
+    display: u32,
+    size: (u64, u64),
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        unsafe {
+            let _: () = send!((); self.stream as Id, b"stopCaptureWithCompletionHandler:\0", std::ptr::null_mut::<c_void>() => Id);
+            objc_release(self.stream as Id);
+            objc_release(self.output as Id);
+        }
+        let previous = std::mem::take(&mut *lock(&LATEST));
+        if previous != 0 {
+            unsafe { CFRelease(previous as _) };
+        }
+    }
+}
+
+struct State {
+    session: Option<Session>,
+    last_failure: Option<Instant>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { session: None, last_failure: None });
+
+/// 系统是否提供 ScreenCaptureKit（macOS 12.3+）
+pub(crate) fn available() -> bool {
+    unsafe { !class(b"SCStream\0").is_null() }
+}
+
+/// 丢弃当前捕获流，下次截图时按新的显示器几何重建（显示器分辨率或排列变化后调用）
+pub(crate) fn invalidate() {
+    STOPPED.store(true, Ordering::SeqCst);
+}
+
+/// 返回主显示器的最新一帧；首次调用时启动捕获流并等待首帧
+pub(crate) fn capture_main_display() -> Result<BgraFrame, String> {
+    let mut state = lock(&STATE);
+    if STOPPED.swap(false, Ordering::SeqCst) {
+        state.session = None;
+    }
+    // 会话按启动时的主显示器几何配置，变化后重建
+    let main = CGDisplay::main();
+    if state
+        .session
+        .as_ref()
+        .is_some_and(|session| session.display != main.id || session.size != (main.pixels_wide(), main.pixels_high()))
+    {
+        state.session = None;
+    }
+    if state.session.is_none() {
+        if let Some(failed) = state.last_failure
+            && failed.elapsed() < RETRY_AFTER
+        {
+            return Err("ScreenCaptureKit failed recently".to_string());
+        }
+        let pool = unsafe { objc_autoreleasePoolPush() };
+        let started = unsafe { start_session() };
+        unsafe { objc_autoreleasePoolPop(pool) };
+        match started {
+            Ok(session) => {
+                state.session = Some(session);
+                state.last_failure = None;
+            }
+            Err(error) => {
+                state.last_failure = Some(Instant::now());
+                return Err(error);
+            }
+        }
+    }
+    drop(state);
+    copy_latest()
+}
+
+/// 等待首帧并复制最新帧的像素
+fn copy_latest() -> Result<BgraFrame, String> {
+    let latest = lock(&LATEST);
+    let (latest, _) = FRAME_READY
+        .wait_timeout_while(latest, STARTUP_TIMEOUT, |buffer| *buffer == 0)
+        .unwrap_or_else(|e| e.into_inner());
+    if *latest == 0 {
+        return Err("no frame received from ScreenCaptureKit".to_string());
+    }
+    let buffer = *latest as *mut c_void;
+    unsafe {
+        if CVPixelBufferLockBaseAddress(buffer, LOCK_READ_ONLY) != 0 {
+            return Err("CVPixelBufferLockBaseAddress failed".to_string());
+        }
+        let width = CVPixelBufferGetWidth(buffer);
+        let height = CVPixelBufferGetHeight(buffer);
+        let stride = CVPixelBufferGetBytesPerRow(buffer);
+        let base = CVPixelBufferGetBaseAddress(buffer) as *const u8;
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        if !base.is_null() {
+            for row in 0..height {
+                pixels.extend_from_slice(std::slice::from_raw_parts(base.add(row * stride), width * 4));
+            }
+        }
+        CVPixelBufferUnlockBaseAddress(buffer, LOCK_READ_ONLY);
+        if base.is_null() {
+            return Err("pixel buffer has no base address".to_string());
+        }
+        Ok(BgraFrame { width: width as u32, height: height as u32, pixels })
+    }
+}
+
+/// 查找主显示器并启动捕获流；需在自动释放池内调用
+unsafe fn start_session() -> Result<Session, String> {
+    unsafe {
+        if !available() {
+            return Err("ScreenCaptureKit requires macOS 12.3 or later".to_string());
+        }
+
+        // 可共享内容（显示器列表）
+        let completion = Arc::new(Completion::default());
+        let mut block = Block::new(shareable_content_done as *const c_void, &completion);
+        let _: () = send!((); class(b"SCShareableContent\0"), b"getShareableContentWithCompletionHandler:\0", &mut block as *mut Block as Id => Id);
+        let content = completion.wait("SCShareableContent")? as Id;
+
+        let main = CGDisplay::main();
+        let displays: Id = send!(Id; content, b"displays\0");
+        let count: usize = if displays.is_null() { 0 } else { send!(usize; displays, b"count\0") };
+        let display = (0..count)
+            .map(|i| send!(Id; displays, b"objectAtIndex:\0", i => usize))
+            .find(|&display| send!(u32; display, b"displayID\0") == main.id);
+        let Some(display) = display else {
+            objc_release(content);
+            return Err("main display not found in SCShareableContent".to_string());
+        };
+
+        let empty: Id = send!(Id; class(b"NSArray\0"), b"array\0");
+        let filter: Id = send!(Id; class(b"SCContentFilter\0"), b"alloc\0");
+        let filter: Id = send!(Id; filter, b"initWithDisplay:excludingWindows:\0", display => Id, empty => Id);
+        objc_release(content);
+
+        // 以物理像素输出，与 CGDisplay::image 的尺寸一致；不绘制光标
+        let config: Id = send!(Id; class(b"SCStreamConfiguration\0"), b"new\0");
+        let _: () = send!((); config, b"setWidth:\0", main.pixels_wide() as usize => usize);
+        let _: () = send!((); config, b"setHeight:\0", main.pixels_high() as usize => usize);
+        let _: () = send!((); config, b"setPixelFormat:\0", PIXEL_FORMAT_BGRA => u32);
+        let _: () = send!((); config, b"setShowsCursor:\0", false => bool);
+        let _: () = send!((); config, b"setColorSpaceName:\0", kCGColorSpaceSRGB as Id => Id);
+        let interval = CMTime { value: 1, timescale: MAX_FRAMES_PER_SECOND, flags: CMTIME_VALID, epoch: 0 };
+        let _: () = send!((); config, b"setMinimumFrameInterval:\0", interval => CMTime);
+
+        let output: Id = send!(Id; send!(Id; output_class(), b"alloc\0"), b"init\0");
+        let stream: Id = send!(Id; class(b"SCStream\0"), b"alloc\0");
+        let stream: Id = send!(
+            Id;
+            stream,
+            b"initWithFilter:configuration:delegate:\0",
+            filter => Id,
+            config => Id,
+            output => Id
+        );
+        objc_release(filter);
+        objc_release(config);
+        if stream.is_null() {
+            objc_release(output);
+            return Err("SCStream init failed".to_string());
+        }
+        // Session 负责在失败时停止并释放流与输出对象
+        let session = Session {
+            stream: stream as usize,
+            output: output as usize,
+            display: main.id,
+            size: (main.pixels_wide(), main.pixels_high()),
+        };
+
+        let queue = dispatch_queue_create(c"iris.screen-capture".as_ptr(), std::ptr::null_mut());
+        let mut error: Id = std::ptr::null_mut();
+        let added: bool = send!(
+            bool;
+            stream,
+            b"addStreamOutput:type:sampleHandlerQueue:error:\0",
+            output => Id,
+            OUTPUT_TYPE_SCREEN => isize,
+            queue => *mut c_void,
+            &mut error => *mut Id
+        );
+        if !added {
+            return Err(format!("addStreamOutput: {}", error_description(error)));
+        }
+
+        let completion = Arc::new(Completion::default());
+        let mut block = Block::new(capture_started as *const c_void, &completion);
+        let _: () = send!((); stream, b"startCaptureWithCompletionHandler:\0", &mut block as *mut Block as Id => Id);
+        completion.wait("startCapture")?;
+
+        eprintln!("[screen] ScreenCaptureKit stream started for display {}", main.id);
+        Ok(session)
+    }
+}
@@ -0,0 +1,104 @@
+//! 将连续的鼠标移动事件合并为笔画，减少路径密集会话的返回数据量。
+//!
+//! 相邻移动间隔超过 `gestures::STROKE_GAP_MICROS`、中间出现按钮或滚轮事件，
+//! 或注入标记不同时开始新的笔画；非移动事件原样保留。
+
+use super::gestures::{Point, STROKE_GAP_MICROS};
+use super::key_mouse::{MouseEvent, MouseEventKind, WindowContext};
+use serde::Serialize;
+
+/// 笔画经过的矩形范围（含边界）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BoundingBox {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+impl BoundingBox {
+    fn at(point: Point) -> Self {
+        BoundingBox { min_x: point.x, min_y: point.y, max_x: point.x, max_y: point.y }
+    }
+
+    fn include(&mut self, point: Point) {
+        self.min_x = self.min_x.min(point.x);
+        self.min_y = self.min_y.min(point.y);
+        self.max_x = self.max_x.max(point.x);
+        self.max_y = self.max_y.max(point.y);
+    }
+}
+
+/// 一段连续的移动
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveStroke {
+    /// 第一个与最后一个移动事件的序号
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub start: Point,
+    pub end: Point,
+    pub point_count: usize,
+    pub start_micros: u128,
+    pub end_micros: u128,
+    pub bounding_box: BoundingBox,
+    pub synthetic: bool,
+    /// 第一个移动事件的前台窗口
+    pub window: Option<WindowContext>,
+}
+
+impl MoveStroke {
+    pub fn duration_micros(&self) -> u128 {
+        self.end_micros.saturating_sub(self.start_micros)
+    }
+}
+
+/// 合并后的事件：笔画或原样保留的非移动事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Coalesced<'a> {
+    Stroke(MoveStroke),
+    Event(u64, &'a MouseEvent),
+}
+
+/// 按顺序合并 `(序号, 事件)` 中的连续移动
+pub fn coalesce(events: &[(u64, MouseEvent)]) -> Vec<Coalesced<'_>> {
+    let mut result = Vec::new();
+    let mut stroke: Option<MoveStroke> = None;
+
+    for (seq, event) in events {
+        let MouseEventKind::Move { x, y } = event.kind else {
+            result.extend(stroke.take().map(Coalesced::Stroke));
+            result.push(Coalesced::Event(*seq, event));
+            continue;
+        };
+        let point = Point { x, y };
+        match stroke.as_mut() {
+            Some(s)
+                if s.synthetic == event.synthetic
+                    && event.timestamp_micros.saturating_sub(s.end_micros) <= STROKE_GAP_MICROS =>
+            {
+                s.last_seq = *seq;
+                s.end = point;
+                s.end_micros = event.timestamp_micros;
+                s.point_count += 1;
+                s.bounding_box.include(point);
+            }
+            _ => {
+                result.extend(stroke.take().map(Coalesced::Stroke));
+                stroke = Some(MoveStroke {
+                    first_seq: *seq,
+                    last_seq: *seq,
+                    start: point,
+                    end: point,
+                    point_count: 1,
+                    start_micros: event.timestamp_micros,
+                    end_micros: event.timestamp_micros,
+                    bounding_box: BoundingBox::at(point),
+                    synthetic: event.synthetic,
+                    window: event.window.clone(),
+                });
+            }
+        }
+    }
+    result.extend(stroke.map(Coalesced::Stroke));
+    result
+}
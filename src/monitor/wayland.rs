@@ -0,0 +1,13 @@
+//! Wayland 会话检测。
+//!
+//! Wayland 下 rdev 的 X11 监听只能连到 XWayland，原生 Wayland 窗口的输入不会出现，且不会报错；
+//! 检测到 Wayland 会话时监控改用 evdev 后端。经 xdg-desktop-portal（InputCapture / RemoteDesktop，libei）
+//! 的捕获尚未实现。
+
+use std::env;
+
+/// 当前进程是否运行在 Wayland 会话中
+pub fn is_wayland_session() -> bool {
+    env::var_os("WAYLAND_DISPLAY").is_some()
+        || env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+}
@@ -0,0 +1,173 @@
+//! 系统剪贴板文本读写（借助系统自带命令行工具）。
+//!
+//! - macOS：`pbcopy` / `pbpaste`
+//! - Linux：Wayland 下 `wl-copy` / `wl-paste`，X11 下 `xclip`，不可用时 `xsel`
+//! - Windows：PowerShell `Set-Clipboard` / `Get-Clipboard`
+
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Debug)]
+pub enum ClipboardError {
+    UnsupportedPlatform(&'static str),
+    Io(String),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClipboardError::UnsupportedPlatform(os) => write!(f, "clipboard not supported on {}", os),
+            ClipboardError::Io(msg) => write!(f, "clipboard failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+/// 读取剪贴板文本；剪贴板为空或不是文本时返回 None
+pub fn get_text() -> Result<Option<String>, ClipboardError> {
+    let mut last_err = None;
+    for (program, args) in platform::paste_commands() {
+        match Command::new(program).args(*args).stderr(Stdio::null()).output() {
+            Ok(output) if output.status.success() => {
+                let text = String::from_utf8_lossy(&output.stdout).into_owned();
+                return Ok((!text.is_empty()).then_some(text));
+            }
+            // 工具存在但剪贴板无文本内容（如 wl-paste / xclip 在空剪贴板上返回非零）
+            Ok(_) => return Ok(None),
+            Err(e) => last_err = Some(format!("{}: {}", program, e)),
+        }
+    }
+    Err(no_tool(last_err))
+}
+
+/// 写入剪贴板文本
+pub fn set_text(text: &str) -> Result<(), ClipboardError> {
+    let mut last_err = None;
+    for (program, args) in platform::copy_commands() {
+        let mut child = match Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                last_err = Some(format!("{}: {}", program, e));
+                continue;
+            }
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| ClipboardError::Io(format!("{}: {}", program, e)))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| ClipboardError::Io(format!("{}: {}", program, e)))?;
+        if status.success() {
+            return Ok(());
+        }
+        last_err = Some(format!("{} exited with {}", program, status));
+    }
+    Err(no_tool(last_err))
+}
+
+fn no_tool(last_err: Option<String>) -> ClipboardError {
+    match last_err {
+        Some(e) => ClipboardError::Io(e),
+        None => ClipboardError::UnsupportedPlatform(std::env::consts::OS),
+    }
+}
+
+type CommandSpec = (&'static str, &'static [&'static str]);
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::CommandSpec;
+
+    pub fn paste_commands() -> &'static [CommandSpec] {
+        &[("pbpaste", &[])]
+    }
+
+    pub fn copy_commands() -> &'static [CommandSpec] {
+        &[("pbcopy", &[])]
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::CommandSpec;
+
+    fn wayland() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    pub fn paste_commands() -> &'static [CommandSpec] {
+        const X11: &[CommandSpec] = &[
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ];
+        const WAYLAND: &[CommandSpec] = &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ];
+        if wayland() { WAYLAND } else { X11 }
+    }
+
+    pub fn copy_commands() -> &'static [CommandSpec] {
+        const X11: &[CommandSpec] = &[
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ];
+        const WAYLAND: &[CommandSpec] = &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ];
+        if wayland() { WAYLAND } else { X11 }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::CommandSpec;
+
+    pub fn paste_commands() -> &'static [CommandSpec] {
+        &[(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Get-Clipboard -Raw",
+            ],
+        )]
+    }
+
+    pub fn copy_commands() -> &'static [CommandSpec] {
+        &[(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ],
+        )]
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::CommandSpec;
+
+    pub fn paste_commands() -> &'static [CommandSpec] {
+        &[]
+    }
+
+    pub fn copy_commands() -> &'static [CommandSpec] {
+        &[]
+    }
+}
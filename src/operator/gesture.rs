@@ -0,0 +1,179 @@
+//! 触控板手势合成：捏合缩放、旋转与双指滑动。
+//!
+//! macOS 上通过未公开的 CGEvent 手势事件（kCGSEventGesture）模拟触控板，
+//! 作用于光标下方的视图；双指滑动以连续的像素滚动事件实现。其他平台暂未实现。
+
+use super::mouse::MouseController;
+use enigo::Mouse;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum GestureError {
+    NotImplemented(&'static str),
+    Io(String),
+}
+
+impl fmt::Display for GestureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GestureError::NotImplemented(msg) => write!(f, "gesture not implemented: {}", msg),
+            GestureError::Io(msg) => write!(f, "gesture failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for GestureError {}
+
+/// 捏合缩放。`magnification` 为总缩放量：正值放大（pinch out），负值缩小（pinch in），
+/// 1.0 约相当于双指张开一整次的幅度。
+pub fn pinch(magnification: f64, steps: u32, duration_ms: u64) -> Result<(), GestureError> {
+    run(platform::Kind::Zoom, magnification, steps, duration_ms)
+}
+
+/// 旋转手势，`degrees` 为正时逆时针旋转（与触控板一致）
+pub fn rotate(degrees: f64, steps: u32, duration_ms: u64) -> Result<(), GestureError> {
+    run(platform::Kind::Rotate, degrees, steps, duration_ms)
+}
+
+/// 发送开始阶段，把 `total` 均分到 `steps` 个变化事件，最后发送结束阶段
+fn run(kind: platform::Kind, total: f64, steps: u32, duration_ms: u64) -> Result<(), GestureError> {
+    let steps = steps.max(1);
+    let delay = Duration::from_millis(duration_ms / steps as u64);
+    platform::begin(kind)?;
+    let changed = (0..steps).try_for_each(|_| {
+        platform::change(kind, total / steps as f64)?;
+        thread::sleep(delay);
+        Ok(())
+    });
+    // 中途失败也要发送结束阶段，否则目标视图停留在手势进行中的状态
+    let ended = platform::end(kind);
+    changed.and(ended)
+}
+
+/// 双指滑动（平移），正值向下/向右，等价于平滑像素滚动
+pub fn swipe<M: Mouse + Send>(
+    mouse: &mut MouseController<M>,
+    dx: i32,
+    dy: i32,
+    steps: u32,
+    duration_ms: u64,
+) -> Result<(), GestureError> {
+    mouse
+        .mouse_smooth_scroll(dx, dy, steps, duration_ms)
+        .map_err(|e| GestureError::Io(e.to_string()))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::GestureError;
+    use crate::operator::source;
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    type CGEventRef = *mut c_void;
+
+    /// 私有事件类型 kCGSEventGesture
+    const EVENT_TYPE_GESTURE: u32 = 29;
+    /// 手势相关的私有事件字段
+    const FIELD_GESTURE_HID_TYPE: u32 = 110;
+    const FIELD_GESTURE_ZOOM_VALUE: u32 = 113;
+    const FIELD_GESTURE_ROTATION_VALUE: u32 = 114;
+    const FIELD_GESTURE_PHASE: u32 = 132;
+    /// IOHIDEventType
+    const HID_TYPE_ROTATION: i64 = 5;
+    const HID_TYPE_ZOOM: i64 = 8;
+    /// 手势阶段
+    const PHASE_BEGAN: i64 = 1;
+    const PHASE_CHANGED: i64 = 2;
+    const PHASE_ENDED: i64 = 4;
+    /// kCGHIDEventTap
+    const TAP_HID: u32 = 0;
+    /// kCGEventSourceUserData
+    const FIELD_SOURCE_USER_DATA: u32 = 42;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventCreate(source: *const c_void) -> CGEventRef;
+        fn CGEventSetType(event: CGEventRef, event_type: u32);
+        fn CGEventSetIntegerValueField(event: CGEventRef, field: u32, value: i64);
+        fn CGEventSetDoubleValueField(event: CGEventRef, field: u32, value: f64);
+        fn CGEventPost(tap: u32, event: CGEventRef);
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum Kind {
+        Zoom,
+        Rotate,
+    }
+
+    fn post(kind: Kind, phase: i64, value: f64) -> Result<(), GestureError> {
+        let event = unsafe { CGEventCreate(ptr::null()) };
+        if event.is_null() {
+            return Err(GestureError::Io("CGEventCreate failed".to_string()));
+        }
+        let (hid_type, field) = match kind {
+            Kind::Zoom => (HID_TYPE_ZOOM, FIELD_GESTURE_ZOOM_VALUE),
+            Kind::Rotate => (HID_TYPE_ROTATION, FIELD_GESTURE_ROTATION_VALUE),
+        };
+        unsafe {
+            CGEventSetType(event, EVENT_TYPE_GESTURE);
+            CGEventSetIntegerValueField(event, FIELD_GESTURE_HID_TYPE, hid_type);
+            CGEventSetIntegerValueField(event, FIELD_GESTURE_PHASE, phase);
+            CGEventSetDoubleValueField(event, field, value);
+            CGEventSetIntegerValueField(event, FIELD_SOURCE_USER_DATA, source::SOURCE_SIGNATURE);
+            let _injecting = source::begin_injection();
+            CGEventPost(TAP_HID, event);
+            CFRelease(event);
+        }
+        Ok(())
+    }
+
+    pub fn begin(kind: Kind) -> Result<(), GestureError> {
+        post(kind, PHASE_BEGAN, 0.0)
+    }
+
+    pub fn change(kind: Kind, delta: f64) -> Result<(), GestureError> {
+        post(kind, PHASE_CHANGED, delta)
+    }
+
+    pub fn end(kind: Kind) -> Result<(), GestureError> {
+        post(kind, PHASE_ENDED, 0.0)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::GestureError;
+
+    #[derive(Clone, Copy)]
+    pub enum Kind {
+        Zoom,
+        Rotate,
+    }
+
+    fn unsupported(kind: Kind) -> Result<(), GestureError> {
+        Err(GestureError::NotImplemented(match kind {
+            Kind::Zoom => "pinch gestures are only synthesized on macOS",
+            Kind::Rotate => "rotate gestures are only synthesized on macOS",
+        }))
+    }
+
+    pub fn begin(kind: Kind) -> Result<(), GestureError> {
+        unsupported(kind)
+    }
+
+    pub fn change(kind: Kind, _delta: f64) -> Result<(), GestureError> {
+        unsupported(kind)
+    }
+
+    pub fn end(kind: Kind) -> Result<(), GestureError> {
+        unsupported(kind)
+    }
+}
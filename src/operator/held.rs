@@ -0,0 +1,114 @@
+//! 记录经操作层按下且尚未释放的键和鼠标按键。
+//!
+//! 拖拽或组合键中途失败时，按键可能停留在按下状态，导致之后的输入全部带上修饰键。
+//! 工具出错时服务器用 `release_since` 只释放该次调用按下的输入（`key_control` 等跨调用
+//! 保持按下的键不受影响）；关闭时以及 `release_all_inputs` 工具中调用 `release_all` 统一释放。
+
+use super::source;
+use enigo::{Button, Direction, Enigo, Key, Keyboard, Mouse};
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeldInput {
+    Key(Key),
+    Button(Button),
+}
+
+impl HeldInput {
+    fn describe(&self) -> String {
+        match self {
+            HeldInput::Key(key) => format!("{:?}", key),
+            HeldInput::Button(button) => format!("{:?} button", button),
+        }
+    }
+}
+
+/// 释放结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseReport {
+    pub released: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// 按下顺序排列
+static HELD: Mutex<Vec<HeldInput>> = Mutex::new(Vec::new());
+
+fn held_inputs() -> MutexGuard<'static, Vec<HeldInput>> {
+    HELD.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn record(input: HeldInput, direction: Direction) {
+    let mut held = held_inputs();
+    match direction {
+        Direction::Press => {
+            if !held.contains(&input) {
+                held.push(input);
+            }
+        }
+        Direction::Release => held.retain(|h| *h != input),
+        Direction::Click => {}
+    }
+}
+
+/// 记录一次成功注入的按键事件
+pub fn record_key(key: Key, direction: Direction) {
+    record(HeldInput::Key(key), direction);
+}
+
+/// 记录一次成功注入的鼠标按键事件
+pub fn record_button(button: Button, direction: Direction) {
+    record(HeldInput::Button(button), direction);
+}
+
+/// 当前仍处于按下状态的输入
+pub fn held() -> Vec<HeldInput> {
+    held_inputs().clone()
+}
+
+/// 按与按下相反的顺序释放全部记录的输入
+pub fn release_all() -> Result<ReleaseReport, enigo::NewConError> {
+    release_matching(|_| true)
+}
+
+/// 只释放 `before`（调用前的 `held()` 快照）中没有的输入，即其后新按下的输入
+pub fn release_since(before: &[HeldInput]) -> Result<ReleaseReport, enigo::NewConError> {
+    release_matching(|input| !before.contains(input))
+}
+
+fn release_matching(matches: impl Fn(&HeldInput) -> bool) -> Result<ReleaseReport, enigo::NewConError> {
+    let inputs: Vec<HeldInput> = {
+        let mut held = held_inputs();
+        let (matched, kept): (Vec<HeldInput>, Vec<HeldInput>) = held.drain(..).partition(|input| matches(input));
+        *held = kept;
+        matched.into_iter().rev().collect()
+    };
+    let mut report = ReleaseReport {
+        released: Vec::new(),
+        failed: Vec::new(),
+    };
+    if inputs.is_empty() {
+        return Ok(report);
+    }
+
+    let mut enigo = match Enigo::new(&source::settings()) {
+        Ok(enigo) => enigo,
+        Err(e) => {
+            // 无法连接输入系统时保留记录，供之后重试
+            held_inputs().extend(inputs.into_iter().rev());
+            return Err(e);
+        }
+    };
+    let _injecting = source::begin_injection();
+    for input in inputs {
+        let result = match input {
+            HeldInput::Key(key) => enigo.key(key, Direction::Release),
+            HeldInput::Button(button) => enigo.button(button, Direction::Release),
+        };
+        match result {
+            Ok(()) => report.released.push(input.describe()),
+            Err(e) => report.failed.push(format!("{}: {}", input.describe(), e)),
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,187 @@
+//! 输入法（IME）临时关闭与恢复：输入法激活时注入的按键会被候选窗拦截，
+//! 中日韩文本输入前切换到 ASCII 键盘布局，输入完成后恢复原输入法。
+//!
+//! - macOS：Text Input Sources（切换到当前 ASCII 键盘布局，结束后选回原输入源）
+//! - Linux：fcitx5 / fcitx（`fcitx5-remote -c` / `-o`），其次 IBus（`ibus engine`）
+//! - Windows：尚未实现
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ImeError {
+    NotImplemented(&'static str),
+    Io(String),
+}
+
+impl fmt::Display for ImeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImeError::NotImplemented(msg) => write!(f, "IME control not implemented: {}", msg),
+            ImeError::Io(msg) => write!(f, "IME control failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ImeError {}
+
+/// 关闭输入法前的状态；离开作用域时恢复
+pub struct ImeGuard {
+    state: Option<platform::State>,
+}
+
+impl ImeGuard {
+    /// 立即恢复原输入法（与 drop 相同，但返回错误）
+    pub fn restore(mut self) -> Result<(), ImeError> {
+        match self.state.take() {
+            Some(state) => platform::restore(state),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for ImeGuard {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take()
+            && let Err(e) = platform::restore(state)
+        {
+            eprintln!("[ime] restore failed: {}", e);
+        }
+    }
+}
+
+/// 临时关闭输入法，返回的 guard 被释放时恢复
+pub fn disable() -> Result<ImeGuard, ImeError> {
+    Ok(ImeGuard {
+        state: Some(platform::disable()?),
+    })
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::ImeError;
+    use std::os::raw::c_void;
+
+    type TISInputSourceRef = *const c_void;
+
+    #[link(name = "Carbon", kind = "framework")]
+    unsafe extern "C" {
+        fn TISCopyCurrentKeyboardInputSource() -> TISInputSourceRef;
+        fn TISCopyCurrentASCIICapableKeyboardLayoutInputSource() -> TISInputSourceRef;
+        fn TISSelectInputSource(source: TISInputSourceRef) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFRelease(cf: *const c_void);
+        fn CFEqual(a: *const c_void, b: *const c_void) -> u8;
+    }
+
+    /// 原输入源（持有一次引用，恢复时释放）
+    pub struct State(TISInputSourceRef);
+
+    pub fn disable() -> Result<State, ImeError> {
+        unsafe {
+            let current = TISCopyCurrentKeyboardInputSource();
+            if current.is_null() {
+                return Err(ImeError::Io("no current input source".to_string()));
+            }
+            let ascii = TISCopyCurrentASCIICapableKeyboardLayoutInputSource();
+            if ascii.is_null() {
+                CFRelease(current);
+                return Err(ImeError::Io("no ASCII-capable keyboard layout".to_string()));
+            }
+            let status = if CFEqual(current, ascii) != 0 { 0 } else { TISSelectInputSource(ascii) };
+            CFRelease(ascii);
+            if status != 0 {
+                CFRelease(current);
+                return Err(ImeError::Io(format!("TISSelectInputSource failed: {}", status)));
+            }
+            Ok(State(current))
+        }
+    }
+
+    pub fn restore(state: State) -> Result<(), ImeError> {
+        unsafe {
+            let status = TISSelectInputSource(state.0);
+            CFRelease(state.0);
+            if status != 0 {
+                return Err(ImeError::Io(format!("TISSelectInputSource failed: {}", status)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::ImeError;
+    use std::process::Command;
+
+    /// IBus 无引擎时使用的美式键盘布局
+    const IBUS_ASCII_ENGINE: &str = "xkb:us::eng";
+
+    pub enum State {
+        /// fcitx 远程控制程序名与关闭前是否处于激活状态
+        Fcitx { program: &'static str, was_active: bool },
+        /// 关闭前的 IBus 引擎
+        Ibus { engine: String },
+    }
+
+    fn run(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn disable() -> Result<State, ImeError> {
+        for program in ["fcitx5-remote", "fcitx-remote"] {
+            // 输出 2 表示激活，1 表示未激活，0 表示 fcitx 未运行
+            match run(program, &[]).as_deref() {
+                Some("2") => {
+                    run(program, &["-c"]).ok_or_else(|| ImeError::Io(format!("{} -c failed", program)))?;
+                    return Ok(State::Fcitx { program, was_active: true });
+                }
+                Some("1") => return Ok(State::Fcitx { program, was_active: false }),
+                _ => {}
+            }
+        }
+        if let Some(engine) = run("ibus", &["engine"]).filter(|e| !e.is_empty()) {
+            if engine != IBUS_ASCII_ENGINE {
+                run("ibus", &["engine", IBUS_ASCII_ENGINE])
+                    .ok_or_else(|| ImeError::Io("ibus engine switch failed".to_string()))?;
+            }
+            return Ok(State::Ibus { engine });
+        }
+        Err(ImeError::NotImplemented("no supported input method framework (fcitx5, fcitx, ibus) is running"))
+    }
+
+    pub fn restore(state: State) -> Result<(), ImeError> {
+        match state {
+            State::Fcitx { program, was_active: true } => run(program, &["-o"])
+                .map(|_| ())
+                .ok_or_else(|| ImeError::Io(format!("{} -o failed", program))),
+            State::Fcitx { was_active: false, .. } => Ok(()),
+            State::Ibus { engine } if engine == IBUS_ASCII_ENGINE => Ok(()),
+            State::Ibus { engine } => run("ibus", &["engine", &engine])
+                .map(|_| ())
+                .ok_or_else(|| ImeError::Io("ibus engine restore failed".to_string())),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+mod platform {
+    use super::ImeError;
+
+    pub struct State;
+
+    pub fn disable() -> Result<State, ImeError> {
+        Err(ImeError::NotImplemented("Windows: implement ImmSetOpenStatus via the foreground IME window"))
+    }
+
+    pub fn restore(_state: State) -> Result<(), ImeError> {
+        Ok(())
+    }
+}
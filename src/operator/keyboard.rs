@@ -1,4 +1,19 @@
+use super::{held, pacing, source};
 use enigo::{Direction, Key, Keyboard};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Delay between consecutive Tab presses so focus changes are processed in order.
+const FOCUS_STEP_DELAY_MS: u64 = 30;
+
+/// One step of a key sequence
+#[derive(Debug, Clone, Copy)]
+pub struct KeyStep {
+    pub key: Key,
+    pub direction: Direction,
+    /// Pause after this step
+    pub delay_ms: u64,
+}
 
 /// Keyboard operations wrapper; generic over any `Keyboard` impl so we can mock in tests.
 pub struct KeyboardController<K: Keyboard + Send> {
@@ -10,38 +25,206 @@ impl<K: Keyboard + Send> KeyboardController<K> {
         Self { keyboard }
     }
 
+    /// Inject one key event and track it in the held-input registry
+    fn key(&mut self, key: Key, direction: Direction) -> Result<(), enigo::InputError> {
+        let _injecting = source::begin_injection();
+        self.keyboard.key(key, direction)?;
+        held::record_key(key, direction);
+        Ok(())
+    }
+
+    /// Inject text without tracking (text input leaves no key held)
+    fn text(&mut self, text: &str) -> Result<(), enigo::InputError> {
+        let _injecting = source::begin_injection();
+        self.keyboard.text(text)
+    }
+
     /// Type text
     pub fn type_text(&mut self, text: &str) -> Result<(), enigo::InputError> {
-        self.keyboard.text(text)
+        self.text(text)
+    }
+
+    /// Type text one character at a time, pausing `delay_ms` ± `variance_ms` (uniformly
+    /// random) between characters so slow sessions keep up and entry looks human.
+    pub fn type_text_paced(&mut self, text: &str, delay_ms: u64, variance_ms: u64) -> Result<(), enigo::InputError> {
+        let mut rng = Pacer::seeded();
+        let mut buf = [0u8; 4];
+        for (i, c) in text.chars().enumerate() {
+            if i > 0 {
+                let jitter = if variance_ms > 0 {
+                    rng.below(2 * variance_ms) as i64 - variance_ms as i64
+                } else {
+                    0
+                };
+                pacing::sleep(Duration::from_millis((delay_ms as i64 + jitter).max(0) as u64));
+            }
+            self.text(c.encode_utf8(&mut buf))?;
+        }
+        Ok(())
+    }
+
+    /// Type text in chunks of `chunk_chars` characters (paced as in `type_text_paced`), calling
+    /// `on_chunk(typed, total)` after each chunk. Stops early when the callback returns false.
+    /// Returns the number of characters typed.
+    pub fn type_text_chunked(
+        &mut self,
+        text: &str,
+        chunk_chars: usize,
+        delay_ms: u64,
+        variance_ms: u64,
+        mut on_chunk: impl FnMut(usize, usize) -> bool,
+    ) -> Result<usize, enigo::InputError> {
+        let total = text.chars().count();
+        let chunk_chars = chunk_chars.max(1);
+        let paced = delay_ms > 0 || variance_ms > 0;
+        let mut typed = 0;
+        let mut rest = text;
+        while !rest.is_empty() {
+            let split = rest.char_indices().nth(chunk_chars).map_or(rest.len(), |(i, _)| i);
+            let (chunk, tail) = rest.split_at(split);
+            if paced {
+                if typed > 0 {
+                    pacing::sleep(Duration::from_millis(delay_ms));
+                }
+                self.type_text_paced(chunk, delay_ms, variance_ms)?;
+            } else {
+                self.text(chunk)?;
+            }
+            typed += chunk.chars().count();
+            rest = tail;
+            if !on_chunk(typed, total) {
+                break;
+            }
+        }
+        Ok(typed)
     }
 
     /// Advanced key press/release control
     pub fn key_control(&mut self, key: Key, direction: Direction) -> Result<(), enigo::InputError> {
-        self.keyboard.key(key, direction)
+        self.key(key, direction)
     }
 
-    /// Common shortcuts (copy, paste, undo, save, etc.)
-    pub fn system_command(&mut self, command: SystemCommand) -> Result<(), enigo::InputError> {
-        #[cfg(target_os = "macos")]
-        let modifier = Key::Meta;
-        #[cfg(not(target_os = "macos"))]
-        let modifier = Key::Control;
-
-        self.keyboard.key(modifier, Direction::Press)?;
-        
-        let key = match command {
-            SystemCommand::Copy => Key::Unicode('c'),
-            SystemCommand::Paste => Key::Unicode('v'),
-            SystemCommand::Cut => Key::Unicode('x'),
-            SystemCommand::Undo => Key::Unicode('z'),
-            SystemCommand::Save => Key::Unicode('s'),
-            SystemCommand::SelectAll => Key::Unicode('a'),
+    /// Press `key`, hold it for `hold`, then release it, all in one call so the hold time
+    /// doesn't depend on client round-trips.
+    pub fn key_hold(&mut self, key: Key, hold: Duration) -> Result<(), enigo::InputError> {
+        self.key(key, Direction::Press)?;
+        thread::sleep(hold);
+        self.key(key, Direction::Release)
+    }
+
+    /// Click `key` `count` times, sleeping `interval` between clicks.
+    pub fn key_repeat(&mut self, key: Key, count: u32, interval: Duration) -> Result<(), enigo::InputError> {
+        for i in 0..count {
+            if i > 0 && !interval.is_zero() {
+                pacing::sleep(interval);
+            }
+            self.key(key, Direction::Click)?;
+        }
+        Ok(())
+    }
+
+    /// Press a chord: hold `keys[..n-1]` in order, click the last key, then release
+    /// the held keys in reverse order. Held keys are released even if a later step fails.
+    pub fn key_combo(&mut self, keys: &[Key]) -> Result<(), enigo::InputError> {
+        let Some((&last, modifiers)) = keys.split_last() else {
+            return Ok(());
         };
 
-        self.keyboard.key(key, Direction::Click)?;
-        self.keyboard.key(modifier, Direction::Release)?;
+        let mut pressed = Vec::with_capacity(modifiers.len());
+        let mut result = Ok(());
+        for &key in modifiers {
+            if let Err(e) = self.key(key, Direction::Press) {
+                result = Err(e);
+                break;
+            }
+            pressed.push(key);
+        }
+        if result.is_ok() {
+            result = self.key(last, Direction::Click);
+        }
+        for &key in pressed.iter().rev() {
+            let released = self.key(key, Direction::Release);
+            if result.is_ok() {
+                result = released;
+            }
+        }
+        result
+    }
+
+    /// Run `steps` in order, sleeping each step's `delay_ms` after it. Keys the sequence
+    /// pressed and has not released yet are released in reverse order if a step fails;
+    /// on success they stay down, as requested by the caller.
+    pub fn key_sequence(&mut self, steps: &[KeyStep]) -> Result<(), enigo::InputError> {
+        let mut held: Vec<Key> = Vec::new();
+        for step in steps {
+            if let Err(e) = self.key(step.key, step.direction) {
+                for &key in held.iter().rev() {
+                    let _ = self.key(key, Direction::Release);
+                }
+                return Err(e);
+            }
+            match step.direction {
+                Direction::Press => held.push(step.key),
+                Direction::Release => held.retain(|&k| k != step.key),
+                Direction::Click => {}
+            }
+            if step.delay_ms > 0 {
+                pacing::sleep(Duration::from_millis(step.delay_ms));
+            }
+        }
+        Ok(())
+    }
+
+    /// Move keyboard focus forward (Tab) or backward (Shift+Tab) `times` times
+    pub fn move_focus(&mut self, forward: bool, times: u32) -> Result<(), enigo::InputError> {
+        for i in 0..times {
+            if i > 0 {
+                thread::sleep(Duration::from_millis(FOCUS_STEP_DELAY_MS));
+            }
+            if forward {
+                self.key(Key::Tab, Direction::Click)?;
+            } else {
+                self.key(Key::Shift, Direction::Press)?;
+                let result = self.key(Key::Tab, Direction::Click);
+                self.key(Key::Shift, Direction::Release)?;
+                result?;
+            }
+        }
         Ok(())
     }
+
+    /// Common shortcuts (copy, paste, undo, save, etc.)
+    pub fn system_command(&mut self, command: SystemCommand) -> Result<(), enigo::InputError> {
+        self.key_combo(&command.keys())
+    }
+
+    /// Like `system_command`, but with `primary` standing in for the OS primary modifier
+    /// (for VMs and remapped keyboards where Cmd/Ctrl is swapped)
+    pub fn system_command_with(&mut self, command: SystemCommand, primary: Key) -> Result<(), enigo::InputError> {
+        self.key_combo(&command.keys_with_primary(primary))
+    }
+}
+
+/// Minimal splitmix64 generator for typing jitter; not suitable for anything security related.
+struct Pacer(u64);
+
+impl Pacer {
+    fn seeded() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Pacer(seed)
+    }
+
+    /// Uniform value in 0..=max
+    fn below(&mut self, max: u64) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) % (max + 1)
+    }
 }
 
 pub enum SystemCommand {
@@ -49,6 +232,92 @@ pub enum SystemCommand {
     Paste,
     Cut,
     Undo,
+    Redo,
     Save,
     SelectAll,
+    Find,
+    FindNext,
+    NewTab,
+    CloseTab,
+    CloseWindow,
+    SwitchApp,
+    QuitApp,
+    Refresh,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl SystemCommand {
+    /// The chord for this command on the current OS, modifiers first
+    pub fn keys(&self) -> Vec<Key> {
+        self.keys_with_primary(platform::PRIMARY)
+    }
+
+    /// The chord with `primary` substituted for the OS primary modifier (Cmd on macOS, Ctrl elsewhere)
+    pub fn keys_with_primary(&self, primary: Key) -> Vec<Key> {
+        let substitute = |keys: &[Key]| {
+            keys.iter()
+                .map(|&k| if k == platform::PRIMARY { primary } else { k })
+                .collect()
+        };
+        let primary = |c: char| vec![primary, Key::Unicode(c)];
+        match self {
+            SystemCommand::Copy => primary('c'),
+            SystemCommand::Paste => primary('v'),
+            SystemCommand::Cut => primary('x'),
+            SystemCommand::Undo => primary('z'),
+            SystemCommand::Save => primary('s'),
+            SystemCommand::SelectAll => primary('a'),
+            SystemCommand::Find => primary('f'),
+            SystemCommand::NewTab => primary('t'),
+            SystemCommand::CloseTab => primary('w'),
+            SystemCommand::ZoomIn => primary('='),
+            SystemCommand::ZoomOut => primary('-'),
+            SystemCommand::Redo => substitute(platform::REDO),
+            SystemCommand::FindNext => substitute(platform::FIND_NEXT),
+            SystemCommand::CloseWindow => substitute(platform::CLOSE_WINDOW),
+            SystemCommand::SwitchApp => substitute(platform::SWITCH_APP),
+            SystemCommand::QuitApp => substitute(platform::QUIT_APP),
+            SystemCommand::Refresh => substitute(platform::REFRESH),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use enigo::Key;
+
+    pub const PRIMARY: Key = Key::Meta;
+    pub const REDO: &[Key] = &[Key::Meta, Key::Shift, Key::Unicode('z')];
+    pub const FIND_NEXT: &[Key] = &[Key::Meta, Key::Unicode('g')];
+    pub const CLOSE_WINDOW: &[Key] = &[Key::Meta, Key::Shift, Key::Unicode('w')];
+    pub const SWITCH_APP: &[Key] = &[Key::Meta, Key::Tab];
+    pub const QUIT_APP: &[Key] = &[Key::Meta, Key::Unicode('q')];
+    pub const REFRESH: &[Key] = &[Key::Meta, Key::Unicode('r')];
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use enigo::Key;
+
+    pub const PRIMARY: Key = Key::Control;
+    pub const REDO: &[Key] = &[Key::Control, Key::Unicode('y')];
+    pub const FIND_NEXT: &[Key] = &[Key::F3];
+    pub const CLOSE_WINDOW: &[Key] = &[Key::Alt, Key::F4];
+    pub const SWITCH_APP: &[Key] = &[Key::Alt, Key::Tab];
+    pub const QUIT_APP: &[Key] = &[Key::Alt, Key::F4];
+    pub const REFRESH: &[Key] = &[Key::F5];
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use enigo::Key;
+
+    pub const PRIMARY: Key = Key::Control;
+    pub const REDO: &[Key] = &[Key::Control, Key::Shift, Key::Unicode('z')];
+    pub const FIND_NEXT: &[Key] = &[Key::F3];
+    pub const CLOSE_WINDOW: &[Key] = &[Key::Alt, Key::F4];
+    pub const SWITCH_APP: &[Key] = &[Key::Alt, Key::Tab];
+    pub const QUIT_APP: &[Key] = &[Key::Control, Key::Unicode('q')];
+    pub const REFRESH: &[Key] = &[Key::F5];
 }
@@ -0,0 +1,413 @@
+//! 按键名称表：key_control / key_combo 解析的按键名称与键盘监控输出的按键名称共用一套规范名称，
+//! 监控到的按键名称可以直接回放。
+//!
+//! 名称匹配不区分大小写，并忽略 `_`、`-` 与空格（`page_up`、`PageUp`、`page-up` 等价）；
+//! 单个字符按 Unicode 字符输入。
+
+use enigo::Key;
+use std::fmt;
+
+/// 规范名称及其别名
+const KEY_NAMES: &[(&str, &[&str])] = &[
+    ("Enter", &["return"]),
+    ("Escape", &["esc"]),
+    ("Space", &[]),
+    ("Tab", &[]),
+    ("Backspace", &[]),
+    ("Delete", &["del"]),
+    ("Insert", &["ins"]),
+    ("Up", &["uparrow", "arrowup"]),
+    ("Down", &["downarrow", "arrowdown"]),
+    ("Left", &["leftarrow", "arrowleft"]),
+    ("Right", &["rightarrow", "arrowright"]),
+    ("Home", &[]),
+    ("End", &[]),
+    ("PageUp", &["pgup"]),
+    ("PageDown", &["pgdn"]),
+    ("CapsLock", &["caps"]),
+    ("NumLock", &[]),
+    ("ScrollLock", &[]),
+    ("PrintScreen", &["printscr", "prtsc", "print", "snapshot"]),
+    ("Pause", &["break"]),
+    ("Shift", &[]),
+    ("LeftShift", &["lshift", "shiftleft"]),
+    ("RightShift", &["rshift", "shiftright"]),
+    ("Control", &["ctrl"]),
+    ("LeftControl", &["lctrl", "lcontrol", "leftctrl", "controlleft"]),
+    ("RightControl", &["rctrl", "rcontrol", "rightctrl", "controlright"]),
+    ("Alt", &["option", "opt"]),
+    ("LeftAlt", &["lalt", "leftoption", "altleft"]),
+    ("RightAlt", &["ralt", "rightoption", "altright"]),
+    ("AltGr", &[]),
+    ("Meta", &["command", "cmd", "super", "win", "windows"]),
+    ("LeftMeta", &["lmeta", "leftcommand", "leftcmd", "lwin", "metaleft"]),
+    ("RightMeta", &["rmeta", "rightcommand", "rightcmd", "rwin", "metaright"]),
+    ("Fn", &["function"]),
+    ("F1", &[]),
+    ("F2", &[]),
+    ("F3", &[]),
+    ("F4", &[]),
+    ("F5", &[]),
+    ("F6", &[]),
+    ("F7", &[]),
+    ("F8", &[]),
+    ("F9", &[]),
+    ("F10", &[]),
+    ("F11", &[]),
+    ("F12", &[]),
+    ("F13", &[]),
+    ("F14", &[]),
+    ("F15", &[]),
+    ("F16", &[]),
+    ("F17", &[]),
+    ("F18", &[]),
+    ("F19", &[]),
+    ("F20", &[]),
+    ("F21", &[]),
+    ("F22", &[]),
+    ("F23", &[]),
+    ("F24", &[]),
+    ("Numpad0", &["kp0", "num0"]),
+    ("Numpad1", &["kp1", "num1"]),
+    ("Numpad2", &["kp2", "num2"]),
+    ("Numpad3", &["kp3", "num3"]),
+    ("Numpad4", &["kp4", "num4"]),
+    ("Numpad5", &["kp5", "num5"]),
+    ("Numpad6", &["kp6", "num6"]),
+    ("Numpad7", &["kp7", "num7"]),
+    ("Numpad8", &["kp8", "num8"]),
+    ("Numpad9", &["kp9", "num9"]),
+    ("NumpadAdd", &["kpplus", "numpadplus"]),
+    ("NumpadSubtract", &["kpminus", "numpadminus"]),
+    ("NumpadMultiply", &["kpmultiply"]),
+    ("NumpadDivide", &["kpdivide"]),
+    ("NumpadDecimal", &["kpdecimal", "kpdelete"]),
+    ("NumpadEnter", &["kpenter", "kpreturn"]),
+    ("MediaPlayPause", &["playpause", "mediaplay"]),
+    ("MediaNextTrack", &["nexttrack", "medianext"]),
+    ("MediaPrevTrack", &["prevtrack", "mediaprev", "mediaprevious"]),
+    ("MediaStop", &[]),
+    ("VolumeUp", &[]),
+    ("VolumeDown", &[]),
+    ("VolumeMute", &["mute"]),
+    ("Grave", &["backquote", "backtick"]),
+    ("Minus", &[]),
+    ("Equal", &["equals"]),
+    ("LeftBracket", &[]),
+    ("RightBracket", &[]),
+    ("BackSlash", &[]),
+    ("Semicolon", &[]),
+    ("Apostrophe", &["quote"]),
+    ("Comma", &[]),
+    ("Dot", &["period"]),
+    ("Slash", &[]),
+];
+
+#[derive(Debug)]
+pub enum KeyNameError {
+    Unknown(String),
+    /// 名称有效，但当前平台无法注入该键
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for KeyNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyNameError::Unknown(name) => write!(f, "Unknown key: {}", name),
+            KeyNameError::Unsupported(name) => write!(f, "Key {} is not available on this platform", name),
+        }
+    }
+}
+
+impl std::error::Error for KeyNameError {}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 把任意写法的按键名称转换为规范名称
+pub fn canonical_name(name: &str) -> Option<&'static str> {
+    let normalized = normalize(name);
+    KEY_NAMES
+        .iter()
+        .find(|(canonical, aliases)| {
+            normalize(canonical) == normalized || aliases.iter().any(|a| *a == normalized)
+        })
+        .map(|(canonical, _)| *canonical)
+}
+
+/// 解析按键名称为可注入的按键
+pub fn parse(name: &str) -> Result<Key, KeyNameError> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(Key::Unicode(c.to_lowercase().next().unwrap_or(c)));
+    }
+    let canonical = canonical_name(name).ok_or_else(|| KeyNameError::Unknown(name.to_string()))?;
+    enigo_key(canonical).ok_or(KeyNameError::Unsupported(canonical))
+}
+
+fn enigo_key(canonical: &str) -> Option<Key> {
+    let key = match canonical {
+        "Enter" | "NumpadEnter" => Key::Return,
+        "Escape" => Key::Escape,
+        "Space" => Key::Space,
+        "Tab" => Key::Tab,
+        "Backspace" => Key::Backspace,
+        "Delete" => Key::Delete,
+        "Up" => Key::UpArrow,
+        "Down" => Key::DownArrow,
+        "Left" => Key::LeftArrow,
+        "Right" => Key::RightArrow,
+        "Home" => Key::Home,
+        "End" => Key::End,
+        "PageUp" => Key::PageUp,
+        "PageDown" => Key::PageDown,
+        "CapsLock" => Key::CapsLock,
+        "Shift" => Key::Shift,
+        "LeftShift" => Key::LShift,
+        "RightShift" => Key::RShift,
+        "Control" => Key::Control,
+        "LeftControl" => Key::LControl,
+        "RightControl" => Key::RControl,
+        "Alt" => Key::Alt,
+        "Meta" => Key::Meta,
+        "F1" => Key::F1,
+        "F2" => Key::F2,
+        "F3" => Key::F3,
+        "F4" => Key::F4,
+        "F5" => Key::F5,
+        "F6" => Key::F6,
+        "F7" => Key::F7,
+        "F8" => Key::F8,
+        "F9" => Key::F9,
+        "F10" => Key::F10,
+        "F11" => Key::F11,
+        "F12" => Key::F12,
+        "F13" => Key::F13,
+        "F14" => Key::F14,
+        "F15" => Key::F15,
+        "F16" => Key::F16,
+        "F17" => Key::F17,
+        "F18" => Key::F18,
+        "F19" => Key::F19,
+        "F20" => Key::F20,
+        "Numpad0" => Key::Numpad0,
+        "Numpad1" => Key::Numpad1,
+        "Numpad2" => Key::Numpad2,
+        "Numpad3" => Key::Numpad3,
+        "Numpad4" => Key::Numpad4,
+        "Numpad5" => Key::Numpad5,
+        "Numpad6" => Key::Numpad6,
+        "Numpad7" => Key::Numpad7,
+        "Numpad8" => Key::Numpad8,
+        "Numpad9" => Key::Numpad9,
+        "NumpadAdd" => Key::Add,
+        "NumpadSubtract" => Key::Subtract,
+        "NumpadMultiply" => Key::Multiply,
+        "NumpadDivide" => Key::Divide,
+        "NumpadDecimal" => Key::Decimal,
+        "MediaPlayPause" => Key::MediaPlayPause,
+        "MediaNextTrack" => Key::MediaNextTrack,
+        "MediaPrevTrack" => Key::MediaPrevTrack,
+        "VolumeUp" => Key::VolumeUp,
+        "VolumeDown" => Key::VolumeDown,
+        "VolumeMute" => Key::VolumeMute,
+        "Grave" => Key::Unicode('`'),
+        "Minus" => Key::Unicode('-'),
+        "Equal" => Key::Unicode('='),
+        "LeftBracket" => Key::Unicode('['),
+        "RightBracket" => Key::Unicode(']'),
+        "BackSlash" => Key::Unicode('\\'),
+        "Semicolon" => Key::Unicode(';'),
+        "Apostrophe" => Key::Unicode('\''),
+        "Comma" => Key::Unicode(','),
+        "Dot" => Key::Unicode('.'),
+        "Slash" => Key::Unicode('/'),
+        other => return platform::enigo_key(other),
+    };
+    Some(key)
+}
+
+/// 监控到的按键对应的规范名称
+pub fn name_of(key: rdev::Key) -> String {
+    use rdev::Key as K;
+    let name = match key {
+        K::Num0 => "0",
+        K::Num1 => "1",
+        K::Num2 => "2",
+        K::Num3 => "3",
+        K::Num4 => "4",
+        K::Num5 => "5",
+        K::Num6 => "6",
+        K::Num7 => "7",
+        K::Num8 => "8",
+        K::Num9 => "9",
+        K::KeyA => "A",
+        K::KeyB => "B",
+        K::KeyC => "C",
+        K::KeyD => "D",
+        K::KeyE => "E",
+        K::KeyF => "F",
+        K::KeyG => "G",
+        K::KeyH => "H",
+        K::KeyI => "I",
+        K::KeyJ => "J",
+        K::KeyK => "K",
+        K::KeyL => "L",
+        K::KeyM => "M",
+        K::KeyN => "N",
+        K::KeyO => "O",
+        K::KeyP => "P",
+        K::KeyQ => "Q",
+        K::KeyR => "R",
+        K::KeyS => "S",
+        K::KeyT => "T",
+        K::KeyU => "U",
+        K::KeyV => "V",
+        K::KeyW => "W",
+        K::KeyX => "X",
+        K::KeyY => "Y",
+        K::KeyZ => "Z",
+        K::F1 => "F1",
+        K::F2 => "F2",
+        K::F3 => "F3",
+        K::F4 => "F4",
+        K::F5 => "F5",
+        K::F6 => "F6",
+        K::F7 => "F7",
+        K::F8 => "F8",
+        K::F9 => "F9",
+        K::F10 => "F10",
+        K::F11 => "F11",
+        K::F12 => "F12",
+        K::Escape => "Escape",
+        K::Space => "Space",
+        K::ControlLeft => "LeftControl",
+        K::ControlRight => "RightControl",
+        K::ShiftLeft => "LeftShift",
+        K::ShiftRight => "RightShift",
+        K::Alt => "Alt",
+        K::AltGr => "AltGr",
+        K::MetaLeft => "LeftMeta",
+        K::MetaRight => "RightMeta",
+        K::Function => "Fn",
+        K::Return => "Enter",
+        K::UpArrow => "Up",
+        K::DownArrow => "Down",
+        K::LeftArrow => "Left",
+        K::RightArrow => "Right",
+        K::Backspace => "Backspace",
+        K::CapsLock => "CapsLock",
+        K::NumLock => "NumLock",
+        K::ScrollLock => "ScrollLock",
+        K::PrintScreen => "PrintScreen",
+        K::Pause => "Pause",
+        K::Tab => "Tab",
+        K::Home => "Home",
+        K::End => "End",
+        K::PageUp => "PageUp",
+        K::PageDown => "PageDown",
+        K::Insert => "Insert",
+        K::Delete => "Delete",
+        K::Kp0 => "Numpad0",
+        K::Kp1 => "Numpad1",
+        K::Kp2 => "Numpad2",
+        K::Kp3 => "Numpad3",
+        K::Kp4 => "Numpad4",
+        K::Kp5 => "Numpad5",
+        K::Kp6 => "Numpad6",
+        K::Kp7 => "Numpad7",
+        K::Kp8 => "Numpad8",
+        K::Kp9 => "Numpad9",
+        K::KpMinus => "NumpadSubtract",
+        K::KpPlus => "NumpadAdd",
+        K::KpDivide => "NumpadDivide",
+        K::KpMultiply => "NumpadMultiply",
+        K::KpDelete => "NumpadDecimal",
+        K::KpReturn => "NumpadEnter",
+        K::BackQuote => "Grave",
+        K::Minus => "Minus",
+        K::Equal => "Equal",
+        K::LeftBracket => "LeftBracket",
+        K::RightBracket => "RightBracket",
+        K::BackSlash | K::IntlBackslash => "BackSlash",
+        K::SemiColon => "Semicolon",
+        K::Quote => "Apostrophe",
+        K::Comma => "Comma",
+        K::Dot => "Dot",
+        K::Slash => "Slash",
+        K::Unknown(code) => return format!("Unknown({})", code),
+    };
+    name.to_string()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use enigo::Key;
+
+    pub fn enigo_key(canonical: &str) -> Option<Key> {
+        Some(match canonical {
+            "LeftAlt" => Key::Option,
+            "RightAlt" | "AltGr" => Key::ROption,
+            "LeftMeta" => Key::Meta,
+            "RightMeta" => Key::RCommand,
+            "Fn" => Key::Function,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use enigo::Key;
+
+    pub fn enigo_key(canonical: &str) -> Option<Key> {
+        Some(match canonical {
+            "LeftAlt" => Key::LMenu,
+            "RightAlt" | "AltGr" => Key::RMenu,
+            "LeftMeta" => Key::LWin,
+            "RightMeta" => Key::RWin,
+            "Insert" => Key::Insert,
+            "NumLock" => Key::Numlock,
+            "ScrollLock" => Key::Scroll,
+            "PrintScreen" => Key::PrintScr,
+            "Pause" => Key::Pause,
+            "MediaStop" => Key::MediaStop,
+            "F21" => Key::F21,
+            "F22" => Key::F22,
+            "F23" => Key::F23,
+            "F24" => Key::F24,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use enigo::Key;
+
+    pub fn enigo_key(canonical: &str) -> Option<Key> {
+        Some(match canonical {
+            // X11 keysym：Alt_L / Alt_R / ISO_Level3_Shift / Super_L / Super_R
+            "LeftAlt" => Key::Other(0xffe9),
+            "RightAlt" => Key::Other(0xffea),
+            "AltGr" => Key::Other(0xfe03),
+            "LeftMeta" => Key::Other(0xffeb),
+            "RightMeta" => Key::Other(0xffec),
+            "Insert" => Key::Insert,
+            "NumLock" => Key::Numlock,
+            "ScrollLock" => Key::ScrollLock,
+            "PrintScreen" => Key::PrintScr,
+            "Pause" => Key::Pause,
+            "MediaStop" => Key::MediaStop,
+            "F21" => Key::F21,
+            "F22" => Key::F22,
+            "F23" => Key::F23,
+            "F24" => Key::F24,
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,440 @@
+//! 目标定位：按策略链（AX → OCR → 模板 → 坐标）把目标描述解析为屏幕坐标。
+//! AX 按无障碍选择器查找元素（目前仅 macOS），OCR 经 tesseract 识别主显示器上的文字。
+//!
+//! 成功的策略按目标描述缓存，下次优先尝试。
+//! 昂贵策略（AX/OCR/模板）解析出的坐标按目标描述与窗口缓存，
+//! 在窗口移动/缩放、屏幕变化或主显示器尺寸改变时失效。
+
+use crate::monitor::accessibility::{self, AccessibilityError, Selector};
+use crate::monitor::display;
+use crate::monitor::ocr;
+use crate::monitor::screen::{self, ScreenEventKind};
+use super::{source, window};
+use enigo::{Enigo, Mouse};
+use image::{imageops, GrayImage};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+/// 模板匹配时粗搜索阶段屏幕的目标宽度（像素）
+const COARSE_SEARCH_WIDTH: u32 = 480;
+
+/// 模板匹配可接受的最大平均像素差（0.0–1.0）
+const TEMPLATE_MATCH_THRESHOLD: f32 = 0.1;
+
+/// OCR 策略丢弃置信度低于该值的词
+const OCR_MIN_CONFIDENCE: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    Accessibility,
+    Ocr,
+    Template,
+    Coordinates,
+}
+
+impl Strategy {
+    /// 默认的尝试顺序
+    pub const CHAIN: [Strategy; 4] = [
+        Strategy::Accessibility,
+        Strategy::Ocr,
+        Strategy::Template,
+        Strategy::Coordinates,
+    ];
+
+    /// 是否值得缓存定位结果（坐标策略本身无开销）
+    fn is_expensive(&self) -> bool {
+        !matches!(self, Strategy::Coordinates)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Strategy::Accessibility => "accessibility",
+            Strategy::Ocr => "ocr",
+            Strategy::Template => "template",
+            Strategy::Coordinates => "coordinates",
+        }
+    }
+}
+
+/// 目标描述，各字段对应一种定位策略
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Target {
+    pub selector: Option<String>,
+    pub text: Option<String>,
+    /// PNG 等编码的模板图像字节
+    pub template: Option<Vec<u8>>,
+    pub point: Option<(i32, i32)>,
+    /// 目标所在窗口的 ID（list_windows 返回的 id），用于缓存分区与按窗口失效
+    pub window: Option<String>,
+}
+
+impl Target {
+    /// 用于缓存的稳定键
+    pub fn cache_key(&self) -> String {
+        let template_len = self.template.as_ref().map(|t| t.len());
+        let template_sum = self
+            .template
+            .as_ref()
+            .map(|t| t.iter().fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(*b as u64)));
+        format!(
+            "selector={:?};text={:?};template={:?}/{:?};point={:?};window={:?}",
+            self.selector, self.text, template_len, template_sum, self.point, self.window
+        )
+    }
+
+    fn supports(&self, strategy: Strategy) -> bool {
+        match strategy {
+            Strategy::Accessibility => self.selector.is_some(),
+            Strategy::Ocr => self.text.is_some(),
+            Strategy::Template => self.template.is_some(),
+            Strategy::Coordinates => self.point.is_some(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LocateError {
+    /// 策略所需的平台接口或外部程序不可用（无障碍权限、tesseract 等）
+    Unavailable(String),
+    NotFound(String),
+    Capture(String),
+    InvalidTemplate(String),
+}
+
+impl fmt::Display for LocateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocateError::Unavailable(msg) => write!(f, "locate strategy unavailable: {}", msg),
+            LocateError::NotFound(msg) => write!(f, "target not found: {}", msg),
+            LocateError::Capture(msg) => write!(f, "screen capture failed: {}", msg),
+            LocateError::InvalidTemplate(msg) => write!(f, "invalid template image: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LocateError {}
+
+/// 定位成功的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Located {
+    pub x: i32,
+    pub y: i32,
+    pub strategy: Strategy,
+    /// 坐标是否直接取自位置缓存（未重新执行策略）
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedLocation {
+    x: i32,
+    y: i32,
+    strategy: Strategy,
+    display_size: Option<(i32, i32)>,
+}
+
+/// 策略链全部失败时，记录每个策略的失败原因
+#[derive(Debug)]
+pub struct ChainFailure {
+    pub attempts: Vec<(Strategy, LocateError)>,
+}
+
+impl fmt::Display for ChainFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.attempts.is_empty() {
+            return write!(f, "target has no usable locator (text, template, selector or x/y)");
+        }
+        let parts: Vec<String> = self
+            .attempts
+            .iter()
+            .map(|(s, e)| format!("{}: {}", s.as_str(), e))
+            .collect();
+        write!(f, "all strategies failed ({})", parts.join("; "))
+    }
+}
+
+fn strategy_cache() -> &'static Mutex<HashMap<String, Strategy>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Strategy>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn location_cache() -> &'static Mutex<HashMap<String, CachedLocation>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CachedLocation>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 清空全部位置缓存；显示器拓扑监控发现增减或几何变化时调用
+pub fn invalidate_locations() {
+    location_cache().lock().unwrap().clear();
+}
+
+/// 清除指定窗口（`Target::window`，即 list_windows 的 ID）下的位置缓存；
+/// `window::set_frame` 与 `window::control` 改变窗口后调用
+pub fn invalidate_window(window: &str) {
+    let suffix = format!(";window={:?}", Some(window));
+    location_cache()
+        .lock()
+        .unwrap()
+        .retain(|key, _| !key.ends_with(&suffix));
+}
+
+fn main_display_size() -> Option<(i32, i32)> {
+    Enigo::new(&source::settings()).ok()?.main_display().ok()
+}
+
+/// 按策略链定位目标；优先使用位置缓存，其次优先尝试该目标成功过的策略。
+/// `refresh` 为 true 时忽略位置缓存并重新定位。
+pub fn locate(target: &Target, refresh: bool) -> Result<Located, ChainFailure> {
+    let key = target.cache_key();
+    let display_size = main_display_size();
+
+    if !refresh {
+        let mut locations = location_cache().lock().unwrap();
+        if let Some(hit) = locations.get(&key).copied() {
+            if hit.display_size == display_size {
+                return Ok(Located {
+                    x: hit.x,
+                    y: hit.y,
+                    strategy: hit.strategy,
+                    from_cache: true,
+                });
+            }
+            locations.remove(&key);
+        }
+    }
+
+    let cached = strategy_cache().lock().unwrap().get(&key).copied();
+
+    let mut order: Vec<Strategy> = Vec::with_capacity(Strategy::CHAIN.len());
+    if let Some(s) = cached {
+        order.push(s);
+    }
+    order.extend(Strategy::CHAIN.iter().copied().filter(|s| Some(*s) != cached));
+
+    let mut attempts = Vec::new();
+    for strategy in order {
+        if !target.supports(strategy) {
+            continue;
+        }
+        match run_strategy(strategy, target) {
+            Ok((x, y)) => {
+                if strategy.is_expensive() {
+                    location_cache().lock().unwrap().insert(
+                        key.clone(),
+                        CachedLocation { x, y, strategy, display_size },
+                    );
+                }
+                strategy_cache().lock().unwrap().insert(key, strategy);
+                return Ok(Located {
+                    x,
+                    y,
+                    strategy,
+                    from_cache: false,
+                });
+            }
+            Err(e) => attempts.push((strategy, e)),
+        }
+    }
+
+    Err(ChainFailure { attempts })
+}
+
+fn run_strategy(strategy: Strategy, target: &Target) -> Result<(i32, i32), LocateError> {
+    match strategy {
+        Strategy::Accessibility => {
+            locate_selector(target.selector.as_deref().unwrap_or_default(), target.window.as_deref())
+        }
+        Strategy::Ocr => locate_text(target.text.as_deref().unwrap_or_default()),
+        Strategy::Template => {
+            let template = target.template.as_deref().unwrap_or_default();
+            locate_template(template)
+        }
+        Strategy::Coordinates => target
+            .point
+            .ok_or_else(|| LocateError::NotFound("no coordinates".to_string())),
+    }
+}
+
+/// 按无障碍选择器查找元素，返回元素中心坐标；`window` 为窗口 ID 时只在该窗口中查找
+fn locate_selector(selector: &str, window: Option<&str>) -> Result<(i32, i32), LocateError> {
+    let selector = Selector::parse(selector).map_err(|e| LocateError::NotFound(format!("invalid selector: {}", e)))?;
+    let (pid, window_id) = match window.and_then(|w| w.parse::<u64>().ok()) {
+        Some(id) => {
+            let info = window::get_window(id).map_err(|e| LocateError::NotFound(e.to_string()))?;
+            (info.pid, Some(id))
+        }
+        None => (None, None),
+    };
+    let element = accessibility::find_element(&selector, pid, window_id).map_err(|e| match e {
+        AccessibilityError::NotFound(msg) => LocateError::NotFound(msg),
+        other => LocateError::Unavailable(other.to_string()),
+    })?;
+    match (element.position, element.size) {
+        // 无障碍接口报告的是全局逻辑坐标，与鼠标坐标一致
+        (Some((x, y)), Some((w, h))) => Ok(((x + w / 2.0).round() as i32, (y + h / 2.0).round() as i32)),
+        _ => Err(LocateError::NotFound(format!("element {} has no frame", selector))),
+    }
+}
+
+/// 在当前屏幕截图中识别文字，返回包含 `text`（不区分大小写）的最短连续词组的中心坐标
+fn locate_text(text: &str) -> Result<(i32, i32), LocateError> {
+    let needle = text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    if needle.is_empty() {
+        return Err(LocateError::NotFound("empty text".to_string()));
+    }
+    let png = capture_png()?;
+    let lines = ocr::recognize(&png, &ocr::default_language(), OCR_MIN_CONFIDENCE)
+        .map_err(|e| LocateError::Unavailable(e.to_string()))?;
+
+    let mut best: Option<(usize, ocr::TextBox)> = None;
+    for line in &lines {
+        for start in 0..line.words.len() {
+            let mut joined = String::new();
+            for (end, word) in line.words.iter().enumerate().skip(start) {
+                if end > start {
+                    joined.push(' ');
+                }
+                joined.push_str(&word.text.to_lowercase());
+                if joined.contains(&needle) {
+                    let span = end - start;
+                    if best.as_ref().is_none_or(|(s, _)| span < *s) {
+                        let bbox = line.words[start + 1..=end]
+                            .iter()
+                            .fold(line.words[start].bbox, |b, w| b.union(w.bbox));
+                        best = Some((span, bbox));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    let (_, bbox) = best.ok_or_else(|| LocateError::NotFound(format!("no text matching {:?} on screen", text)))?;
+    let (cx, cy) = bbox.center();
+    Ok(frame_to_global(cx, cy))
+}
+
+/// 截取主显示器，返回 PNG 数据
+fn capture_png() -> Result<Vec<u8>, LocateError> {
+    let event = screen::capture_frame().map_err(|e| LocateError::Capture(e.to_string()))?;
+    match event.kind {
+        ScreenEventKind::FrameCaptured { image_data: Some(data), .. } => Ok(data),
+        _ => Err(LocateError::Capture("frame has no image data".to_string())),
+    }
+}
+
+/// 截图为主显示器的物理像素，换算为鼠标操作使用的逻辑坐标
+fn frame_to_global(x: f64, y: f64) -> (i32, i32) {
+    match display::get_display(0) {
+        Ok(main) => main.to_logical(main.x as f64 + x, main.y as f64 + y),
+        Err(_) => (x.round() as i32, y.round() as i32),
+    }
+}
+
+/// 在当前屏幕截图中查找模板图像，返回匹配区域中心坐标
+fn locate_template(template_bytes: &[u8]) -> Result<(i32, i32), LocateError> {
+    let template = image::load_from_memory(template_bytes)
+        .map_err(|e| LocateError::InvalidTemplate(e.to_string()))?
+        .to_luma8();
+
+    let png = capture_png()?;
+    let screen_img = image::load_from_memory(&png)
+        .map_err(|e| LocateError::Capture(e.to_string()))?
+        .to_luma8();
+
+    let (x, y, score) = find_template(&screen_img, &template)
+        .ok_or_else(|| LocateError::NotFound("template larger than screen".to_string()))?;
+    if score > TEMPLATE_MATCH_THRESHOLD {
+        return Err(LocateError::NotFound(format!(
+            "best template match differs by {:.3} (threshold {})",
+            score, TEMPLATE_MATCH_THRESHOLD
+        )));
+    }
+
+    let (cx, cy) = (x + template.width() / 2, y + template.height() / 2);
+    Ok(frame_to_global(cx as f64, cy as f64))
+}
+
+/// 粗到细的 SAD 模板匹配，返回左上角坐标与归一化差异分数
+fn find_template(haystack: &GrayImage, needle: &GrayImage) -> Option<(u32, u32, f32)> {
+    if needle.width() == 0
+        || needle.height() == 0
+        || needle.width() > haystack.width()
+        || needle.height() > haystack.height()
+    {
+        return None;
+    }
+
+    // 缩小后模板至少保留 4 像素
+    let scale = (haystack.width() / COARSE_SEARCH_WIDTH)
+        .min(needle.width() / 4)
+        .min(needle.height() / 4)
+        .max(1);
+
+    let (coarse_x, coarse_y) = if scale > 1 {
+        let small_hay = imageops::resize(
+            haystack,
+            haystack.width() / scale,
+            haystack.height() / scale,
+            imageops::FilterType::Triangle,
+        );
+        let small_needle = imageops::resize(
+            needle,
+            needle.width() / scale,
+            needle.height() / scale,
+            imageops::FilterType::Triangle,
+        );
+        let (x, y, _) = best_match(&small_hay, &small_needle, None)?;
+        (x * scale, y * scale)
+    } else {
+        (0, 0)
+    };
+
+    let window = if scale > 1 {
+        let radius = scale * 2;
+        Some((
+            coarse_x.saturating_sub(radius),
+            coarse_y.saturating_sub(radius),
+            coarse_x + radius,
+            coarse_y + radius,
+        ))
+    } else {
+        None
+    };
+    best_match(haystack, needle, window)
+}
+
+/// 在给定窗口（左上角范围，含边界）内穷举搜索最小平均绝对差
+fn best_match(
+    haystack: &GrayImage,
+    needle: &GrayImage,
+    window: Option<(u32, u32, u32, u32)>,
+) -> Option<(u32, u32, f32)> {
+    let max_x = haystack.width().checked_sub(needle.width())?;
+    let max_y = haystack.height().checked_sub(needle.height())?;
+    let (x0, y0, x1, y1) = window.unwrap_or((0, 0, max_x, max_y));
+    let (x1, y1) = (x1.min(max_x), y1.min(max_y));
+
+    let pixels = (needle.width() * needle.height()) as u64;
+    let mut best: Option<(u32, u32, u64)> = None;
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            let limit = best.map(|b| b.2).unwrap_or(u64::MAX);
+            let mut sad = 0u64;
+            'rows: for ny in 0..needle.height() {
+                for nx in 0..needle.width() {
+                    let a = haystack.get_pixel(x + nx, y + ny)[0];
+                    let b = needle.get_pixel(nx, ny)[0];
+                    sad += a.abs_diff(b) as u64;
+                }
+                if sad >= limit {
+                    break 'rows;
+                }
+            }
+            if sad < limit {
+                best = Some((x, y, sad));
+            }
+        }
+    }
+
+    best.map(|(x, y, sad)| (x, y, sad as f32 / (pixels as f32 * 255.0)))
+}
@@ -1,2 +1,13 @@
+pub mod clipboard;
+pub mod gesture;
+pub mod held;
+pub mod ime;
 pub mod keyboard;
+pub mod keys;
+pub mod locate;
 pub mod mouse;
+pub mod pacing;
+pub mod replay;
+pub mod shortcuts;
+pub mod source;
+pub mod window;
@@ -1,52 +1,189 @@
+use super::{held, pacing, source};
 use enigo::{
-    Button, Coordinate, Direction, Enigo, Mouse,
+    Button, Coordinate, Direction, Mouse,
 };
-use std::{thread, time::Duration};
+use std::time::Duration;
 
-pub struct MouseController {
-    enigo: Enigo,
+/// Default delay between clicks of a multi-click, well inside typical OS double-click timeouts.
+pub const DEFAULT_CLICK_INTERVAL_MS: u64 = 100;
+
+/// Upper bound for the delay between clicks; longer gaps no longer register as a multi-click anyway.
+pub const MAX_CLICK_INTERVAL_MS: u64 = 2000;
+
+/// Maximum number of clicks supported by `mouse_multi_click` (triple click).
+pub const MAX_CLICK_COUNT: u32 = 3;
+
+/// Approximate pixels per wheel line, used where the OS has no pixel scroll API.
+pub const PIXELS_PER_SCROLL_LINE: i32 = 20;
+
+/// Easing curve applied to the movement between two consecutive path waypoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(Easing::Linear),
+            "ease_in" => Some(Easing::EaseIn),
+            "ease_out" => Some(Easing::EaseOut),
+            "ease_in_out" => Some(Easing::EaseInOut),
+            _ => None,
+        }
+    }
+
+    /// Map linear progress `t` in [0, 1] onto the eased progress (cubic curves).
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
 }
 
-impl MouseController {
-    pub fn new(enigo: Enigo) -> Self {
-        Self { enigo }
+/// A path waypoint; `delay_ms` is the time spent travelling to it from the previous waypoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathPoint {
+    pub x: i32,
+    pub y: i32,
+    pub delay_ms: u64,
+}
+
+/// How `mouse_move_path_with` interpolates between waypoints.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PathOptions {
+    pub easing: Easing,
+    /// Subdivide segments so no single move exceeds this many pixels; `None` jumps straight to each waypoint.
+    pub max_step_px: Option<u32>,
+}
+
+/// Mouse operations wrapper; generic over any `Mouse` impl so we can mock in tests.
+pub struct MouseController<M: Mouse + Send> {
+    mouse: M,
+}
+
+impl<M: Mouse + Send> MouseController<M> {
+    pub fn new(mouse: M) -> Self {
+        Self { mouse }
+    }
+
+    /// Inject one button event and track it in the held-input registry
+    fn button(&mut self, button: Button, direction: Direction) -> Result<(), enigo::InputError> {
+        let _injecting = source::begin_injection();
+        self.mouse.button(button, direction)?;
+        held::record_button(button, direction);
+        Ok(())
+    }
+
+    /// Inject one absolute cursor move
+    fn move_to(&mut self, x: i32, y: i32) -> Result<(), enigo::InputError> {
+        let _injecting = source::begin_injection();
+        self.mouse.move_mouse(x, y, Coordinate::Abs)
     }
 
     /// Click at coordinates with left/right/middle button
     pub fn mouse_click(&mut self, x: i32, y: i32, button: Button) -> Result<(), enigo::InputError> {
-        self.enigo.move_mouse(x, y, Coordinate::Abs)?;
-        self.enigo.button(button, Direction::Click)?;
+        self.move_to(x, y)?;
+        self.button(button, Direction::Click)?;
         Ok(())
     }
 
     /// Double-click at coordinates
     pub fn mouse_double_click(&mut self, x: i32, y: i32, button: Button) -> Result<(), enigo::InputError> {
-        self.enigo.move_mouse(x, y, Coordinate::Abs)?;
-        self.enigo.button(button, Direction::Click)?;
-        thread::sleep(Duration::from_millis(100));
-        self.enigo.button(button, Direction::Click)?;
+        self.mouse_multi_click(x, y, button, 2, DEFAULT_CLICK_INTERVAL_MS)
+    }
+
+    /// Click `clicks` times at coordinates, waiting `interval_ms` between clicks
+    /// so the OS recognizes the sequence as a double/triple click.
+    pub fn mouse_multi_click(
+        &mut self,
+        x: i32,
+        y: i32,
+        button: Button,
+        clicks: u32,
+        interval_ms: u64,
+    ) -> Result<(), enigo::InputError> {
+        self.move_to(x, y)?;
+        for i in 0..clicks {
+            if i > 0 {
+                pacing::sleep(Duration::from_millis(interval_ms));
+            }
+            self.button(button, Direction::Click)?;
+        }
         Ok(())
     }
 
     /// Move cursor to position
     pub fn mouse_move(&mut self, x: i32, y: i32) -> Result<(), enigo::InputError> {
-        self.enigo.move_mouse(x, y, Coordinate::Abs)
+        self.move_to(x, y)
     }
 
     /// Get current cursor location
     pub fn mouse_get_position(&self) -> Result<(i32, i32), enigo::InputError> {
-        self.enigo.location()
+        self.mouse.location()
     }
 
     /// Scroll in any direction
     /// axis: horizontal or vertical (usually vertical is standard scroll)
     /// clicks: number of "clicks" to scroll. Positive is up/right, negative is down/left usually, but depends on OS.
     pub fn mouse_scroll(&mut self, lines_x: i32, lines_y: i32) -> Result<(), enigo::InputError> {
+        let _injecting = source::begin_injection();
         if lines_x != 0 {
-             self.enigo.scroll(lines_x, enigo::Axis::Horizontal)?;
+             self.mouse.scroll(lines_x, enigo::Axis::Horizontal)?;
         }
         if lines_y != 0 {
-             self.enigo.scroll(lines_y, enigo::Axis::Vertical)?;
+             self.mouse.scroll(lines_y, enigo::Axis::Vertical)?;
+        }
+        Ok(())
+    }
+
+    /// Smooth scroll by a total pixel distance, split into `steps` increments over `duration_ms`.
+    /// Positive values scroll down/right, matching `mouse_scroll`.
+    pub fn mouse_smooth_scroll(
+        &mut self,
+        distance_x: i32,
+        distance_y: i32,
+        steps: u32,
+        duration_ms: u64,
+    ) -> Result<(), enigo::InputError> {
+        let steps = steps.max(1);
+        let delay = Duration::from_millis(duration_ms / steps as u64);
+        let (mut sent_x, mut sent_y) = (0i32, 0i32);
+        // Fallback path accumulates sub-line remainders so the total distance is preserved.
+        let (mut lines_sent_x, mut lines_sent_y) = (0i32, 0i32);
+
+        for i in 1..=steps {
+            let target_x = (distance_x as i64 * i as i64 / steps as i64) as i32;
+            let target_y = (distance_y as i64 * i as i64 / steps as i64) as i32;
+            let (dx, dy) = (target_x - sent_x, target_y - sent_y);
+            sent_x = target_x;
+            sent_y = target_y;
+
+            if !platform::scroll_pixels(dx, dy) {
+                let lines_x = target_x / PIXELS_PER_SCROLL_LINE - lines_sent_x;
+                let lines_y = target_y / PIXELS_PER_SCROLL_LINE - lines_sent_y;
+                lines_sent_x += lines_x;
+                lines_sent_y += lines_y;
+                self.mouse_scroll(lines_x, lines_y)?;
+            }
+
+            if i < steps {
+                pacing::sleep(delay);
+            }
         }
         Ok(())
     }
@@ -54,27 +191,252 @@ impl MouseController {
     /// Drag from current position to target
     pub fn mouse_drag(&mut self, target_x: i32, target_y: i32, button: Button) -> Result<(), enigo::InputError> {
         // Press button
-        self.enigo.button(button, Direction::Press)?;
+        self.button(button, Direction::Press)?;
         // Move to target
-        self.enigo.move_mouse(target_x, target_y, Coordinate::Abs)?;
+        self.move_to(target_x, target_y)?;
         // Release button
-        self.enigo.button(button, Direction::Release)?;
+        self.button(button, Direction::Release)?;
         Ok(())
     }
 
     /// Press/release mouse buttons
     pub fn mouse_button_control(&mut self, button: Button, direction: Direction) -> Result<(), enigo::InputError> {
-        self.enigo.button(button, direction)
+        self.button(button, direction)
     }
 
     /// Follow a smooth path with multiple points
     /// points: List of (x, y) tuples
     /// speed_ms: delay between points in milliseconds
     pub fn mouse_move_path(&mut self, points: &[(i32, i32)], speed_ms: u64) -> Result<(), enigo::InputError> {
-        for &(x, y) in points {
-            self.enigo.move_mouse(x, y, Coordinate::Abs)?;
-            thread::sleep(Duration::from_millis(speed_ms));
+        let points: Vec<PathPoint> = points
+            .iter()
+            .map(|&(x, y)| PathPoint { x, y, delay_ms: speed_ms })
+            .collect();
+        self.mouse_move_path_with(&points, PathOptions::default())
+    }
+
+    /// Follow a path, interpolating between waypoints.
+    /// The cursor jumps to the first point; each following segment is split into
+    /// enough steps to respect `max_step_px`, spaced along the easing curve, with the
+    /// waypoint's `delay_ms` spread evenly across the steps.
+    pub fn mouse_move_path_with(&mut self, points: &[PathPoint], options: PathOptions) -> Result<(), enigo::InputError> {
+        let Some(first) = points.first() else {
+            return Ok(());
+        };
+        self.move_to(first.x, first.y)?;
+        pacing::sleep(Duration::from_millis(first.delay_ms));
+
+        for pair in points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let (dx, dy) = ((to.x - from.x) as f64, (to.y - from.y) as f64);
+            let steps = match options.max_step_px {
+                Some(max_step) if max_step > 0 => ((dx.hypot(dy) / max_step as f64).ceil() as u32).max(1),
+                _ => 1,
+            };
+            let delay = Duration::from_millis(to.delay_ms / steps as u64);
+
+            for i in 1..=steps {
+                let (x, y) = if i == steps {
+                    (to.x, to.y)
+                } else {
+                    let t = options.easing.apply(i as f64 / steps as f64);
+                    (from.x + (dx * t).round() as i32, from.y + (dy * t).round() as i32)
+                };
+                self.move_to(x, y)?;
+                pacing::sleep(delay);
+            }
         }
         Ok(())
     }
 }
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_graphics::event::{CGEvent, CGEventTapLocation, EventField, ScrollEventUnit};
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+    use crate::operator::source::SOURCE_SIGNATURE;
+
+    /// Post a pixel-unit scroll wheel event; returns false if it could not be sent.
+    pub fn scroll_pixels(dx: i32, dy: i32) -> bool {
+        if dx == 0 && dy == 0 {
+            return true;
+        }
+        let _injecting = crate::operator::source::begin_injection();
+        let Ok(source) = CGEventSource::new(CGEventSourceStateID::HIDSystemState) else {
+            return false;
+        };
+        // Core Graphics wheel deltas are positive for up/left.
+        match CGEvent::new_scroll_event(source, ScrollEventUnit::PIXEL, 2, -dy, -dx, 0) {
+            Ok(event) => {
+                event.set_integer_value_field(EventField::EVENT_SOURCE_USER_DATA, SOURCE_SIGNATURE);
+                event.post(CGEventTapLocation::HID);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    /// No pixel scroll API wired up; callers fall back to line scrolling.
+    pub fn scroll_pixels(_dx: i32, _dy: i32) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enigo::{Axis, InputResult};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Call {
+        Button(Button, Direction),
+        Move(i32, i32),
+        Scroll(i32, Axis),
+    }
+
+    /// Records every injected call instead of touching the real input system.
+    #[derive(Default)]
+    struct MockMouse {
+        calls: Vec<Call>,
+        position: (i32, i32),
+    }
+
+    impl Mouse for MockMouse {
+        fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+            self.calls.push(Call::Button(button, direction));
+            Ok(())
+        }
+
+        fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+            assert_eq!(coordinate, Coordinate::Abs);
+            self.calls.push(Call::Move(x, y));
+            self.position = (x, y);
+            Ok(())
+        }
+
+        fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+            self.calls.push(Call::Scroll(length, axis));
+            Ok(())
+        }
+
+        fn main_display(&self) -> InputResult<(i32, i32)> {
+            Ok((1920, 1080))
+        }
+
+        fn location(&self) -> InputResult<(i32, i32)> {
+            Ok(self.position)
+        }
+    }
+
+    fn controller() -> MouseController<MockMouse> {
+        MouseController::new(MockMouse::default())
+    }
+
+    fn moves(calls: &[Call]) -> Vec<(i32, i32)> {
+        calls
+            .iter()
+            .filter_map(|c| match c {
+                Call::Move(x, y) => Some((*x, *y)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drag_presses_moves_and_releases() {
+        let mut mouse = controller();
+        mouse.mouse_drag(300, 400, Button::Left).unwrap();
+        assert_eq!(
+            mouse.mouse.calls,
+            vec![
+                Call::Button(Button::Left, Direction::Press),
+                Call::Move(300, 400),
+                Call::Button(Button::Left, Direction::Release),
+            ]
+        );
+        assert_eq!(mouse.mouse_get_position().unwrap(), (300, 400));
+    }
+
+    #[test]
+    fn path_without_step_limit_visits_each_waypoint() {
+        let mut mouse = controller();
+        mouse.mouse_move_path(&[(0, 0), (10, 20), (-5, 7)], 0).unwrap();
+        assert_eq!(moves(&mouse.mouse.calls), vec![(0, 0), (10, 20), (-5, 7)]);
+    }
+
+    #[test]
+    fn path_subdivides_segments_and_ends_on_waypoint() {
+        let mut mouse = controller();
+        let points = [
+            PathPoint { x: 0, y: 0, delay_ms: 0 },
+            PathPoint { x: 100, y: 0, delay_ms: 0 },
+        ];
+        let options = PathOptions { easing: Easing::Linear, max_step_px: Some(30) };
+        mouse.mouse_move_path_with(&points, options).unwrap();
+        // 100px at 30px per step needs 4 steps after the initial jump
+        assert_eq!(moves(&mouse.mouse.calls), vec![(0, 0), (25, 0), (50, 0), (75, 0), (100, 0)]);
+    }
+
+    #[test]
+    fn path_with_easing_is_monotonic() {
+        let mut mouse = controller();
+        let points = [
+            PathPoint { x: 0, y: 0, delay_ms: 0 },
+            PathPoint { x: 0, y: 90, delay_ms: 0 },
+        ];
+        let options = PathOptions { easing: Easing::EaseInOut, max_step_px: Some(10) };
+        mouse.mouse_move_path_with(&points, options).unwrap();
+        let ys: Vec<i32> = moves(&mouse.mouse.calls).iter().map(|&(_, y)| y).collect();
+        assert_eq!(ys.len(), 10);
+        assert!(ys.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(ys.last(), Some(&90));
+    }
+
+    #[test]
+    fn empty_path_does_nothing() {
+        let mut mouse = controller();
+        mouse.mouse_move_path_with(&[], PathOptions::default()).unwrap();
+        assert!(mouse.mouse.calls.is_empty());
+    }
+
+    #[test]
+    fn scroll_skips_zero_axes() {
+        let mut mouse = controller();
+        mouse.mouse_scroll(0, -3).unwrap();
+        mouse.mouse_scroll(2, 0).unwrap();
+        mouse.mouse_scroll(0, 0).unwrap();
+        assert_eq!(
+            mouse.mouse.calls,
+            vec![Call::Scroll(-3, Axis::Vertical), Call::Scroll(2, Axis::Horizontal)]
+        );
+    }
+
+    /// Without a pixel scroll API the distance is sent as whole lines, carrying remainders.
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn smooth_scroll_falls_back_to_lines_and_preserves_distance() {
+        let mut mouse = controller();
+        mouse.mouse_smooth_scroll(0, 5 * PIXELS_PER_SCROLL_LINE, 7, 0).unwrap();
+        let total: i32 = mouse
+            .mouse
+            .calls
+            .iter()
+            .map(|c| match c {
+                Call::Scroll(lines, Axis::Vertical) => *lines,
+                other => panic!("unexpected call {:?}", other),
+            })
+            .sum();
+        assert_eq!(total, 5);
+    }
+
+    #[test]
+    fn easing_endpoints_are_fixed() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+    }
+}
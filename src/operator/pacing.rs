@@ -0,0 +1,100 @@
+//! 全局输入节奏配置：按统一倍率缩放键鼠注入中的等待时间（逐字符输入间隔、多击间隔、
+//! 路径移动耗时、平滑滚动、按键重复与序列延迟），无需逐个调用修改参数即可在
+//! "快速 CI" 与 "人工节奏演示" 之间切换。
+//!
+//! 启动时读取环境变量：
+//! - `IRIS_INPUT_PROFILE`：预设名称（`fast` / `normal` / `human`），缺省为 `normal`
+//! - `IRIS_INPUT_DELAY_SCALE`：覆盖预设的延迟倍率
+//!
+//! 运行期间可通过 `set_input_profile` 工具切换。`key_control` 的 `hold_ms` 等表达按住时长语义的参数，
+//! 以及等待界面响应的固定等待不受影响。
+
+use serde::Serialize;
+use std::env;
+use std::sync::{OnceLock, RwLock};
+use std::thread;
+use std::time::Duration;
+
+/// 延迟倍率上限，避免误配置导致单次调用长时间阻塞
+pub const MAX_DELAY_SCALE: f64 = 10.0;
+
+/// 逐字符输入间隔与随机浮动各自的上限（毫秒，缩放前）
+pub const MAX_TYPING_DELAY_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct InputProfile {
+    /// 预设名称；自定义倍率或输入间隔时为 `custom`
+    pub name: &'static str,
+    /// 所有注入等待时间的倍率，0 表示跳过等待
+    pub delay_scale: f64,
+    /// type_text 未指定 `char_delay_ms` 时的字符间隔（毫秒，缩放前）
+    pub typing_delay_ms: u64,
+    /// type_text 未指定 `char_delay_variance_ms` 时的随机浮动（毫秒，缩放前）
+    pub typing_variance_ms: u64,
+}
+
+impl InputProfile {
+    pub const FAST: Self = Self { name: "fast", delay_scale: 0.0, typing_delay_ms: 0, typing_variance_ms: 0 };
+    pub const NORMAL: Self = Self { name: "normal", delay_scale: 1.0, typing_delay_ms: 0, typing_variance_ms: 0 };
+    pub const HUMAN: Self = Self { name: "human", delay_scale: 2.0, typing_delay_ms: 60, typing_variance_ms: 30 };
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "fast" => Some(Self::FAST),
+            "normal" => Some(Self::NORMAL),
+            "human" => Some(Self::HUMAN),
+            _ => None,
+        }
+    }
+
+    fn from_env() -> Self {
+        let mut profile = env::var("IRIS_INPUT_PROFILE")
+            .ok()
+            .and_then(|v| Self::preset(&v))
+            .unwrap_or(Self::NORMAL);
+        if let Some(scale) = env::var("IRIS_INPUT_DELAY_SCALE")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .filter(|s| s.is_finite() && *s >= 0.0)
+        {
+            profile.delay_scale = scale.min(MAX_DELAY_SCALE);
+            profile.name = "custom";
+        }
+        profile
+    }
+}
+
+fn state() -> &'static RwLock<InputProfile> {
+    static PROFILE: OnceLock<RwLock<InputProfile>> = OnceLock::new();
+    PROFILE.get_or_init(|| RwLock::new(InputProfile::from_env()))
+}
+
+/// 当前生效的输入节奏配置
+pub fn current() -> InputProfile {
+    *state().read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 替换当前输入节奏配置，倍率限制在 0..=`MAX_DELAY_SCALE`
+pub fn set(mut profile: InputProfile) -> InputProfile {
+    profile.delay_scale = profile.delay_scale.clamp(0.0, MAX_DELAY_SCALE);
+    *state().write().unwrap_or_else(|e| e.into_inner()) = profile;
+    profile
+}
+
+/// 按当前倍率缩放等待时间
+pub fn scaled(delay: Duration) -> Duration {
+    delay.mul_f64(current().delay_scale)
+}
+
+/// 按当前倍率缩放毫秒数
+pub fn scaled_ms(ms: u64) -> u64 {
+    scaled(Duration::from_millis(ms)).as_millis() as u64
+}
+
+/// 按当前倍率等待；缩放后为 0 时直接返回
+pub fn sleep(delay: Duration) {
+    let delay = scaled(delay);
+    if !delay.is_zero() {
+        thread::sleep(delay);
+    }
+}
@@ -0,0 +1,138 @@
+//! Replay of recorded input (see `monitor::recorder`) through the keyboard and mouse controllers.
+//!
+//! Events are scheduled against a monotonic clock so injection overhead does not accumulate
+//! as drift. Recorded timing is the point of a replay, so the global pacing profile does not
+//! apply; use `speed` instead. Keys and buttons still held when the replay ends or is stopped
+//! are released so a partial replay never leaves stuck input behind.
+
+use super::keyboard::KeyboardController;
+use super::keys;
+use super::mouse::MouseController;
+use crate::monitor::key_mouse::MouseButton;
+use crate::monitor::recorder::{RecordedAction, Recording};
+use enigo::{Button, Direction, Key, Keyboard, Mouse};
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Allowed range for the replay speed multiplier
+pub const MIN_REPLAY_SPEED: f64 = 0.1;
+pub const MAX_REPLAY_SPEED: f64 = 10.0;
+
+/// Longest single sleep while waiting for the next event, so stop requests are noticed promptly
+const WAIT_SLICE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplayReport {
+    /// Events injected
+    pub replayed: usize,
+    /// Events that could not be injected on this platform (unknown keys, extra mouse buttons)
+    pub skipped: usize,
+    /// Total events in the recording
+    pub total: usize,
+    /// Whether the replay stopped early because `keep_going` returned false
+    pub stopped: bool,
+}
+
+fn enigo_button(button: MouseButton) -> Option<Button> {
+    match button {
+        MouseButton::Left => Some(Button::Left),
+        MouseButton::Middle => Some(Button::Middle),
+        MouseButton::Right => Some(Button::Right),
+        MouseButton::Other(_) => None,
+    }
+}
+
+/// Replay `recording` at `speed` (2.0 = twice as fast). `keep_going(done, total)` is polled
+/// after every event and while waiting; returning false stops the replay.
+pub fn replay<K: Keyboard + Send, M: Mouse + Send>(
+    keyboard: &mut KeyboardController<K>,
+    mouse: &mut MouseController<M>,
+    recording: &Recording,
+    speed: f64,
+    mut keep_going: impl FnMut(usize, usize) -> bool,
+) -> Result<ReplayReport, enigo::InputError> {
+    let speed = speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED);
+    let mut report = ReplayReport { total: recording.events.len(), ..ReplayReport::default() };
+    let mut held_keys: Vec<Key> = Vec::new();
+    let mut held_buttons: Vec<Button> = Vec::new();
+    let start = Instant::now();
+
+    let result = (|| {
+        for event in &recording.events {
+            let due = start + Duration::from_micros(event.offset_micros).div_f64(speed);
+            loop {
+                let now = Instant::now();
+                if now >= due {
+                    break;
+                }
+                if !keep_going(report.replayed + report.skipped, report.total) {
+                    report.stopped = true;
+                    return Ok(());
+                }
+                thread::sleep((due - now).min(WAIT_SLICE));
+            }
+
+            let injected = match &event.action {
+                RecordedAction::KeyPress { key } | RecordedAction::KeyRelease { key } => {
+                    let press = matches!(event.action, RecordedAction::KeyPress { .. });
+                    match keys::parse(key) {
+                        Ok(key) => {
+                            let direction = if press { Direction::Press } else { Direction::Release };
+                            keyboard.key_control(key, direction)?;
+                            held_keys.retain(|k| *k != key);
+                            if press {
+                                held_keys.push(key);
+                            }
+                            true
+                        }
+                        Err(_) => false,
+                    }
+                }
+                RecordedAction::MouseMove { x, y } => {
+                    mouse.mouse_move(*x, *y)?;
+                    true
+                }
+                RecordedAction::ButtonPress { button } | RecordedAction::ButtonRelease { button } => {
+                    let press = matches!(event.action, RecordedAction::ButtonPress { .. });
+                    match enigo_button(*button) {
+                        Some(button) => {
+                            let direction = if press { Direction::Press } else { Direction::Release };
+                            mouse.mouse_button_control(button, direction)?;
+                            held_buttons.retain(|b| *b != button);
+                            if press {
+                                held_buttons.push(button);
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                // Monitored wheel deltas are positive up; enigo scrolls down for positive values
+                RecordedAction::Scroll { delta_x, delta_y } => {
+                    mouse.mouse_scroll(*delta_x, -*delta_y)?;
+                    true
+                }
+            };
+            if injected {
+                report.replayed += 1;
+            } else {
+                report.skipped += 1;
+            }
+            if !keep_going(report.replayed + report.skipped, report.total) {
+                report.stopped = report.replayed + report.skipped < report.total;
+                return Ok(());
+            }
+        }
+        Ok(())
+    })();
+
+    // Release whatever the replay left pressed, even after an injection error
+    for key in held_keys.into_iter().rev() {
+        let _ = keyboard.key_control(key, Direction::Release);
+    }
+    for button in held_buttons.into_iter().rev() {
+        let _ = mouse.mouse_button_control(button, Direction::Release);
+    }
+    result.map(|()| report)
+}
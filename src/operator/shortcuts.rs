@@ -0,0 +1,383 @@
+//! 全局快捷键冲突检测：注入组合键前检查该组合是否已被系统或其他应用注册为全局快捷键。
+//! 被占用的组合键会先被系统拦截，目标应用收不到按键，预期操作不会发生。
+//!
+//! - macOS：读取 `com.apple.symbolichotkeys` 中已启用的系统快捷键
+//! - Linux：读取 GNOME 的 gsettings 按键绑定（窗口管理、Shell、媒体键）
+//! - Windows：系统不公开已注册的全局热键，仅检查内置的系统保留组合
+
+use super::keys;
+use serde::Serialize;
+use std::fmt;
+
+const MOD_CONTROL: u8 = 1;
+const MOD_SHIFT: u8 = 1 << 1;
+const MOD_ALT: u8 = 1 << 2;
+const MOD_META: u8 = 1 << 3;
+
+#[derive(Debug)]
+pub enum ShortcutError {
+    NotImplemented(&'static str),
+    Io(String),
+}
+
+impl fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutError::NotImplemented(msg) => write!(f, "shortcut lookup not implemented: {}", msg),
+            ShortcutError::Io(msg) => write!(f, "shortcut lookup failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutError {}
+
+/// 修饰键集合 + 主键（字母为大写，其余为规范按键名称）
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Combo {
+    modifiers: u8,
+    key: String,
+}
+
+impl Combo {
+    /// 从按键名称数组构造，修饰键不区分左右
+    fn from_names<S: AsRef<str>>(names: &[S]) -> Option<Self> {
+        let (last, mods) = names.split_last()?;
+        let mut modifiers = 0;
+        for name in mods {
+            modifiers |= modifier_bit(name.as_ref())?;
+        }
+        Some(Combo {
+            modifiers,
+            key: normalize_key(last.as_ref()),
+        })
+    }
+}
+
+fn modifier_bit(name: &str) -> Option<u8> {
+    match keys::canonical_name(name)? {
+        "Control" | "LeftControl" | "RightControl" => Some(MOD_CONTROL),
+        "Shift" | "LeftShift" | "RightShift" => Some(MOD_SHIFT),
+        "Alt" | "LeftAlt" | "RightAlt" => Some(MOD_ALT),
+        "Meta" | "LeftMeta" | "RightMeta" => Some(MOD_META),
+        _ => None,
+    }
+}
+
+fn normalize_key(name: &str) -> String {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return c.to_uppercase().collect();
+    }
+    keys::canonical_name(name)
+        .map(str::to_string)
+        .unwrap_or_else(|| name.to_lowercase())
+}
+
+/// 与组合键冲突的已注册快捷键
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutConflict {
+    /// 注册方：系统或应用
+    pub owner: String,
+    /// 快捷键的用途
+    pub action: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictReport {
+    pub conflicts: Vec<ShortcutConflict>,
+    /// 是否读取到了平台注册表；为 false 时只检查了内置的系统保留组合
+    pub registry_checked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// 检查组合键（如 `["cmd", "space"]`）是否与已注册的全局快捷键冲突
+pub fn find_conflicts<S: AsRef<str>>(names: &[S]) -> ConflictReport {
+    let Some(combo) = Combo::from_names(names) else {
+        return ConflictReport {
+            conflicts: Vec::new(),
+            registry_checked: false,
+            note: Some("empty key combo".to_string()),
+        };
+    };
+
+    let mut conflicts: Vec<ShortcutConflict> = platform::RESERVED
+        .iter()
+        .filter(|(keys, _)| Combo::from_names(keys).as_ref() == Some(&combo))
+        .map(|(_, action)| ShortcutConflict {
+            owner: "system".to_string(),
+            action: action.to_string(),
+        })
+        .collect();
+
+    let (registry_checked, note) = match platform::registered() {
+        Ok(registered) => {
+            for (registered_combo, conflict) in registered {
+                if registered_combo == combo && !conflicts.iter().any(|c| c.action == conflict.action) {
+                    conflicts.push(conflict);
+                }
+            }
+            (true, None)
+        }
+        Err(e) => (false, Some(e.to_string())),
+    };
+
+    ConflictReport {
+        conflicts,
+        registry_checked,
+        note,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use serde_json::Value;
+    use std::process::Command;
+
+    /// 不在 symbolichotkeys 中、但始终由系统处理的组合
+    pub const RESERVED: &[(&[&str], &str)] = &[
+        (&["cmd", "tab"], "App Switcher"),
+        (&["cmd", "shift", "tab"], "App Switcher (reverse)"),
+        (&["cmd", "alt", "escape"], "Force Quit Applications"),
+        (&["ctrl", "cmd", "q"], "Lock Screen"),
+    ];
+
+    const NS_SHIFT: u64 = 0x20000;
+    const NS_CONTROL: u64 = 0x40000;
+    const NS_ALT: u64 = 0x80000;
+    const NS_COMMAND: u64 = 0x100000;
+
+    /// 常见 symbolic hotkey ID 的用途
+    fn action_name(id: &str) -> String {
+        let name = match id {
+            "7" => "Move focus to the menu bar",
+            "8" => "Move focus to the Dock",
+            "9" => "Move focus to active or next window",
+            "10" => "Move focus to the window toolbar",
+            "11" => "Move focus to the floating window",
+            "12" => "Turn keyboard access on or off",
+            "27" => "Move focus to next window",
+            "28" => "Save picture of screen as a file",
+            "29" => "Copy picture of screen to the clipboard",
+            "30" => "Save picture of selected area as a file",
+            "31" => "Copy picture of selected area to the clipboard",
+            "32" => "Mission Control",
+            "33" => "Application windows",
+            "36" => "Show Desktop",
+            "52" => "Turn Dock hiding on/off",
+            "57" => "Move focus to the status menus",
+            "60" => "Select the previous input source",
+            "61" => "Select next source in Input menu",
+            "62" => "Show Dashboard",
+            "64" => "Show Spotlight search",
+            "65" => "Show Finder search window",
+            "79" => "Move left a space",
+            "81" => "Move right a space",
+            "118" => "Switch to Desktop 1",
+            "119" => "Switch to Desktop 2",
+            "120" => "Switch to Desktop 3",
+            "121" => "Switch to Desktop 4",
+            "160" => "Show Launchpad",
+            "162" => "Show Notification Center",
+            "163" => "Turn Do Not Disturb on/off",
+            "175" => "Show Accessibility controls",
+            "184" => "Screenshot and recording options",
+            _ => return format!("symbolic hotkey {}", id),
+        };
+        name.to_string()
+    }
+
+    /// macOS 虚拟键码 → 规范按键名称（仅非字符键）
+    fn keycode_name(code: u64) -> Option<&'static str> {
+        Some(match code {
+            36 => "Enter",
+            48 => "Tab",
+            49 => "Space",
+            51 => "Backspace",
+            53 => "Escape",
+            117 => "Delete",
+            115 => "Home",
+            119 => "End",
+            116 => "PageUp",
+            121 => "PageDown",
+            123 => "Left",
+            124 => "Right",
+            125 => "Down",
+            126 => "Up",
+            122 => "F1",
+            120 => "F2",
+            99 => "F3",
+            118 => "F4",
+            96 => "F5",
+            97 => "F6",
+            98 => "F7",
+            100 => "F8",
+            101 => "F9",
+            109 => "F10",
+            103 => "F11",
+            111 => "F12",
+            _ => return None,
+        })
+    }
+
+    pub fn registered() -> Result<Vec<(Combo, ShortcutConflict)>, ShortcutError> {
+        let home = std::env::var("HOME").map_err(|e| ShortcutError::Io(e.to_string()))?;
+        let path = format!("{}/Library/Preferences/com.apple.symbolichotkeys.plist", home);
+        let output = Command::new("plutil")
+            .args(["-convert", "json", "-o", "-", &path])
+            .output()
+            .map_err(|e| ShortcutError::Io(format!("plutil: {}", e)))?;
+        if !output.status.success() {
+            return Err(ShortcutError::Io(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        let plist: Value =
+            serde_json::from_slice(&output.stdout).map_err(|e| ShortcutError::Io(e.to_string()))?;
+
+        let mut result = Vec::new();
+        let Some(hotkeys) = plist["AppleSymbolicHotKeys"].as_object() else {
+            return Ok(result);
+        };
+        for (id, entry) in hotkeys {
+            let enabled = entry["enabled"].as_bool().or_else(|| entry["enabled"].as_u64().map(|v| v != 0));
+            if enabled != Some(true) {
+                continue;
+            }
+            let params = &entry["value"]["parameters"];
+            let (Some(ascii), Some(keycode), Some(flags)) =
+                (params[0].as_u64(), params[1].as_u64(), params[2].as_u64())
+            else {
+                continue;
+            };
+            let key = match keycode_name(keycode) {
+                Some(name) => name.to_string(),
+                None => match char::from_u32(ascii as u32).filter(|_| ascii != 65535) {
+                    Some(c) => c.to_uppercase().collect(),
+                    None => continue,
+                },
+            };
+            let mut modifiers = 0;
+            if flags & NS_CONTROL != 0 {
+                modifiers |= MOD_CONTROL;
+            }
+            if flags & NS_SHIFT != 0 {
+                modifiers |= MOD_SHIFT;
+            }
+            if flags & NS_ALT != 0 {
+                modifiers |= MOD_ALT;
+            }
+            if flags & NS_COMMAND != 0 {
+                modifiers |= MOD_META;
+            }
+            result.push((
+                Combo { modifiers, key },
+                ShortcutConflict {
+                    owner: "macOS".to_string(),
+                    action: action_name(id),
+                },
+            ));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+
+    pub const RESERVED: &[(&[&str], &str)] = &[
+        (&["ctrl", "alt", "delete"], "Secure Attention Sequence"),
+        (&["alt", "tab"], "Task Switcher"),
+        (&["win", "l"], "Lock Workstation"),
+        (&["win", "d"], "Show Desktop"),
+        (&["win", "r"], "Run dialog"),
+        (&["win", "e"], "File Explorer"),
+        (&["win", "tab"], "Task View"),
+        (&["ctrl", "shift", "escape"], "Task Manager"),
+        (&["win", "shift", "s"], "Snipping Tool"),
+    ];
+
+    pub fn registered() -> Result<Vec<(Combo, ShortcutConflict)>, ShortcutError> {
+        Err(ShortcutError::NotImplemented(
+            "Windows does not expose hotkeys registered by other applications",
+        ))
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+    use std::process::Command;
+
+    pub const RESERVED: &[(&[&str], &str)] = &[
+        (&["ctrl", "alt", "delete"], "Log out / reboot"),
+        (&["ctrl", "alt", "backspace"], "Terminate X server (if enabled)"),
+    ];
+
+    const SCHEMAS: &[(&str, &str)] = &[
+        ("org.gnome.desktop.wm.keybindings", "GNOME window manager"),
+        ("org.gnome.shell.keybindings", "GNOME Shell"),
+        ("org.gnome.settings-daemon.plugins.media-keys", "GNOME media keys"),
+        ("org.gnome.mutter.keybindings", "Mutter"),
+    ];
+
+    /// 解析 GTK 加速键字符串，如 `<Primary><Alt>t`
+    fn parse_accelerator(accel: &str) -> Option<Combo> {
+        let mut modifiers = 0;
+        let mut rest = accel.trim();
+        while let Some(stripped) = rest.strip_prefix('<') {
+            let end = stripped.find('>')?;
+            modifiers |= match stripped[..end].to_lowercase().as_str() {
+                "primary" | "control" | "ctrl" => MOD_CONTROL,
+                "shift" => MOD_SHIFT,
+                "alt" | "mod1" => MOD_ALT,
+                "super" | "meta" | "mod4" | "hyper" => MOD_META,
+                _ => 0,
+            };
+            rest = &stripped[end + 1..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        Some(Combo {
+            modifiers,
+            key: normalize_key(rest),
+        })
+    }
+
+    pub fn registered() -> Result<Vec<(Combo, ShortcutConflict)>, ShortcutError> {
+        let mut result = Vec::new();
+        let mut any_schema = false;
+        for (schema, owner) in SCHEMAS {
+            let Ok(output) = Command::new("gsettings").args(["list-recursively", schema]).output() else {
+                return Err(ShortcutError::NotImplemented("gsettings not available (non-GNOME desktop)"));
+            };
+            if !output.status.success() {
+                continue;
+            }
+            any_schema = true;
+            // 每行形如：<schema> <key> ['<Super>Up', '<Alt>F10']
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let mut parts = line.splitn(3, ' ');
+                let (Some(_), Some(key), Some(value)) = (parts.next(), parts.next(), parts.next()) else {
+                    continue;
+                };
+                for accel in value.split('\'').skip(1).step_by(2) {
+                    if let Some(combo) = parse_accelerator(accel) {
+                        result.push((
+                            combo,
+                            ShortcutConflict {
+                                owner: owner.to_string(),
+                                action: key.to_string(),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        if !any_schema {
+            return Err(ShortcutError::NotImplemented("no GNOME keybinding schemas found"));
+        }
+        Ok(result)
+    }
+}
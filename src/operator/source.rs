@@ -0,0 +1,125 @@
+//! 注入输入的来源标记：让终端安全软件与监控模块能区分 iris-mcp 合成的输入与真人输入。
+//!
+//! - macOS：所有注入事件的 `kCGEventSourceUserData` 字段写入 `SOURCE_SIGNATURE`
+//! - Windows：`SendInput` 的 `dwExtraInfo` 写入 `SOURCE_SIGNATURE`
+//! - Linux（X11 XTest）：协议没有可携带标记的字段，无法在事件上打标
+//!
+//! rdev 回调不暴露上述字段，因此监控模块另按注入时间窗口判断：
+//! 操作层每次注入事件期间（及其后短暂宽限期）捕获的事件视为合成输入；
+//! 工具在两次注入之间等待（连击间隔、路径延时等）时的真人输入不受影响。
+
+use enigo::Settings;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 注入事件携带的来源签名（ASCII "IRIS"）
+pub const SOURCE_SIGNATURE: i64 = 0x4952_4953;
+
+/// 注入结束后仍视为合成事件的宽限期（微秒），覆盖系统投递事件的延迟
+const INJECTION_GRACE_MICROS: u64 = 50_000;
+
+/// 正在进行的注入数量
+static ACTIVE_INJECTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// 最近一次注入结束的时间（Unix 微秒）
+static LAST_INJECTION_END: AtomicU64 = AtomicU64::new(0);
+
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+/// 带来源标记的 Enigo 设置；所有注入输入的 Enigo 实例都应使用它。
+/// 按键不在实例销毁时自动释放，使 key_control 的 press / release 可以跨调用配对；
+/// 仍按下的输入由 `held` 模块记录并统一释放。
+pub fn settings() -> Settings {
+    Settings {
+        windows_dw_extra_info: Some(SOURCE_SIGNATURE as usize),
+        event_source_user_data: Some(SOURCE_SIGNATURE),
+        release_keys_when_dropped: false,
+        ..Settings::default()
+    }
+}
+
+/// 注入期间持有，离开作用域时记录结束时间
+pub struct InjectionGuard(());
+
+impl Drop for InjectionGuard {
+    fn drop(&mut self) {
+        LAST_INJECTION_END.store(now_micros(), Ordering::SeqCst);
+        ACTIVE_INJECTIONS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 标记注入开始
+pub fn begin_injection() -> InjectionGuard {
+    ACTIVE_INJECTIONS.fetch_add(1, Ordering::SeqCst);
+    InjectionGuard(())
+}
+
+/// 指定时刻（Unix 微秒）捕获的事件是否可能由 iris-mcp 注入
+pub fn is_synthetic_at(timestamp_micros: u128) -> bool {
+    if ACTIVE_INJECTIONS.load(Ordering::SeqCst) > 0 {
+        return true;
+    }
+    let last_end = LAST_INJECTION_END.load(Ordering::SeqCst) as u128;
+    last_end > 0 && timestamp_micros <= last_end + INJECTION_GRACE_MICROS as u128
+}
+
+/// 当前平台的来源标记方式
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceInfo {
+    pub signature: i64,
+    pub signature_hex: String,
+    /// 注入事件本身是否携带签名
+    pub events_tagged: bool,
+    /// 签名所在字段
+    pub field: Option<&'static str>,
+    pub note: &'static str,
+}
+
+pub fn info() -> SourceInfo {
+    let (events_tagged, field, note) = platform::TAGGING;
+    SourceInfo {
+        signature: SOURCE_SIGNATURE,
+        signature_hex: format!("{:#x}", SOURCE_SIGNATURE),
+        events_tagged,
+        field,
+        note,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub const TAGGING: (bool, Option<&str>, &str) = (
+        true,
+        Some("kCGEventSourceUserData"),
+        "CGEventGetIntegerValueField(event, kCGEventSourceUserData) 等于签名的事件由 iris-mcp 注入",
+    );
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    pub const TAGGING: (bool, Option<&str>, &str) = (
+        true,
+        Some("dwExtraInfo"),
+        "低级钩子中 KBDLLHOOKSTRUCT/MSLLHOOKSTRUCT.dwExtraInfo 等于签名的事件由 iris-mcp 注入",
+    );
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    pub const TAGGING: (bool, Option<&str>, &str) = (
+        false,
+        None,
+        "X11 XTest 事件无法携带来源标记；仅监控模块可按注入时间窗口区分",
+    );
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub const TAGGING: (bool, Option<&str>, &str) = (false, None, "当前平台不支持来源标记");
+}
@@ -0,0 +1,808 @@
+//! 窗口管理：枚举顶层窗口并移动/缩放窗口。
+//! macOS 基于 CGWindowList + AXUIElement；Linux 基于 X11 EWMH；Windows 基于 EnumWindows / SetWindowPos（不报告应用名）。
+
+use super::locate;
+use crate::monitor::display::Rect;
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowInfo {
+    /// 平台窗口 ID（macOS CGWindowID / X11 Window / Windows HWND）
+    pub id: u64,
+    pub title: String,
+    pub app: Option<String>,
+    pub pid: Option<u32>,
+    pub frame: Rect,
+}
+
+#[derive(Debug)]
+pub enum WindowError {
+    UnsupportedPlatform(&'static str),
+    PermissionDenied(String),
+    NotImplemented(&'static str),
+    NotFound(String),
+    Io(String),
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::UnsupportedPlatform(p) => write!(f, "window management unsupported on {}", p),
+            WindowError::PermissionDenied(msg) => write!(f, "window management permission denied: {}", msg),
+            WindowError::NotImplemented(msg) => write!(f, "window management not implemented: {}", msg),
+            WindowError::NotFound(msg) => write!(f, "window not found: {}", msg),
+            WindowError::Io(msg) => write!(f, "window management io error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WindowError {}
+
+/// 窗口贴靠预设，基于显示器可用工作区计算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapPosition {
+    LeftHalf,
+    RightHalf,
+    MaximizeWorkArea,
+    Center60,
+}
+
+impl SnapPosition {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "left-half" => Some(SnapPosition::LeftHalf),
+            "right-half" => Some(SnapPosition::RightHalf),
+            "maximize-work-area" => Some(SnapPosition::MaximizeWorkArea),
+            "center-60" => Some(SnapPosition::Center60),
+            _ => None,
+        }
+    }
+
+    /// 在给定工作区内计算目标窗口矩形
+    pub fn frame_in(&self, area: &Rect) -> Rect {
+        match self {
+            SnapPosition::LeftHalf => Rect {
+                x: area.x,
+                y: area.y,
+                width: area.width / 2,
+                height: area.height,
+            },
+            SnapPosition::RightHalf => Rect {
+                x: area.x + (area.width / 2) as i32,
+                y: area.y,
+                width: area.width - area.width / 2,
+                height: area.height,
+            },
+            SnapPosition::MaximizeWorkArea => *area,
+            SnapPosition::Center60 => {
+                let width = area.width * 3 / 5;
+                let height = area.height * 3 / 5;
+                Rect {
+                    x: area.x + ((area.width - width) / 2) as i32,
+                    y: area.y + ((area.height - height) / 2) as i32,
+                    width,
+                    height,
+                }
+            }
+        }
+    }
+}
+
+/// 窗口状态操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowAction {
+    Minimize,
+    Maximize,
+    /// 取消最小化 / 最大化（macOS 同时退出全屏）并置于前台
+    Restore,
+    /// 请求关闭，应用可能弹出保存确认
+    Close,
+}
+
+impl WindowAction {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "minimize" => Some(WindowAction::Minimize),
+            "maximize" => Some(WindowAction::Maximize),
+            "restore" => Some(WindowAction::Restore),
+            "close" => Some(WindowAction::Close),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WindowAction::Minimize => "minimize",
+            WindowAction::Maximize => "maximize",
+            WindowAction::Restore => "restore",
+            WindowAction::Close => "close",
+        }
+    }
+}
+
+/// 按标题与应用名筛选窗口；含 `*` / `?` 的模式按通配符匹配整个字符串，否则按子串匹配，均不区分大小写
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowFilter {
+    pub title: Option<String>,
+    pub app: Option<String>,
+}
+
+impl WindowFilter {
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        self.title.as_deref().is_none_or(|p| pattern_matches(p, &window.title))
+            && self
+                .app
+                .as_deref()
+                .is_none_or(|p| window.app.as_deref().is_some_and(|app| pattern_matches(p, app)))
+    }
+}
+
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    if !pattern.iter().any(|c| *c == '*' || *c == '?') {
+        return pattern.is_empty() || text.windows(pattern.len()).any(|w| w == pattern.as_slice());
+    }
+    // 贪婪匹配，遇到不匹配时回溯到上一个 `*`
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 列出屏幕上的普通顶层窗口（按前后顺序，最前在先）
+pub fn list_windows() -> Result<Vec<WindowInfo>, WindowError> {
+    platform::list_windows()
+}
+
+/// 当前拥有输入焦点的窗口
+pub fn focused_window() -> Result<WindowInfo, WindowError> {
+    platform::focused_window()
+}
+
+/// 全局坐标处最前的窗口
+pub fn window_at(x: i32, y: i32) -> Result<WindowInfo, WindowError> {
+    platform::window_at(x, y)
+}
+
+/// 按 ID 查找窗口
+pub fn get_window(id: u64) -> Result<WindowInfo, WindowError> {
+    list_windows()?
+        .into_iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| WindowError::NotFound(format!("id {}", id)))
+}
+
+/// 将窗口移动并缩放到指定矩形（全局坐标）；该窗口下缓存的定位结果随之失效
+pub fn set_frame(id: u64, frame: Rect) -> Result<(), WindowError> {
+    platform::set_frame(id, frame)?;
+    locate::invalidate_window(&id.to_string());
+    Ok(())
+}
+
+/// 通过平台窗口管理接口最小化、最大化、还原或关闭窗口；该窗口下缓存的定位结果随之失效
+pub fn control(id: u64, action: WindowAction) -> Result<(), WindowError> {
+    platform::control(id, action)?;
+    locate::invalidate_window(&id.to_string());
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use crate::monitor::accessibility::{ax, AccessibilityError};
+    use core_foundation::array::{CFArrayGetCount, CFArrayGetValueAtIndex, CFArrayRef};
+    use core_foundation::base::{CFGetTypeID, TCFType};
+    use core_foundation::dictionary::{CFDictionaryGetValue, CFDictionaryRef};
+    use core_foundation::number::{kCFNumberSInt64Type, CFNumberGetTypeID, CFNumberGetValue, CFNumberRef};
+    use core_foundation::string::{CFString, CFStringGetTypeID, CFStringRef};
+    use crate::monitor::display;
+    use core_graphics::geometry::CGRect;
+    use std::collections::HashMap;
+    use std::os::raw::c_void;
+    use std::sync::{Mutex, OnceLock};
+
+    const K_CG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY: u32 = 1 << 0;
+    const K_CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
+    const K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> CFArrayRef;
+        fn CGRectMakeWithDictionaryRepresentation(dict: CFDictionaryRef, rect: *mut CGRect) -> bool;
+    }
+
+    impl From<AccessibilityError> for WindowError {
+        fn from(e: AccessibilityError) -> Self {
+            match e {
+                AccessibilityError::PermissionDenied(msg) => WindowError::PermissionDenied(msg.to_string()),
+                AccessibilityError::NotFound(msg) => WindowError::NotFound(msg),
+                other => WindowError::Io(other.to_string()),
+            }
+        }
+    }
+
+    fn dict_value(dict: CFDictionaryRef, key: &str) -> *const c_void {
+        let key = CFString::new(key);
+        unsafe { CFDictionaryGetValue(dict, key.as_concrete_TypeRef() as *const c_void) }
+    }
+
+    fn dict_number(dict: CFDictionaryRef, key: &str) -> Option<i64> {
+        let value = dict_value(dict, key);
+        if value.is_null() || unsafe { CFGetTypeID(value) } != unsafe { CFNumberGetTypeID() } {
+            return None;
+        }
+        let mut out = 0i64;
+        let ok = unsafe {
+            CFNumberGetValue(value as CFNumberRef, kCFNumberSInt64Type, &mut out as *mut i64 as *mut c_void)
+        };
+        ok.then_some(out)
+    }
+
+    fn dict_string(dict: CFDictionaryRef, key: &str) -> Option<String> {
+        let value = dict_value(dict, key);
+        if value.is_null() || unsafe { CFGetTypeID(value) } != unsafe { CFStringGetTypeID() } {
+            return None;
+        }
+        Some(unsafe { CFString::wrap_under_get_rule(value as CFStringRef) }.to_string())
+    }
+
+    fn dict_rect(dict: CFDictionaryRef, key: &str) -> Option<Rect> {
+        let value = dict_value(dict, key);
+        if value.is_null() {
+            return None;
+        }
+        let mut rect = CGRect::new(
+            &core_graphics::geometry::CGPoint::new(0.0, 0.0),
+            &core_graphics::geometry::CGSize::new(0.0, 0.0),
+        );
+        let ok = unsafe { CGRectMakeWithDictionaryRepresentation(value as CFDictionaryRef, &mut rect) };
+        ok.then(|| Rect {
+            x: rect.origin.x.round() as i32,
+            y: rect.origin.y.round() as i32,
+            width: rect.size.width.round() as u32,
+            height: rect.size.height.round() as u32,
+        })
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, WindowError> {
+        copy_windows(K_CG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY | K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS, 0)
+    }
+
+    /// 按 ID 查找窗口，包括最小化等不在屏幕上的窗口
+    fn find_window(id: u64) -> Result<WindowInfo, WindowError> {
+        copy_windows(K_CG_WINDOW_LIST_OPTION_INCLUDING_WINDOW, id as u32)?
+            .into_iter()
+            .find(|w| w.id == id)
+            .ok_or_else(|| WindowError::NotFound(format!("id {}", id)))
+    }
+
+    fn copy_windows(option: u32, relative_to_window: u32) -> Result<Vec<WindowInfo>, WindowError> {
+        let array = unsafe { CGWindowListCopyWindowInfo(option, relative_to_window) };
+        let array = ax::Owned::new(array as _)
+            .ok_or_else(|| WindowError::Io("CGWindowListCopyWindowInfo failed".to_string()))?;
+
+        let count = unsafe { CFArrayGetCount(array.0 as CFArrayRef) };
+        let mut windows = Vec::new();
+        for i in 0..count {
+            let dict = unsafe { CFArrayGetValueAtIndex(array.0 as CFArrayRef, i) } as CFDictionaryRef;
+            if dict.is_null() {
+                continue;
+            }
+            // 只保留普通应用窗口（layer 0），排除菜单栏、Dock 等
+            if dict_number(dict, "kCGWindowLayer") != Some(0) {
+                continue;
+            }
+            let (Some(id), Some(frame)) = (dict_number(dict, "kCGWindowNumber"), dict_rect(dict, "kCGWindowBounds"))
+            else {
+                continue;
+            };
+            windows.push(WindowInfo {
+                id: id as u64,
+                title: dict_string(dict, "kCGWindowName").unwrap_or_default(),
+                app: dict_string(dict, "kCGWindowOwnerName"),
+                pid: dict_number(dict, "kCGWindowOwnerPID").map(|p| p as u32),
+                frame,
+            });
+        }
+        Ok(windows)
+    }
+
+    /// CGWindowList 按前后顺序排列，最前的普通窗口即为前台应用的窗口
+    /// （置顶的浮动窗口也会排在前面，此时结果为该窗口）
+    pub fn focused_window() -> Result<WindowInfo, WindowError> {
+        list_windows()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WindowError::NotFound("no on-screen window".to_string()))
+    }
+
+    /// 屏幕上的窗口按前后顺序排列，第一个包含该点的即为最前的窗口
+    pub fn window_at(x: i32, y: i32) -> Result<WindowInfo, WindowError> {
+        list_windows()?
+            .into_iter()
+            .find(|w| w.frame.contains(x, y))
+            .ok_or_else(|| WindowError::NotFound(format!("no window at ({}, {})", x, y)))
+    }
+
+    /// 找到 CGWindowID 对应的 AX 窗口元素
+    pub(super) fn ax_window(info: &WindowInfo) -> Result<ax::Owned, WindowError> {
+        ax::ensure_trusted()?;
+        let pid = info
+            .pid
+            .ok_or_else(|| WindowError::NotFound(format!("id {} has no owner pid", info.id)))?;
+        let app = ax::application(pid as i32)?;
+        let candidates = ax::children(app.0, "AXWindows");
+
+        let mut fallback = None;
+        for window in candidates {
+            if ax::window_id(window.0) == Some(info.id as u32) {
+                return Ok(window);
+            }
+            // 私有 API 不可用时按位置与尺寸匹配
+            let position = ax::point_attr(window.0, "AXPosition");
+            let size = ax::size_attr(window.0, "AXSize");
+            if fallback.is_none()
+                && position == Some((info.frame.x as f64, info.frame.y as f64))
+                && size == Some((info.frame.width as f64, info.frame.height as f64))
+            {
+                fallback = Some(window);
+            }
+        }
+        fallback.ok_or_else(|| WindowError::NotFound(format!("no AX window for id {}", info.id)))
+    }
+
+    pub fn set_frame(id: u64, frame: Rect) -> Result<(), WindowError> {
+        let info = get_window(id)?;
+        let window = ax_window(&info)?;
+
+        // 先移动再缩放，最后再移动一次：缩放可能因跨屏受限
+        let (x, y) = (frame.x as f64, frame.y as f64);
+        let moved = ax::set_point_attr(window.0, "AXPosition", x, y);
+        let resized = ax::set_size_attr(window.0, "AXSize", frame.width as f64, frame.height as f64);
+        ax::set_point_attr(window.0, "AXPosition", x, y);
+
+        if moved && resized {
+            Ok(())
+        } else {
+            Err(WindowError::Io(format!("window {} rejected position/size change", id)))
+        }
+    }
+
+    /// 最大化前的窗口位置，供 restore 还原；macOS 没有最大化状态，最大化即铺满显示器可用工作区
+    fn saved_frames() -> &'static Mutex<HashMap<u64, Rect>> {
+        static SAVED: OnceLock<Mutex<HashMap<u64, Rect>>> = OnceLock::new();
+        SAVED.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    /// 按下窗口标题栏上的按钮（AXCloseButton 等）
+    fn press_button(window: &ax::Owned, button: &str, id: u64) -> Result<(), WindowError> {
+        let button_element = ax::copy_attr(window.0, button)
+            .ok_or_else(|| WindowError::Io(format!("window {} has no {}", id, button)))?;
+        if ax::perform_action(button_element.0, "AXPress") {
+            Ok(())
+        } else {
+            Err(WindowError::Io(format!("window {} rejected {} press", id, button)))
+        }
+    }
+
+    pub fn control(id: u64, action: WindowAction) -> Result<(), WindowError> {
+        let info = find_window(id)?;
+        let window = ax_window(&info)?;
+        let rejected = || WindowError::Io(format!("window {} rejected {}", id, action.as_str()));
+
+        match action {
+            WindowAction::Minimize => ax::set_bool_attr(window.0, "AXMinimized", true).then_some(()).ok_or_else(rejected),
+            WindowAction::Maximize => {
+                let (cx, cy) = (
+                    info.frame.x + (info.frame.width / 2) as i32,
+                    info.frame.y + (info.frame.height / 2) as i32,
+                );
+                let displays = display::list_displays().map_err(|e| WindowError::Io(e.to_string()))?;
+                let target = displays
+                    .iter()
+                    .find(|d| d.contains(cx, cy))
+                    .or_else(|| displays.first())
+                    .ok_or_else(|| WindowError::Io("no displays available".to_string()))?;
+                let area = target.usable_area();
+                if info.frame != area {
+                    saved_frames().lock().unwrap_or_else(|e| e.into_inner()).insert(id, info.frame);
+                }
+                set_frame(id, area)
+            }
+            WindowAction::Restore => {
+                if ax::bool_attr(window.0, "AXMinimized") == Some(true)
+                    && !ax::set_bool_attr(window.0, "AXMinimized", false)
+                {
+                    return Err(rejected());
+                }
+                if ax::bool_attr(window.0, "AXFullScreen") == Some(true)
+                    && !ax::set_bool_attr(window.0, "AXFullScreen", false)
+                {
+                    return Err(rejected());
+                }
+                let saved = saved_frames().lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+                if let Some(frame) = saved {
+                    let (x, y) = (frame.x as f64, frame.y as f64);
+                    ax::set_point_attr(window.0, "AXPosition", x, y);
+                    ax::set_size_attr(window.0, "AXSize", frame.width as f64, frame.height as f64);
+                    ax::set_point_attr(window.0, "AXPosition", x, y);
+                }
+                ax::perform_action(window.0, "AXRaise");
+                Ok(())
+            }
+            WindowAction::Close => press_button(&window, "AXCloseButton", id),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, MapState, Window,
+    };
+    use x11rb::rust_connection::RustConnection;
+
+    pub(super) fn connect() -> Result<(RustConnection, Window), WindowError> {
+        let (conn, screen_num) = x11rb::connect(None)
+            .map_err(|e| WindowError::Io(format!("X11 connect failed: {}", e)))?;
+        let root = conn.setup().roots[screen_num].root;
+        Ok((conn, root))
+    }
+
+    pub(super) fn atom(conn: &RustConnection, name: &[u8]) -> Result<u32, WindowError> {
+        conn.intern_atom(false, name)
+            .map_err(|e| WindowError::Io(e.to_string()))?
+            .reply()
+            .map(|r| r.atom)
+            .map_err(|e| WindowError::Io(e.to_string()))
+    }
+
+    fn property_u32(conn: &RustConnection, window: Window, name: &[u8], kind: AtomEnum) -> Vec<u32> {
+        let Ok(prop) = atom(conn, name) else {
+            return Vec::new();
+        };
+        conn.get_property(false, window, prop, kind, 0, u32::MAX / 4)
+            .ok()
+            .and_then(|c| c.reply().ok())
+            .and_then(|r| r.value32().map(|v| v.collect()))
+            .unwrap_or_default()
+    }
+
+    fn window_title(conn: &RustConnection, window: Window) -> String {
+        let utf8 = atom(conn, b"UTF8_STRING").unwrap_or(AtomEnum::STRING.into());
+        for (name, kind) in [(&b"_NET_WM_NAME"[..], utf8), (&b"WM_NAME"[..], AtomEnum::STRING.into())] {
+            let Ok(prop) = atom(conn, name) else { continue };
+            if let Some(reply) = conn
+                .get_property(false, window, prop, kind, 0, 1024)
+                .ok()
+                .and_then(|c| c.reply().ok())
+                && !reply.value.is_empty()
+            {
+                return String::from_utf8_lossy(&reply.value).into_owned();
+            }
+        }
+        String::new()
+    }
+
+    fn window_class(conn: &RustConnection, window: Window) -> Option<String> {
+        let reply = conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 1024)
+            .ok()?
+            .reply()
+            .ok()?;
+        // WM_CLASS 为 "instance\0class\0"，取 class 部分
+        reply
+            .value
+            .split(|b| *b == 0)
+            .filter(|s| !s.is_empty())
+            .nth(1)
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+    }
+
+    fn window_frame(conn: &RustConnection, root: Window, window: Window) -> Option<Rect> {
+        let geometry = conn.get_geometry(window).ok()?.reply().ok()?;
+        let translated = conn.translate_coordinates(window, root, 0, 0).ok()?.reply().ok()?;
+        Some(Rect {
+            x: translated.dst_x as i32,
+            y: translated.dst_y as i32,
+            width: geometry.width as u32,
+            height: geometry.height as u32,
+        })
+    }
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, WindowError> {
+        let (conn, root) = connect()?;
+        // _NET_CLIENT_LIST_STACKING 由底到顶排列，反转为最前在先
+        let mut ids = property_u32(&conn, root, b"_NET_CLIENT_LIST_STACKING", AtomEnum::WINDOW);
+        if ids.is_empty() {
+            ids = property_u32(&conn, root, b"_NET_CLIENT_LIST", AtomEnum::WINDOW);
+        }
+        ids.reverse();
+
+        Ok(ids
+            .into_iter()
+            .filter_map(|id| {
+                let frame = window_frame(&conn, root, id)?;
+                Some(WindowInfo {
+                    id: id as u64,
+                    title: window_title(&conn, id),
+                    app: window_class(&conn, id),
+                    pid: property_u32(&conn, id, b"_NET_WM_PID", AtomEnum::CARDINAL).first().copied(),
+                    frame,
+                })
+            })
+            .collect())
+    }
+
+    pub fn focused_window() -> Result<WindowInfo, WindowError> {
+        let (conn, root) = connect()?;
+        let id = property_u32(&conn, root, b"_NET_ACTIVE_WINDOW", AtomEnum::WINDOW)
+            .first()
+            .copied()
+            .filter(|id| *id != 0)
+            .ok_or_else(|| WindowError::NotFound("no active window".to_string()))?;
+        let frame = window_frame(&conn, root, id)
+            .ok_or_else(|| WindowError::NotFound(format!("active window {} has no geometry", id)))?;
+        Ok(WindowInfo {
+            id: id as u64,
+            title: window_title(&conn, id),
+            app: window_class(&conn, id),
+            pid: property_u32(&conn, id, b"_NET_WM_PID", AtomEnum::CARDINAL).first().copied(),
+            frame,
+        })
+    }
+
+    /// 堆叠顺序中第一个包含该点且已映射的窗口；最小化或位于其他工作区的窗口不会被映射
+    pub fn window_at(x: i32, y: i32) -> Result<WindowInfo, WindowError> {
+        let (conn, _) = connect()?;
+        list_windows()?
+            .into_iter()
+            .find(|w| {
+                w.frame.contains(x, y)
+                    && conn
+                        .get_window_attributes(w.id as Window)
+                        .ok()
+                        .and_then(|c| c.reply().ok())
+                        .is_some_and(|a| a.map_state == MapState::VIEWABLE)
+            })
+            .ok_or_else(|| WindowError::NotFound(format!("no window at ({}, {})", x, y)))
+    }
+
+    /// 向根窗口发送 EWMH 客户端消息
+    pub(super) fn send_root_message(
+        conn: &RustConnection,
+        root: Window,
+        window: Window,
+        message: &[u8],
+        data: [u32; 5],
+    ) -> Result<(), WindowError> {
+        let kind = atom(conn, message)?;
+        let event = ClientMessageEvent::new(32, window, kind, data);
+        conn.send_event(
+            false,
+            root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )
+        .map_err(|e| WindowError::Io(e.to_string()))?;
+        conn.flush().map_err(|e| WindowError::Io(e.to_string()))
+    }
+
+    pub fn set_frame(id: u64, frame: Rect) -> Result<(), WindowError> {
+        let (conn, root) = connect()?;
+        let window = id as Window;
+
+        // 先取消最大化，否则窗口管理器会忽略几何变更
+        let max_vert = atom(&conn, b"_NET_WM_STATE_MAXIMIZED_VERT")?;
+        let max_horz = atom(&conn, b"_NET_WM_STATE_MAXIMIZED_HORZ")?;
+        send_root_message(&conn, root, window, b"_NET_WM_STATE", [0, max_vert, max_horz, 2, 0])?;
+
+        // flags: gravity=NorthWest(1) | x/y/width/height 有效(bit 8-11) | 来源为工具(2 << 12)
+        let flags = 1 | (0xF << 8) | (2 << 12);
+        send_root_message(
+            &conn,
+            root,
+            window,
+            b"_NET_MOVERESIZE_WINDOW",
+            [flags, frame.x as u32, frame.y as u32, frame.width, frame.height],
+        )
+    }
+
+    /// ICCCM IconicState
+    const ICONIC_STATE: u32 = 3;
+    /// EWMH 客户端消息中的来源标识：工具（pager 等）
+    const SOURCE_TOOL: u32 = 2;
+
+    pub fn control(id: u64, action: WindowAction) -> Result<(), WindowError> {
+        // 先确认窗口由窗口管理器管理，避免向任意 ID 发送消息
+        get_window(id)?;
+        let (conn, root) = connect()?;
+        let window = id as Window;
+        let max_vert = atom(&conn, b"_NET_WM_STATE_MAXIMIZED_VERT")?;
+        let max_horz = atom(&conn, b"_NET_WM_STATE_MAXIMIZED_HORZ")?;
+
+        match action {
+            WindowAction::Minimize => send_root_message(&conn, root, window, b"WM_CHANGE_STATE", [ICONIC_STATE, 0, 0, 0, 0]),
+            // _NET_WM_STATE 的 action：0 移除，1 添加
+            WindowAction::Maximize => {
+                send_root_message(&conn, root, window, b"_NET_WM_STATE", [1, max_vert, max_horz, SOURCE_TOOL, 0])
+            }
+            WindowAction::Restore => {
+                send_root_message(&conn, root, window, b"_NET_WM_STATE", [0, max_vert, max_horz, SOURCE_TOOL, 0])?;
+                // 激活窗口即可取消最小化
+                send_root_message(&conn, root, window, b"_NET_ACTIVE_WINDOW", [SOURCE_TOOL, 0, 0, 0, 0])
+            }
+            WindowAction::Close => send_root_message(&conn, root, window, b"_NET_CLOSE_WINDOW", [0, SOURCE_TOOL, 0, 0, 0]),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetAncestor, GetForegroundWindow, GetWindow, GetWindowLongW, GetWindowRect, GetWindowTextW,
+        GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, IsZoomed, SetWindowPos, ShowWindow,
+        WindowFromPoint, GA_ROOT, GWL_EXSTYLE, GW_OWNER, SWP_NOACTIVATE, SWP_NOZORDER, SW_RESTORE, WS_EX_TOOLWINDOW,
+    };
+
+    /// 窗口 ID 为 HWND 的数值
+    fn hwnd(id: u64) -> Result<HWND, WindowError> {
+        let hwnd = HWND(id as usize as *mut std::ffi::c_void);
+        if id == 0 || !unsafe { IsWindow(Some(hwnd)) }.as_bool() {
+            return Err(WindowError::NotFound(format!("id {}", id)));
+        }
+        Ok(hwnd)
+    }
+
+    fn window_info(hwnd: HWND) -> Result<WindowInfo, WindowError> {
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(hwnd, &mut rect) }.map_err(|e| WindowError::Io(format!("GetWindowRect failed: {}", e)))?;
+        let mut title = [0u16; 512];
+        let len = unsafe { GetWindowTextW(hwnd, &mut title) }.max(0) as usize;
+        let mut pid = 0u32;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+        Ok(WindowInfo {
+            id: hwnd.0 as usize as u64,
+            title: String::from_utf16_lossy(&title[..len]),
+            app: None,
+            pid: (pid != 0).then_some(pid),
+            frame: Rect {
+                x: rect.left,
+                y: rect.top,
+                width: (rect.right - rect.left).max(0) as u32,
+                height: (rect.bottom - rect.top).max(0) as u32,
+            },
+        })
+    }
+
+    /// 任务栏上会出现的普通顶层窗口：可见、未最小化、无所有者、不是工具窗口且有标题
+    fn is_normal_window(hwnd: HWND) -> bool {
+        let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+        unsafe { IsWindowVisible(hwnd) }.as_bool()
+            && !unsafe { IsIconic(hwnd) }.as_bool()
+            && unsafe { GetWindow(hwnd, GW_OWNER) }.is_err()
+            && ex_style & WS_EX_TOOLWINDOW.0 == 0
+            && unsafe { GetWindowTextW(hwnd, &mut [0u16; 2]) } > 0
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, data: LPARAM) -> BOOL {
+        let windows = unsafe { &mut *(data.0 as *mut Vec<HWND>) };
+        windows.push(hwnd);
+        BOOL(1)
+    }
+
+    /// EnumWindows 按 Z 序从前到后枚举顶层窗口
+    pub fn list_windows() -> Result<Vec<WindowInfo>, WindowError> {
+        let mut handles: Vec<HWND> = Vec::new();
+        unsafe { EnumWindows(Some(collect), LPARAM(&mut handles as *mut Vec<HWND> as isize)) }
+            .map_err(|e| WindowError::Io(format!("EnumWindows failed: {}", e)))?;
+        Ok(handles
+            .into_iter()
+            .filter(|h| is_normal_window(*h))
+            .filter_map(|h| window_info(h).ok())
+            .collect())
+    }
+
+    pub fn focused_window() -> Result<WindowInfo, WindowError> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.is_invalid() {
+            return Err(WindowError::NotFound("no foreground window".to_string()));
+        }
+        window_info(hwnd)
+    }
+
+    /// 最大化的窗口先还原，否则 SetWindowPos 设置的位置会在还原时被系统覆盖
+    pub fn set_frame(id: u64, frame: Rect) -> Result<(), WindowError> {
+        let hwnd = hwnd(id)?;
+        if unsafe { IsZoomed(hwnd) }.as_bool() || unsafe { IsIconic(hwnd) }.as_bool() {
+            let _ = unsafe { ShowWindow(hwnd, SW_RESTORE) };
+        }
+        unsafe {
+            SetWindowPos(
+                hwnd,
+                None,
+                frame.x,
+                frame.y,
+                frame.width as i32,
+                frame.height as i32,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            )
+        }
+        .map_err(|e| WindowError::Io(format!("SetWindowPos failed: {}", e)))
+    }
+
+    /// WindowFromPoint 返回最深的子窗口，取其顶层窗口
+    pub fn window_at(x: i32, y: i32) -> Result<WindowInfo, WindowError> {
+        let child = unsafe { WindowFromPoint(POINT { x, y }) };
+        if child.is_invalid() {
+            return Err(WindowError::NotFound(format!("no window at ({}, {})", x, y)));
+        }
+        let hwnd = unsafe { GetAncestor(child, GA_ROOT) };
+        window_info(if hwnd.is_invalid() { child } else { hwnd })
+    }
+
+    pub fn control(id: u64, action: WindowAction) -> Result<(), WindowError> {
+        use windows::Win32::Foundation::WPARAM;
+        use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, SW_MAXIMIZE, SW_MINIMIZE, WM_CLOSE};
+
+        let hwnd = hwnd(id)?;
+        // ShowWindow 的返回值是窗口先前是否可见，不表示成败
+        let command = match action {
+            WindowAction::Minimize => SW_MINIMIZE,
+            WindowAction::Maximize => SW_MAXIMIZE,
+            WindowAction::Restore => SW_RESTORE,
+            WindowAction::Close => {
+                return unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) }
+                    .map_err(|e| WindowError::Io(format!("PostMessageW(WM_CLOSE) failed: {}", e)));
+            }
+        };
+        let _ = unsafe { ShowWindow(hwnd, command) };
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    pub fn list_windows() -> Result<Vec<WindowInfo>, WindowError> {
+        Err(WindowError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn focused_window() -> Result<WindowInfo, WindowError> {
+        Err(WindowError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn set_frame(_id: u64, _frame: Rect) -> Result<(), WindowError> {
+        Err(WindowError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn window_at(_x: i32, _y: i32) -> Result<WindowInfo, WindowError> {
+        Err(WindowError::UnsupportedPlatform(std::env::consts::OS))
+    }
+
+    pub fn control(_id: u64, _action: WindowAction) -> Result<(), WindowError> {
+        Err(WindowError::UnsupportedPlatform(std::env::consts::OS))
+    }
+}
@@ -0,0 +1,308 @@
+//! 桥接传输：拆分权限部署。
+//!
+//! - `bridge-host`：运行在有界面访问权限的机器上，监听 TCP，接受携带令牌认证的连接，
+//!   执行转发来的 JSON-RPC 请求，并把服务器通知（进度、资源更新等）推送给所有已认证连接。
+//! - `bridge`：运行在沙箱/容器中，对 LLM 宿主表现为普通的 stdio MCP 服务器，
+//!   自身不需要任何系统权限，只把消息原样转发给 bridge-host。
+//!
+//! 线路协议为逐行 JSON-RPC。连接建立后第一条消息必须是
+//! `{"jsonrpc":"2.0","id":0,"method":"bridge/auth","params":{"token":"..."}}`，认证失败即断开。
+//! 令牌通过 `--token` 或环境变量 IRIS_BRIDGE_TOKEN 提供；未配置令牌时拒绝启动。
+//!
+//! 连接不加密，令牌以明文传输：默认只允许监听回环地址，监听其他地址须显式传入
+//! `--allow-remote`，并应置于 SSH 隧道、VPN 或 TLS 隧道内。连接数与同时执行的请求数有上限。
+
+use super::{handle_message, notify, progress};
+use crate::monitor::key_mouse;
+use serde_json::{json, Value};
+use std::env;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// bridge-host 默认监听地址（仅本机；容器访问时需显式指定）
+pub const DEFAULT_BRIDGE_ADDR: &str = "127.0.0.1:7332";
+
+/// 认证消息的方法名
+const AUTH_METHOD: &str = "bridge/auth";
+
+/// 等待认证消息的超时
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 认证消息的长度上限（字节）
+const MAX_AUTH_LINE_BYTES: usize = 4 * 1024;
+
+/// 认证后单条消息的长度上限（字节）
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// 同时保持的连接数上限（含尚未认证的连接）
+const MAX_CONNECTIONS: usize = 16;
+
+/// 所有连接合计同时执行的请求数上限；超出时直接返回错误
+const MAX_IN_FLIGHT: usize = 32;
+
+static OPEN_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// 计数器中占用的一个名额，释放时归还
+struct Slot(&'static AtomicUsize);
+
+impl Slot {
+    fn acquire(counter: &'static AtomicUsize, max: usize) -> Option<Slot> {
+        counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max).then_some(n + 1))
+            .ok()
+            .map(|_| Slot(counter))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+type SharedStream = Arc<Mutex<TcpStream>>;
+
+/// 已认证的连接，用于广播通知
+fn connections() -> &'static Mutex<Vec<SharedStream>> {
+    static CONNECTIONS: OnceLock<Mutex<Vec<SharedStream>>> = OnceLock::new();
+    CONNECTIONS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// 读取令牌：命令行参数优先，其次环境变量 IRIS_BRIDGE_TOKEN
+pub fn resolve_token(arg: Option<String>) -> io::Result<String> {
+    arg.or_else(|| env::var("IRIS_BRIDGE_TOKEN").ok())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "bridge requires a token (--token or IRIS_BRIDGE_TOKEN)",
+            )
+        })
+}
+
+/// 逐字节比较，耗时与不匹配位置无关
+pub(crate) fn token_matches(expected: &str, given: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), given.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 读取一行（不含换行符）；连接关闭时返回 None，超过 `max` 字节时返回错误
+fn read_line_limited(reader: &mut impl BufRead, max: usize) -> io::Result<Option<String>> {
+    let mut line = Vec::new();
+    let read = reader.take(max as u64 + 1).read_until(b'\n', &mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.ends_with(b"\n") {
+        line.pop();
+    } else if read > max {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("message longer than {} bytes", max)));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_line(stream: &SharedStream, message: &Value) -> io::Result<()> {
+    let mut stream = stream.lock().unwrap_or_else(|e| e.into_inner());
+    writeln!(stream, "{}", message)?;
+    stream.flush()
+}
+
+// ============================================================
+// bridge-host：执行端
+// ============================================================
+
+pub fn run_bridge_host(addr: &str, token: String, allow_remote: bool) -> io::Result<()> {
+    if !allow_remote && !addr.to_socket_addrs()?.all(|a| a.ip().is_loopback()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "refusing to listen on non-loopback address {}: the bridge is unencrypted; \
+                 pass --allow-remote and tunnel it over SSH, a VPN or TLS",
+                addr
+            ),
+        ));
+    }
+    let listener = TcpListener::bind(addr)?;
+    eprintln!("Iris MCP Bridge Host 启动中（{}）...", listener.local_addr()?);
+
+    key_mouse::initialize();
+
+    // 通知广播给所有已认证连接；写入失败的连接视为已断开
+    notify::set_notifier(|notification| {
+        connections()
+            .lock()
+            .unwrap()
+            .retain(|stream| write_line(stream, &notification).is_ok());
+    });
+
+    let token = Arc::new(token);
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let Some(slot) = Slot::acquire(&OPEN_CONNECTIONS, MAX_CONNECTIONS) else {
+                    eprintln!("[bridge] too many connections, rejecting {:?}", stream.peer_addr());
+                    continue;
+                };
+                let token = token.clone();
+                thread::spawn(move || {
+                    let _slot = slot;
+                    let peer = stream
+                        .peer_addr()
+                        .map(|a| a.to_string())
+                        .unwrap_or_default();
+                    if let Err(e) = handle_bridge_connection(stream, &token) {
+                        eprintln!("[bridge] connection {} error: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("[bridge] accept failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn handle_bridge_connection(stream: TcpStream, token: &str) -> io::Result<()> {
+    let peer = stream.peer_addr()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let shared: SharedStream = Arc::new(Mutex::new(stream));
+
+    // 认证
+    shared.lock().unwrap().set_read_timeout(Some(AUTH_TIMEOUT))?;
+    let line = read_line_limited(&mut reader, MAX_AUTH_LINE_BYTES)?.unwrap_or_default();
+    let auth: Value = serde_json::from_str(line.trim()).unwrap_or(Value::Null);
+    let id = auth.get("id").cloned().filter(|v| !v.is_null()).unwrap_or(json!(0));
+    let authenticated = auth["method"] == AUTH_METHOD
+        && auth["params"]["token"].as_str().is_some_and(|t| token_matches(token, t));
+    if !authenticated {
+        eprintln!("[bridge] authentication failed from {}", peer);
+        return write_line(
+            &shared,
+            &json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32001, "message": "Authentication failed" }
+            }),
+        );
+    }
+    shared.lock().unwrap().set_read_timeout(None)?;
+    write_line(
+        &shared,
+        &json!({ "jsonrpc": "2.0", "id": id, "result": { "authenticated": true } }),
+    )?;
+    connections().lock().unwrap().push(shared.clone());
+    eprintln!("[bridge] {} authenticated", peer);
+
+    // 每条请求在独立线程执行，使取消通知与只读工具不必排在长时间工具之后
+    while let Some(line) = read_line_limited(&mut reader, MAX_MESSAGE_BYTES)? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let msg = serde_json::from_str::<Value>(&line).unwrap_or(Value::Null);
+        if msg["method"] == "notifications/cancelled" {
+            progress::handle_cancelled(msg.get("params").cloned());
+            continue;
+        }
+        let Some(slot) = Slot::acquire(&IN_FLIGHT, MAX_IN_FLIGHT) else {
+            // 通知没有 id，无需回复
+            if let Some(id) = msg.get("id").filter(|id| !id.is_null()) {
+                write_line(
+                    &shared,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32001, "message": "Too many concurrent requests" }
+                    }),
+                )?;
+            }
+            continue;
+        };
+        let shared = shared.clone();
+        thread::spawn(move || {
+            let _slot = slot;
+            let response = handle_message(&line);
+            if let Ok(response) = serde_json::to_value(&response) {
+                let _ = write_line(&shared, &response);
+            }
+        });
+    }
+
+    connections()
+        .lock()
+        .unwrap()
+        .retain(|stream| !Arc::ptr_eq(stream, &shared));
+    eprintln!("[bridge] {} disconnected", peer);
+    Ok(())
+}
+
+// ============================================================
+// bridge：沙箱端（stdio ⇄ TCP 转发）
+// ============================================================
+
+pub fn run_bridge_client(addr: &str, token: &str) -> io::Result<()> {
+    eprintln!("Iris MCP Bridge 连接 {} ...", addr);
+    let stream = TcpStream::connect(addr)?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    writeln!(
+        writer,
+        "{}",
+        json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": AUTH_METHOD,
+            "params": { "token": token }
+        })
+    )?;
+    writer.flush()?;
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let reply: Value = serde_json::from_str(line.trim()).unwrap_or(Value::Null);
+    if reply["result"]["authenticated"] != true {
+        let message = reply["error"]["message"]
+            .as_str()
+            .unwrap_or("bridge host closed the connection");
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, message.to_string()));
+    }
+    eprintln!("Iris MCP Bridge 已连接");
+
+    // host → stdout：响应与通知原样输出
+    let downstream = thread::Builder::new()
+        .name("bridge-downstream".to_string())
+        .spawn(move || -> io::Result<()> {
+            for line in reader.lines() {
+                let line = line?;
+                let mut stdout = io::stdout().lock();
+                writeln!(stdout, "{}", line)?;
+                stdout.flush()?;
+            }
+            Ok(())
+        })?;
+
+    // stdin → host
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(writer, "{}", line)?;
+        writer.flush()?;
+    }
+
+    // stdin 关闭后等待已发出请求的响应，直到 host 断开
+    let _ = writer.shutdown(std::net::Shutdown::Write);
+    downstream
+        .join()
+        .unwrap_or_else(|_| Err(io::Error::other("bridge downstream thread panicked")))
+}
@@ -0,0 +1,115 @@
+//! 混沌测试模式：模拟真实桌面环境的不稳定性，用于验证 Agent 的重试与校验逻辑。
+//!
+//! 通过环境变量开启（均未设置时完全不生效）：
+//! - `IRIS_CHAOS_DELAY_MS`：每次工具调用前随机延迟 0..=N 毫秒
+//! - `IRIS_CHAOS_DROP_CLICK_PCT`：以该百分比（0–100）丢弃点击，仅移动光标
+//! - `IRIS_CHAOS_JITTER_PX`：鼠标目标坐标在 ±N 像素内随机偏移
+//! - `IRIS_CHAOS_SEED`：随机种子，便于复现；缺省时取当前时间
+
+use std::env;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug)]
+struct Chaos {
+    delay_ms: u64,
+    drop_click_pct: u32,
+    jitter_px: i32,
+    /// splitmix64 状态
+    state: u64,
+}
+
+impl Chaos {
+    fn from_env() -> Option<Self> {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|v| v.trim().parse().ok())
+        }
+
+        let delay_ms = var::<u64>("IRIS_CHAOS_DELAY_MS").unwrap_or(0);
+        let drop_click_pct = var::<u32>("IRIS_CHAOS_DROP_CLICK_PCT").unwrap_or(0).min(100);
+        let jitter_px = var::<i32>("IRIS_CHAOS_JITTER_PX").unwrap_or(0).max(0);
+        if delay_ms == 0 && drop_click_pct == 0 && jitter_px == 0 {
+            return None;
+        }
+
+        let seed = var::<u64>("IRIS_CHAOS_SEED").unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+        });
+        eprintln!(
+            "[chaos] enabled: delay<={}ms drop_click={}% jitter=±{}px seed={}",
+            delay_ms, drop_click_pct, jitter_px, seed
+        );
+        Some(Chaos {
+            delay_ms,
+            drop_click_pct,
+            jitter_px,
+            state: seed,
+        })
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 0..=max 的均匀随机数
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % (max + 1)
+    }
+}
+
+fn chaos() -> Option<&'static Mutex<Chaos>> {
+    static CHAOS: OnceLock<Option<Mutex<Chaos>>> = OnceLock::new();
+    CHAOS.get_or_init(|| Chaos::from_env().map(Mutex::new)).as_ref()
+}
+
+/// 工具调用前的随机延迟
+pub fn delay(tool: &str) {
+    let Some(chaos) = chaos() else { return };
+    let ms = {
+        let mut c = chaos.lock().unwrap();
+        if c.delay_ms == 0 {
+            return;
+        }
+        let max = c.delay_ms;
+        c.below(max)
+    };
+    if ms > 0 {
+        eprintln!("[chaos] delaying {} by {}ms", tool, ms);
+        thread::sleep(Duration::from_millis(ms));
+    }
+}
+
+/// 本次点击是否应被丢弃
+pub fn drop_click() -> bool {
+    let Some(chaos) = chaos() else { return false };
+    let mut c = chaos.lock().unwrap();
+    if c.drop_click_pct == 0 {
+        return false;
+    }
+    let dropped = c.below(99) < c.drop_click_pct as u64;
+    if dropped {
+        eprintln!("[chaos] dropping click");
+    }
+    dropped
+}
+
+/// 对目标坐标施加随机偏移
+pub fn jitter(x: i32, y: i32) -> (i32, i32) {
+    let Some(chaos) = chaos() else { return (x, y) };
+    let mut c = chaos.lock().unwrap();
+    if c.jitter_px == 0 {
+        return (x, y);
+    }
+    let span = (c.jitter_px * 2) as u64;
+    let dx = c.below(span) as i32 - c.jitter_px;
+    let dy = c.below(span) as i32 - c.jitter_px;
+    (x + dx, y + dy)
+}
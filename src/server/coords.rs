@@ -0,0 +1,436 @@
+//! 工具参数中坐标的解析与转换：把请求里的坐标统一换算为鼠标操作使用的全局坐标。
+//!
+//! 支持的转换：
+//! - `display`：坐标相对于指定显示器左上角
+//! - `coordinate_space: "normalized"`：坐标为 0–1 的比例，按显示器实际分辨率换算（1.0 为最后一个像素）
+//! - `coordinate_space: "physical"`：坐标为物理像素（如截图中的位置），按显示器缩放比例换算为逻辑坐标
+//! - `area: "work_area"`：坐标相对于显示器可用工作区（避开菜单栏/刘海、Dock、任务栏）
+//! - `window_id`：坐标相对于窗口左上角（调用时查询窗口位置），normalized 按窗口尺寸换算
+//!
+//! 换算后的坐标会按当前显示器几何校验，超出范围时按 `out_of_bounds` 策略
+//! （默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject）拒绝或钳制。
+
+use super::chaos;
+use super::jsonrpc::JsonRpcError;
+use crate::monitor::display::{self, DisplayInfo, Rect};
+use crate::monitor::scaling;
+use crate::operator::source;
+use crate::operator::window::{self, WindowError};
+use enigo::{Enigo, Mouse};
+use serde_json::Value;
+use std::env;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateSpace {
+    /// 像素/逻辑坐标（默认）
+    Pixels,
+    /// 0.0–1.0 的比例坐标
+    Normalized,
+    /// Hi-DPI 显示器上的物理像素坐标
+    Physical,
+}
+
+/// 坐标超出显示器范围时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsPolicy {
+    /// 返回错误（默认）
+    Reject,
+    /// 钳制到最近的有效位置
+    Clamp,
+}
+
+impl BoundsPolicy {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "reject" => Some(BoundsPolicy::Reject),
+            "clamp" => Some(BoundsPolicy::Clamp),
+            _ => None,
+        }
+    }
+}
+
+/// 一次工具调用中坐标换算所需的上下文
+#[derive(Debug, Clone)]
+pub struct CoordContext {
+    pub display: Option<DisplayInfo>,
+    pub space: CoordinateSpace,
+    pub policy: BoundsPolicy,
+    /// 坐标原点在全局坐标系中的位置（None 表示全局坐标）
+    origin: Option<(i32, i32)>,
+    /// 比例坐标换算使用的参考尺寸（显示器、工作区或主显示器尺寸）
+    extent: (f64, f64),
+    /// 换算后坐标允许落入的区域（任一矩形内即有效）
+    bounds: Vec<Rect>,
+}
+
+/// 解析可选的 `display` 参数（显示器序号，0 为主显示器）
+pub fn parse_display(arguments: &Value) -> Result<Option<DisplayInfo>, JsonRpcError> {
+    let Some(index) = arguments.get("display").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    let index = index.as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Invalid display: expected a non-negative integer".to_string(),
+        data: None,
+    })?;
+    display::get_display(index as usize)
+        .map(Some)
+        .map_err(|e| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid display: {}", e),
+            data: None,
+        })
+}
+
+fn parse_space(arguments: &Value) -> Result<CoordinateSpace, JsonRpcError> {
+    match arguments["coordinate_space"].as_str() {
+        None | Some("pixels") => Ok(CoordinateSpace::Pixels),
+        Some("normalized") => Ok(CoordinateSpace::Normalized),
+        Some("physical") => Ok(CoordinateSpace::Physical),
+        Some(other) => Err(JsonRpcError {
+            code: -32602,
+            message: format!("Invalid coordinate_space: {}", other),
+            data: None,
+        }),
+    }
+}
+
+fn main_display_extent() -> Result<(f64, f64), JsonRpcError> {
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+    let (w, h) = enigo.main_display().map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to get display size: {}", e),
+        data: None,
+    })?;
+    Ok((w as f64, h as f64))
+}
+
+/// 默认越界策略。
+/// 读取环境变量 IRIS_OUT_OF_BOUNDS（reject / clamp），未设置或无效时为 reject。
+fn default_bounds_policy() -> BoundsPolicy {
+    static POLICY: OnceLock<BoundsPolicy> = OnceLock::new();
+    *POLICY.get_or_init(|| {
+        env::var("IRIS_OUT_OF_BOUNDS")
+            .ok()
+            .and_then(|v| BoundsPolicy::parse(v.trim()))
+            .unwrap_or(BoundsPolicy::Reject)
+    })
+}
+
+fn parse_policy(arguments: &Value) -> Result<BoundsPolicy, JsonRpcError> {
+    match arguments["out_of_bounds"].as_str() {
+        None => Ok(default_bounds_policy()),
+        Some(s) => BoundsPolicy::parse(s).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid out_of_bounds: {}", s),
+            data: None,
+        }),
+    }
+}
+
+/// 全局坐标的有效范围：所有显示器区域；无法枚举显示器时退化为主显示器尺寸
+fn global_bounds() -> Result<Vec<Rect>, JsonRpcError> {
+    if let Ok(displays) = display::list_displays()
+        && !displays.is_empty()
+    {
+        return Ok(displays.iter().map(|d| d.bounds()).collect());
+    }
+    let (w, h) = main_display_extent()?;
+    Ok(vec![Rect { x: 0, y: 0, width: w as u32, height: h as u32 }])
+}
+
+fn parse_work_area(arguments: &Value) -> Result<bool, JsonRpcError> {
+    match arguments["area"].as_str() {
+        None | Some("display") => Ok(false),
+        Some("work_area") => Ok(true),
+        Some(other) => Err(JsonRpcError {
+            code: -32602,
+            message: format!("Invalid area: {}", other),
+            data: None,
+        }),
+    }
+}
+
+/// 解析可选的 `window_id` 参数，返回窗口当前的全局矩形
+pub fn parse_window_frame(arguments: &Value) -> Result<Option<Rect>, JsonRpcError> {
+    let Some(id) = arguments.get("window_id").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    let id = id.as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Invalid window_id: expected a non-negative integer".to_string(),
+        data: None,
+    })?;
+    window::get_window(id).map(|w| Some(w.frame)).map_err(|e| JsonRpcError {
+        code: if matches!(e, WindowError::NotFound(_)) { -32602 } else { -32001 },
+        message: format!("Invalid window_id: {}", e),
+        data: None,
+    })
+}
+
+/// 窗口坐标的上下文：原点为窗口左上角，有效范围为窗口在各显示器上的可见部分
+fn window_context(
+    frame: Rect,
+    space: CoordinateSpace,
+    policy: BoundsPolicy,
+) -> Result<CoordContext, JsonRpcError> {
+    let displays = display::list_displays().unwrap_or_default();
+    let bounds: Vec<Rect> = if displays.is_empty() {
+        vec![frame]
+    } else {
+        displays.iter().filter_map(|d| frame.intersect(&d.bounds())).collect()
+    };
+    if bounds.is_empty() {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!(
+                "Invalid window_id: window at ({}, {}) {}x{} is not on any display",
+                frame.x, frame.y, frame.width, frame.height
+            ),
+            data: None,
+        });
+    }
+    // 物理坐标按窗口中心所在显示器的缩放比例换算
+    let (cx, cy) = (frame.x + (frame.width / 2) as i32, frame.y + (frame.height / 2) as i32);
+    let display = displays.into_iter().find(|d| d.contains(cx, cy));
+    Ok(CoordContext {
+        display,
+        space,
+        policy,
+        origin: Some((frame.x, frame.y)),
+        extent: (frame.width as f64, frame.height as f64),
+        bounds,
+    })
+}
+
+/// 从工具参数中解析坐标上下文（`display`、`coordinate_space`、`area` 与 `window_id`）
+pub fn parse_context(arguments: &Value) -> Result<CoordContext, JsonRpcError> {
+    let display = parse_display(arguments)?;
+    let space = parse_space(arguments)?;
+    let policy = parse_policy(arguments)?;
+
+    if let Some(frame) = parse_window_frame(arguments)? {
+        if display.is_some() || parse_work_area(arguments)? {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: "window_id cannot be combined with display or area".to_string(),
+                data: None,
+            });
+        }
+        return window_context(frame, space, policy);
+    }
+
+    if parse_work_area(arguments)? {
+        // 未指定显示器时以主显示器的工作区为准
+        let target = match display {
+            Some(d) => d,
+            None => display::get_display(0).map_err(|e| JsonRpcError {
+                code: -32603,
+                message: format!("Failed to get work area: {}", e),
+                data: None,
+            })?,
+        };
+        let area = target.usable_area();
+        return Ok(CoordContext {
+            display: Some(target),
+            space,
+            policy,
+            origin: Some((area.x, area.y)),
+            extent: (area.width as f64, area.height as f64),
+            bounds: vec![area],
+        });
+    }
+
+    let (origin, extent) = match (&display, space) {
+        (Some(d), _) => (Some((d.x, d.y)), (d.width as f64, d.height as f64)),
+        (None, CoordinateSpace::Normalized) => (None, main_display_extent()?),
+        (None, _) => (None, (0.0, 0.0)),
+    };
+    let bounds = match &display {
+        Some(d) => vec![d.bounds()],
+        None => global_bounds()?,
+    };
+    Ok(CoordContext { display, space, policy, origin, extent, bounds })
+}
+
+impl CoordContext {
+    /// 将请求中的坐标换算为全局坐标
+    pub fn to_global(&self, x: f64, y: f64) -> (i32, i32) {
+        let (x, y) = match self.space {
+            CoordinateSpace::Pixels => (x.round() as i32, y.round() as i32),
+            CoordinateSpace::Physical => {
+                let scale = match (&self.display, self.origin) {
+                    (Some(d), _) => d.scale,
+                    // 全局物理坐标：按所在显示器换算
+                    (None, None) => return scaling::physical_to_logical(x, y),
+                    (None, Some(_)) => 1.0,
+                };
+                let scale = if scale > 0.0 { scale } else { 1.0 };
+                ((x / scale).round() as i32, (y / scale).round() as i32)
+            }
+            // 按 extent - 1 换算：1.0 对应最后一个像素而不是区域外的下一个像素
+            CoordinateSpace::Normalized => (
+                (x * (self.extent.0 - 1.0).max(0.0)).round() as i32,
+                (y * (self.extent.1 - 1.0).max(0.0)).round() as i32,
+            ),
+        };
+        match self.origin {
+            Some((ox, oy)) => (ox + x, oy + y),
+            None => (x, y),
+        }
+    }
+
+    /// 从 `value[x_key]` / `value[y_key]` 读取坐标（缺失时报 "Missing <key>"）并换算为全局坐标
+    pub fn point(&self, value: &Value, x_key: &str, y_key: &str) -> Result<(i32, i32), JsonRpcError> {
+        let x = self.coordinate(value, x_key)?;
+        let y = self.coordinate(value, y_key)?;
+        let (gx, gy) = self.to_global(x, y);
+        let (gx, gy) = self.check_bounds(gx, gy, x_key, y_key)?;
+        Ok(self.perturb(gx, gy))
+    }
+
+    /// 混沌模式下的坐标扰动，结果仍限制在原坐标所在区域内
+    fn perturb(&self, x: i32, y: i32) -> (i32, i32) {
+        let (jx, jy) = chaos::jitter(x, y);
+        match self.bounds.iter().find(|r| r.contains(x, y)) {
+            Some(r) => r.clamp(jx, jy),
+            None => (jx, jy),
+        }
+    }
+
+    /// 按越界策略校验全局坐标：在任一有效区域内则原样返回，否则拒绝或钳制到最近区域
+    fn check_bounds(&self, x: i32, y: i32, x_key: &str, y_key: &str) -> Result<(i32, i32), JsonRpcError> {
+        if self.bounds.is_empty() || self.bounds.iter().any(|r| r.contains(x, y)) {
+            return Ok((x, y));
+        }
+        match self.policy {
+            BoundsPolicy::Clamp => Ok(self
+                .bounds
+                .iter()
+                .map(|r| r.clamp(x, y))
+                .min_by_key(|(cx, cy)| {
+                    let (dx, dy) = ((cx - x) as i64, (cy - y) as i64);
+                    dx * dx + dy * dy
+                })
+                .unwrap_or((x, y))),
+            BoundsPolicy::Reject => {
+                let areas: Vec<String> = self
+                    .bounds
+                    .iter()
+                    .map(|r| format!("({}, {}) {}x{}", r.x, r.y, r.width, r.height))
+                    .collect();
+                Err(JsonRpcError {
+                    code: -32602,
+                    message: format!(
+                        "Out of bounds {}/{}: ({}, {}) is outside {}; pass out_of_bounds=\"clamp\" to clamp instead",
+                        x_key,
+                        y_key,
+                        x,
+                        y,
+                        areas.join(", ")
+                    ),
+                    data: None,
+                })
+            }
+        }
+    }
+
+    fn coordinate(&self, value: &Value, key: &str) -> Result<f64, JsonRpcError> {
+        let v = value[key].as_f64().ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Missing {}", key),
+            data: None,
+        })?;
+        if self.space == CoordinateSpace::Normalized && !(0.0..=1.0).contains(&v) {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid {}: normalized coordinates must be within 0-1", key),
+                data: None,
+            });
+        }
+        Ok(v)
+    }
+}
+
+/// 读取工具参数中的 `x`/`y` 并按 `display` / `coordinate_space` / `area` 参数换算
+pub fn resolve_point(arguments: &Value) -> Result<(i32, i32), JsonRpcError> {
+    parse_context(arguments)?.point(arguments, "x", "y")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 以 (100, 50) 为原点、1920x1080 的区域，不依赖真实显示器
+    fn context(space: CoordinateSpace, policy: BoundsPolicy) -> CoordContext {
+        CoordContext {
+            display: None,
+            space,
+            policy,
+            origin: Some((100, 50)),
+            extent: (1920.0, 1080.0),
+            bounds: vec![Rect { x: 100, y: 50, width: 1920, height: 1080 }],
+        }
+    }
+
+    #[test]
+    fn normalized_corners_map_to_first_and_last_pixel() {
+        let ctx = context(CoordinateSpace::Normalized, BoundsPolicy::Reject);
+        assert_eq!(ctx.point(&json!({"x": 0.0, "y": 0.0}), "x", "y").unwrap(), (100, 50));
+        assert_eq!(ctx.point(&json!({"x": 1.0, "y": 1.0}), "x", "y").unwrap(), (100 + 1919, 50 + 1079));
+        assert_eq!(ctx.point(&json!({"x": 1.0, "y": 0.0}), "x", "y").unwrap(), (100 + 1919, 50));
+    }
+
+    #[test]
+    fn normalized_rejects_values_outside_unit_range() {
+        let ctx = context(CoordinateSpace::Normalized, BoundsPolicy::Clamp);
+        for point in [json!({"x": 1.01, "y": 0.5}), json!({"x": 0.5, "y": -0.1})] {
+            let err = ctx.point(&point, "x", "y").unwrap_err();
+            assert_eq!(err.code, -32602);
+            assert!(err.message.contains("within 0-1"), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn pixels_out_of_bounds_are_rejected() {
+        let ctx = context(CoordinateSpace::Pixels, BoundsPolicy::Reject);
+        // 区域右下角之外的第一个像素
+        let err = ctx.point(&json!({"x": 1920, "y": 0}), "x", "y").unwrap_err();
+        assert_eq!(err.code, -32602);
+        assert!(err.message.starts_with("Out of bounds x/y: (2020, 50)"), "{}", err.message);
+        assert!(ctx.point(&json!({"x": -1, "y": 10}), "x", "y").is_err());
+        assert_eq!(ctx.point(&json!({"x": 1919, "y": 1079}), "x", "y").unwrap(), (2019, 1129));
+    }
+
+    #[test]
+    fn pixels_out_of_bounds_are_clamped_to_nearest_edge() {
+        let ctx = context(CoordinateSpace::Pixels, BoundsPolicy::Clamp);
+        assert_eq!(ctx.point(&json!({"x": 5000, "y": 5000}), "x", "y").unwrap(), (2019, 1129));
+        assert_eq!(ctx.point(&json!({"x": -30, "y": 500}), "x", "y").unwrap(), (100, 550));
+    }
+
+    #[test]
+    fn clamp_picks_the_closest_of_several_areas() {
+        let mut ctx = context(CoordinateSpace::Pixels, BoundsPolicy::Clamp);
+        ctx.origin = None;
+        ctx.bounds = vec![
+            Rect { x: 0, y: 0, width: 1000, height: 800 },
+            Rect { x: 1000, y: 0, width: 1000, height: 800 },
+        ];
+        assert_eq!(ctx.point(&json!({"x": 1500, "y": 900}), "x", "y").unwrap(), (1500, 799));
+        assert_eq!(ctx.point(&json!({"x": 2500, "y": 10}), "x", "y").unwrap(), (1999, 10));
+    }
+
+    #[test]
+    fn missing_coordinate_is_reported() {
+        let ctx = context(CoordinateSpace::Pixels, BoundsPolicy::Reject);
+        let err = ctx.point(&json!({"x": 10}), "x", "y").unwrap_err();
+        assert_eq!(err.message, "Missing y");
+    }
+}
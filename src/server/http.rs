@@ -0,0 +1,156 @@
+//! HTTP 传输：在本地端口上接收 `POST` 的 JSON-RPC 请求，每个连接处理一条消息。
+//! 供后台服务（登录项 / systemd 用户服务 / 计划任务）常驻运行时使用。
+//!
+//! 设置令牌（`--token` 或 IRIS_HTTP_TOKEN）后请求须带 `Authorization: Bearer <令牌>`；
+//! 未设置令牌时只允许监听本机回环地址。带 `Origin` 头的请求（来自浏览器页面）与
+//! Content-Type 不是 `application/json` 的请求一律拒绝，防止网页跨站调用本地服务。
+//!
+//! HTTP 传输没有推送通道：`resources/subscribe` 与 monitor_subscribe 会被拒绝，进度通知被丢弃。
+
+use super::bridge::token_matches;
+use crate::monitor::key_mouse;
+use std::env;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// 默认监听地址（仅本机）
+pub const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:7331";
+
+/// 单个请求体的大小上限（字节）
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// 请求行与单个请求头的长度上限（字节）
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// 请求行与全部请求头的总长度上限（字节）
+const MAX_HEADER_BYTES: usize = 32 * 1024;
+
+/// 连接读写超时，避免不发完请求或不读响应的连接一直占用线程
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 读取令牌：命令行参数优先，其次环境变量 IRIS_HTTP_TOKEN；均未设置时为 None
+pub fn resolve_token(arg: Option<String>) -> Option<String> {
+    arg.or_else(|| env::var("IRIS_HTTP_TOKEN").ok()).filter(|t| !t.is_empty())
+}
+
+pub fn run_http_server(addr: &str, token: Option<String>) -> io::Result<()> {
+    if token.is_none() && !addr.to_socket_addrs()?.all(|a| a.ip().is_loopback()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("refusing to listen on non-loopback address {} without a token (--token or IRIS_HTTP_TOKEN)", addr),
+        ));
+    }
+    let listener = TcpListener::bind(addr)?;
+    eprintln!("Iris MCP Server 启动中（HTTP {}）...", listener.local_addr()?);
+
+    // 常驻模式下不等待 initialize，启动即开始键鼠监控
+    key_mouse::initialize();
+
+    let token: Option<Arc<str>> = token.map(Arc::from);
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let token = token.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, token.as_deref()) {
+                        eprintln!("HTTP connection error: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("HTTP accept failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, token: Option<&str>) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+
+    let mut header_budget = MAX_HEADER_BYTES;
+    let Some(request_line) = read_line_limited(&mut reader, &mut header_budget)? else {
+        return write_response(&mut stream, "431 Request Header Fields Too Large", "");
+    };
+    let method = request_line.split_whitespace().next().unwrap_or_default();
+
+    let mut content_length = None;
+    let mut content_type = None;
+    let mut authorization = None;
+    let mut has_origin = false;
+    loop {
+        let Some(header) = read_line_limited(&mut reader, &mut header_budget)? else {
+            return write_response(&mut stream, "431 Request Header Fields Too Large", "");
+        };
+        if header.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else { continue };
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<usize>().ok();
+        } else if name.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.to_ascii_lowercase());
+        } else if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("origin") {
+            has_origin = true;
+        }
+    }
+
+    if method != "POST" {
+        return write_response(&mut stream, "405 Method Not Allowed", "");
+    }
+    if has_origin {
+        return write_response(&mut stream, "403 Forbidden", "");
+    }
+    if let Some(token) = token {
+        let given = authorization.as_deref().and_then(|a| a.strip_prefix("Bearer ")).map(str::trim);
+        if !given.is_some_and(|t| token_matches(token, t)) {
+            return write_response(&mut stream, "401 Unauthorized", "");
+        }
+    }
+    // 允许 `application/json; charset=utf-8` 等带参数的写法
+    if content_type.as_deref().and_then(|t| t.split(';').next()).map(str::trim) != Some("application/json") {
+        return write_response(&mut stream, "415 Unsupported Media Type", "");
+    }
+    let Some(length) = content_length.filter(|l| *l <= MAX_BODY_BYTES) else {
+        return write_response(&mut stream, "411 Length Required", "");
+    };
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+
+    let response = super::handle_message(body.trim());
+    let response_json = serde_json::to_string(&response)?;
+    write_response(&mut stream, "200 OK", &response_json)
+}
+
+/// 读取一行，长度不超过 `MAX_LINE_BYTES` 与剩余的 `budget`；超出时返回 None。
+/// 连接关闭时返回空字符串
+fn read_line_limited(reader: &mut impl BufRead, budget: &mut usize) -> io::Result<Option<String>> {
+    let limit = MAX_LINE_BYTES.min(*budget);
+    let mut line = Vec::new();
+    let read = reader.take(limit as u64).read_until(b'\n', &mut line)?;
+    if read == limit && !line.ends_with(b"\n") {
+        return Ok(None);
+    }
+    *budget -= read;
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
@@ -1,7 +1,49 @@
 use super::jsonrpc::JsonRpcError;
-use crate::operator::keyboard::{KeyboardController, SystemCommand};
-use enigo::{Direction, Enigo, Key, Settings};
+use super::progress;
+use super::redact;
+use crate::monitor::{accessibility, privacy};
+use crate::operator::{clipboard, held, ime, pacing};
+use crate::operator::pacing::MAX_TYPING_DELAY_MS;
+use crate::operator::keyboard::{KeyStep, KeyboardController, SystemCommand};
+use crate::operator::keys;
+use crate::operator::shortcuts;
+use crate::operator::source;
+use enigo::{Direction, Enigo, Key};
 use serde_json::{json, Value};
+use std::env;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+/// 焦点变化后等待界面更新再查询无障碍信息的毫秒数
+const FOCUS_SETTLE_MS: u64 = 80;
+
+/// type_text 默认每块字符数；每块输入后发送进度并检查取消
+const DEFAULT_TYPE_CHUNK_SIZE: u64 = 200;
+
+/// type_text 逐字符输入的总等待上限（毫秒，缩放前）；执行期间持有独占执行锁
+const MAX_TYPE_DURATION_MS: u64 = 120_000;
+
+/// 剪贴板模式下粘贴后等待目标应用读取剪贴板的毫秒数
+const PASTE_SETTLE_MS: u64 = 150;
+
+/// key_control 的 hold_ms 上限，避免按键被长时间按住
+const MAX_KEY_HOLD_MS: u64 = 60_000;
+
+/// key_repeat 的次数上限
+const MAX_KEY_REPEAT_COUNT: u64 = 1000;
+
+/// key_repeat 默认的按键间隔（毫秒）
+const DEFAULT_KEY_REPEAT_INTERVAL_MS: u64 = 30;
+
+/// key_sequence 的步骤数上限
+const MAX_KEY_SEQUENCE_STEPS: usize = 200;
+
+/// key_sequence 单步延迟上限（毫秒）
+const MAX_KEY_SEQUENCE_DELAY_MS: u64 = 10_000;
+
+/// focus_next / focus_prev 的次数上限
+const MAX_FOCUS_MOVES: u64 = 100;
 
 pub fn handle_type_text(arguments: &Value) -> Result<Value, JsonRpcError> {
     let text = arguments["text"].as_str().ok_or_else(|| JsonRpcError {
@@ -9,27 +51,333 @@ pub fn handle_type_text(arguments: &Value) -> Result<Value, JsonRpcError> {
         message: "Missing text".to_string(),
         data: None,
     })?;
+    let mode = arguments["mode"].as_str().unwrap_or("keys");
+    if mode != "keys" && mode != "clipboard" {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("Invalid mode: {} (expected keys or clipboard)", mode),
+            data: None,
+        });
+    }
+    // 敏感模式：响应与日志中只出现长度和指纹
+    let sensitive = arguments["sensitive"].as_bool().unwrap_or(false);
+    if sensitive && mode == "clipboard" {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: "sensitive text cannot use clipboard mode (clipboard history may record it)".to_string(),
+            data: None,
+        });
+    }
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to initialize: {}", e),
         data: None,
     })?;
     let mut keyboard = KeyboardController::new(enigo);
-    keyboard.type_text(text).map_err(|e| JsonRpcError {
+
+    // 敏感文本注入期间键盘监控只记录事件数量，按键与字符不进入缓冲区、日志与订阅
+    let _privacy_scope = sensitive.then(privacy::sensitive_scope);
+
+    // 可选：输入期间临时关闭输入法，避免按键被候选窗拦截；guard 释放时恢复
+    let ime_guard = if arguments["disable_ime"].as_bool().unwrap_or(false) {
+        Some(ime::disable().map_err(|e| JsonRpcError {
+            code: -32001,
+            message: e.to_string(),
+            data: None,
+        })?)
+    } else {
+        None
+    };
+
+    let result = if mode == "clipboard" {
+        paste_text(&mut keyboard, text)
+    } else {
+        type_keys(&mut keyboard, text, arguments, sensitive)
+    };
+
+    if let Some(guard) = ime_guard
+        && let Err(e) = guard.restore()
+    {
+        eprintln!("[type_text] {}", e);
+    }
+    result
+}
+
+/// 解析逐字符输入的间隔参数：缺省时取输入节奏配置，显式传入时不超过 `MAX_TYPING_DELAY_MS`
+fn typing_delay(arguments: &Value, name: &str, default: u64) -> Result<u64, JsonRpcError> {
+    if arguments[name].is_null() {
+        return Ok(default.min(MAX_TYPING_DELAY_MS));
+    }
+    arguments[name]
+        .as_u64()
+        .filter(|ms| *ms <= MAX_TYPING_DELAY_MS)
+        .ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("{} must be an integer between 0 and {}", name, MAX_TYPING_DELAY_MS),
+            data: None,
+        })
+}
+
+/// 逐块注入按键：每块之后发送进度通知，并在块之间响应取消
+fn type_keys(
+    keyboard: &mut KeyboardController<Enigo>,
+    text: &str,
+    arguments: &Value,
+    sensitive: bool,
+) -> Result<Value, JsonRpcError> {
+    // 未指定时使用全局输入节奏配置的默认字符间隔
+    let profile = pacing::current();
+    let char_delay_ms = typing_delay(arguments, "char_delay_ms", profile.typing_delay_ms)?;
+    let variance_ms = typing_delay(arguments, "char_delay_variance_ms", profile.typing_variance_ms)?;
+    // 按每个字符间隔取浮动上限估算最长耗时
+    let gaps = text.chars().count().saturating_sub(1) as u64;
+    let total_ms = gaps.saturating_mul(char_delay_ms + variance_ms);
+    if total_ms > MAX_TYPE_DURATION_MS {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!(
+                "Typing would take up to {}ms (at most {}ms); shorten the text or the character delay",
+                total_ms, MAX_TYPE_DURATION_MS
+            ),
+            data: None,
+        });
+    }
+
+    let chunk_size = arguments["chunk_size"]
+        .as_u64()
+        .unwrap_or(DEFAULT_TYPE_CHUNK_SIZE)
+        .max(1) as usize;
+
+    // 逐键注入 emoji 等辅助平面字符依赖平台实现，可选择经剪贴板粘贴这些片段。
+    // 剪贴板只能按文本保存与恢复，其中的图片、文件会在恢复时丢失，因此需显式开启；
+    // 敏感文本不经过剪贴板
+    let fallback = !sensitive && arguments["astral_fallback"].as_bool().unwrap_or(false);
+    let segments = if fallback {
+        split_astral(text)
+    } else {
+        vec![(text, false)]
+    };
+    let previous_clipboard = if segments.iter().any(|&(_, astral)| astral) {
+        Some(clipboard::get_text().map_err(|e| JsonRpcError {
+            code: -32001,
+            message: e.to_string(),
+            data: None,
+        })?)
+    } else {
+        None
+    };
+
+    let total = text.chars().count();
+    let mut typed = 0;
+    let mut pasted = 0;
+    let mut cancelled = false;
+    let mut outcome = Ok(());
+    for &(segment, astral) in &segments {
+        if astral {
+            if let Err(e) = paste_via_clipboard(keyboard, segment, false, default_system_modifier()) {
+                outcome = Err(e);
+                break;
+            }
+            // 等目标应用读取剪贴板后再写入下一段
+            thread::sleep(Duration::from_millis(PASTE_SETTLE_MS));
+            let count = segment.chars().count();
+            typed += count;
+            pasted += count;
+            progress::report(typed as u64, Some(total as u64), &format!("已输入 {}/{} 字符", typed, total));
+            cancelled = typed < total && progress::is_cancelled();
+        } else {
+            let offset = typed;
+            let result = keyboard.type_text_chunked(segment, chunk_size, char_delay_ms, variance_ms, |done, _| {
+                let done = offset + done;
+                progress::report(done as u64, Some(total as u64), &format!("已输入 {}/{} 字符", done, total));
+                cancelled = done < total && progress::is_cancelled();
+                !cancelled
+            });
+            match result {
+                Ok(count) => typed += count,
+                Err(e) => {
+                    outcome = Err(JsonRpcError {
+                        code: -32603,
+                        message: format!("Failed to type: {}", e),
+                        data: None,
+                    });
+                    break;
+                }
+            }
+        }
+        if cancelled {
+            break;
+        }
+    }
+    if let Some(Some(previous)) = previous_clipboard
+        && let Err(e) = clipboard::set_text(&previous)
+    {
+        eprintln!("[type_text] failed to restore clipboard: {}", e);
+    }
+    outcome?;
+
+    let summary = if cancelled {
+        format!("输入已取消: 已输入 {}/{} 字符", typed, total)
+    } else if sensitive {
+        format!("已输入敏感文本: {} 字符（指纹 {}）", total, redact::fingerprint(text))
+    } else {
+        format!("已输入文本: {}", text)
+    };
+    let mut structured = json!({
+        "typed_chars": typed,
+        "total_chars": total,
+        "cancelled": cancelled
+    });
+    if pasted > 0 {
+        structured["pasted_chars"] = json!(pasted);
+    }
+    if sensitive {
+        structured["text"] = redact::summary(text);
+    }
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": summary
+        }],
+        "structuredContent": structured
+    }))
+}
+
+/// 将文本切分为连续片段，标记片段是否由辅助平面字符（U+10000 以上，如 emoji）组成。
+/// 零宽连接符、变体选择符与组合用键帽符号归入前一个片段，避免拆开 emoji 序列。
+fn split_astral(text: &str) -> Vec<(&str, bool)> {
+    fn joins_previous(c: char) -> bool {
+        matches!(c, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}')
+    }
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (i, c) in text.char_indices() {
+        let astral = match current {
+            Some(astral) if joins_previous(c) => astral,
+            _ => c as u32 > 0xFFFF,
+        };
+        match current {
+            Some(previous) if previous != astral => {
+                segments.push((&text[start..i], previous));
+                start = i;
+            }
+            _ => {}
+        }
+        current = Some(astral);
+    }
+    if let Some(astral) = current {
+        segments.push((&text[start..], astral));
+    }
+    segments
+}
+
+/// 通过剪贴板粘贴文本，绕过输入法；完成后恢复原剪贴板文本
+fn paste_text(keyboard: &mut KeyboardController<Enigo>, text: &str) -> Result<Value, JsonRpcError> {
+    paste_via_clipboard(keyboard, text, true, default_system_modifier())?;
+
+    let total = text.chars().count();
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!("已通过剪贴板输入文本: {}", text)
+        }],
+        "structuredContent": {
+            "typed_chars": total,
+            "total_chars": total,
+            "cancelled": false
+        }
+    }))
+}
+
+/// 写入剪贴板并发送粘贴快捷键；`restore` 为 true 时在粘贴后恢复原剪贴板文本
+///
+/// 返回原剪贴板文本是否已恢复（原剪贴板为空或不是文本时无法恢复）
+fn paste_via_clipboard(
+    keyboard: &mut KeyboardController<Enigo>,
+    text: &str,
+    restore: bool,
+    modifier: Option<Key>,
+) -> Result<bool, JsonRpcError> {
+    let clipboard_error = |e: clipboard::ClipboardError| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    };
+    let previous = if restore {
+        clipboard::get_text().map_err(clipboard_error)?
+    } else {
+        None
+    };
+    clipboard::set_text(text).map_err(clipboard_error)?;
+
+    let pasted = match modifier {
+        Some(primary) => keyboard.system_command_with(SystemCommand::Paste, primary),
+        None => keyboard.system_command(SystemCommand::Paste),
+    };
+    let mut restored = false;
+    if let Some(previous) = previous {
+        // 等目标应用读取剪贴板后再恢复，过早恢复会粘贴出旧内容
+        thread::sleep(Duration::from_millis(PASTE_SETTLE_MS));
+        match clipboard::set_text(&previous) {
+            Ok(()) => restored = true,
+            Err(e) => eprintln!("[paste] failed to restore clipboard: {}", e),
+        }
+    }
+    pasted.map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to paste: {}", e),
+        data: None,
+    })?;
+    Ok(restored)
+}
+
+/// 经剪贴板粘贴文本：适合大段文本或逐键输入易出错的 Unicode 文本
+pub fn handle_paste_text(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let text = arguments["text"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing text".to_string(),
+        data: None,
+    })?;
+    let restore = arguments["restore_clipboard"].as_bool().unwrap_or(true);
+    let modifier = modifier_argument(arguments)?;
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
         code: -32603,
-        message: format!("Failed to type: {}", e),
+        message: format!("Failed to initialize: {}", e),
         data: None,
     })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    let restored = paste_via_clipboard(&mut keyboard, text, restore, modifier)?;
 
+    let total = text.chars().count();
+    let mut message = format!("已粘贴 {} 个字符", total);
+    if restore {
+        message.push_str(if restored { "，已恢复原剪贴板" } else { "，原剪贴板无文本内容，未恢复" });
+    }
     Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("已输入文本: {}", text)
-        }]
+        "content": [{ "type": "text", "text": message }],
+        "structuredContent": {
+            "pasted_chars": total,
+            "clipboard_restored": restored
+        }
     }))
 }
 
+/// 解析可选的 `modifier` 参数，未提供时使用环境变量配置的默认值
+fn modifier_argument(arguments: &Value) -> Result<Option<Key>, JsonRpcError> {
+    match arguments["modifier"].as_str() {
+        None => Ok(default_system_modifier()),
+        Some(s) => Ok(Some(parse_system_modifier(s).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid modifier: {} (expected ctrl, cmd or alt)", s),
+            data: None,
+        })?)),
+    }
+}
+
 pub fn handle_system_command(arguments: &Value) -> Result<Value, JsonRpcError> {
     let cmd_str = arguments["command"].as_str().ok_or_else(|| JsonRpcError {
         code: -32602,
@@ -44,6 +392,17 @@ pub fn handle_system_command(arguments: &Value) -> Result<Value, JsonRpcError> {
         "undo" => SystemCommand::Undo,
         "save" => SystemCommand::Save,
         "select_all" => SystemCommand::SelectAll,
+        "redo" => SystemCommand::Redo,
+        "find" => SystemCommand::Find,
+        "find_next" => SystemCommand::FindNext,
+        "new_tab" => SystemCommand::NewTab,
+        "close_tab" => SystemCommand::CloseTab,
+        "close_window" => SystemCommand::CloseWindow,
+        "switch_app" => SystemCommand::SwitchApp,
+        "quit_app" => SystemCommand::QuitApp,
+        "refresh" => SystemCommand::Refresh,
+        "zoom_in" => SystemCommand::ZoomIn,
+        "zoom_out" => SystemCommand::ZoomOut,
         _ => return Err(JsonRpcError {
             code: -32602,
             message: format!("Unknown command: {}", cmd_str),
@@ -51,13 +410,19 @@ pub fn handle_system_command(arguments: &Value) -> Result<Value, JsonRpcError> {
         }),
     };
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
+    let modifier = modifier_argument(arguments)?;
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to initialize: {}", e),
         data: None,
     })?;
     let mut keyboard = KeyboardController::new(enigo);
-    keyboard.system_command(command).map_err(|e| JsonRpcError {
+    let result = match modifier {
+        Some(primary) => keyboard.system_command_with(command, primary),
+        None => keyboard.system_command(command),
+    };
+    result.map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to execute command: {}", e),
         data: None,
@@ -77,62 +442,397 @@ pub fn handle_key_control(arguments: &Value) -> Result<Value, JsonRpcError> {
         message: "Missing key".to_string(),
         data: None,
     })?;
-    let direction_str = arguments["direction"].as_str().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing direction".to_string(),
-        data: None,
-    })?;
+    let hold_ms = arguments["hold_ms"].as_u64();
+    if let Some(ms) = hold_ms
+        && ms > MAX_KEY_HOLD_MS
+    {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("hold_ms must be at most {}", MAX_KEY_HOLD_MS),
+            data: None,
+        });
+    }
+
+    // 指定 hold_ms 时 direction 可省略；只能与 click 搭配
+    let direction_str = match (arguments["direction"].as_str(), hold_ms) {
+        (Some(d), _) => d,
+        (None, Some(_)) => "click",
+        (None, None) => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: "Missing direction".to_string(),
+                data: None,
+            })
+        }
+    };
     
-    let direction = match direction_str {
-        "press" => Direction::Press,
-        "release" => Direction::Release,
-        "click" => Direction::Click,
-        _ => return Err(JsonRpcError {
+    let direction = parse_direction(direction_str)?;
+    if hold_ms.is_some() && direction != Direction::Click {
+        return Err(JsonRpcError {
             code: -32602,
-            message: format!("Invalid direction: {}", direction_str),
+            message: "hold_ms can only be used with direction \"click\"".to_string(),
             data: None,
-        }),
+        });
+    }
+
+    let key = parse_key(key_str)?;
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    let result = match hold_ms {
+        Some(ms) => keyboard.key_hold(key, Duration::from_millis(ms)),
+        None => keyboard.key_control(key, direction),
     };
+    result.map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to control key: {}", e),
+        data: None,
+    })?;
 
-    let key = match key_str.to_lowercase().as_str() {
-        "return" | "enter" => Key::Return,
-        "shift" => Key::Shift,
-        "control" | "ctrl" => Key::Control,
-        "alt" | "option" => Key::Alt,
-        "meta" | "command" | "cmd" => Key::Meta,
-        "space" => Key::Space,
-        "tab" => Key::Tab,
-        "escape" | "esc" => Key::Escape,
-        "backspace" => Key::Backspace,
-        "delete" => Key::Delete,
-        "up" | "uparrow" => Key::UpArrow,
-        "down" | "downarrow" => Key::DownArrow,
-        "left" | "leftarrow" => Key::LeftArrow,
-        "right" | "rightarrow" => Key::RightArrow,
-        s if s.len() == 1 => Key::Unicode(s.chars().next().unwrap()),
-        _ => return Err(JsonRpcError {
+    let text = match hold_ms {
+        Some(ms) => format!("已按住按键{} {}ms 后释放", key_str, ms),
+        None => format!("已执行按键{}操作: {}", key_str, direction_str),
+    };
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": text
+        }]
+    }))
+}
+
+fn parse_system_modifier(name: &str) -> Option<Key> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(Key::Control),
+        "cmd" | "command" | "meta" => Some(Key::Meta),
+        "alt" | "option" => Some(Key::Alt),
+        _ => None,
+    }
+}
+
+/// system_command 的默认主修饰键。
+/// 读取环境变量 IRIS_SYSTEM_COMMAND_MODIFIER（ctrl / cmd / alt），未设置或无效时按操作系统选择。
+fn default_system_modifier() -> Option<Key> {
+    static MODIFIER: OnceLock<Option<Key>> = OnceLock::new();
+    *MODIFIER.get_or_init(|| {
+        env::var("IRIS_SYSTEM_COMMAND_MODIFIER")
+            .ok()
+            .and_then(|v| parse_system_modifier(&v))
+    })
+}
+
+fn parse_direction(direction: &str) -> Result<Direction, JsonRpcError> {
+    match direction {
+        "press" => Ok(Direction::Press),
+        "release" => Ok(Direction::Release),
+        "click" => Ok(Direction::Click),
+        _ => Err(JsonRpcError {
             code: -32602,
-            message: format!("Unknown key: {}", key_str),
+            message: format!("Invalid direction: {}", direction),
             data: None,
         }),
-    };
+    }
+}
+
+/// 解析按键名称（见 `operator::keys`）
+fn parse_key(key_str: &str) -> Result<Key, JsonRpcError> {
+    keys::parse(key_str).map_err(|e| JsonRpcError {
+        code: -32602,
+        message: e.to_string(),
+        data: None,
+    })
+}
+
+pub fn handle_key_combo(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let names: Vec<&str> = arguments["keys"]
+        .as_array()
+        .map(|keys| keys.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if names.is_empty() || names.len() != arguments["keys"].as_array().map_or(0, Vec::len) {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: "Missing or invalid keys: expected a non-empty array of key names".to_string(),
+            data: None,
+        });
+    }
+    let keys = names.iter().map(|k| parse_key(k)).collect::<Result<Vec<_>, _>>()?;
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
+    // 可选：注入前检查组合键是否已被系统或其他应用注册为全局快捷键
+    let report = arguments["check_conflicts"]
+        .as_bool()
+        .unwrap_or(false)
+        .then(|| shortcuts::find_conflicts(&names));
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to initialize: {}", e),
         data: None,
     })?;
     let mut keyboard = KeyboardController::new(enigo);
-    keyboard.key_control(key, direction).map_err(|e| JsonRpcError {
+    keyboard.key_combo(&keys).map_err(|e| JsonRpcError {
         code: -32603,
-        message: format!("Failed to control key: {}", e),
+        message: format!("Failed to press key combo: {}", e),
+        data: None,
+    })?;
+
+    let mut text = format!("已按下组合键: {}", names.join("+"));
+    let mut result = json!({});
+    if let Some(report) = report {
+        for conflict in &report.conflicts {
+            text.push_str(&format!(
+                "\n⚠️ 该组合键已被 {} 注册为「{}」，可能被系统拦截，目标应用未必收到按键",
+                conflict.owner, conflict.action
+            ));
+        }
+        if let Some(note) = &report.note {
+            text.push_str(&format!("\n快捷键冲突检测不完整: {}", note));
+        }
+        result["structuredContent"] = json!({ "shortcut_check": report });
+    }
+    result["content"] = json!([{ "type": "text", "text": text }]);
+    Ok(result)
+}
+
+pub fn handle_key_repeat(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let key_str = arguments["key"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing key".to_string(),
+        data: None,
+    })?;
+    let count = arguments["count"].as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing count".to_string(),
+        data: None,
+    })?;
+    if count == 0 || count > MAX_KEY_REPEAT_COUNT {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("count must be between 1 and {}", MAX_KEY_REPEAT_COUNT),
+            data: None,
+        });
+    }
+    let interval_ms = arguments["interval_ms"].as_u64().unwrap_or(DEFAULT_KEY_REPEAT_INTERVAL_MS);
+    if interval_ms > MAX_KEY_SEQUENCE_DELAY_MS {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("interval_ms must be at most {}", MAX_KEY_SEQUENCE_DELAY_MS),
+            data: None,
+        });
+    }
+    let key = parse_key(key_str)?;
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
         data: None,
     })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    keyboard
+        .key_repeat(key, count as u32, Duration::from_millis(interval_ms))
+        .map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to repeat key: {}", e),
+            data: None,
+        })?;
 
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": format!("已执行按键{}操作: {}", key_str, direction_str)
+            "text": format!("已按键 {} {} 次（间隔 {}ms）", key_str, count, interval_ms)
         }]
     }))
 }
+
+pub fn handle_key_sequence(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let raw_steps = arguments["steps"]
+        .as_array()
+        .filter(|steps| !steps.is_empty())
+        .ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: "Missing or invalid steps: expected a non-empty array".to_string(),
+            data: None,
+        })?;
+    if raw_steps.len() > MAX_KEY_SEQUENCE_STEPS {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("Too many steps: {} (max {})", raw_steps.len(), MAX_KEY_SEQUENCE_STEPS),
+            data: None,
+        });
+    }
+
+    // 注入前先校验全部步骤，避免执行到一半才发现参数错误
+    let mut steps = Vec::with_capacity(raw_steps.len());
+    for (i, step) in raw_steps.iter().enumerate() {
+        let invalid = |message: String| JsonRpcError {
+            code: -32602,
+            message: format!("Step {}: {}", i + 1, message),
+            data: None,
+        };
+        let key_str = step["key"].as_str().ok_or_else(|| invalid("missing key".to_string()))?;
+        let key = parse_key(key_str).map_err(|e| invalid(e.message))?;
+        let direction = parse_direction(step["direction"].as_str().unwrap_or("click"))
+            .map_err(|e| invalid(e.message))?;
+        let delay_ms = step["delay_ms"].as_u64().unwrap_or(0);
+        if delay_ms > MAX_KEY_SEQUENCE_DELAY_MS {
+            return Err(invalid(format!("delay_ms must be at most {}", MAX_KEY_SEQUENCE_DELAY_MS)));
+        }
+        steps.push(KeyStep { key, direction, delay_ms });
+    }
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    keyboard.key_sequence(&steps).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to run key sequence: {}", e),
+        data: None,
+    })?;
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!("已执行按键序列: {} 步", steps.len())
+        }]
+    }))
+}
+
+/// 释放所有仍按下的键与鼠标按键
+pub fn handle_release_all_inputs(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let report = held::release_all().map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+
+    let mut text = if report.released.is_empty() && report.failed.is_empty() {
+        "没有按下的键或鼠标按键".to_string()
+    } else {
+        format!("已释放: {}", report.released.join(", "))
+    };
+    if !report.failed.is_empty() {
+        text.push_str(&format!("\n释放失败: {}", report.failed.join(", ")));
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": report
+    }))
+}
+
+/// 查询当前焦点元素，失败时返回错误说明而非中断工具调用
+fn focused_element_json() -> Value {
+    thread::sleep(Duration::from_millis(FOCUS_SETTLE_MS));
+    match accessibility::focused_element() {
+        Ok(info) => json!(info),
+        Err(e) => json!({ "error": e.to_string() }),
+    }
+}
+
+fn focus_result(summary: String, focused: Value) -> Value {
+    let focused_text = serde_json::to_string_pretty(&focused)
+        .unwrap_or_else(|_| focused.to_string());
+    json!({
+        "content": [{
+            "type": "text",
+            "text": format!("{}\n\n当前焦点元素：\n{}", summary, focused_text)
+        }]
+    })
+}
+
+fn handle_move_focus(arguments: &Value, forward: bool) -> Result<Value, JsonRpcError> {
+    let times = match &arguments["times"] {
+        Value::Null => Some(1),
+        value => value.as_u64(),
+    };
+    let Some(times) = times.filter(|t| (1..=MAX_FOCUS_MOVES).contains(t)) else {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("times must be an integer between 1 and {}", MAX_FOCUS_MOVES),
+            data: None,
+        });
+    };
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    keyboard.move_focus(forward, times as u32).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to move focus: {}", e),
+        data: None,
+    })?;
+
+    let key_label = if forward { "Tab" } else { "Shift+Tab" };
+    Ok(focus_result(
+        format!("已按 {} {} 次", key_label, times),
+        focused_element_json(),
+    ))
+}
+
+pub fn handle_focus_next(arguments: &Value) -> Result<Value, JsonRpcError> {
+    handle_move_focus(arguments, true)
+}
+
+pub fn handle_focus_prev(arguments: &Value) -> Result<Value, JsonRpcError> {
+    handle_move_focus(arguments, false)
+}
+
+/// 根据控件角色选择激活键：按钮类用空格，链接、菜单项与输入框用回车。
+/// 角色名涵盖 macOS AX、Windows UIA 控件类型与 Linux AT-SPI 角色；目前只有 macOS 能读取焦点元素，
+/// 其他平台读不到角色时使用空格，需要回车时请传入 `key`
+fn activation_key_for_role(role: Option<&str>) -> (Key, &'static str) {
+    match role {
+        // macOS AX
+        Some("AXLink" | "AXMenuItem" | "AXTextField" | "AXComboBox" | "AXSearchField")
+        // Windows UIA
+        | Some("Hyperlink" | "MenuItem" | "Edit" | "ComboBox")
+        // Linux AT-SPI
+        | Some("link" | "menu item" | "entry" | "text" | "combo box" | "password text") => (Key::Return, "return"),
+        _ => (Key::Space, "space"),
+    }
+}
+
+pub fn handle_activate_focused(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let role = accessibility::focused_element().ok().and_then(|info| info.role);
+    let (key, key_name) = match arguments["key"].as_str() {
+        Some("space") => (Key::Space, "space"),
+        Some("return") | Some("enter") => (Key::Return, "return"),
+        Some(other) => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid activation key: {}", other),
+                data: None,
+            });
+        }
+        None => activation_key_for_role(role.as_deref()),
+    };
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    keyboard.key_control(key, Direction::Click).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to activate: {}", e),
+        data: None,
+    })?;
+
+    Ok(focus_result(
+        format!(
+            "已使用 {} 激活焦点控件（角色: {}）",
+            key_name,
+            role.as_deref().unwrap_or("未知")
+        ),
+        focused_element_json(),
+    ))
+}
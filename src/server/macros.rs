@@ -0,0 +1,133 @@
+//! 命名宏：保存常用的多步操作，之后按名称调用。
+//!
+//! 宏的步骤格式与 execute_script 相同（工具步骤及 if / repeat 控制步骤），
+//! 调用时交给脚本执行器运行，可传入变量。宏保存在 JSON 文件中，跨会话保留：
+//! 路径取环境变量 IRIS_MACROS_FILE，未设置时为用户配置目录下的 `iris-mcp/macros.json`。
+//! 宏文件以明文保存步骤，因此不接受 `sensitive: true` 的步骤，密码等凭据应通过 execute_script 输入。
+
+use super::jsonrpc::JsonRpcError;
+use super::script;
+use super::store::{invalid, require_name, Store};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+const STORE: Store = Store {
+    env_var: "IRIS_MACROS_FILE",
+    file_name: "macros.json",
+    section: "macros",
+};
+
+/// 串行化宏文件的读-改-写
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+fn not_found(name: &str) -> JsonRpcError {
+    invalid(format!("Unknown macro: {}", name))
+}
+
+/// 步骤（含 if / repeat 中嵌套的步骤）是否带有 `sensitive: true`
+fn contains_sensitive(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => {
+            map.get("sensitive").and_then(Value::as_bool) == Some(true) || map.values().any(contains_sensitive)
+        }
+        Value::Array(items) => items.iter().any(contains_sensitive),
+        _ => false,
+    }
+}
+
+pub fn handle_macro_define(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let name = require_name(arguments)?;
+    let steps = match &arguments["steps"] {
+        Value::Array(steps) if !steps.is_empty() => steps,
+        _ => return Err(invalid("Missing or invalid steps: expected a non-empty array".to_string())),
+    };
+    script::validate_steps(&arguments["steps"])?;
+    if contains_sensitive(&arguments["steps"]) {
+        return Err(invalid(
+            "Macro steps cannot be sensitive: macros are stored in plain text; use execute_script for secrets"
+                .to_string(),
+        ));
+    }
+    let overwrite = arguments["overwrite"].as_bool().unwrap_or(false);
+
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut macros = STORE.load()?;
+    let existed = macros.contains_key(name);
+    if existed && !overwrite {
+        return Err(invalid(format!("Macro {} already exists; pass overwrite=true to replace it", name)));
+    }
+    macros.insert(
+        name.to_string(),
+        json!({
+            "description": arguments["description"].as_str().unwrap_or_default(),
+            "steps": steps
+        }),
+    );
+    STORE.save(macros)?;
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!("已{}宏 {}（{} 个步骤）", if existed { "更新" } else { "保存" }, name, steps.len())
+        }]
+    }))
+}
+
+pub fn handle_macro_list(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let macros = STORE.load()?;
+    let list: Vec<Value> = macros
+        .iter()
+        .map(|(name, m)| {
+            json!({
+                "name": name,
+                "description": m["description"],
+                "step_count": m["steps"].as_array().map_or(0, Vec::len)
+            })
+        })
+        .collect();
+
+    let text = if list.is_empty() {
+        "尚未定义任何宏".to_string()
+    } else {
+        list.iter()
+            .map(|m| format!("- {} ({} 步) {}", m["name"].as_str().unwrap_or_default(), m["step_count"], m["description"].as_str().unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("共{}个宏\n{}", list.len(), text) }],
+        "structuredContent": { "macros": list }
+    }))
+}
+
+pub fn handle_macro_run(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let name = require_name(arguments)?;
+    let steps = STORE.load()?
+        .remove(name)
+        .map(|m| m["steps"].clone())
+        .ok_or_else(|| not_found(name))?;
+
+    let mut script_args = json!({ "steps": steps });
+    if !arguments["variables"].is_null() {
+        script_args["variables"] = arguments["variables"].clone();
+    }
+    if !arguments["stop_on_error"].is_null() {
+        script_args["stop_on_error"] = arguments["stop_on_error"].clone();
+    }
+    script::handle_execute_script(&script_args)
+}
+
+pub fn handle_macro_delete(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let name = require_name(arguments)?;
+
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut macros = STORE.load()?;
+    if macros.remove(name).is_none() {
+        return Err(not_found(name));
+    }
+    STORE.save(macros)?;
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("已删除宏 {}", name) }]
+    }))
+}
@@ -1,13 +1,34 @@
+pub mod bridge;
+pub mod chaos;
+pub mod coords;
+pub mod http;
 pub mod jsonrpc;
 pub mod keyboard;
+pub mod macros;
 pub mod monitor;
 pub mod mouse;
+pub mod notify;
+pub mod profile;
+pub mod progress;
+pub mod recordings;
+pub mod redact;
+pub mod resources;
+pub mod schema;
+pub mod script;
+pub mod store;
+pub mod subscriptions;
 pub mod tools_list;
+pub mod update;
+pub mod window;
 
 use jsonrpc::{JsonRpcError, JsonRpcRequest, JsonRpcResponse};
 use crate::monitor::key_mouse;
+use crate::operator::held;
 use serde_json::{json, Value};
 use std::io::{self, BufRead, Write};
+use std::sync::{mpsc, OnceLock, RwLock};
+use std::thread;
+use tools_list::ConcurrencyClass;
 
 fn sanitize_id(id: Option<Value>) -> Value {
     match id {
@@ -32,7 +53,11 @@ fn handle_initialize(_params: Option<Value>) -> Value {
     json!({
         "protocolVersion": "2024-11-05",
         "capabilities": {
-            "tools": {}
+            "tools": {},
+            "resources": {
+                "subscribe": notify::is_available(),
+                "listChanged": false
+            }
         },
         "serverInfo": {
             "name": "iris-mcp",
@@ -45,6 +70,12 @@ fn handle_list_tools(_params: Option<Value>) -> Value {
     tools_list::get_tools_list()
 }
 
+/// 工具调用的执行锁，配合 `ConcurrencyClass` 使用
+fn execution_lock() -> &'static RwLock<()> {
+    static LOCK: OnceLock<RwLock<()>> = OnceLock::new();
+    LOCK.get_or_init(|| RwLock::new(()))
+}
+
 fn handle_call_tool(params: Option<Value>) -> Result<Value, JsonRpcError> {
     let params = params.ok_or_else(|| JsonRpcError {
         code: -32602,
@@ -58,7 +89,54 @@ fn handle_call_tool(params: Option<Value>) -> Result<Value, JsonRpcError> {
         data: None,
     })?;
 
-    let arguments = &params["arguments"];
+    call_tool_locked(name, &params["arguments"])
+}
+
+/// 按工具的并发类别持锁执行：只读工具共享读锁并行执行；改变状态的工具独占写锁，
+/// 排队逐个执行；脚本类工具不在此持锁，由脚本对每个步骤再次调用本函数；
+/// 长时间等待的只读工具（wait_for_window、check_update）不持锁
+pub(crate) fn call_tool_locked(name: &str, arguments: &Value) -> Result<Value, JsonRpcError> {
+    let lock = execution_lock();
+    let _shared;
+    let _exclusive;
+    let class = tools_list::concurrency_class(name);
+    match class {
+        ConcurrencyClass::ReadOnly => _shared = lock.read().unwrap_or_else(|e| e.into_inner()),
+        ConcurrencyClass::Mutating => _exclusive = lock.write().unwrap_or_else(|e| e.into_inner()),
+        ConcurrencyClass::Scripted | ConcurrencyClass::Waiting => {}
+    }
+
+    let mutating = class == ConcurrencyClass::Mutating;
+
+    // 出错的工具可能在中途留下按下的键或鼠标按键；只释放本次调用新按下的，
+    // 之前 key_control / mouse_button_control 有意保持按下的输入不受影响
+    let before = if mutating { held::held() } else { Vec::new() };
+    let result = dispatch_tool(name, arguments);
+    if mutating && result.is_err() {
+        release_held_inputs("tool error", Some(&before));
+    }
+    result
+}
+
+/// 释放仍按下的输入并记录日志；`before` 为调用前的快照时只释放其后新按下的输入
+fn release_held_inputs(reason: &str, before: Option<&[held::HeldInput]>) {
+    if held::held().is_empty() {
+        return;
+    }
+    let report = match before {
+        Some(before) => held::release_since(before),
+        None => held::release_all(),
+    };
+    match report {
+        Ok(report) if report.released.is_empty() && report.failed.is_empty() => {}
+        Ok(report) => eprintln!("[held] {}: released {:?}, failed {:?}", reason, report.released, report.failed),
+        Err(e) => eprintln!("[held] {}: release failed: {}", reason, e),
+    }
+}
+
+/// 按名称执行工具（调用方负责持有执行锁，见 `call_tool_locked`）
+fn dispatch_tool(name: &str, arguments: &Value) -> Result<Value, JsonRpcError> {
+    chaos::delay(name);
 
     match name {
         // 鼠标操作
@@ -70,16 +148,73 @@ fn handle_call_tool(params: Option<Value>) -> Result<Value, JsonRpcError> {
         "mouse_drag" => mouse::handle_mouse_drag(arguments),
         "mouse_button_control" => mouse::handle_mouse_button_control(arguments),
         "mouse_move_path" => mouse::handle_mouse_move_path(arguments),
+        "smart_click" => mouse::handle_smart_click(arguments),
+        "gesture" => mouse::handle_gesture(arguments),
         
         // 键盘操作
         "type_text" => keyboard::handle_type_text(arguments),
         "system_command" => keyboard::handle_system_command(arguments),
         "key_control" => keyboard::handle_key_control(arguments),
+        "key_combo" => keyboard::handle_key_combo(arguments),
+        "key_sequence" => keyboard::handle_key_sequence(arguments),
+        "key_repeat" => keyboard::handle_key_repeat(arguments),
+        "paste_text" => keyboard::handle_paste_text(arguments),
+        "set_input_profile" => profile::handle_set_input_profile(arguments),
+        "release_all_inputs" => keyboard::handle_release_all_inputs(arguments),
+        "focus_next" => keyboard::handle_focus_next(arguments),
+        "focus_prev" => keyboard::handle_focus_prev(arguments),
+        "activate_focused" => keyboard::handle_activate_focused(arguments),
         
         // 监控操作
         "monitor_screen_events" => monitor::handle_monitor_screen_events(arguments),
         "monitor_keyboard_events" => monitor::handle_monitor_keyboard_events(arguments),
         "monitor_mouse_events" => monitor::handle_monitor_mouse_events(arguments),
+        "monitor_gestures" => monitor::handle_monitor_gestures(arguments),
+        "monitor_ack_events" => monitor::handle_monitor_ack_events(arguments),
+        "scroll_metrics" => monitor::handle_scroll_metrics(arguments),
+        "list_displays" => monitor::handle_list_displays(arguments),
+        "input_source_info" => monitor::handle_input_source_info(arguments),
+        "query_event_log" => monitor::handle_query_event_log(arguments),
+        "configure_event_buffers" => monitor::handle_configure_event_buffers(arguments),
+        "monitor_capabilities" => monitor::handle_monitor_capabilities(arguments),
+        "monitor_health" => monitor::handle_monitor_health(arguments),
+        "monitor_subscribe" => subscriptions::handle_monitor_subscribe(arguments),
+        "monitor_unsubscribe" => subscriptions::handle_monitor_unsubscribe(arguments),
+        "screen_ocr" => monitor::handle_screen_ocr(arguments),
+        "capture_all_displays" => monitor::handle_capture_all_displays(arguments),
+        "screen_record_start" => monitor::handle_screen_record_start(arguments),
+        "screen_record_stop" => monitor::handle_screen_record_stop(arguments),
+        "screen_subscribe" => resources::handle_screen_subscribe(arguments),
+        "screen_unsubscribe" => resources::handle_screen_unsubscribe(arguments),
+        "get_keyboard_state" => monitor::handle_get_keyboard_state(arguments),
+        "get_idle_time" => monitor::handle_get_idle_time(arguments),
+        "configure_keyboard_privacy" => monitor::handle_configure_keyboard_privacy(arguments),
+        "monitor_control" => monitor::handle_monitor_control(arguments),
+        "get_input_snapshot" => monitor::handle_get_input_snapshot(arguments),
+
+        // 窗口管理
+        "list_windows" => window::handle_list_windows(arguments),
+        "window_at_point" => window::handle_window_at_point(arguments),
+        "wait_for_window" => window::handle_wait_for_window(arguments),
+        "window_snap" => window::handle_window_snap(arguments),
+        "window_control" => window::handle_window_control(arguments),
+
+        // 脚本
+        "execute_script" => script::handle_execute_script(arguments),
+
+        // 宏
+        "macro_define" => macros::handle_macro_define(arguments),
+        "macro_list" => macros::handle_macro_list(arguments),
+        "macro_run" => macros::handle_macro_run(arguments),
+        "macro_delete" => macros::handle_macro_delete(arguments),
+
+        // 录制与回放
+        "recording_start" => recordings::handle_recording_start(arguments),
+        "recording_stop" => recordings::handle_recording_stop(arguments),
+        "recording_replay" => recordings::handle_recording_replay(arguments),
+
+        // 维护
+        "check_update" => update::handle_check_update(arguments),
         
         _ => Err(JsonRpcError {
             code: -32601,
@@ -107,7 +242,18 @@ fn handle_request(request: JsonRpcRequest) -> JsonRpcResponse {
         "initialize" => Ok(handle_initialize(request.params)),
         "initialized" => Ok(json!({})),
         "tools/list" => Ok(handle_list_tools(request.params)),
-        "tools/call" => handle_call_tool(request.params),
+        "tools/call" => {
+            let _request = progress::begin(request.id.as_ref(), request.params.as_ref());
+            handle_call_tool(request.params)
+        }
+        "notifications/cancelled" => {
+            progress::handle_cancelled(request.params);
+            Ok(json!({}))
+        }
+        "resources/list" => Ok(resources::handle_list_resources(request.params)),
+        "resources/read" => resources::handle_read_resource(request.params),
+        "resources/subscribe" => resources::handle_subscribe(request.params),
+        "resources/unsubscribe" => resources::handle_unsubscribe(request.params),
         _ => Err(JsonRpcError {
             code: -32601,
             message: format!("Method not found: {}", request.method),
@@ -131,64 +277,90 @@ fn handle_request(request: JsonRpcRequest) -> JsonRpcResponse {
     }
 }
 
+/// 解析并处理一条 JSON-RPC 消息，返回待发送的响应（stdio 与 HTTP 传输共用）
+pub(crate) fn handle_message(line: &str) -> JsonRpcResponse {
+    // 一些客户端在握手时发送空对象 {}，在此兼容为 initialize 请求
+    let parsed_req = if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(line) {
+        if map.is_empty() {
+            Ok(default_initialize_request())
+        } else {
+            serde_json::from_value::<JsonRpcRequest>(Value::Object(map))
+        }
+    } else {
+        serde_json::from_str::<JsonRpcRequest>(line)
+    };
+
+    match parsed_req {
+        Ok(request) => {
+            let id = sanitize_id(request.id.clone());
+            let response = handle_request(request);
+            // Ensure id is always string/number to satisfy strict clients
+            JsonRpcResponse {
+                id: Some(id),
+                ..response
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to parse request: {}", e);
+            // Some clients reject `null` ids; use 0 to conform to string/number schema.
+            JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: Some(json!(0)),
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32700,
+                    message: format!("Parse error: {}", e),
+                    data: None,
+                }),
+            }
+        }
+    }
+}
+
 pub fn run_server() -> io::Result<()> {
     eprintln!("Iris MCP Server 启动中...");
-    
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
-    
-    for line in stdin.lock().lines() {
-        let line = line?;
-        if line.trim().is_empty() {
-            continue;
-        }
 
-        eprintln!("Received: {}", line);
+    // 服务器通知与响应共用 stdout，每条消息独占一行
+    notify::set_notifier(|notification| {
+        let mut stdout = io::stdout().lock();
+        let _ = writeln!(stdout, "{}", notification);
+        let _ = stdout.flush();
+    });
 
-        // 一些客户端在握手时发送空对象 {}，在此兼容为 initialize 请求
-        let parsed_req = if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(&line) {
-            if map.is_empty() {
-                Ok(default_initialize_request())
-            } else {
-                serde_json::from_value::<JsonRpcRequest>(Value::Object(map))
+    // 独立线程读取 stdin：取消通知立即生效（不回复），其余消息按顺序交给主循环处理，
+    // 这样长时间运行的工具也能在执行中被取消
+    let (tx, rx) = mpsc::channel::<String>();
+    thread::Builder::new()
+        .name("stdin-reader".to_string())
+        .spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                eprintln!("Received: {}", redact::for_log(&line));
+                if let Ok(msg) = serde_json::from_str::<Value>(&line)
+                    && msg["method"] == "notifications/cancelled"
+                {
+                    progress::handle_cancelled(msg.get("params").cloned());
+                    continue;
+                }
+                if tx.send(line).is_err() {
+                    break;
+                }
             }
-        } else {
-            serde_json::from_str::<JsonRpcRequest>(&line)
-        };
+        })?;
 
-        match parsed_req {
-            Ok(request) => {
-                let id = sanitize_id(request.id.clone());
-                let response = handle_request(request);
-                // Ensure id is always string/number to satisfy strict clients
-                let response = JsonRpcResponse {
-                    id: Some(id),
-                    ..response
-                };
-                let response_json = serde_json::to_string(&response)?;
-                eprintln!("Sending: {}", response_json);
-                writeln!(stdout, "{}", response_json)?;
-                stdout.flush()?;
-            }
-            Err(e) => {
-                eprintln!("Failed to parse request: {}", e);
-                // Some clients reject `null` ids; use 0 to conform to string/number schema.
-                let error_response = JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    id: Some(json!(0)),
-                    result: None,
-                    error: Some(JsonRpcError {
-                        code: -32700,
-                        message: format!("Parse error: {}", e),
-                        data: None,
-                    }),
-                };
-                let response_json = serde_json::to_string(&error_response)?;
-                writeln!(stdout, "{}", response_json)?;
-                stdout.flush()?;
-            }
-        }
+    for line in rx {
+        let response = handle_message(&line);
+        let response_json = serde_json::to_string(&response)?;
+        eprintln!("Sending: {}", response_json);
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{}", response_json)?;
+        stdout.flush()?;
     }
 
+    release_held_inputs("shutdown", None);
+
     Ok(())
 }
@@ -1,18 +1,42 @@
+use super::coords;
 use super::jsonrpc::JsonRpcError;
-use crate::monitor::key_mouse::{self, KeyEvent, KeyEventType, MouseEvent, MouseEventKind, MouseButton, ButtonState};
+use super::resources;
+use super::script;
+use crate::monitor::accessibility;
+use crate::monitor::annotate;
+use crate::monitor::cursor;
+use crate::monitor::display;
+use crate::monitor::event_log::{self, LogRecord};
+use crate::monitor::gestures::{self, Gesture};
+use crate::monitor::keyboard_state;
+use crate::monitor::ocr;
+use crate::monitor::key_mouse::{self, BufferKind, ButtonState, EventBatch, KeyEvent, KeyEventType, MonitorState, MouseButton, MouseEvent, MouseEventKind, ReadMode, TimeRange};
+use crate::monitor::postprocess::{self, Region};
+use crate::monitor::privacy::{self, KeyboardPrivacy};
 use crate::monitor::screen::{self, ScreenEvent, ScreenEventKind};
+use crate::monitor::screen_recording;
+use crate::monitor::strokes::{self, Coalesced, MoveStroke};
+use crate::operator::source;
 use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
 pub fn screen_event_to_json(evt: &ScreenEvent) -> Value {
     let kind = match &evt.kind {
-        ScreenEventKind::GeometryChanged { width, height, scale } => json!({
+        ScreenEventKind::GeometryChanged { display_id, x, y, width, height, scale } => json!({
             "type": "geometry_changed",
+            "display_id": display_id,
+            "x": x,
+            "y": y,
             "width": width,
             "height": height,
             "scale": scale
         }),
-        ScreenEventKind::DisplayAdded => json!({ "type": "display_added" }),
-        ScreenEventKind::DisplayRemoved => json!({ "type": "display_removed" }),
+        ScreenEventKind::DisplayAdded { display_id } => json!({ "type": "display_added", "display_id": display_id }),
+        ScreenEventKind::DisplayRemoved { display_id } => json!({ "type": "display_removed", "display_id": display_id }),
         ScreenEventKind::FrameCaptured { width, height, format, image_data } => {
             let mut result = json!({
                 "type": "frame_captured",
@@ -37,46 +61,606 @@ pub fn screen_event_to_json(evt: &ScreenEvent) -> Value {
 pub fn keyboard_event_to_json(evt: &KeyEvent) -> Value {
     let event_type = match evt.event_type {
         KeyEventType::Press => "press",
+        KeyEventType::Repeat => "repeat",
         KeyEventType::Release => "release",
+        KeyEventType::Combo => "combo",
+        KeyEventType::Gap { .. } => key_mouse::MONITORING_GAP_KEY,
     };
 
-    json!({
+    let mut value = json!({
         "timestamp_micros": evt.timestamp_micros,
+        "monotonic_micros": evt.monotonic_micros,
         "key": evt.key,
         "event_type": event_type,
-    })
+        "synthetic": evt.synthetic,
+    });
+    if let KeyEventType::Gap { since_micros } = evt.event_type {
+        value["since_micros"] = json!(since_micros);
+    }
+    if let Some(char) = &evt.char {
+        value["char"] = json!(char);
+    }
+    if let Some(window) = &evt.window {
+        value["window"] = json!(window);
+    }
+    value
+}
+
+fn button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "left".to_string(),
+        MouseButton::Middle => "middle".to_string(),
+        MouseButton::Right => "right".to_string(),
+        MouseButton::Other(v) => format!("other_{}", v),
+    }
 }
 
 pub fn mouse_event_to_json(evt: &MouseEvent) -> Value {
     let kind = match evt.kind {
         MouseEventKind::Move { x, y } => json!({ "type": "move", "x": x, "y": y }),
         MouseEventKind::Button { button, state } => {
-            let button = match button {
-                MouseButton::Left => "left".to_string(),
-                MouseButton::Middle => "middle".to_string(),
-                MouseButton::Right => "right".to_string(),
-                MouseButton::Other(v) => format!("other_{}", v),
-            };
+            let button = button_name(button);
             let state = match state {
                 ButtonState::Press => "press",
                 ButtonState::Release => "release",
             };
             json!({ "type": "button", "button": button, "state": state })
         }
-        MouseEventKind::Scroll { delta_x, delta_y } => json!({
+        MouseEventKind::Scroll { delta_x, delta_y, raw_delta_x, raw_delta_y, raw_unit } => json!({
             "type": "scroll",
             "delta_x": delta_x,
             "delta_y": delta_y,
+            "raw": { "delta_x": raw_delta_x, "delta_y": raw_delta_y, "unit": raw_unit },
+        }),
+        MouseEventKind::Gap { since_micros } => json!({
+            "type": key_mouse::MONITORING_GAP_KEY,
+            "since_micros": since_micros,
         }),
     };
 
-    json!({
+    let mut value = json!({
         "timestamp_micros": evt.timestamp_micros,
+        "monotonic_micros": evt.monotonic_micros,
         "kind": kind,
+        "synthetic": evt.synthetic,
+    });
+    if let Some(window) = &evt.window {
+        value["window"] = json!(window);
+    }
+    value
+}
+
+/// 解析可选的 `region` 参数（截图像素坐标）；给出 `window_id` 时区域为该窗口，
+/// `region` 则相对于窗口左上角
+fn parse_region(arguments: &Value) -> Result<Option<Region>, JsonRpcError> {
+    let region = &arguments["region"];
+    let field = |name: &str| {
+        region[name].as_u64().map(|v| v as u32).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid region: missing {}", name),
+            data: None,
+        })
+    };
+    let requested = if region.is_null() {
+        None
+    } else {
+        Some(Region {
+            x: field("x")?,
+            y: field("y")?,
+            width: field("width")?,
+            height: field("height")?,
+        })
+    };
+    match coords::parse_window_frame(arguments)? {
+        Some(frame) => window_region(frame, requested).map(Some),
+        None => Ok(requested),
+    }
+}
+
+/// 把窗口矩形（全局逻辑坐标）换算为主显示器截图中的像素区域，并按窗口内的 `region` 进一步裁剪
+fn window_region(frame: display::Rect, requested: Option<Region>) -> Result<Region, JsonRpcError> {
+    let d = display::get_display(0).map_err(|e| JsonRpcError {
+        code: -32001,
+        message: format!("Failed to get display info: {}", e),
+        data: None,
+    })?;
+    // 截图只覆盖主显示器，窗口须至少部分位于其上
+    let visible = frame.intersect(&d.bounds()).ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: format!(
+            "Invalid window_id: window at ({}, {}) {}x{} is not on the main display",
+            frame.x, frame.y, frame.width, frame.height
+        ),
+        data: None,
+    })?;
+    let scale = if d.scale > 0.0 { d.scale } else { 1.0 };
+    let to_pixels = |v: f64| (v * scale).round().max(0.0) as u32;
+    // 窗口左上角在截图中的位置（可能位于主显示器之外）及窗口可见部分
+    let (origin_x, origin_y) = ((frame.x - d.x) as f64 * scale, (frame.y - d.y) as f64 * scale);
+    let left = to_pixels((visible.x - d.x) as f64);
+    let top = to_pixels((visible.y - d.y) as f64);
+    let right = to_pixels((visible.x + visible.width as i32 - d.x) as f64);
+    let bottom = to_pixels((visible.y + visible.height as i32 - d.y) as f64);
+
+    let (x0, y0, x1, y1) = match requested {
+        Some(r) => {
+            let x = (origin_x + r.x as f64).round().max(0.0) as u32;
+            let y = (origin_y + r.y as f64).round().max(0.0) as u32;
+            (
+                x.max(left),
+                y.max(top),
+                x.saturating_add(r.width).min(right),
+                y.saturating_add(r.height).min(bottom),
+            )
+        }
+        None => (left, top, right, bottom),
+    };
+    if x1 <= x0 || y1 <= y0 {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: "Invalid region: outside the visible part of the window".to_string(),
+            data: None,
+        });
+    }
+    Ok(Region { x: x0, y: y0, width: x1 - x0, height: y1 - y0 })
+}
+
+/// 解析 `max_width` / `max_height` 等尺寸上限，未指定时为 None
+fn parse_max_dimension(arguments: &Value, name: &str) -> Result<Option<u32>, JsonRpcError> {
+    match &arguments[name] {
+        Value::Null => Ok(None),
+        value => value.as_u64().filter(|&v| v >= 1).map(|v| Some(v.min(u32::MAX as u64) as u32)).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("{} must be a positive integer", name),
+            data: None,
+        }),
+    }
+}
+
+/// 按请求参数对截图做后处理，未请求任何处理时原样返回
+pub(crate) fn postprocess_capture(
+    arguments: &Value,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+) -> Result<(Vec<u8>, u32, u32, Vec<String>), JsonRpcError> {
+    let region = parse_region(arguments)?;
+    let high_contrast = arguments["high_contrast"].as_bool().unwrap_or(false);
+    let default_upscale = if high_contrast { 2 } else { 1 };
+    let upscale = arguments["upscale"].as_u64().unwrap_or(default_upscale) as u32;
+    let max_width = parse_max_dimension(arguments, "max_width")?;
+    let max_height = parse_max_dimension(arguments, "max_height")?;
+    let scale = match parse_scale(arguments)? {
+        CaptureScale::Physical => None,
+        CaptureScale::Factor(s) => Some(s),
+        // 逻辑分辨率：截图宽度对应主显示器的逻辑宽度（点），已是逻辑分辨率时不缩小
+        CaptureScale::Logical => {
+            let d = display::get_display(0).map_err(|e| JsonRpcError {
+                code: -32001,
+                message: format!("scale \"logical\" requires display geometry: {}", e),
+                data: None,
+            })?;
+            Some(d.width as f64 / width.max(1) as f64).filter(|s| *s > 0.0 && *s < 1.0)
+        }
+    };
+    let resize = max_width.is_some() || max_height.is_some() || scale.is_some_and(|s| s < 1.0);
+    let color_mode = parse_color_mode(arguments)?;
+    let include_cursor = arguments["include_cursor"].as_bool().unwrap_or(false);
+    let annotations = parse_annotations(arguments, width, height)?;
+    let grid = parse_grid_spacing(arguments)?;
+
+    let overlays = include_cursor || !annotations.is_empty() || grid.is_some();
+    if !overlays && region.is_none() && !high_contrast && upscale <= 1 && !resize && color_mode == ColorMode::Color {
+        return Ok((data, width, height, Vec::new()));
+    }
+
+    let to_rpc = |e: screen::MonitorError| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    };
+    let mut applied = Vec::new();
+    let mut img = postprocess::decode(&data).map_err(to_rpc)?;
+    // 指针在裁剪与缩放之前合成，坐标对应原始截图
+    if include_cursor {
+        match cursor::locate(width, height).map_err(to_rpc)? {
+            Some(placement) => {
+                cursor::composite(&mut img, &placement);
+                applied.push(format!("指针 ({}, {})", placement.x, placement.y));
+            }
+            None => applied.push("指针已隐藏或不在主显示器上，未合成".to_string()),
+        }
+    }
+    // 标注同样画在原始截图上，随后的裁剪与缩放一并作用于标注
+    if !annotations.is_empty() {
+        annotate::draw(&mut img, &annotations);
+        applied.push(format!("标注 {} 处", annotations.len()));
+    }
+    if let Some(r) = region {
+        img = postprocess::crop(&img, r).map_err(to_rpc)?;
+        applied.push(format!("裁剪 ({}, {}) {}x{}", r.x, r.y, r.width, r.height));
+    }
+    if high_contrast {
+        img = postprocess::high_contrast(&img, upscale).map_err(to_rpc)?;
+        applied.push(format!("高对比度 x{}", upscale.clamp(1, postprocess::MAX_UPSCALE)));
+    } else if upscale > 1 {
+        let factor = upscale.min(postprocess::MAX_UPSCALE);
+        img = postprocess::upscale_by(&img, factor).map_err(to_rpc)?;
+        applied.push(format!("放大 x{}", factor));
+    }
+    // 缩小在最后进行，限制最终编码的尺寸
+    if resize {
+        let (before_w, before_h) = (img.width(), img.height());
+        if let Some(s) = scale.filter(|s| *s < 1.0) {
+            img = postprocess::downscale(&img, s);
+        }
+        if let Some(fitted) = postprocess::fit_within(&img, max_width, max_height) {
+            img = fitted;
+        }
+        if (img.width(), img.height()) != (before_w, before_h) {
+            applied.push(format!(
+                "缩小 {}x{} → {}x{}（截图中的坐标乘以 {:.4} 换算回缩小前的像素）",
+                before_w,
+                before_h,
+                img.width(),
+                img.height(),
+                before_w as f64 / img.width() as f64
+            ));
+        }
+    }
+
+    // 网格画在最终尺寸上，线条与坐标标签保持清晰
+    if let Some(spacing) = grid {
+        let (origin, units_per_pixel, space) = match coordinate_mapping(arguments, width, img.width()) {
+            mapping if mapping.is_object() => {
+                let value = |a: &str, b: &str| mapping[a][b].as_f64().unwrap_or(0.0);
+                let ppp = mapping["points_per_pixel"].as_f64().unwrap_or(1.0);
+                let origin = (value("origin", "x") + value("offset", "x"), value("origin", "y") + value("offset", "y"));
+                (origin, ppp, "全局逻辑坐标")
+            }
+            _ => {
+                let (x, y, source_width) = match region {
+                    Some(r) => (r.x, r.y, r.width.min(width.saturating_sub(r.x))),
+                    None => (0, 0, width),
+                };
+                ((x as f64, y as f64), source_width as f64 / img.width().max(1) as f64, "原始截图像素")
+            }
+        };
+        let used = annotate::draw_grid(&mut img, spacing, origin, (units_per_pixel, units_per_pixel));
+        applied.push(format!("网格 每 {}（{}）", used, space));
+    }
+
+    let (w, h) = (img.width(), img.height());
+    let encoded = match color_mode {
+        ColorMode::Color => postprocess::encode_png(&img),
+        ColorMode::Grayscale => {
+            applied.push("灰度".to_string());
+            postprocess::encode_grayscale_png(&img)
+        }
+        ColorMode::Palette(colors) => {
+            applied.push(format!("调色板 {} 色", colors));
+            postprocess::encode_palette_png(&img, colors)
+        }
+    }
+    .map_err(to_rpc)?;
+    Ok((encoded, w, h, applied))
+}
+
+/// 网格默认间距与允许的最小间距（坐标单位）
+const DEFAULT_GRID_SPACING: u32 = 100;
+const MIN_GRID_SPACING: u64 = 10;
+
+/// 解析 `grid` / `grid_spacing`；未启用网格时返回 None
+fn parse_grid_spacing(arguments: &Value) -> Result<Option<u32>, JsonRpcError> {
+    let spacing = match &arguments["grid_spacing"] {
+        Value::Null => None,
+        value => Some(value.as_u64().filter(|s| (MIN_GRID_SPACING..=10_000).contains(s)).ok_or_else(|| {
+            JsonRpcError {
+                code: -32602,
+                message: format!("grid_spacing must be an integer between {} and 10000", MIN_GRID_SPACING),
+                data: None,
+            }
+        })? as u32),
+    };
+    match arguments["grid"].as_bool() {
+        Some(true) => Ok(Some(spacing.unwrap_or(DEFAULT_GRID_SPACING))),
+        Some(false) => Ok(None),
+        None => Ok(spacing),
+    }
+}
+
+/// 单次截图允许的标注数
+const MAX_ANNOTATIONS: usize = 32;
+
+/// 解析 `annotations` 并换算到 `capture_width`x`capture_height` 的截图像素。
+/// 坐标默认为全局逻辑坐标（与鼠标工具一致），`annotation_coordinates: "pixel"` 时为截图像素
+fn parse_annotations(
+    arguments: &Value,
+    capture_width: u32,
+    capture_height: u32,
+) -> Result<Vec<annotate::Annotation>, JsonRpcError> {
+    let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+    let items = match &arguments["annotations"] {
+        Value::Null => return Ok(Vec::new()),
+        Value::Array(items) if items.len() <= MAX_ANNOTATIONS => items,
+        Value::Array(_) => return Err(invalid(format!("At most {} annotations are allowed", MAX_ANNOTATIONS))),
+        _ => return Err(invalid("annotations must be an array".to_string())),
+    };
+    if items.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // 全局逻辑坐标 → 截图像素：(原点偏移, 每点像素数)
+    let (origin, scale) = match arguments["annotation_coordinates"].as_str().unwrap_or("global") {
+        "pixel" => ((0.0, 0.0), (1.0, 1.0)),
+        "global" => {
+            let d = display::get_display(0).map_err(|e| JsonRpcError {
+                code: -32001,
+                message: format!("Global annotation coordinates require display geometry: {}", e),
+                data: None,
+            })?;
+            (
+                (d.x as f64, d.y as f64),
+                (capture_width as f64 / d.width.max(1) as f64, capture_height as f64 / d.height.max(1) as f64),
+            )
+        }
+        other => return Err(invalid(format!("Invalid annotation_coordinates: {} (expected global or pixel)", other))),
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let number = |key: &str| item[key].as_f64();
+            let (Some(x), Some(y)) = (number("x"), number("y")) else {
+                return Err(invalid(format!("annotations[{}] requires numeric x and y", i)));
+            };
+            let (px, py) = (((x - origin.0) * scale.0).round() as i64, ((y - origin.1) * scale.1).round() as i64);
+            let shape = match (number("width"), number("height")) {
+                (None, None) => annotate::Shape::Point { x: px, y: py },
+                (Some(w), Some(h)) if w > 0.0 && h > 0.0 => annotate::Shape::Rect {
+                    x: px,
+                    y: py,
+                    width: (w * scale.0).round() as i64,
+                    height: (h * scale.1).round() as i64,
+                },
+                _ => return Err(invalid(format!("annotations[{}]: width and height must both be positive", i))),
+            };
+            let color = match &item["color"] {
+                Value::Null => annotate::DEFAULT_COLOR,
+                value => value
+                    .as_str()
+                    .and_then(script::parse_color)
+                    .ok_or_else(|| invalid(format!("annotations[{}]: color must be \"#rrggbb\"", i)))?,
+            };
+            let label = item["label"].as_str().map(str::to_string);
+            Ok(annotate::Annotation { shape, label, color })
+        })
+        .collect()
+}
+
+/// 缩略图默认宽度与允许范围（像素）
+const DEFAULT_THUMBNAIL_WIDTH: u32 = 320;
+const MAX_THUMBNAIL_WIDTH: u32 = 1024;
+
+/// 解析 `thumbnail` / `thumbnail_width`；未启用缩略图模式时返回 None
+fn parse_thumbnail_width(arguments: &Value) -> Result<Option<u32>, JsonRpcError> {
+    if !arguments["thumbnail"].as_bool().unwrap_or(false) {
+        return Ok(None);
+    }
+    match &arguments["thumbnail_width"] {
+        Value::Null => Ok(Some(DEFAULT_THUMBNAIL_WIDTH)),
+        value => value
+            .as_u64()
+            .filter(|w| (16..=MAX_THUMBNAIL_WIDTH as u64).contains(w))
+            .map(|w| Some(w as u32))
+            .ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: format!("thumbnail_width must be an integer between 16 and {}", MAX_THUMBNAIL_WIDTH),
+                data: None,
+            }),
+    }
+}
+
+/// `scale` 参数：按比例缩小，或缩小到逻辑分辨率
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CaptureScale {
+    /// 保持截图的物理像素
+    Physical,
+    /// 缩小到显示器的逻辑分辨率（点），与鼠标工具的坐标一致
+    Logical,
+    Factor(f64),
+}
+
+fn parse_scale(arguments: &Value) -> Result<CaptureScale, JsonRpcError> {
+    match &arguments["scale"] {
+        Value::Null => Ok(CaptureScale::Physical),
+        Value::String(mode) if mode == "physical" => Ok(CaptureScale::Physical),
+        Value::String(mode) if mode == "logical" => Ok(CaptureScale::Logical),
+        value => value
+            .as_f64()
+            .filter(|s| *s > 0.0 && *s <= 1.0)
+            .map(CaptureScale::Factor)
+            .ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: "scale must be a number in (0, 1], \"logical\" or \"physical\"".to_string(),
+                data: None,
+            }),
+    }
+}
+
+/// 截图像素到全局逻辑坐标（鼠标工具使用的坐标）的换算：
+/// `global = origin + offset + pixel * points_per_pixel`。
+/// `capture_width` 为原始截图宽度，`output_width` 为后处理后的宽度；无法获取主显示器时返回 Null
+fn coordinate_mapping(arguments: &Value, capture_width: u32, output_width: u32) -> Value {
+    let Ok(d) = display::get_display(0) else {
+        return Value::Null;
+    };
+    if d.width == 0 || capture_width == 0 || output_width == 0 {
+        return Value::Null;
+    }
+    let points_per_capture_pixel = d.width as f64 / capture_width as f64;
+    let (offset_x, offset_y, source_width) = match parse_region(arguments).ok().flatten() {
+        Some(r) => (r.x, r.y, r.width.min(capture_width.saturating_sub(r.x))),
+        None => (0, 0, capture_width),
+    };
+    let points_per_pixel = source_width as f64 / output_width as f64 * points_per_capture_pixel;
+    let space = if (points_per_pixel - 1.0).abs() < 0.01 {
+        "logical"
+    } else if source_width == output_width {
+        "physical"
+    } else {
+        "scaled"
+    };
+    json!({
+        "space": space,
+        "origin": { "x": d.x, "y": d.y },
+        "offset": {
+            "x": offset_x as f64 * points_per_capture_pixel,
+            "y": offset_y as f64 * points_per_capture_pixel
+        },
+        "points_per_pixel": points_per_pixel,
+        "display_scale": d.scale
     })
 }
 
-pub fn handle_monitor_screen_events(_arguments: &Value) -> Result<Value, JsonRpcError> {
+/// 截图输出的颜色模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorMode {
+    Color,
+    Grayscale,
+    /// 量化为最多 N 种颜色的索引色 PNG
+    Palette(usize),
+}
+
+/// 解析可选的 `color_mode` 与 `palette_colors` 参数
+fn parse_color_mode(arguments: &Value) -> Result<ColorMode, JsonRpcError> {
+    let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+    let mode = match &arguments["color_mode"] {
+        Value::Null => "color",
+        value => value.as_str().ok_or_else(|| invalid("color_mode must be a string".to_string()))?,
+    };
+    match mode {
+        "color" => Ok(ColorMode::Color),
+        "grayscale" => Ok(ColorMode::Grayscale),
+        "palette" => {
+            let colors = match &arguments["palette_colors"] {
+                Value::Null => postprocess::DEFAULT_PALETTE_COLORS,
+                value => value
+                    .as_u64()
+                    .map(|v| v as usize)
+                    .filter(|v| (postprocess::MIN_PALETTE_COLORS..=postprocess::MAX_PALETTE_COLORS).contains(v))
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "palette_colors must be an integer between {} and {}",
+                            postprocess::MIN_PALETTE_COLORS,
+                            postprocess::MAX_PALETTE_COLORS
+                        ))
+                    })?,
+            };
+            Ok(ColorMode::Palette(colors))
+        }
+        other => Err(invalid(format!("Invalid color_mode: {} (expected color, grayscale or palette)", other))),
+    }
+}
+
+/// 最近一次返回图像的截图，供 `skip_unchanged` 判断画面是否变化
+struct LastFrame {
+    /// 去掉 reason 等无关字段后的调用参数；参数不同时输出不同，不视为未变化
+    key: String,
+    hash: u64,
+    /// 仅在指定 similarity_threshold 时计算
+    fingerprint: Option<image::GrayImage>,
+    timestamp_micros: u128,
+}
+
+fn last_frame() -> &'static Mutex<Option<LastFrame>> {
+    static LAST: OnceLock<Mutex<Option<LastFrame>>> = OnceLock::new();
+    LAST.get_or_init(|| Mutex::new(None))
+}
+
+/// 记录本次截图；`skip_unchanged` 时若与上一次返回的截图相同（或差异不超过 similarity_threshold），
+/// 返回"未变化"的简短结果而不再编码、发送图像
+fn check_unchanged_frame(
+    arguments: &Value,
+    data: &[u8],
+    timestamp_micros: u128,
+) -> Result<Option<Value>, JsonRpcError> {
+    let skip_unchanged = arguments["skip_unchanged"].as_bool().unwrap_or(false);
+    let threshold = match &arguments["similarity_threshold"] {
+        Value::Null => None,
+        value => Some(value.as_f64().filter(|t| (0.0..=1.0).contains(t)).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: "similarity_threshold must be a number in [0, 1]".to_string(),
+            data: None,
+        })?),
+    };
+
+    let mut key = arguments.clone();
+    if let Some(map) = key.as_object_mut() {
+        for field in ["reason", "skip_unchanged", "similarity_threshold"] {
+            map.remove(field);
+        }
+    }
+    let key = key.to_string();
+    let hash = {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        hasher.finish()
+    };
+    let fingerprint = match threshold {
+        Some(t) if t > 0.0 => {
+            let to_rpc = |e: screen::MonitorError| JsonRpcError {
+                code: -32001,
+                message: e.to_string(),
+                data: None,
+            };
+            let mut img = postprocess::decode(data).map_err(to_rpc)?;
+            if let Some(r) = parse_region(arguments)? {
+                img = postprocess::crop(&img, r).map_err(to_rpc)?;
+            }
+            Some(postprocess::fingerprint(&img))
+        }
+        _ => None,
+    };
+
+    let mut last = last_frame().lock().unwrap();
+    if skip_unchanged && let Some(previous) = last.as_ref().filter(|previous| previous.key == key) {
+        let difference = if previous.hash == hash {
+            Some(0.0)
+        } else {
+            match (&previous.fingerprint, &fingerprint, threshold) {
+                (Some(a), Some(b), Some(t)) => postprocess::difference_ratio(a, b).filter(|d| *d <= t),
+                _ => None,
+            }
+        };
+        if let Some(difference) = difference {
+            let since = iso8601(previous.timestamp_micros as u64);
+            return Ok(Some(json!({
+                "content": [
+                    {
+                        "type": "text",
+                        "text": format!(
+                            "画面自 {} 起未变化（差异 {:.2}%），未重新发送图像；需要图像时去掉 skip_unchanged 再调用",
+                            since,
+                            difference * 100.0
+                        )
+                    }
+                ],
+                "structuredContent": {
+                    "unchanged": true,
+                    "since_micros": previous.timestamp_micros as u64,
+                    "since": since,
+                    "difference": difference
+                }
+            })));
+        }
+    }
+    *last = Some(LastFrame { key, hash, fingerprint, timestamp_micros });
+    Ok(None)
+}
+
+pub fn handle_monitor_screen_events(arguments: &Value) -> Result<Value, JsonRpcError> {
     let event = screen::capture_frame().map_err(|e| JsonRpcError {
         code: -32001,
         message: e.to_string(),
@@ -101,9 +685,60 @@ pub fn handle_monitor_screen_events(_arguments: &Value) -> Result<Value, JsonRpc
 
     match image_data {
         Some(data) => {
+            if let Some(unchanged) = check_unchanged_frame(arguments, &data, event.timestamp_micros)? {
+                return Ok(unchanged);
+            }
+            let capture_width = width;
+            let (data, width, height, applied) = postprocess_capture(arguments, data, width, height)?;
+            let mapping = coordinate_mapping(arguments, capture_width, width);
+            let thumbnail_width = parse_thumbnail_width(arguments)?;
+            let size = data.len();
+
+            // 缩略图模式：完整截图保存为资源，只内联缩略图
+            let (inline, frame_uri, thumbnail) = match thumbnail_width {
+                Some(max_width) => {
+                    let to_rpc = |e: screen::MonitorError| JsonRpcError {
+                        code: -32001,
+                        message: e.to_string(),
+                        data: None,
+                    };
+                    let img = postprocess::decode(&data).map_err(to_rpc)?;
+                    let small = postprocess::fit_within(&img, Some(max_width), None).unwrap_or(img);
+                    let thumbnail = json!({ "width": small.width(), "height": small.height() });
+                    let inline = postprocess::encode_png(&small).map_err(to_rpc)?;
+                    let uri = resources::store_frame(data, width, height);
+                    (inline, Some(uri), thumbnail)
+                }
+                None => (data, None, Value::Null),
+            };
+
             // 使用 base64 编码图像数据
             use base64::{Engine as _, engine::general_purpose};
-            let base64_data = general_purpose::STANDARD.encode(&data);
+            let base64_data = general_purpose::STANDARD.encode(&inline);
+            let mut processing = if applied.is_empty() {
+                String::new()
+            } else {
+                format!("\n处理: {}", applied.join("，"))
+            };
+            if let Some(ppp) = mapping["points_per_pixel"].as_f64() {
+                processing.push_str(&format!(
+                    "\n坐标: 全局逻辑坐标 = ({}, {}) + ({:.1}, {:.1}) + 像素坐标 × {:.4}",
+                    mapping["origin"]["x"],
+                    mapping["origin"]["y"],
+                    mapping["offset"]["x"].as_f64().unwrap_or(0.0),
+                    mapping["offset"]["y"].as_f64().unwrap_or(0.0),
+                    ppp
+                ));
+            }
+            if let Some(uri) = &frame_uri {
+                processing.push_str(&format!(
+                    "\n缩略图: {}x{}（像素坐标乘以 {:.4} 换算为完整截图像素）\n完整截图: {}（用 resources/read 读取）",
+                    thumbnail["width"],
+                    thumbnail["height"],
+                    width as f64 / thumbnail["width"].as_f64().unwrap_or(width as f64).max(1.0),
+                    uri
+                ));
+            }
             
             Ok(json!({
                 "content": [
@@ -114,10 +749,17 @@ pub fn handle_monitor_screen_events(_arguments: &Value) -> Result<Value, JsonRpc
                     },
                     {
                         "type": "text",
-                        "text": format!("已捕获屏幕截图\n尺寸: {}x{}\n大小: {} bytes", 
-                            width, height, data.len())
+                        "text": format!("已捕获屏幕截图\n尺寸: {}x{}\n大小: {} bytes{}", 
+                            width, height, size, processing)
                     }
-                ]
+                ],
+                "structuredContent": {
+                    "width": width,
+                    "height": height,
+                    "coordinate_mapping": mapping,
+                    "thumbnail": thumbnail,
+                    "frame_uri": frame_uri
+                }
             }))
         }
         None => {
@@ -138,17 +780,577 @@ pub fn handle_monitor_screen_events(_arguments: &Value) -> Result<Value, JsonRpc
     }
 }
 
-pub fn handle_monitor_keyboard_events(_arguments: &Value) -> Result<Value, JsonRpcError> {
-    // 获取所有键盘事件并清空存储
-    let events = key_mouse::take_keyboard_events();
-    
-    let events_json: Vec<Value> = events.iter().map(keyboard_event_to_json).collect();
+/// 解析监控事件工具的读取方式：`cursor` 增量读取，`peek` 只查看不消费，默认读取并前移默认读取位置。
+/// 指定时间范围而未指定游标时检索整个缓冲区，不改变默认读取位置。
+fn read_mode(arguments: &Value, range: TimeRange, kind: BufferKind) -> Result<ReadMode, JsonRpcError> {
+    if let Some(consumer) = consumer_name(arguments)? {
+        if !arguments["cursor"].is_null() || !arguments["peek"].is_null() {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: "consumer cannot be combined with cursor or peek".to_string(),
+                data: None,
+            });
+        }
+        let cursor = key_mouse::consumer_cursor(kind, consumer).ok_or_else(too_many_consumers)?;
+        return Ok(ReadMode::From(cursor));
+    }
+    if arguments["cursor"].is_null() && !range.is_unbounded() {
+        return Ok(ReadMode::From(0));
+    }
+    if !arguments["cursor"].is_null() {
+        let cursor = arguments["cursor"].as_u64().ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: "cursor must be a non-negative integer".to_string(),
+            data: None,
+        })?;
+        return Ok(ReadMode::From(cursor));
+    }
+    Ok(if arguments["peek"].as_bool().unwrap_or(false) {
+        ReadMode::Peek
+    } else {
+        ReadMode::Take
+    })
+}
+
+/// 解析可选的 `consumer` 参数
+fn consumer_name(arguments: &Value) -> Result<Option<&str>, JsonRpcError> {
+    match &arguments["consumer"] {
+        Value::Null => Ok(None),
+        Value::String(name) if !name.is_empty() && name.len() <= 64 => Ok(Some(name)),
+        _ => Err(JsonRpcError {
+            code: -32602,
+            message: "consumer must be a non-empty string of at most 64 bytes".to_string(),
+            data: None,
+        }),
+    }
+}
+
+fn too_many_consumers() -> JsonRpcError {
+    JsonRpcError {
+        code: -32001,
+        message: format!(
+            "Too many event consumers (max {}); release one with monitor_ack_events release=true",
+            key_mouse::MAX_EVENT_CONSUMERS
+        ),
+        data: None,
+    }
+}
+
+/// 解析 `since_micros` / `until_micros`（Unix 时间戳，微秒，两端包含）
+fn time_range(arguments: &Value) -> Result<TimeRange, JsonRpcError> {
+    let bound = |field: &str| -> Result<Option<u128>, JsonRpcError> {
+        if arguments[field].is_null() {
+            return Ok(None);
+        }
+        arguments[field].as_u64().map(|v| Some(v as u128)).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("{} must be a non-negative integer (Unix time in microseconds)", field),
+            data: None,
+        })
+    };
+    let range = TimeRange {
+        since_micros: bound("since_micros")?,
+        until_micros: bound("until_micros")?,
+    };
+    if let (Some(since), Some(until)) = (range.since_micros, range.until_micros)
+        && since > until
+    {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: "since_micros must not be greater than until_micros".to_string(),
+            data: None,
+        });
+    }
+    Ok(range)
+}
+
+/// 在读取结果中附加丢失统计与缓冲区覆盖的时间范围，便于发现事件缺口
+fn add_loss_accounting<T>(result: &mut Value, batch: &EventBatch<T>) {
+    result["evicted_count"] = json!(batch.evicted_count);
+    result["dropped_count"] = json!(batch.dropped_count);
+    if let Some(covered) = batch.covered {
+        result["covered_since_micros"] = json!(covered.since_micros);
+        result["covered_until_micros"] = json!(covered.until_micros);
+    }
+}
+
+/// 读取结果的丢失说明；没有丢失时为空
+fn loss_note<T>(batch: &EventBatch<T>) -> String {
+    let mut note = String::new();
+    if batch.missed > 0 {
+        note.push_str(&format!("，{}条事件已被缓冲区覆盖", batch.missed));
+    }
+    if batch.dropped_count > 0 {
+        note.push_str(&format!("，监控启动以来共{}条事件因投递通道已满被丢弃", batch.dropped_count));
+    }
+    note
+}
+
+/// 组装事件批次响应；事件附带序号 `seq`
+fn event_batch_response<T>(
+    kind: &str,
+    mode: ReadMode,
+    format: TimestampFormat,
+    batch: &EventBatch<T>,
+    to_json: fn(&T) -> Value,
+) -> Value {
+    let events_json: Vec<Value> = batch
+        .events
+        .iter()
+        .map(|(seq, event)| {
+            let mut value = to_json(event);
+            value["seq"] = json!(seq);
+            value
+        })
+        .collect();
+    batch_response(kind, mode, format, batch, events_json)
+}
+
+/// 组装已转换为 JSON 的事件批次响应
+fn batch_response<T>(
+    kind: &str,
+    mode: ReadMode,
+    format: TimestampFormat,
+    batch: &EventBatch<T>,
+    mut events_json: Vec<Value>,
+) -> Value {
+    let total = events_json.len();
+    if format == TimestampFormat::Iso8601 {
+        for value in &mut events_json {
+            if let Some(micros) = value["timestamp_micros"].as_u64() {
+                value["timestamp"] = json!(iso8601(micros));
+            }
+        }
+    }
+
+    let mut result = json!({
+        "events": events_json,
+        "total": total,
+        "next_cursor": batch.next_cursor,
+        "missed": batch.missed
+    });
+    add_loss_accounting(&mut result, batch);
+    let result_text = serde_json::to_string_pretty(&result)
+        .unwrap_or_else(|_| result.to_string());
+    let note = match mode {
+        ReadMode::Take => "已标记为已读",
+        ReadMode::Peek | ReadMode::From(_) => "未改变读取位置",
+    };
+    let mut text = format!("返回{}条{}事件（{}）", total, kind, note);
+    text.push_str(&loss_note(batch));
+
+    json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("{}\n\n事件数据：\n{}", text, result_text)
+            }
+        ],
+        "structuredContent": result
+    })
+}
+
+/// 事件输出中的时间戳格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimestampFormat {
+    /// 仅 `timestamp_micros`（Unix 时间，微秒）
+    Micros,
+    /// 额外附加 UTC 的 ISO-8601 字符串 `timestamp`
+    Iso8601,
+}
+
+/// 解析可选的 `timestamp_format` 参数
+fn timestamp_format(arguments: &Value) -> Result<TimestampFormat, JsonRpcError> {
+    match &arguments["timestamp_format"] {
+        Value::Null => Ok(TimestampFormat::Micros),
+        value => match value.as_str() {
+            Some("micros") => Ok(TimestampFormat::Micros),
+            Some("iso8601") => Ok(TimestampFormat::Iso8601),
+            _ => Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid timestamp_format: {} (expected micros or iso8601)", value),
+                data: None,
+            }),
+        },
+    }
+}
+
+/// 将 Unix 微秒时间戳格式化为 UTC 的 ISO-8601 字符串，如 `2024-05-01T08:30:15.123456Z`
+fn iso8601(micros: u64) -> String {
+    let secs = micros / 1_000_000;
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // 由 1970-01-01 起的天数换算公历日期（civil_from_days 算法）
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60,
+        micros % 1_000_000
+    )
+}
+
+pub fn handle_monitor_keyboard_events(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let range = time_range(arguments)?;
+    let format = timestamp_format(arguments)?;
+    let mode = read_mode(arguments, range, BufferKind::Keyboard)?;
+    let batch = key_mouse::read_keyboard_events(mode, range);
+    Ok(event_batch_response("键盘", mode, format, &batch, keyboard_event_to_json))
+}
+
+pub fn handle_monitor_mouse_events(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let range = time_range(arguments)?;
+    let format = timestamp_format(arguments)?;
+    let mode = read_mode(arguments, range, BufferKind::Mouse)?;
+    let batch = key_mouse::read_mouse_events(mode, range);
+    if !arguments["coalesce_moves"].as_bool().unwrap_or(false) {
+        return Ok(event_batch_response("鼠标", mode, format, &batch, mouse_event_to_json));
+    }
+    let events_json = strokes::coalesce(&batch.events)
+        .into_iter()
+        .map(|item| match item {
+            Coalesced::Stroke(stroke) => stroke_to_json(&stroke),
+            Coalesced::Event(seq, event) => {
+                let mut value = mouse_event_to_json(event);
+                value["seq"] = json!(seq);
+                value
+            }
+        })
+        .collect();
+    Ok(batch_response("鼠标", mode, format, &batch, events_json))
+}
+
+/// 合并后的移动笔画；`seq` 为第一个移动事件的序号，`last_seq` 为最后一个
+fn stroke_to_json(stroke: &MoveStroke) -> Value {
+    let mut value = json!({
+        "seq": stroke.first_seq,
+        "last_seq": stroke.last_seq,
+        "timestamp_micros": stroke.start_micros,
+        "kind": {
+            "type": "stroke",
+            "start": stroke.start,
+            "end": stroke.end,
+            "point_count": stroke.point_count,
+            "duration_micros": stroke.duration_micros(),
+            "bounding_box": stroke.bounding_box,
+        },
+        "synthetic": stroke.synthetic,
+    });
+    if let Some(window) = &stroke.window {
+        value["window"] = json!(window);
+    }
+    value
+}
+
+/// 确认具名消费者已处理的事件，可裁剪所有消费者都已确认的事件或注销消费者
+pub fn handle_monitor_ack_events(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let consumer = consumer_name(arguments)?.ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing consumer".to_string(),
+        data: None,
+    })?;
+    if arguments["release"].as_bool().unwrap_or(false) {
+        let released = key_mouse::release_consumer(consumer);
+        return Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": if released { format!("已注销消费者 {}", consumer) } else { format!("消费者 {} 未登记", consumer) }
+            }],
+            "structuredContent": { "consumer": consumer, "released": released }
+        }));
+    }
+
+    let kind = match arguments["device"].as_str() {
+        Some("keyboard") => BufferKind::Keyboard,
+        Some("mouse") => BufferKind::Mouse,
+        other => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid device: {} (expected keyboard or mouse)", other.unwrap_or("<missing>")),
+                data: None,
+            })
+        }
+    };
+    let cursor = arguments["cursor"].as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing or invalid cursor: pass next_cursor from the last read".to_string(),
+        data: None,
+    })?;
+    let trim = arguments["trim"].as_bool().unwrap_or(false);
+    let report = key_mouse::ack_events(kind, consumer, cursor, trim).ok_or_else(too_many_consumers)?;
+
+    let mut text = format!("消费者 {} 已确认到 {}", consumer, report.cursor);
+    if trim {
+        text.push_str(&format!("，裁剪了{}条所有消费者都已确认的事件", report.trimmed));
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": {
+            "consumer": consumer,
+            "device": arguments["device"],
+            "cursor": report.cursor,
+            "trimmed": report.trimmed
+        }
+    }))
+}
+
+/// 从鼠标事件缓冲区识别单击、双击、拖拽与滑动手势；不改变默认读取位置
+pub fn handle_monitor_gestures(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let range = time_range(arguments)?;
+    let cursor = match &arguments["cursor"] {
+        Value::Null => 0,
+        value => value.as_u64().ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: "cursor must be a non-negative integer".to_string(),
+            data: None,
+        })?,
+    };
+    let exclude_synthetic = arguments["exclude_synthetic"].as_bool().unwrap_or(false);
+
+    let batch = key_mouse::read_mouse_events(ReadMode::From(cursor), range);
+    let gestures: Vec<Value> = gestures::recognize(batch.events.iter().map(|(_, event)| event))
+        .iter()
+        .filter(|gesture| !(exclude_synthetic && gesture_is_synthetic(gesture)))
+        .map(|gesture| {
+            let mut value = serde_json::to_value(gesture).unwrap_or(Value::Null);
+            if let Gesture::Click { button, .. } | Gesture::DoubleClick { button, .. } | Gesture::Drag { button, .. } =
+                gesture
+            {
+                value["button"] = json!(button_name(*button));
+            }
+            value
+        })
+        .collect();
+    let total = gestures.len();
+
+    let mut result = json!({
+        "gestures": gestures,
+        "total": total,
+        "next_cursor": batch.next_cursor,
+        "missed": batch.missed
+    });
+    add_loss_accounting(&mut result, &batch);
+    let result_text = serde_json::to_string_pretty(&result)
+        .unwrap_or_else(|_| result.to_string());
+    let mut text = format!("从{}条鼠标事件中识别出{}个手势", batch.events.len(), total);
+    text.push_str(&loss_note(&batch));
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("{}\n\n手势数据：\n{}", text, result_text) }],
+        "structuredContent": result
+    }))
+}
+
+fn gesture_is_synthetic(gesture: &Gesture) -> bool {
+    match gesture {
+        Gesture::Click { synthetic, .. }
+        | Gesture::DoubleClick { synthetic, .. }
+        | Gesture::Drag { synthetic, .. }
+        | Gesture::Swipe { synthetic, .. } => *synthetic,
+    }
+}
+
+/// 查看或调整键鼠事件缓冲区容量
+/// query_event_log 默认返回的事件数上限
+const DEFAULT_EVENT_LOG_LIMIT: u64 = 1000;
+
+/// query_event_log 返回的事件数上限
+const MAX_EVENT_LOG_LIMIT: u64 = 10_000;
+
+/// 查询持久化事件日志中时间范围内的事件
+pub fn handle_query_event_log(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let range = time_range(arguments)?;
+    let device = arguments["device"].as_str().unwrap_or("all");
+    if !matches!(device, "keyboard" | "mouse" | "all") {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("Invalid device: {} (expected keyboard, mouse or all)", device),
+            data: None,
+        });
+    }
+    let limit = arguments["limit"]
+        .as_u64()
+        .unwrap_or(DEFAULT_EVENT_LOG_LIMIT)
+        .clamp(1, MAX_EVENT_LOG_LIMIT) as usize;
+
+    let (records, truncated) = event_log::query(
+        range,
+        |record| match record {
+            LogRecord::Keyboard(_) => device != "mouse",
+            LogRecord::Mouse(_) => device != "keyboard",
+        },
+        limit,
+    )
+    .map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+    let events: Vec<Value> = records
+        .iter()
+        .map(|record| match record {
+            LogRecord::Keyboard(e) => {
+                let mut value = keyboard_event_to_json(e);
+                value["device"] = json!("keyboard");
+                value
+            }
+            LogRecord::Mouse(e) => {
+                let mut value = mouse_event_to_json(e);
+                value["device"] = json!("mouse");
+                value
+            }
+        })
+        .collect();
     let total = events.len();
 
     let result = json!({
-        "events": events_json,
-        "total": total
+        "events": events,
+        "total": total,
+        "truncated": truncated
     });
+    let result_text = serde_json::to_string_pretty(&result)
+        .unwrap_or_else(|_| result.to_string());
+    let mut text = format!("事件日志中返回{}条事件", total);
+    if truncated {
+        text.push_str(&format!("（已达到上限 {}，可缩小时间范围）", limit));
+    }
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("{}\n\n事件数据：\n{}", text, result_text) }],
+        "structuredContent": result
+    }))
+}
+
+pub fn handle_configure_event_buffers(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let capacity = |field: &str| -> Result<Option<usize>, JsonRpcError> {
+        if arguments[field].is_null() {
+            return Ok(None);
+        }
+        arguments[field]
+            .as_u64()
+            .filter(|c| (1..=key_mouse::MAX_EVENT_BUFFER_CAPACITY as u64).contains(c))
+            .map(|c| Some(c as usize))
+            .ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: format!(
+                    "{} must be an integer between 1 and {}",
+                    field,
+                    key_mouse::MAX_EVENT_BUFFER_CAPACITY
+                ),
+                data: None,
+            })
+    };
+    let keyboard = capacity("keyboard_capacity")?;
+    let mouse = capacity("mouse_capacity")?;
+    if keyboard.is_some() || mouse.is_some() {
+        key_mouse::set_buffer_capacity(keyboard, mouse);
+    }
+
+    let report = key_mouse::buffer_report();
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "键盘事件缓冲区 {}/{}（已覆盖{}条），鼠标事件缓冲区 {}/{}（已覆盖{}条），投递通道丢弃{}条",
+                report.keyboard.occupancy, report.keyboard.capacity, report.keyboard.evicted_count,
+                report.mouse.occupancy, report.mouse.capacity, report.mouse.evicted_count,
+                report.dropped_count
+            )
+        }],
+        "structuredContent": report
+    }))
+}
+
+/// 启动、暂停、恢复或停止键鼠监控，可同时清空事件缓冲区
+pub fn handle_monitor_control(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let action = arguments["action"].as_str().unwrap_or("status");
+    let failed = |message: String| JsonRpcError { code: -32001, message, data: None };
+    match action {
+        "start" => key_mouse::start_monitor().map_err(failed)?,
+        "resume" => key_mouse::resume_monitor().map_err(failed)?,
+        "pause" => key_mouse::pause_monitor(),
+        "stop" => key_mouse::stop_monitor(),
+        "status" => {}
+        other => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid action: {} (expected start, pause, resume, stop or status)", other),
+                data: None,
+            })
+        }
+    }
+
+    let mut structured = json!({ "state": key_mouse::monitor_state() });
+    let state = match key_mouse::monitor_state() {
+        MonitorState::Running => "运行中",
+        MonitorState::Paused => "已暂停",
+        MonitorState::Stopped => "已停止",
+    };
+    let mut text = format!("键鼠监控{}", state);
+    if arguments["clear_buffers"].as_bool().unwrap_or(false) {
+        let (keyboard, mouse) = key_mouse::clear_buffers();
+        text.push_str(&format!("，已清空{}条键盘事件与{}条鼠标事件", keyboard, mouse));
+        structured["cleared"] = json!({ "keyboard": keyboard, "mouse": mouse });
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": structured
+    }))
+}
+
+/// 查看或切换键盘监控的隐私策略
+pub fn handle_configure_keyboard_privacy(arguments: &Value) -> Result<Value, JsonRpcError> {
+    if let Some(mode) = arguments["mode"].as_str() {
+        let policy = KeyboardPrivacy::parse(mode).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid mode: {} (expected full, mask, counts or disabled)", mode),
+            data: None,
+        })?;
+        privacy::set_policy(policy);
+    }
+
+    let policy = privacy::policy();
+    let description = match policy {
+        KeyboardPrivacy::Full => "记录完整按键",
+        KeyboardPrivacy::Mask => "字母与数字键记为 *",
+        KeyboardPrivacy::Counts => "只记录事件数量与时间",
+        KeyboardPrivacy::Disabled => "不采集键盘事件，鼠标监控不受影响",
+    };
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!("键盘监控隐私策略: {}（{}）", policy.as_str(), description)
+        }],
+        "structuredContent": { "mode": policy }
+    }))
+}
+
+pub fn handle_scroll_metrics(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let at = match (arguments["x"].as_f64(), arguments["y"].as_f64()) {
+        (Some(x), Some(y)) => Some((x, y)),
+        _ => None,
+    };
+
+    let metrics = accessibility::scroll_metrics(at).map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+
+    let result = json!(metrics);
     let result_text = serde_json::to_string_pretty(&result)
         .unwrap_or_else(|_| result.to_string());
 
@@ -156,23 +1358,382 @@ pub fn handle_monitor_keyboard_events(_arguments: &Value) -> Result<Value, JsonR
         "content": [
             {
                 "type": "text",
-                "text": format!("返回{}条键盘事件（已清空存储）\n\n事件数据：\n{}", 
-                    total, result_text)
+                "text": format!("滚动容器信息：\n{}", result_text)
             }
         ]
     }))
 }
 
-pub fn handle_monitor_mouse_events(_arguments: &Value) -> Result<Value, JsonRpcError> {
-    // 获取所有鼠标事件并清空存储
-    let events = key_mouse::take_mouse_events();
-    
-    let events_json: Vec<Value> = events.iter().map(mouse_event_to_json).collect();
-    let total = events.len();
+/// OCR 的默认最低置信度，低于该值的词通常是噪点
+const DEFAULT_OCR_MIN_CONFIDENCE: f64 = 30.0;
+
+pub fn handle_screen_ocr(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+    let to_rpc = |e: screen::MonitorError| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    };
+    let language = match &arguments["language"] {
+        Value::Null => ocr::default_language(),
+        value => value
+            .as_str()
+            .filter(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '+'))
+            .ok_or_else(|| invalid("language must be tesseract language codes such as eng or chi_sim+eng".to_string()))?
+            .to_string(),
+    };
+    let min_confidence = match &arguments["min_confidence"] {
+        Value::Null => DEFAULT_OCR_MIN_CONFIDENCE,
+        value => value
+            .as_f64()
+            .filter(|c| (0.0..=100.0).contains(c))
+            .ok_or_else(|| invalid("min_confidence must be a number between 0 and 100".to_string()))?,
+    };
+    let upscale = match &arguments["upscale"] {
+        Value::Null => 1,
+        value => value
+            .as_u64()
+            .filter(|v| (1..=postprocess::MAX_UPSCALE as u64).contains(v))
+            .ok_or_else(|| invalid(format!("upscale must be an integer between 1 and {}", postprocess::MAX_UPSCALE)))?
+            as u32,
+    };
+    let contains = arguments["contains"].as_str().map(str::to_lowercase);
+    let region = parse_region(arguments)?;
+
+    let event = screen::capture_frame().map_err(to_rpc)?;
+    let ScreenEventKind::FrameCaptured { width, height, image_data: Some(data), .. } = event.kind else {
+        return Err(JsonRpcError {
+            code: -32001,
+            message: "Frame has no image data".to_string(),
+            data: None,
+        });
+    };
+    let png = if region.is_some() || upscale > 1 {
+        let mut img = postprocess::decode(&data).map_err(to_rpc)?;
+        if let Some(r) = region {
+            img = postprocess::crop(&img, r).map_err(to_rpc)?;
+        }
+        if upscale > 1 {
+            img = postprocess::upscale_by(&img, upscale).map_err(to_rpc)?;
+        }
+        postprocess::encode_png(&img).map_err(to_rpc)?
+    } else {
+        data
+    };
+
+    let mut lines = ocr::recognize(&png, &language, min_confidence).map_err(to_rpc)?;
+    if let Some(needle) = &contains {
+        lines.retain(|line| line.text.to_lowercase().contains(needle));
+    }
+
+    // 识别结果换算回整张截图的像素坐标，并给出可直接用于鼠标工具的全局坐标
+    let (offset_x, offset_y) = region.map_or((0, 0), |r| (r.x, r.y));
+    let to_frame = |b: ocr::TextBox| ocr::TextBox {
+        x: b.x / upscale + offset_x,
+        y: b.y / upscale + offset_y,
+        width: b.width.div_ceil(upscale),
+        height: b.height.div_ceil(upscale),
+    };
+    let main_display = display::get_display(0).ok().filter(|d| d.width > 0 && d.height > 0);
+    let click = |b: &ocr::TextBox| {
+        main_display.as_ref().map(|d| {
+            let (cx, cy) = b.center();
+            json!({
+                "x": d.x + (cx * d.width as f64 / width as f64).round() as i32,
+                "y": d.y + (cy * d.height as f64 / height as f64).round() as i32,
+            })
+        })
+    };
+    let mut summary = Vec::new();
+    let lines_json: Vec<Value> = lines
+        .into_iter()
+        .map(|line| {
+            let bbox = to_frame(line.bbox);
+            let line_click = click(&bbox);
+            summary.push(match &line_click {
+                Some(c) => format!("\"{}\" 点击 ({}, {}) 置信度 {:.0}", line.text, c["x"], c["y"], line.confidence),
+                None => format!(
+                    "\"{}\" 截图 ({}, {}) {}x{} 置信度 {:.0}",
+                    line.text, bbox.x, bbox.y, bbox.width, bbox.height, line.confidence
+                ),
+            });
+            let words: Vec<Value> = line
+                .words
+                .into_iter()
+                .map(|word| {
+                    let bbox = to_frame(word.bbox);
+                    json!({ "text": word.text, "confidence": word.confidence, "bbox": bbox, "click": click(&bbox) })
+                })
+                .collect();
+            json!({ "text": line.text, "confidence": line.confidence, "bbox": bbox, "click": line_click, "words": words })
+        })
+        .collect();
+
+    let text = if summary.is_empty() {
+        "未识别到文字".to_string()
+    } else {
+        format!("识别到 {} 行文字（click 为鼠标工具使用的全局坐标）：\n{}", summary.len(), summary.join("\n"))
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": {
+            "language": language,
+            "width": width,
+            "height": height,
+            "lines": lines_json
+        }
+    }))
+}
+
+pub fn handle_capture_all_displays(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let to_rpc = |e: screen::MonitorError| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    };
+    let max_width = parse_max_dimension(arguments, "max_width")?;
+    let max_height = parse_max_dimension(arguments, "max_height")?;
+
+    let capture = screen::capture_all_displays().map_err(to_rpc)?;
+    let (full_width, full_height) = capture.image.dimensions();
+    let mut img = image::DynamicImage::ImageRgb8(capture.image);
+    if let Some(resized) = postprocess::fit_within(&img, max_width, max_height) {
+        img = resized;
+    }
+    let (width, height) = (img.width(), img.height());
+    let png = postprocess::encode_png(&img).map_err(to_rpc)?;
+    use base64::{Engine as _, engine::general_purpose};
+
+    // 图像像素 × points_per_pixel 为相对 origin 的逻辑坐标
+    let ppp_x = full_width as f64 / width as f64;
+    let ppp_y = full_height as f64 / height as f64;
+    let mut lines = Vec::new();
+    let displays: Vec<Value> = capture
+        .displays
+        .iter()
+        .map(|d| {
+            let offset_x = (d.x - capture.origin_x) as f64 / ppp_x;
+            let offset_y = (d.y - capture.origin_y) as f64 / ppp_y;
+            lines.push(format!(
+                "显示器 {}（ID {}{}）: 全局 ({}, {}) {}x{}，图中偏移 ({:.0}, {:.0})",
+                d.index,
+                d.id,
+                if d.primary { "，主显示器" } else { "" },
+                d.x,
+                d.y,
+                d.width,
+                d.height,
+                offset_x,
+                offset_y
+            ));
+            json!({
+                "index": d.index,
+                "id": d.id,
+                "primary": d.primary,
+                "x": d.x,
+                "y": d.y,
+                "width": d.width,
+                "height": d.height,
+                "scale": d.scale,
+                "image_offset": { "x": offset_x, "y": offset_y },
+                "image_size": { "width": d.width as f64 / ppp_x, "height": d.height as f64 / ppp_y },
+                "captured": !capture.failed.iter().any(|(id, _)| *id == d.id),
+            })
+        })
+        .collect();
+    let failed: Vec<Value> = capture
+        .failed
+        .iter()
+        .map(|(id, reason)| json!({ "id": id, "error": reason }))
+        .collect();
+    for (id, reason) in &capture.failed {
+        lines.push(format!("显示器 ID {} 截图失败（区域为黑色）: {}", id, reason));
+    }
+
+    let text = format!(
+        "已拼接 {} 个显示器的截图\n尺寸: {}x{}\n坐标换算: 全局坐标 = ({}, {}) + 像素坐标 × ({:.4}, {:.4})\n{}",
+        capture.displays.len(),
+        width,
+        height,
+        capture.origin_x,
+        capture.origin_y,
+        ppp_x,
+        ppp_y,
+        lines.join("\n")
+    );
+    Ok(json!({
+        "content": [
+            {
+                "type": "image",
+                "data": general_purpose::STANDARD.encode(&png),
+                "mimeType": "image/png"
+            },
+            { "type": "text", "text": text }
+        ],
+        "structuredContent": {
+            "width": width,
+            "height": height,
+            "origin": { "x": capture.origin_x, "y": capture.origin_y },
+            "points_per_pixel": { "x": ppp_x, "y": ppp_y },
+            "displays": displays,
+            "failed": failed
+        }
+    }))
+}
+
+/// 录制文件的默认目录：环境变量 IRIS_SCREEN_RECORDINGS_DIR，未设置时为系统临时目录下的 `iris-mcp`
+fn screen_recordings_dir() -> PathBuf {
+    std::env::var_os("IRIS_SCREEN_RECORDINGS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("iris-mcp"))
+}
+
+/// 解析录制输出的文件名：只接受不含目录的文件名，写入录制目录且不覆盖已有文件
+fn parse_recording_path(arguments: &Value) -> Result<Option<PathBuf>, JsonRpcError> {
+    let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+    let Some(name) = arguments["path"].as_str() else {
+        return Ok(None);
+    };
+    let mut components = Path::new(name).components();
+    let file_name = match (components.next(), components.next()) {
+        (Some(Component::Normal(file_name)), None) => file_name,
+        _ => {
+            return Err(invalid(format!(
+                "Invalid path: {} (expected a file name; recordings are written to {})",
+                name,
+                screen_recordings_dir().display()
+            )))
+        }
+    };
+    let path = screen_recordings_dir().join(file_name);
+    if path.exists() {
+        return Err(invalid(format!("Recording file already exists: {}", path.display())));
+    }
+    Ok(Some(path))
+}
+
+pub fn handle_screen_record_start(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+    let path = parse_recording_path(arguments)?;
+    let format = match (&arguments["format"], &path) {
+        (Value::Null, Some(path)) => match path.extension().and_then(|e| e.to_str()) {
+            Some(extension) => screen_recording::VideoFormat::parse(extension)
+                .ok_or_else(|| invalid(format!("Unsupported recording extension: .{} (expected .gif or .mp4)", extension)))?,
+            None => screen_recording::VideoFormat::Gif,
+        },
+        (Value::Null, None) => screen_recording::VideoFormat::Gif,
+        (value, _) => value
+            .as_str()
+            .and_then(screen_recording::VideoFormat::parse)
+            .ok_or_else(|| invalid(format!("Invalid format: {} (expected gif or mp4)", value)))?,
+    };
+    let fps = match &arguments["fps"] {
+        Value::Null => screen_recording::DEFAULT_FPS,
+        value => value
+            .as_u64()
+            .filter(|v| (1..=screen_recording::MAX_FPS as u64).contains(v))
+            .ok_or_else(|| invalid(format!("fps must be an integer between 1 and {}", screen_recording::MAX_FPS)))?
+            as u32,
+    };
+    let max_duration = match &arguments["max_duration_secs"] {
+        Value::Null => screen_recording::DEFAULT_MAX_DURATION,
+        value => value
+            .as_f64()
+            .filter(|v| *v > 0.0 && *v <= screen_recording::MAX_DURATION_LIMIT.as_secs_f64())
+            .map(Duration::from_secs_f64)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "max_duration_secs must be a number in (0, {}]",
+                    screen_recording::MAX_DURATION_LIMIT.as_secs()
+                ))
+            })?,
+    };
+    let mut max_width = parse_max_dimension(arguments, "max_width")?;
+    let max_height = parse_max_dimension(arguments, "max_height")?;
+    if format == screen_recording::VideoFormat::Gif && max_width.is_none() && max_height.is_none() {
+        max_width = Some(screen_recording::DEFAULT_GIF_MAX_WIDTH);
+    }
+    let path = path.unwrap_or_else(|| {
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        screen_recordings_dir().join(format!("screen-{}.{}", millis, format.extension()))
+    });
+
+    let options = screen_recording::ScreenRecordOptions {
+        path: path.clone(),
+        format,
+        fps,
+        region: parse_region(arguments)?,
+        max_width,
+        max_height,
+        max_duration,
+    };
+    screen_recording::start(options).map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "开始录制屏幕到 {}（{}，{} fps，最长 {} 秒），完成后调用 screen_record_stop",
+                path.display(),
+                format.extension(),
+                fps,
+                max_duration.as_secs_f64()
+            )
+        }],
+        "structuredContent": {
+            "path": path.display().to_string(),
+            "format": format,
+            "fps": fps,
+            "max_duration_secs": max_duration.as_secs_f64()
+        }
+    }))
+}
+
+pub fn handle_screen_record_stop(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let info = screen_recording::stop().map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+    let stopped = match info.stopped_by {
+        screen_recording::StopReason::Requested => "",
+        screen_recording::StopReason::MaxDuration => "（已达到最长时长自动停止）",
+    };
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "屏幕录制已保存{}\n文件: {}\n尺寸: {}x{}\n时长: {:.1} 秒，{} 帧\n大小: {} bytes",
+                stopped,
+                info.path,
+                info.width,
+                info.height,
+                info.duration_ms as f64 / 1000.0,
+                info.encoded_frames,
+                info.bytes
+            )
+        }],
+        "structuredContent": info
+    }))
+}
+
+pub fn handle_list_displays(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let displays = display::list_displays().map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
 
     let result = json!({
-        "events": events_json,
-        "total": total
+        "displays": displays,
+        "total": displays.len()
     });
     let result_text = serde_json::to_string_pretty(&result)
         .unwrap_or_else(|_| result.to_string());
@@ -181,9 +1742,243 @@ pub fn handle_monitor_mouse_events(_arguments: &Value) -> Result<Value, JsonRpcE
         "content": [
             {
                 "type": "text",
-                "text": format!("返回{}条鼠标事件（已清空存储）\n\n事件数据：\n{}", 
-                    total, result_text)
+                "text": format!("共{}个显示器（index 0 为主显示器）\n\n{}", displays.len(), result_text)
             }
         ]
     }))
 }
+
+pub fn handle_input_source_info(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let info = source::info();
+    let result = json!({
+        "source": info,
+        "monitor_synthetic_flag": "键盘/鼠标监控事件的 synthetic 字段：事件发生在 iris-mcp 注入输入期间（含 50ms 宽限期）时为 true"
+    });
+    let result_text = serde_json::to_string_pretty(&result)
+        .unwrap_or_else(|_| result.to_string());
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("注入输入来源签名 {}\n\n{}", info.signature_hex, result_text)
+            }
+        ],
+        "structuredContent": result
+    }))
+}
+
+pub fn handle_monitor_capabilities(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let capabilities = key_mouse::capabilities();
+    let result = serde_json::to_value(&capabilities).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to serialize capabilities: {}", e),
+        data: None,
+    })?;
+
+    let backend = capabilities.listener.backend.unwrap_or("none");
+    let summary = match (&capabilities.listener.error, capabilities.running) {
+        (Some(error), _) => format!("键鼠监控后端 {} 不可用: {}", backend, error),
+        (None, true) => format!("键鼠监控运行中（后端 {}）", backend),
+        (None, false) => "键鼠监控未在本进程运行（可能已有其他进程在监控）".to_string(),
+    };
+    #[cfg(target_os = "linux")]
+    let summary = if capabilities.wayland_session {
+        format!("{}\nWayland 会话：使用 evdev 后端（需要 input 组权限），尚不支持经门户（libei）捕获", summary)
+    } else {
+        summary
+    };
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": summary }],
+        "structuredContent": result
+    }))
+}
+
+/// 监控线程健康状态；本进程未在采集时同样返回，便于诊断原因
+pub fn handle_monitor_health(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let health = key_mouse::health();
+    let result = serde_json::to_value(&health).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to serialize monitor health: {}", e),
+        data: None,
+    })?;
+
+    let seconds = |micros: u64| micros as f64 / 1_000_000.0;
+    let mut lines = Vec::new();
+    if !health.collector_alive {
+        lines.push("收集线程已退出，事件不会再写入缓冲区".to_string());
+    }
+    if health.listener_alive {
+        lines.push(format!(
+            "监听线程运行中（后端 {}，状态 {:?}）",
+            health.listener.backend.unwrap_or("unknown"),
+            health.state
+        ));
+    } else if let Some(error) = &health.listener.error {
+        lines.push(format!("监听线程已退出: {}", error));
+    } else {
+        lines.push("监听线程未在本进程运行".to_string());
+    }
+    match health.gap_since_micros {
+        Some(since) => lines.push(format!(
+            "监听后端于 {:.1} 秒前退出，正在按退避间隔重启（已重启 {} 次），恢复前的事件缺失",
+            seconds(health.timestamp_micros.saturating_sub(since)),
+            health.listener_restarts
+        )),
+        None if health.listener_restarts > 0 => lines.push(format!(
+            "监听后端曾退出并重启 {} 次，缺失区间以 monitoring_gap 事件标记",
+            health.listener_restarts
+        )),
+        None => {}
+    }
+    match (health.lock.holder_pid, health.lock.held_by_self) {
+        (Some(_), true) => lines.push("监听器锁由本进程持有".to_string()),
+        (Some(pid), false) if health.lock.holder_alive == Some(true) => {
+            lines.push(format!("监听器锁由进程 {} 持有，事件由该进程采集", pid))
+        }
+        (Some(pid), false) => lines.push(format!("监听器锁由已退出的进程 {} 遗留，启动时会回收", pid)),
+        (None, _) => lines.push("没有进程持有监听器锁".to_string()),
+    }
+    if health.listener_alive {
+        lines.push(match health.last_callback_age_micros {
+            Some(age) => format!("最近一次系统输入回调在 {:.1} 秒前", seconds(age)),
+            None => "尚未收到任何系统输入回调；用户正在操作时说明钩子未生效（检查输入监控 / 辅助功能权限）".to_string(),
+        });
+        for (name, device) in [("键盘", &health.keyboard), ("鼠标", &health.mouse)] {
+            lines.push(match (device.capturing, device.last_event_age_micros) {
+                (false, _) => format!("{}未在采集", name),
+                (true, Some(age)) => format!("{}最近事件在 {:.1} 秒前", name, seconds(age)),
+                (true, None) => format!("{}尚无事件", name),
+            });
+        }
+    }
+    if health.dropped_count > 0 {
+        lines.push(format!("通道已满丢弃了{}个事件", health.dropped_count));
+    }
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": lines.join("\n") }],
+        "structuredContent": result
+    }))
+}
+
+pub fn handle_get_keyboard_state(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let state = keyboard_state::keyboard_state();
+
+    let mut summary = if !state.monitoring {
+        "键盘监控未在本进程运行或已关闭，修饰键状态未知".to_string()
+    } else if state.any_modifier() {
+        let names: Vec<&str> = [
+            (state.shift, "Shift"),
+            (state.control, "Ctrl"),
+            (state.alt, "Alt"),
+            (state.meta, "Meta"),
+        ]
+        .iter()
+        .filter(|(down, _)| *down)
+        .map(|(_, name)| *name)
+        .collect();
+        format!("⚠️ 修饰键按下中: {}，此时输入会变成快捷键", names.join("+"))
+    } else {
+        "没有修饰键按下".to_string()
+    };
+    let lock = |state: Option<bool>| match state {
+        Some(true) => "开",
+        Some(false) => "关",
+        None => "未知",
+    };
+    summary.push_str(&format!(
+        "\nCapsLock: {}，NumLock: {}",
+        lock(state.caps_lock),
+        lock(state.num_lock)
+    ));
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": summary
+            }
+        ],
+        "structuredContent": state
+    }))
+}
+
+/// 距最近一次键鼠事件的空闲时间，用于等待用户停止操作后再注入输入
+pub fn handle_get_idle_time(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let idle = key_mouse::idle_time().ok_or_else(|| JsonRpcError {
+        code: -32001,
+        message: "Keyboard/mouse monitor is not running in this process; idle time is unknown".to_string(),
+        data: None,
+    })?;
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "用户已空闲 {:.1} 秒（含注入输入在内空闲 {:.1} 秒）",
+                idle.human_idle_micros as f64 / 1_000_000.0,
+                idle.idle_micros as f64 / 1_000_000.0
+            )
+        }],
+        "structuredContent": idle
+    }))
+}
+
+/// 当前按下的键与鼠标按键，用于在注入前发现卡住的修饰键或用户正在进行的拖拽
+pub fn handle_get_input_snapshot(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let snapshot = key_mouse::input_snapshot().ok_or_else(|| JsonRpcError {
+        code: -32001,
+        message: "Keyboard/mouse monitor is not running in this process; input state is unknown".to_string(),
+        data: None,
+    })?;
+
+    let buttons: Vec<Value> = snapshot
+        .buttons
+        .iter()
+        .map(|held| {
+            let mut value = json!({
+                "button": button_name(held.button),
+                "since_micros": held.since_micros,
+                "held_micros": (snapshot.timestamp_micros as u128).saturating_sub(held.since_micros),
+                "synthetic": held.synthetic,
+            });
+            if let Some((x, y)) = held.press_position {
+                value["press_position"] = json!({ "x": x, "y": y });
+            }
+            value
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    if snapshot.keys.is_empty() {
+        lines.push("没有按键按下".to_string());
+    } else {
+        lines.push(format!("按下的键: {}", snapshot.keys.join(", ")));
+    }
+    if snapshot.buttons.is_empty() {
+        lines.push("没有鼠标按键按下".to_string());
+    } else {
+        let names: Vec<String> = snapshot.buttons.iter().map(|held| button_name(held.button)).collect();
+        lines.push(format!("⚠️ 鼠标按键按下中: {}，用户可能正在拖拽", names.join(", ")));
+    }
+    if let Some((x, y)) = snapshot.cursor {
+        lines.push(format!("光标位置: ({}, {})", x, y));
+    }
+
+    let mut structured = json!({
+        "keys": snapshot.keys,
+        "buttons": buttons,
+        "timestamp_micros": snapshot.timestamp_micros,
+    });
+    if let Some((x, y)) = snapshot.cursor {
+        structured["cursor"] = json!({ "x": x, "y": y });
+    }
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": lines.join("\n")
+        }],
+        "structuredContent": structured
+    }))
+}
@@ -1,7 +1,44 @@
+use super::chaos;
+use super::coords::{self, CoordContext};
 use super::jsonrpc::JsonRpcError;
-use crate::operator::mouse::MouseController;
-use enigo::{Button, Direction, Enigo, Settings};
+use crate::monitor::{display, screen};
+use crate::operator::gesture;
+use crate::operator::locate::{self, Target};
+use crate::operator::mouse::{
+    Easing, MouseController, PathOptions, PathPoint, DEFAULT_CLICK_INTERVAL_MS, MAX_CLICK_COUNT,
+    MAX_CLICK_INTERVAL_MS,
+};
+use crate::operator::source;
+use enigo::{Button, Direction, Enigo};
 use serde_json::{json, Value};
+use std::ops::{Deref, DerefMut, RangeInclusive};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// 滚动前移动光标后等待悬停状态更新的毫秒数
+const SCROLL_HOVER_SETTLE_MS: u64 = 30;
+
+/// 平滑滚动（pixels 模式）的步数上限
+const MAX_SCROLL_STEPS: u64 = 200;
+
+/// 平滑滚动（pixels 模式）的总时长上限（毫秒）；执行期间持有独占执行锁
+const MAX_SCROLL_DURATION_MS: u64 = 10_000;
+
+/// gesture 的事件数上限
+const MAX_GESTURE_STEPS: u64 = 200;
+
+/// gesture 的总时长上限（毫秒）
+const MAX_GESTURE_DURATION_MS: u64 = 10_000;
+
+/// mouse_move_path 的路径点数上限
+const MAX_PATH_POINTS: usize = 500;
+
+/// mouse_move_path 单个路径点的延迟上限（毫秒）
+const MAX_PATH_DELAY_MS: u64 = 5_000;
+
+/// mouse_move_path 的总时长上限（毫秒）；执行期间持有独占执行锁
+const MAX_PATH_DURATION_MS: u64 = 30_000;
 
 pub fn parse_button(s: &str) -> Result<Button, JsonRpcError> {
     match s {
@@ -16,162 +53,306 @@ pub fn parse_button(s: &str) -> Result<Button, JsonRpcError> {
     }
 }
 
-pub fn handle_mouse_move(arguments: &Value) -> Result<Value, JsonRpcError> {
-    let x = arguments["x"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing x".to_string(),
-        data: None,
-    })? as i32;
-    let y = arguments["y"].as_i64().ok_or_else(|| JsonRpcError {
+/// 跨调用复用的鼠标控制器：首次使用时初始化 Enigo，之后各工具调用共享同一实例，
+/// 避免每次调用的初始化开销与高负载下偶发的初始化失败
+static SHARED_MOUSE: OnceLock<Mutex<Option<MouseController<Enigo>>>> = OnceLock::new();
+
+/// 持有共享鼠标控制器的锁；调用期间独占，保证多步操作（如拖拽）不被其他调用打断
+pub(crate) struct SharedMouse(MutexGuard<'static, Option<MouseController<Enigo>>>);
+
+impl Deref for SharedMouse {
+    type Target = MouseController<Enigo>;
+
+    fn deref(&self) -> &MouseController<Enigo> {
+        self.0.as_ref().expect("shared mouse is initialized")
+    }
+}
+
+impl DerefMut for SharedMouse {
+    fn deref_mut(&mut self) -> &mut MouseController<Enigo> {
+        self.0.as_mut().expect("shared mouse is initialized")
+    }
+}
+
+/// 获取共享鼠标控制器；初始化失败时返回错误，下次调用重试
+pub(crate) fn shared_mouse() -> Result<SharedMouse, JsonRpcError> {
+    let mut guard = SHARED_MOUSE
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if guard.is_none() {
+        let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to initialize: {}", e),
+            data: None,
+        })?;
+        *guard = Some(MouseController::new(enigo));
+    }
+    Ok(SharedMouse(guard))
+}
+
+/// 解析可选的整数参数：缺省时为 `default`，不是非负整数或超出 `range` 时返回 -32602
+fn bounded_u64(arguments: &Value, name: &str, default: u64, range: RangeInclusive<u64>) -> Result<u64, JsonRpcError> {
+    let value = match &arguments[name] {
+        Value::Null => Some(default),
+        value => value.as_u64(),
+    };
+    value.filter(|v| range.contains(v)).ok_or_else(|| JsonRpcError {
         code: -32602,
-        message: "Missing y".to_string(),
+        message: format!(
+            "Invalid {}: {} (expected an integer {}-{})",
+            name,
+            arguments[name],
+            range.start(),
+            range.end()
+        ),
         data: None,
-    })? as i32;
+    })
+}
+
+/// 解析 `clicks` 参数（1–3，默认 1）；不是整数时返回 -32602 而不是按单击处理
+fn parse_click_count(arguments: &Value) -> Result<u32, JsonRpcError> {
+    Ok(bounded_u64(arguments, "clicks", 1, 1..=MAX_CLICK_COUNT as u64)? as u32)
+}
+
+/// 解析 `interval_ms` 参数（0–MAX_CLICK_INTERVAL_MS，默认 DEFAULT_CLICK_INTERVAL_MS）
+fn parse_click_interval(arguments: &Value) -> Result<u64, JsonRpcError> {
+    bounded_u64(arguments, "interval_ms", DEFAULT_CLICK_INTERVAL_MS, 0..=MAX_CLICK_INTERVAL_MS)
+}
+
+fn click_count_label(clicks: u32) -> &'static str {
+    match clicks {
+        1 => "点击了",
+        2 => "双击了",
+        _ => "三击了",
+    }
+}
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
+/// 单击、双击、三击的共用实现
+fn click_with_count(
+    mouse: &mut MouseController<Enigo>,
+    x: i32,
+    y: i32,
+    button: Button,
+    clicks: u32,
+    interval_ms: u64,
+) -> Result<(), JsonRpcError> {
+    if chaos::drop_click() {
+        // 模拟丢失的点击：光标到位但不产生按键事件
+        return mouse.mouse_move(x, y).map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to move mouse: {}", e),
+            data: None,
+        });
+    }
+    mouse.mouse_multi_click(x, y, button, clicks, interval_ms).map_err(|e| JsonRpcError {
         code: -32603,
-        message: format!("Failed to initialize: {}", e),
+        message: format!("Failed to click: {}", e),
         data: None,
-    })?;
-    let mut mouse = MouseController::new(enigo);
+    })
+}
+
+/// 构造鼠标工具的返回值：附带操作后重新读取的光标位置，
+/// 以便调用方发现系统对坐标的截断或忽略。
+fn mouse_result(mouse: &MouseController<Enigo>, text: String, requested: Option<(i32, i32)>) -> Value {
+    let cursor = mouse.mouse_get_position().ok();
+
+    let mut structured = json!({
+        "cursor": cursor.map(|(x, y)| json!({ "x": x, "y": y })),
+    });
+    if let Some((rx, ry)) = requested {
+        structured["requested"] = json!({ "x": rx, "y": ry });
+        structured["position_matches"] = json!(cursor.map(|c| c == (rx, ry)));
+    }
+
+    let text = match cursor {
+        Some((cx, cy)) => format!("{}\n当前光标位置: ({}, {})", text, cx, cy),
+        None => text,
+    };
+
+    json!({
+        "content": [{
+            "type": "text",
+            "text": text
+        }],
+        "structuredContent": structured
+    })
+}
+
+pub fn handle_mouse_move(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let (x, y) = coords::resolve_point(arguments)?;
+
+    let mut mouse = shared_mouse()?;
     mouse.mouse_move(x, y).map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to move mouse: {}", e),
         data: None,
     })?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("鼠标已移动到 ({}, {})", x, y)
-        }]
-    }))
+    Ok(mouse_result(&mouse, format!("鼠标已移动到 ({}, {})", x, y), Some((x, y))))
 }
 
 pub fn handle_mouse_click(arguments: &Value) -> Result<Value, JsonRpcError> {
-    let x = arguments["x"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing x".to_string(),
-        data: None,
-    })? as i32;
-    let y = arguments["y"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing y".to_string(),
-        data: None,
-    })? as i32;
+    let (x, y) = coords::resolve_point(arguments)?;
     let btn_str = arguments["button"].as_str().unwrap_or("left");
     let button = parse_button(btn_str)?;
+    let clicks = parse_click_count(arguments)?;
+    let interval_ms = parse_click_interval(arguments)?;
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
-        data: None,
-    })?;
-    let mut mouse = MouseController::new(enigo);
-    mouse.mouse_click(x, y, button).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to click: {}", e),
-        data: None,
-    })?;
+    let mut mouse = shared_mouse()?;
+    click_with_count(&mut mouse, x, y, button, clicks, interval_ms)?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("在 ({}, {}) 点击了 {} 键", x, y, btn_str)
-        }]
-    }))
+    Ok(mouse_result(
+        &mouse,
+        format!("在 ({}, {}) {} {} 键", x, y, click_count_label(clicks), btn_str),
+        Some((x, y)),
+    ))
 }
 
 pub fn handle_mouse_double_click(arguments: &Value) -> Result<Value, JsonRpcError> {
-    let x = arguments["x"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing x".to_string(),
-        data: None,
-    })? as i32;
-    let y = arguments["y"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing y".to_string(),
-        data: None,
-    })? as i32;
+    let (x, y) = coords::resolve_point(arguments)?;
     let btn_str = arguments["button"].as_str().unwrap_or("left");
     let button = parse_button(btn_str)?;
+    let interval_ms = parse_click_interval(arguments)?;
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
-        data: None,
-    })?;
-    let mut mouse = MouseController::new(enigo);
-    mouse.mouse_double_click(x, y, button).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to double click: {}", e),
-        data: None,
-    })?;
+    let mut mouse = shared_mouse()?;
+    click_with_count(&mut mouse, x, y, button, 2, interval_ms)?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("在 ({}, {}) 双击了 {} 键", x, y, btn_str)
-        }]
-    }))
+    Ok(mouse_result(
+        &mouse,
+        format!("在 ({}, {}) 双击了 {} 键", x, y, btn_str),
+        Some((x, y)),
+    ))
+}
+
+/// 可选的 `x`/`y`：指定时先移动光标并等待悬停状态更新，
+/// 确保滚动或手势落在目标面板而非当前悬停区域
+fn hover_at(mouse: &mut MouseController<Enigo>, arguments: &Value) -> Result<Option<(i32, i32)>, JsonRpcError> {
+    let at = match (arguments["x"].as_f64(), arguments["y"].as_f64()) {
+        (Some(_), Some(_)) => Some(coords::resolve_point(arguments)?),
+        (None, None) => None,
+        _ => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: "x and y must be provided together".to_string(),
+                data: None,
+            });
+        }
+    };
+    if let Some((x, y)) = at {
+        mouse.mouse_move(x, y).map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to move mouse: {}", e),
+            data: None,
+        })?;
+        thread::sleep(Duration::from_millis(SCROLL_HOVER_SETTLE_MS));
+    }
+    Ok(at)
 }
 
 pub fn handle_mouse_scroll(arguments: &Value) -> Result<Value, JsonRpcError> {
-    let lines_x = arguments["lines_x"].as_i64().unwrap_or(0) as i32;
-    let lines_y = arguments["lines_y"].as_i64().unwrap_or(0) as i32;
+    let mode = arguments["mode"].as_str().unwrap_or("lines");
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
-        data: None,
-    })?;
-    let mut mouse = MouseController::new(enigo);
-    mouse.mouse_scroll(lines_x, lines_y).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to scroll: {}", e),
-        data: None,
-    })?;
+    let mut mouse = shared_mouse()?;
+    let at = hover_at(&mut mouse, arguments)?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("滚动 ({}, {})", lines_x, lines_y)
-        }]
-    }))
+    let text = match mode {
+        "lines" => {
+            let lines_x = arguments["lines_x"].as_i64().unwrap_or(0) as i32;
+            let lines_y = arguments["lines_y"].as_i64().unwrap_or(0) as i32;
+            mouse.mouse_scroll(lines_x, lines_y).map_err(|e| JsonRpcError {
+                code: -32603,
+                message: format!("Failed to scroll: {}", e),
+                data: None,
+            })?;
+            format!("滚动 ({}, {})", lines_x, lines_y)
+        }
+        "pixels" => {
+            let distance_x = arguments["distance_x"].as_i64().unwrap_or(0) as i32;
+            let distance_y = arguments["distance_y"].as_i64().unwrap_or(0) as i32;
+            let duration_ms = bounded_u64(arguments, "duration_ms", 0, 0..=MAX_SCROLL_DURATION_MS)?;
+            let steps = bounded_u64(arguments, "steps", 10, 1..=MAX_SCROLL_STEPS)? as u32;
+            mouse
+                .mouse_smooth_scroll(distance_x, distance_y, steps, duration_ms)
+                .map_err(|e| JsonRpcError {
+                    code: -32603,
+                    message: format!("Failed to scroll: {}", e),
+                    data: None,
+                })?;
+            format!(
+                "平滑滚动 ({}, {}) 像素，{} 步，耗时 {}ms",
+                distance_x, distance_y, steps, duration_ms
+            )
+        }
+        _ => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid mode: {}", mode),
+                data: None,
+            });
+        }
+    };
+
+    let text = match at {
+        Some((x, y)) => format!("在 ({}, {}) {}", x, y, text),
+        None => text,
+    };
+
+    Ok(mouse_result(&mouse, text, at))
 }
 
 pub fn handle_mouse_get_position(_arguments: &Value) -> Result<Value, JsonRpcError> {
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
-        data: None,
-    })?;
-    let mouse = MouseController::new(enigo);
+    let mouse = shared_mouse()?;
     let (x, y) = mouse.mouse_get_position().map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to get position: {}", e),
         data: None,
     })?;
 
+    let mut text = format!("当前鼠标位置: ({}, {})", x, y);
+    let mut structured = json!({
+        "cursor": { "x": x, "y": y }
+    });
+
+    // 光标所在显示器及显示器本地坐标
+    if let Ok(displays) = display::list_displays()
+        && let Some(d) = displays.iter().find(|d| d.contains(x, y))
+    {
+        let (px, py) = d.to_physical(x as f64, y as f64);
+        text.push_str(&format!("\n显示器: {}（本地坐标 ({}, {})）", d.index, x - d.x, y - d.y));
+        if d.scale != 1.0 {
+            text.push_str(&format!("\n物理像素: ({}, {})，缩放 {}x", px, py, d.scale));
+        }
+        structured["display"] = json!({
+            "index": d.index,
+            "local": { "x": x - d.x, "y": y - d.y },
+            "scale": d.scale,
+        });
+        structured["physical"] = json!({ "x": px, "y": py });
+    }
+
+    // 光标下像素颜色，读取失败时省略
+    match screen::pixel_color(x, y) {
+        Ok([r, g, b]) => {
+            let hex = format!("#{:02x}{:02x}{:02x}", r, g, b);
+            text.push_str(&format!("\n像素颜色: {} (rgb {}, {}, {})", hex, r, g, b));
+            structured["color"] = json!({ "r": r, "g": g, "b": b, "hex": hex });
+        }
+        Err(e) => eprintln!("pixel color unavailable: {}", e),
+    }
+
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": format!("当前鼠标位置: ({}, {})", x, y)
-        }]
+            "text": text
+        }],
+        "structuredContent": structured
     }))
 }
 
 pub fn handle_mouse_drag(arguments: &Value) -> Result<Value, JsonRpcError> {
-    let target_x = arguments["target_x"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing target_x".to_string(),
-        data: None,
-    })? as i32;
-    let target_y = arguments["target_y"].as_i64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing target_y".to_string(),
-        data: None,
-    })? as i32;
+    let ctx = coords::parse_context(arguments)?;
+    let (target_x, target_y) = ctx.point(arguments, "target_x", "target_y")?;
     let button_str = arguments["button"].as_str().ok_or_else(|| JsonRpcError {
         code: -32602,
         message: "Missing button".to_string(),
@@ -179,24 +360,18 @@ pub fn handle_mouse_drag(arguments: &Value) -> Result<Value, JsonRpcError> {
     })?;
     let button = parse_button(button_str)?;
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
-        data: None,
-    })?;
-    let mut mouse = MouseController::new(enigo);
+    let mut mouse = shared_mouse()?;
     mouse.mouse_drag(target_x, target_y, button).map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to drag: {}", e),
         data: None,
     })?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("已拖拽鼠标到 ({}, {}) 使用{}键", target_x, target_y, button_str)
-        }]
-    }))
+    Ok(mouse_result(
+        &mouse,
+        format!("已拖拽鼠标到 ({}, {}) 使用{}键", target_x, target_y, button_str),
+        Some((target_x, target_y)),
+    ))
 }
 
 pub fn handle_mouse_button_control(arguments: &Value) -> Result<Value, JsonRpcError> {
@@ -222,24 +397,14 @@ pub fn handle_mouse_button_control(arguments: &Value) -> Result<Value, JsonRpcEr
         }),
     };
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
-        data: None,
-    })?;
-    let mut mouse = MouseController::new(enigo);
+    let mut mouse = shared_mouse()?;
     mouse.mouse_button_control(button, direction).map_err(|e| JsonRpcError {
         code: -32603,
         message: format!("Failed to control button: {}", e),
         data: None,
     })?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("已执行鼠标{}键{}", button_str, direction_str)
-        }]
-    }))
+    Ok(mouse_result(&mouse, format!("已执行鼠标{}键{}", button_str, direction_str), None))
 }
 
 pub fn handle_mouse_move_path(arguments: &Value) -> Result<Value, JsonRpcError> {
@@ -248,43 +413,184 @@ pub fn handle_mouse_move_path(arguments: &Value) -> Result<Value, JsonRpcError>
         message: "Missing or invalid points".to_string(),
         data: None,
     })?;
-    let speed_ms = arguments["speed_ms"].as_u64().ok_or_else(|| JsonRpcError {
-        code: -32602,
-        message: "Missing speed_ms".to_string(),
-        data: None,
-    })?;
+    if points_array.len() > MAX_PATH_POINTS {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("Too many points: {} (at most {})", points_array.len(), MAX_PATH_POINTS),
+            data: None,
+        });
+    }
+    if arguments["speed_ms"].is_null() {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: "Missing speed_ms".to_string(),
+            data: None,
+        });
+    }
+    let speed_ms = bounded_u64(arguments, "speed_ms", 0, 0..=MAX_PATH_DELAY_MS)?;
+
+    let easing = match arguments["easing"].as_str() {
+        Some(name) => Easing::parse(name).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Invalid easing: {}", name),
+            data: None,
+        })?,
+        None => Easing::Linear,
+    };
+    let max_step_px = arguments["max_step_px"].as_u64().map(|v| v as u32);
 
+    let ctx = coords::parse_context(arguments)?;
     let mut points = Vec::new();
     for point in points_array {
-        let x = point["x"].as_i64().ok_or_else(|| JsonRpcError {
+        let (x, y) = ctx.point(point, "x", "y")?;
+        let delay_ms = bounded_u64(point, "delay_ms", speed_ms, 0..=MAX_PATH_DELAY_MS)?;
+        points.push(PathPoint { x, y, delay_ms });
+    }
+    let total_ms: u64 = points.iter().map(|p| p.delay_ms).sum();
+    if total_ms > MAX_PATH_DURATION_MS {
+        return Err(JsonRpcError {
             code: -32602,
-            message: "Invalid point x coordinate".to_string(),
+            message: format!("Path too long: {}ms in total (at most {}ms)", total_ms, MAX_PATH_DURATION_MS),
+            data: None,
+        });
+    }
+
+    let mut mouse = shared_mouse()?;
+    mouse
+        .mouse_move_path_with(&points, PathOptions { easing, max_step_px })
+        .map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to move path: {}", e),
             data: None,
-        })? as i32;
-        let y = point["y"].as_i64().ok_or_else(|| JsonRpcError {
+        })?;
+
+    Ok(mouse_result(
+        &mouse,
+        format!("已沿路径移动鼠标，共{}个点", points.len()),
+        points.last().map(|p| (p.x, p.y)),
+    ))
+}
+
+/// 解析 smart_click 的 target 参数
+pub(crate) fn parse_target(value: &Value, ctx: &CoordContext) -> Result<Target, JsonRpcError> {
+    if !value.is_object() {
+        return Err(JsonRpcError {
             code: -32602,
-            message: "Invalid point y coordinate".to_string(),
+            message: "Missing or invalid target".to_string(),
             data: None,
-        })? as i32;
-        points.push((x, y));
+        });
     }
 
-    let enigo = Enigo::new(&Settings::default()).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to initialize: {}", e),
+    let template = match value["template"].as_str() {
+        Some(encoded) => {
+            use base64::{Engine as _, engine::general_purpose};
+            Some(general_purpose::STANDARD.decode(encoded).map_err(|e| JsonRpcError {
+                code: -32602,
+                message: format!("Invalid template base64: {}", e),
+                data: None,
+            })?)
+        }
+        None => None,
+    };
+    let point = match (value["x"].is_number(), value["y"].is_number()) {
+        (true, true) => Some(ctx.point(value, "x", "y")?),
+        _ => None,
+    };
+
+    Ok(Target {
+        selector: value["selector"].as_str().map(str::to_string),
+        text: value["text"].as_str().map(str::to_string),
+        template,
+        point,
+        window: value["window"].as_str().map(str::to_string),
+    })
+}
+
+pub fn handle_smart_click(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let ctx = coords::parse_context(arguments)?;
+    let target = parse_target(&arguments["target"], &ctx)?;
+    let btn_str = arguments["button"].as_str().unwrap_or("left");
+    let button = parse_button(btn_str)?;
+    let clicks = parse_click_count(arguments)?;
+    let refresh = arguments["refresh"].as_bool().unwrap_or(false);
+
+    let located = locate::locate(&target, refresh).map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
         data: None,
     })?;
-    let mut mouse = MouseController::new(enigo);
-    mouse.mouse_move_path(&points, speed_ms).map_err(|e| JsonRpcError {
-        code: -32603,
-        message: format!("Failed to move path: {}", e),
+
+    let mut mouse = shared_mouse()?;
+    click_with_count(&mut mouse, located.x, located.y, button, clicks, DEFAULT_CLICK_INTERVAL_MS)?;
+
+    Ok(mouse_result(
+        &mouse,
+        format!(
+            "通过 {} 策略定位到 ({}, {}){}，{} {} 键",
+            located.strategy.as_str(),
+            located.x,
+            located.y,
+            if located.from_cache { "（缓存位置）" } else { "" },
+            click_count_label(clicks),
+            btn_str
+        ),
+        Some((located.x, located.y)),
+    ))
+}
+
+pub fn handle_gesture(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let kind = arguments["type"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing type".to_string(),
         data: None,
     })?;
+    let steps = bounded_u64(arguments, "steps", 20, 1..=MAX_GESTURE_STEPS)? as u32;
+    let duration_ms = bounded_u64(arguments, "duration_ms", 300, 0..=MAX_GESTURE_DURATION_MS)?;
 
-    Ok(json!({
-        "content": [{
-            "type": "text",
-            "text": format!("已沿路径移动鼠标，共{}个点", points.len())
-        }]
-    }))
+    let mut mouse = shared_mouse()?;
+    let at = hover_at(&mut mouse, arguments)?;
+
+    let (result, text) = match kind {
+        "pinch_in" | "pinch_out" => {
+            let magnitude = arguments["magnitude"].as_f64().unwrap_or(0.5).abs();
+            let magnification = if kind == "pinch_in" { -magnitude } else { magnitude };
+            (
+                gesture::pinch(magnification, steps, duration_ms),
+                format!("捏合缩放 {:+.2}", magnification),
+            )
+        }
+        "rotate" => {
+            let degrees = arguments["degrees"].as_f64().unwrap_or(45.0);
+            (
+                gesture::rotate(degrees, steps, duration_ms),
+                format!("旋转 {:.1}°", degrees),
+            )
+        }
+        "swipe" => {
+            let dx = arguments["dx"].as_i64().unwrap_or(0) as i32;
+            let dy = arguments["dy"].as_i64().unwrap_or(0) as i32;
+            (
+                gesture::swipe(&mut mouse, dx, dy, steps, duration_ms),
+                format!("双指滑动 ({}, {}) 像素", dx, dy),
+            )
+        }
+        _ => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: format!("Invalid type: {}", kind),
+                data: None,
+            });
+        }
+    };
+    result.map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+
+    let text = match at {
+        Some((x, y)) => format!("在 ({}, {}) {}", x, y, text),
+        None => text,
+    };
+    Ok(mouse_result(&mouse, text, at))
 }
@@ -0,0 +1,32 @@
+//! 服务器主动推送的 JSON-RPC 通知（资源更新、进度等）。
+//!
+//! 通知需要可推送的传输：stdio 传输在启动时注册发送函数；HTTP 传输没有推送通道，通知被丢弃。
+
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+type Notifier = Box<dyn Fn(Value) + Send + Sync>;
+
+/// 服务器推送通知的出口，由支持推送的传输在启动时注册
+static NOTIFIER: OnceLock<Notifier> = OnceLock::new();
+
+/// 注册通知发送函数（每个进程仅首次注册生效）
+pub fn set_notifier(notifier: impl Fn(Value) + Send + Sync + 'static) {
+    let _ = NOTIFIER.set(Box::new(notifier));
+}
+
+/// 当前传输是否支持推送通知
+pub fn is_available() -> bool {
+    NOTIFIER.get().is_some()
+}
+
+/// 发送一条通知；未注册发送函数时忽略
+pub fn send(method: &str, params: Value) {
+    if let Some(notifier) = NOTIFIER.get() {
+        notifier(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        }));
+    }
+}
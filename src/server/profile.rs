@@ -0,0 +1,59 @@
+//! 输入节奏配置工具处理
+
+use super::jsonrpc::JsonRpcError;
+use crate::operator::pacing::{self, InputProfile, MAX_DELAY_SCALE, MAX_TYPING_DELAY_MS};
+use serde_json::{json, Value};
+
+/// 切换全局输入节奏：选择预设并可单独覆盖倍率与默认字符间隔；不带参数时返回当前配置
+pub fn handle_set_input_profile(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let mut profile = match arguments["profile"].as_str() {
+        Some(name) => InputProfile::preset(name).ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: format!("Unknown profile: {} (expected fast, normal or human)", name),
+            data: None,
+        })?,
+        None => pacing::current(),
+    };
+
+    if !arguments["delay_scale"].is_null() {
+        let scale = arguments["delay_scale"]
+            .as_f64()
+            .filter(|s| (0.0..=MAX_DELAY_SCALE).contains(s))
+            .ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: format!("delay_scale must be a number between 0 and {}", MAX_DELAY_SCALE),
+                data: None,
+            })?;
+        profile.delay_scale = scale;
+        profile.name = "custom";
+    }
+    for (field, target) in [
+        ("typing_delay_ms", &mut profile.typing_delay_ms),
+        ("typing_variance_ms", &mut profile.typing_variance_ms),
+    ] {
+        if arguments[field].is_null() {
+            continue;
+        }
+        *target = arguments[field]
+            .as_u64()
+            .filter(|ms| *ms <= MAX_TYPING_DELAY_MS)
+            .ok_or_else(|| JsonRpcError {
+                code: -32602,
+                message: format!("{} must be an integer between 0 and {}", field, MAX_TYPING_DELAY_MS),
+                data: None,
+            })?;
+        profile.name = "custom";
+    }
+
+    let profile = pacing::set(profile);
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "输入节奏: {}（延迟倍率 {}，默认字符间隔 {}±{}ms）",
+                profile.name, profile.delay_scale, profile.typing_delay_ms, profile.typing_variance_ms
+            )
+        }],
+        "structuredContent": profile
+    }))
+}
@@ -0,0 +1,97 @@
+//! 长时间工具调用的进度通知与取消。
+//!
+//! `tools/call` 执行期间，当前线程记录请求 id 与客户端提供的 `_meta.progressToken`。
+//! 工具可通过 `report` 发送 `notifications/progress`，并在安全点检查 `is_cancelled`，
+//! 以响应客户端发送的 `notifications/cancelled`。
+
+use super::notify;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// 正在执行的请求
+struct RequestContext {
+    key: String,
+    progress_token: Option<Value>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<RequestContext>> = const { RefCell::new(None) };
+}
+
+/// 进行中的请求 id -> 是否已被取消
+fn active_requests() -> &'static Mutex<HashMap<String, bool>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<String, bool>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 请求 id 统一按 JSON 文本比较（数字 1 与字符串 "1" 视为不同请求）
+fn request_key(id: &Value) -> String {
+    id.to_string()
+}
+
+/// 请求执行期间的上下文，离开作用域时注销
+pub(crate) struct RequestGuard;
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        if let Some(ctx) = CURRENT.with(|c| c.borrow_mut().take()) {
+            active_requests().lock().unwrap().remove(&ctx.key);
+        }
+    }
+}
+
+/// 登记当前线程正在执行的请求
+pub(crate) fn begin(id: Option<&Value>, params: Option<&Value>) -> RequestGuard {
+    let key = request_key(id.unwrap_or(&Value::Null));
+    let progress_token = params
+        .map(|p| &p["_meta"]["progressToken"])
+        .filter(|t| t.is_string() || t.is_number())
+        .cloned();
+    active_requests().lock().unwrap().insert(key.clone(), false);
+    CURRENT.with(|c| *c.borrow_mut() = Some(RequestContext { key, progress_token }));
+    RequestGuard
+}
+
+/// 处理 `notifications/cancelled`；只对进行中的请求生效
+pub fn handle_cancelled(params: Option<Value>) {
+    let Some(id) = params.as_ref().map(|p| &p["requestId"]).filter(|id| !id.is_null()) else {
+        return;
+    };
+    if let Some(cancelled) = active_requests().lock().unwrap().get_mut(&request_key(id)) {
+        *cancelled = true;
+        eprintln!("[progress] request {} cancelled", id);
+    }
+}
+
+/// 当前请求是否已被客户端取消
+pub fn is_cancelled() -> bool {
+    CURRENT.with(|c| {
+        c.borrow().as_ref().is_some_and(|ctx| {
+            active_requests()
+                .lock()
+                .unwrap()
+                .get(&ctx.key)
+                .copied()
+                .unwrap_or(false)
+        })
+    })
+}
+
+/// 发送当前请求的进度通知；客户端未提供 progressToken 时忽略
+pub fn report(progress: u64, total: Option<u64>, message: &str) {
+    let token = CURRENT.with(|c| c.borrow().as_ref().and_then(|ctx| ctx.progress_token.clone()));
+    let Some(token) = token else {
+        return;
+    };
+    let mut params = json!({
+        "progressToken": token,
+        "progress": progress,
+        "message": message
+    });
+    if let Some(total) = total {
+        params["total"] = json!(total);
+    }
+    notify::send("notifications/progress", params);
+}
@@ -0,0 +1,161 @@
+//! 录制与回放工具处理：`recording_start` 开始录制键鼠输入，`recording_stop` 停止并按名称保存，
+//! `recording_replay` 按原有时序（可调速度）经操作层回放。
+//! 录制保存在 JSON 文件中，跨会话保留：路径取环境变量 IRIS_RECORDINGS_FILE，
+//! 未设置时为用户配置目录下的 `iris-mcp/recordings.json`。
+
+use super::jsonrpc::JsonRpcError;
+use super::mouse::shared_mouse;
+use super::progress;
+use super::store::{invalid, require_name, Store};
+use crate::monitor::key_mouse;
+use crate::monitor::recorder::{self, RecordOptions, Recording};
+use crate::operator::keyboard::KeyboardController;
+use crate::operator::replay::{self, MAX_REPLAY_SPEED, MIN_REPLAY_SPEED};
+use crate::operator::source;
+use enigo::Enigo;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+const STORE: Store = Store {
+    env_var: "IRIS_RECORDINGS_FILE",
+    file_name: "recordings.json",
+    section: "recordings",
+};
+
+/// 串行化录制文件的读-改-写
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn handle_recording_start(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let name = require_name(arguments)?;
+    let overwrite = arguments["overwrite"].as_bool().unwrap_or(false);
+    if !overwrite && STORE.load()?.contains_key(name) {
+        return Err(invalid(format!("Recording {} already exists; pass overwrite=true to replace it", name)));
+    }
+    let defaults = RecordOptions::default();
+    let options = RecordOptions {
+        keyboard: arguments["keyboard"].as_bool().unwrap_or(defaults.keyboard),
+        mouse: arguments["mouse"].as_bool().unwrap_or(defaults.mouse),
+        mouse_moves: arguments["mouse_moves"].as_bool().unwrap_or(defaults.mouse_moves),
+        include_synthetic: arguments["include_synthetic"].as_bool().unwrap_or(defaults.include_synthetic),
+    };
+    if !options.keyboard && !options.mouse {
+        return Err(invalid("At least one of keyboard and mouse must be recorded".to_string()));
+    }
+
+    key_mouse::initialize();
+    if !key_mouse::is_running() {
+        return Err(JsonRpcError {
+            code: -32001,
+            message: "Keyboard/mouse monitor is not running in this process; nothing can be recorded".to_string(),
+            data: None,
+        });
+    }
+    recorder::start(name, options).map_err(|current| JsonRpcError {
+        code: -32001,
+        message: format!("Recording {} is in progress; stop it first", current),
+        data: None,
+    })?;
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("开始录制 {}，完成后调用 recording_stop 保存", name) }],
+        "structuredContent": { "name": name, "recording": true }
+    }))
+}
+
+pub fn handle_recording_stop(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let (name, recording) = recorder::stop().ok_or_else(|| JsonRpcError {
+        code: -32001,
+        message: "No recording in progress".to_string(),
+        data: None,
+    })?;
+    let summary = json!({
+        "name": name,
+        "event_count": recording.events.len(),
+        "duration_ms": recording.duration_micros / 1000,
+        "truncated": recording.truncated
+    });
+
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut recordings = STORE.load()?;
+    recordings.insert(
+        name.clone(),
+        serde_json::to_value(&recording).map_err(|e| JsonRpcError {
+            code: -32603,
+            message: e.to_string(),
+            data: None,
+        })?,
+    );
+    STORE.save(recordings)?;
+
+    let mut text = format!(
+        "已保存录制 {}（{} 个事件，{:.1} 秒）",
+        name,
+        recording.events.len(),
+        recording.duration_micros as f64 / 1_000_000.0
+    );
+    if recording.truncated {
+        text.push_str(&format!("，已达到事件上限 {}，之后的输入未录制", recorder::MAX_RECORDED_EVENTS));
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": summary
+    }))
+}
+
+pub fn handle_recording_replay(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let name = require_name(arguments)?;
+    let speed = match &arguments["speed"] {
+        Value::Null => 1.0,
+        value => value
+            .as_f64()
+            .filter(|s| (MIN_REPLAY_SPEED..=MAX_REPLAY_SPEED).contains(s))
+            .ok_or_else(|| {
+                invalid(format!("speed must be a number between {} and {}", MIN_REPLAY_SPEED, MAX_REPLAY_SPEED))
+            })?,
+    };
+    let recording: Recording = STORE
+        .load()?
+        .remove(name)
+        .ok_or_else(|| invalid(format!("Unknown recording: {}", name)))
+        .and_then(|value| {
+            serde_json::from_value(value).map_err(|e| JsonRpcError {
+                code: -32603,
+                message: format!("Invalid recording {}: {}", name, e),
+                data: None,
+            })
+        })?;
+
+    let enigo = Enigo::new(&source::settings()).map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to initialize: {}", e),
+        data: None,
+    })?;
+    let mut keyboard = KeyboardController::new(enigo);
+    let mut mouse = shared_mouse()?;
+    let mut last_reported = 0;
+    let report = replay::replay(&mut keyboard, &mut mouse, &recording, speed, |done, total| {
+        if done != last_reported {
+            last_reported = done;
+            progress::report(done as u64, Some(total as u64), &format!("已回放 {}/{} 个事件", done, total));
+        }
+        !progress::is_cancelled()
+    })
+    .map_err(|e| JsonRpcError {
+        code: -32603,
+        message: format!("Failed to replay {}: {}", name, e),
+        data: None,
+    })?;
+
+    let mut text = if report.stopped {
+        format!("回放已取消: {}（已回放 {}/{} 个事件）", name, report.replayed, report.total)
+    } else {
+        format!("已回放录制 {}（{} 个事件，速度 {}x）", name, report.replayed, speed)
+    };
+    if report.skipped > 0 {
+        text.push_str(&format!("，{} 个事件在当前平台无法注入已跳过", report.skipped));
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": report
+    }))
+}
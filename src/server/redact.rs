@@ -0,0 +1,109 @@
+//! 敏感参数脱敏：`sensitive: true` 的工具参数（如 type_text 输入的密码）不出现在响应与日志中，
+//! 只保留长度与指纹，便于核对输入是否一致而不泄露内容。键盘监控中对应的按键由
+//! `privacy::sensitive_scope` 在输入期间按 `counts` 策略脱敏。
+//! 脚本经 `{{var}}` 传入敏感步骤的变量同样视为敏感，在日志、返回的 `variables` 与错误数据中脱敏。
+
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::OnceLock;
+
+/// 文本指纹（16 位十六进制），以进程启动时随机生成的密钥计算：同一进程内可比对，
+/// 不同进程之间不可比，也无法离线穷举短密码反推原文
+pub fn fingerprint(text: &str) -> String {
+    static KEY: OnceLock<RandomState> = OnceLock::new();
+    format!("{:016x}", KEY.get_or_init(RandomState::new).hash_one(text))
+}
+
+/// 敏感文本的摘要：字符数与指纹
+pub fn summary(text: &str) -> Value {
+    json!({
+        "redacted": true,
+        "length": text.chars().count(),
+        "fingerprint": fingerprint(text)
+    })
+}
+
+/// 脚本步骤中带 `sensitive: true` 的对象在 `text` 里引用的变量名（`{{name.path}}` 取 `name`）
+pub fn sensitive_variables(steps: &Value) -> HashSet<String> {
+    fn collect(value: &Value, names: &mut HashSet<String>) {
+        match value {
+            Value::Object(map) => {
+                if map.get("sensitive").and_then(Value::as_bool) == Some(true)
+                    && let Some(text) = map.get("text").and_then(Value::as_str)
+                {
+                    let mut rest = text;
+                    while let Some(start) = rest.find("{{") {
+                        let Some(len) = rest[start + 2..].find("}}") else {
+                            break;
+                        };
+                        let path = &rest[start + 2..start + 2 + len];
+                        names.insert(path.split('.').next().unwrap_or_default().trim().to_string());
+                        rest = &rest[start + 2 + len + 2..];
+                    }
+                }
+                map.values().for_each(|v| collect(v, names));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect(v, names)),
+            _ => {}
+        }
+    }
+    let mut names = HashSet::new();
+    collect(steps, &mut names);
+    names
+}
+
+/// 把 `variables` 中的敏感变量替换为摘要
+pub fn redact_variables(variables: &mut Map<String, Value>, names: &HashSet<String>) {
+    for name in names {
+        if let Some(value) = variables.get_mut(name) {
+            let text = match &*value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            *value = summary(&text);
+        }
+    }
+}
+
+/// 递归替换带 `sensitive: true` 的对象中的 `text` 字段（含脚本中嵌套的步骤），
+/// 以及脚本参数中被敏感步骤引用的 `variables`
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(steps) = map.get("steps") {
+                let names = sensitive_variables(steps);
+                if let Some(Value::Object(variables)) = map.get_mut("variables") {
+                    redact_variables(variables, &names);
+                }
+            }
+            if map.get("sensitive").and_then(Value::as_bool) == Some(true)
+                && let Some(text) = map.get("text").and_then(Value::as_str)
+            {
+                let summary = summary(text);
+                map.insert("text".to_string(), summary);
+            }
+            for v in map.values_mut() {
+                redact_value(v);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// 用于日志输出的消息文本：含敏感参数时返回脱敏后的 JSON，否则原样返回
+pub fn for_log(line: &str) -> String {
+    if !line.contains("sensitive") {
+        return line.to_string();
+    }
+    match serde_json::from_str::<Value>(line) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        // 无法解析时不记录原文，避免泄露
+        Err(_) => format!("<unparseable message, {} bytes>", line.len()),
+    }
+}
@@ -0,0 +1,504 @@
+//! MCP 资源：`screen://latest` 提供最新屏幕截图，`screen://subscription/{id}` 提供帧订阅的最新一帧。
+//!
+//! 客户端订阅 `screen://latest` 后，后台线程按固定间隔截图（环境变量 IRIS_SCREEN_WATCH_INTERVAL_MS，默认 1000ms），
+//! 画面变化时更新缓存并发送 `notifications/resources/updated`，便于宿主显示实时预览。
+//!
+//! `screen_subscribe` 工具创建独立的帧订阅：按请求的间隔或帧率截图并做与 monitor_screen_events 相同的后处理，
+//! 写入该订阅的资源并发送 `notifications/resources/updated`，监控面板读取资源即可，无需轮询截图工具。
+//! 通知需要可推送的传输（stdio）；HTTP 传输下订阅不会产生通知，但仍可读取资源。
+//!
+//! monitor_screen_events 的缩略图模式把完整截图保存为 `screen://frame/{id}`，只内联缩略图，
+//! 客户端按需读取原图；只保留最近 `MAX_STORED_FRAMES` 帧。
+use super::jsonrpc::JsonRpcError;
+use super::monitor::postprocess_capture;
+use super::notify;
+use crate::monitor::key_mouse::now_micros;
+use crate::monitor::screen::{self, ScreenEventKind};
+use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const SCREEN_LATEST_URI: &str = "screen://latest";
+
+/// 帧订阅资源的 URI 前缀，后接订阅 ID
+pub const SCREEN_SUBSCRIPTION_URI_PREFIX: &str = "screen://subscription/";
+
+/// 完整截图资源的 URI 前缀，后接帧 ID
+pub const SCREEN_FRAME_URI_PREFIX: &str = "screen://frame/";
+
+/// 保留的完整截图资源数
+const MAX_STORED_FRAMES: usize = 8;
+
+/// 同时存在的帧订阅数上限
+const MAX_FRAME_SUBSCRIPTIONS: usize = 4;
+
+/// 帧订阅允许的最高帧率
+const MAX_SUBSCRIPTION_FPS: f64 = 10.0;
+
+/// 默认截图间隔（毫秒）
+const DEFAULT_WATCH_INTERVAL_MS: u64 = 1000;
+
+/// 允许的最小截图间隔（毫秒），避免占满 CPU
+const MIN_WATCH_INTERVAL_MS: u64 = 100;
+
+/// 后台截图线程的状态
+struct Watcher {
+    running: Arc<AtomicBool>,
+}
+
+fn watcher() -> &'static Mutex<Option<Watcher>> {
+    static WATCHER: OnceLock<Mutex<Option<Watcher>>> = OnceLock::new();
+    WATCHER.get_or_init(|| Mutex::new(None))
+}
+
+/// 最近一次截图（PNG）
+fn latest_frame() -> &'static Mutex<Option<Vec<u8>>> {
+    static LATEST: OnceLock<Mutex<Option<Vec<u8>>>> = OnceLock::new();
+    LATEST.get_or_init(|| Mutex::new(None))
+}
+
+/// 缩略图模式保存的完整截图
+struct StoredFrame {
+    id: u64,
+    png: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// 已保存的完整截图，按保存顺序排列
+fn stored_frames() -> MutexGuard<'static, VecDeque<StoredFrame>> {
+    static FRAMES: OnceLock<Mutex<VecDeque<StoredFrame>>> = OnceLock::new();
+    FRAMES.get_or_init(|| Mutex::new(VecDeque::new())).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 保存一帧完整截图，返回其资源 URI；超出上限时丢弃最早的帧
+pub(crate) fn store_frame(png: Vec<u8>, width: u32, height: u32) -> String {
+    static NEXT_FRAME_ID: AtomicU64 = AtomicU64::new(1);
+    let id = NEXT_FRAME_ID.fetch_add(1, Ordering::Relaxed);
+    let mut frames = stored_frames();
+    while frames.len() >= MAX_STORED_FRAMES {
+        frames.pop_front();
+    }
+    frames.push_back(StoredFrame { id, png, width, height });
+    format!("{}{}", SCREEN_FRAME_URI_PREFIX, id)
+}
+
+fn watch_interval() -> Duration {
+    let ms = env::var("IRIS_SCREEN_WATCH_INTERVAL_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_WATCH_INTERVAL_MS)
+        .max(MIN_WATCH_INTERVAL_MS);
+    Duration::from_millis(ms)
+}
+
+fn capture_png() -> Result<Vec<u8>, JsonRpcError> {
+    capture_frame_png().map(|(data, _, _)| data)
+}
+
+/// 截取一帧，返回 (PNG, 宽, 高)
+fn capture_frame_png() -> Result<(Vec<u8>, u32, u32), JsonRpcError> {
+    let event = screen::capture_frame().map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+    match event.kind {
+        ScreenEventKind::FrameCaptured { width, height, image_data: Some(data), .. } => Ok((data, width, height)),
+        _ => Err(JsonRpcError {
+            code: -32001,
+            message: "Frame has no image data".to_string(),
+            data: None,
+        }),
+    }
+}
+
+fn frame_hash(png: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    png.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn start_watcher() {
+    let mut slot = watcher().lock().unwrap();
+    if slot.is_some() {
+        return;
+    }
+    let running = Arc::new(AtomicBool::new(true));
+    let running_clone = running.clone();
+    let interval = watch_interval();
+
+    thread::Builder::new()
+        .name("screen-watch".to_string())
+        .spawn(move || {
+            let mut last_hash = None;
+            while running_clone.load(Ordering::SeqCst) {
+                match capture_png() {
+                    Ok(png) => {
+                        let hash = frame_hash(&png);
+                        if last_hash != Some(hash) {
+                            last_hash = Some(hash);
+                            *latest_frame().lock().unwrap() = Some(png);
+                            notify::send(
+                                "notifications/resources/updated",
+                                json!({ "uri": SCREEN_LATEST_URI }),
+                            );
+                        }
+                    }
+                    Err(e) => eprintln!("[screen-watch] capture failed: {}", e.message),
+                }
+                thread::sleep(interval);
+            }
+        })
+        .expect("Failed to start screen watch thread");
+
+    *slot = Some(Watcher { running });
+}
+
+fn stop_watcher() {
+    if let Some(w) = watcher().lock().unwrap().take() {
+        w.running.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 资源 URI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScreenResource {
+    Latest,
+    Subscription(u64),
+    Frame(u64),
+}
+
+fn require_uri(params: &Option<Value>) -> Result<(String, ScreenResource), JsonRpcError> {
+    let uri = params
+        .as_ref()
+        .and_then(|p| p["uri"].as_str())
+        .ok_or_else(|| JsonRpcError {
+            code: -32602,
+            message: "Missing uri".to_string(),
+            data: None,
+        })?;
+    let resource = if uri == SCREEN_LATEST_URI {
+        Some(ScreenResource::Latest)
+    } else {
+        uri.strip_prefix(SCREEN_SUBSCRIPTION_URI_PREFIX)
+            .and_then(|id| id.parse().ok())
+            .filter(|id| frame_subscriptions().contains_key(id))
+            .map(ScreenResource::Subscription)
+            .or_else(|| {
+                uri.strip_prefix(SCREEN_FRAME_URI_PREFIX)
+                    .and_then(|id| id.parse().ok())
+                    .filter(|id| stored_frames().iter().any(|frame| frame.id == *id))
+                    .map(ScreenResource::Frame)
+            })
+    };
+    let resource = resource.ok_or_else(|| JsonRpcError {
+        code: -32002,
+        message: format!("Resource not found: {}", uri),
+        data: None,
+    })?;
+    Ok((uri.to_string(), resource))
+}
+
+pub fn handle_list_resources(_params: Option<Value>) -> Value {
+    let mut resources = vec![json!({
+        "uri": SCREEN_LATEST_URI,
+        "name": "最新屏幕截图",
+        "description": "主显示器的最新截图；订阅后按间隔刷新并在画面变化时发送 resources/updated 通知",
+        "mimeType": "image/png"
+    })];
+    for (id, subscription) in frame_subscriptions().iter() {
+        resources.push(json!({
+            "uri": subscription_uri(*id),
+            "name": format!("屏幕帧订阅 {}", id),
+            "description": format!(
+                "screen_subscribe 创建的帧订阅，每 {} ms 截图，新帧写入后发送 resources/updated 通知",
+                subscription.interval.as_millis()
+            ),
+            "mimeType": "image/png"
+        }));
+    }
+    for frame in stored_frames().iter() {
+        resources.push(json!({
+            "uri": format!("{}{}", SCREEN_FRAME_URI_PREFIX, frame.id),
+            "name": format!("屏幕截图 {}", frame.id),
+            "description": format!("monitor_screen_events 缩略图模式保存的完整截图（{}x{}）", frame.width, frame.height),
+            "mimeType": "image/png"
+        }));
+    }
+    json!({ "resources": resources })
+}
+
+pub fn handle_read_resource(params: Option<Value>) -> Result<Value, JsonRpcError> {
+    let (uri, resource) = require_uri(&params)?;
+
+    let png = match resource {
+        ScreenResource::Latest => {
+            // 订阅中优先返回后台线程缓存的帧，否则即时截图
+            let cached = if watcher().lock().unwrap().is_some() {
+                latest_frame().lock().unwrap().clone()
+            } else {
+                None
+            };
+            match cached {
+                Some(png) => png,
+                None => capture_png()?,
+            }
+        }
+        ScreenResource::Subscription(id) => {
+            let latest = frame_subscriptions().get(&id).map(|s| s.latest.clone());
+            match latest.and_then(|latest| latest.lock().unwrap().clone()) {
+                Some(png) => png,
+                None => {
+                    return Err(JsonRpcError {
+                        code: -32001,
+                        message: format!("Subscription {} has not captured a frame yet", id),
+                        data: None,
+                    });
+                }
+            }
+        }
+        ScreenResource::Frame(id) => {
+            let png = stored_frames().iter().find(|frame| frame.id == id).map(|frame| frame.png.clone());
+            png.ok_or_else(|| JsonRpcError {
+                code: -32002,
+                message: format!("Resource not found: {}", uri),
+                data: None,
+            })?
+        }
+    };
+
+    use base64::{Engine as _, engine::general_purpose};
+    Ok(json!({
+        "contents": [
+            {
+                "uri": uri,
+                "mimeType": "image/png",
+                "blob": general_purpose::STANDARD.encode(&png)
+            }
+        ]
+    }))
+}
+
+/// resources/subscribe：`screen://latest` 启动后台截图；帧订阅资源本身已在推送，无需额外操作
+pub fn handle_subscribe(params: Option<Value>) -> Result<Value, JsonRpcError> {
+    // 没有推送通道（HTTP 传输）时订阅收不到 notifications/resources/updated，直接拒绝
+    if !notify::is_available() {
+        return Err(JsonRpcError {
+            code: -32001,
+            message: "Resource subscriptions require a transport with server notifications (stdio or bridge); \
+                      poll resources/read over HTTP"
+                .to_string(),
+            data: None,
+        });
+    }
+    if require_uri(&params)?.1 == ScreenResource::Latest {
+        start_watcher();
+    }
+    Ok(json!({}))
+}
+
+/// resources/unsubscribe：帧订阅需通过 screen_unsubscribe 停止
+pub fn handle_unsubscribe(params: Option<Value>) -> Result<Value, JsonRpcError> {
+    if require_uri(&params)?.1 == ScreenResource::Latest {
+        stop_watcher();
+        *latest_frame().lock().unwrap() = None;
+    }
+    Ok(json!({}))
+}
+
+// ============================================================
+// 帧订阅（screen_subscribe）
+// ============================================================
+
+struct FrameSubscription {
+    running: Arc<AtomicBool>,
+    /// 最新一帧（PNG）
+    latest: Arc<Mutex<Option<Vec<u8>>>>,
+    interval: Duration,
+}
+
+fn frame_subscriptions() -> MutexGuard<'static, BTreeMap<u64, FrameSubscription>> {
+    static SUBSCRIPTIONS: OnceLock<Mutex<BTreeMap<u64, FrameSubscription>>> = OnceLock::new();
+    SUBSCRIPTIONS
+        .get_or_init(|| Mutex::new(BTreeMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+static NEXT_FRAME_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn subscription_uri(id: u64) -> String {
+    format!("{}{}", SCREEN_SUBSCRIPTION_URI_PREFIX, id)
+}
+
+/// 由 `interval_ms` 或 `fps` 得到截图间隔；两者都未指定时使用 IRIS_SCREEN_WATCH_INTERVAL_MS
+fn subscription_interval(arguments: &Value) -> Result<Duration, JsonRpcError> {
+    let invalid = |message: &str| JsonRpcError { code: -32602, message: message.to_string(), data: None };
+    let min_interval =
+        Duration::from_secs_f64(1.0 / MAX_SUBSCRIPTION_FPS).max(Duration::from_millis(MIN_WATCH_INTERVAL_MS));
+    let interval = match (&arguments["interval_ms"], &arguments["fps"]) {
+        (Value::Null, Value::Null) => watch_interval(),
+        (interval_ms, Value::Null) => {
+            Duration::from_millis(interval_ms.as_u64().ok_or_else(|| invalid("interval_ms must be a positive integer"))?)
+        }
+        (Value::Null, fps) => {
+            let fps = fps.as_f64().filter(|f| *f > 0.0).ok_or_else(|| invalid("fps must be a positive number"))?;
+            Duration::from_secs_f64(1.0 / fps)
+        }
+        _ => return Err(invalid("Specify either interval_ms or fps, not both")),
+    };
+    Ok(interval.max(min_interval))
+}
+
+/// 帧订阅线程：按间隔截图、后处理，有新帧时写入缓存并通知
+fn run_frame_subscription(
+    id: u64,
+    arguments: Value,
+    interval: Duration,
+    only_changes: bool,
+    mut last_hash: Option<u64>,
+    running: Arc<AtomicBool>,
+    latest: Arc<Mutex<Option<Vec<u8>>>>,
+) {
+    let uri = subscription_uri(id);
+    // 创建订阅时截取的首帧序号为 1
+    let mut sequence = 1u64;
+    while running.load(Ordering::SeqCst) {
+        let started = Instant::now();
+        let frame = capture_frame_png()
+            .and_then(|(data, width, height)| postprocess_capture(&arguments, data, width, height));
+        match frame {
+            Ok((png, width, height, _)) => {
+                let hash = frame_hash(&png);
+                if !only_changes || last_hash != Some(hash) {
+                    last_hash = Some(hash);
+                    sequence += 1;
+                    *latest.lock().unwrap() = Some(png);
+                    notify::send(
+                        "notifications/resources/updated",
+                        json!({
+                            "uri": uri,
+                            "subscription_id": id,
+                            "sequence": sequence,
+                            "width": width,
+                            "height": height,
+                            "timestamp_micros": now_micros(),
+                        }),
+                    );
+                }
+            }
+            Err(e) => eprintln!("[screen-subscription {}] capture failed: {}", id, e.message),
+        }
+        // 扣除截图与编码耗时，尽量保持请求的帧率
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+/// 创建帧订阅，返回订阅 ID 与资源 URI
+pub fn handle_screen_subscribe(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let interval = subscription_interval(arguments)?;
+    let only_changes = arguments["only_changes"].as_bool().unwrap_or(true);
+
+    // 先截一帧校验截图与后处理参数，避免后台线程反复失败
+    let (data, width, height) = capture_frame_png()?;
+    let (png, width, height, applied) = postprocess_capture(arguments, data, width, height)?;
+
+    let mut subscriptions = frame_subscriptions();
+    if subscriptions.len() >= MAX_FRAME_SUBSCRIPTIONS {
+        return Err(JsonRpcError {
+            code: -32001,
+            message: format!("Too many frame subscriptions (max {}); unsubscribe first", MAX_FRAME_SUBSCRIPTIONS),
+            data: None,
+        });
+    }
+    let id = NEXT_FRAME_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed);
+    let running = Arc::new(AtomicBool::new(true));
+    let first_hash = frame_hash(&png);
+    let latest = Arc::new(Mutex::new(Some(png)));
+    let thread_arguments = arguments.clone();
+    let (thread_running, thread_latest) = (running.clone(), latest.clone());
+    thread::Builder::new()
+        .name(format!("screen-subscription-{}", id))
+        .spawn(move || {
+            // 首帧已在创建时截取
+            thread::sleep(interval);
+            run_frame_subscription(
+                id,
+                thread_arguments,
+                interval,
+                only_changes,
+                Some(first_hash),
+                thread_running,
+                thread_latest,
+            )
+        })
+        .map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to start frame subscription thread: {}", e),
+            data: None,
+        })?;
+    subscriptions.insert(id, FrameSubscription { running, latest, interval });
+    drop(subscriptions);
+
+    let uri = subscription_uri(id);
+    let push = if notify::is_available() {
+        "新帧写入后发送 notifications/resources/updated 通知"
+    } else {
+        "当前传输不支持推送通知，请读取资源获取最新帧"
+    };
+    let processing = if applied.is_empty() {
+        String::new()
+    } else {
+        format!("\n处理: {}", applied.join("，"))
+    };
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "已创建帧订阅 {}，每 {} ms 截图（{}x{}），最新帧写入资源 {}；{}{}",
+                id,
+                interval.as_millis(),
+                width,
+                height,
+                uri,
+                push,
+                processing
+            )
+        }],
+        "structuredContent": {
+            "subscription_id": id,
+            "uri": uri,
+            "interval_ms": interval.as_millis() as u64,
+            "only_changes": only_changes,
+            "notifications": notify::is_available(),
+            "width": width,
+            "height": height
+        }
+    }))
+}
+
+/// 停止帧订阅并移除其资源
+pub fn handle_screen_unsubscribe(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let id = arguments["subscription_id"].as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing or invalid 'subscription_id' parameter".to_string(),
+        data: None,
+    })?;
+    let subscription = frame_subscriptions().remove(&id).ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: format!("Unknown frame subscription: {}", id),
+        data: None,
+    })?;
+    subscription.running.store(false, Ordering::SeqCst);
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("已取消帧订阅 {}", id) }],
+        "structuredContent": { "subscription_id": id, "unsubscribed": true }
+    }))
+}
@@ -0,0 +1,673 @@
+//! 导出工具 JSON Schema 与服务器 OpenRPC 描述，供其他语言生成客户端或做契约测试。
+
+use super::tools_list;
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// tools/call 的返回结构；工具返回 structuredContent 时附带其 schema，否则不含该字段
+pub fn tool_output_schema(tool: &str) -> Value {
+    let mut schema = json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "type": "object",
+        "properties": {
+            "content": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "type": { "type": "string", "enum": ["text", "image"] },
+                        "text": { "type": "string" },
+                        "data": { "type": "string", "description": "Base64 编码的图像数据" },
+                        "mimeType": { "type": "string" }
+                    },
+                    "required": ["type"]
+                }
+            }
+        },
+        "required": ["content"]
+    });
+    if let Some(structured) = structured_content_schema(tool) {
+        schema["properties"]["structuredContent"] = structured;
+    }
+    schema
+}
+
+fn point() -> Value {
+    json!({
+        "type": "object",
+        "properties": { "x": { "type": "number" }, "y": { "type": "number" } },
+        "required": ["x", "y"]
+    })
+}
+
+/// 鼠标工具共用：操作后重新读取的光标位置及请求的位置
+fn mouse_result_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "cursor": { "oneOf": [point(), { "type": "null" }], "description": "操作后的光标位置，读取失败时为 null" },
+            "requested": point(),
+            "position_matches": { "type": ["boolean", "null"], "description": "光标是否停在请求的位置" }
+        },
+        "required": ["cursor"]
+    })
+}
+
+/// monitor_keyboard_events / monitor_mouse_events 的事件批次
+fn event_batch_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "events": { "type": "array", "items": { "type": "object" }, "description": "事件（带序号 seq）" },
+            "total": { "type": "integer" },
+            "next_cursor": { "type": "integer", "description": "下一次读取的起始序号" },
+            "missed": { "type": "integer", "description": "已被缓冲区覆盖、未能读取的事件数" },
+            "evicted_count": { "type": "integer" },
+            "dropped_count": { "type": "integer" },
+            "covered_since_micros": { "type": "integer" },
+            "covered_until_micros": { "type": "integer" }
+        },
+        "required": ["events", "total", "next_cursor", "missed"]
+    })
+}
+
+fn subscription_schema(extra: Value) -> Value {
+    let mut schema = json!({
+        "type": "object",
+        "properties": { "subscription_id": { "type": "integer" } },
+        "required": ["subscription_id"]
+    });
+    if let (Some(properties), Value::Object(extra)) = (schema["properties"].as_object_mut(), extra) {
+        properties.extend(extra);
+    }
+    schema
+}
+
+/// 各工具 structuredContent 的 schema；不返回结构化结果的工具为 None
+fn structured_content_schema(tool: &str) -> Option<Value> {
+    let schema = match tool {
+        "mouse_move" | "mouse_click" | "mouse_double_click" | "mouse_scroll" | "mouse_drag"
+        | "mouse_button_control" | "mouse_move_path" | "smart_click" | "gesture" => mouse_result_schema(),
+        "mouse_get_position" => json!({
+            "type": "object",
+            "properties": {
+                "cursor": point(),
+                "display": {
+                    "type": "object",
+                    "properties": {
+                        "index": { "type": "integer" },
+                        "local": point(),
+                        "scale": { "type": "number" }
+                    }
+                },
+                "physical": point(),
+                "color": {
+                    "type": "object",
+                    "properties": {
+                        "r": { "type": "integer" },
+                        "g": { "type": "integer" },
+                        "b": { "type": "integer" },
+                        "hex": { "type": "string" }
+                    }
+                }
+            },
+            "required": ["cursor"]
+        }),
+        "type_text" => json!({
+            "type": "object",
+            "properties": {
+                "typed_chars": { "type": "integer" },
+                "total_chars": { "type": "integer" },
+                "cancelled": { "type": "boolean" },
+                "pasted_chars": { "type": "integer", "description": "经剪贴板粘贴的辅助平面字符数" },
+                "text": {
+                    "type": "object",
+                    "description": "sensitive 时的脱敏摘要",
+                    "properties": {
+                        "redacted": { "type": "boolean" },
+                        "length": { "type": "integer" },
+                        "fingerprint": { "type": "string" }
+                    }
+                }
+            },
+            "required": ["typed_chars", "total_chars", "cancelled"]
+        }),
+        "paste_text" => json!({
+            "type": "object",
+            "properties": {
+                "pasted_chars": { "type": "integer" },
+                "clipboard_restored": { "type": "boolean" }
+            },
+            "required": ["pasted_chars", "clipboard_restored"]
+        }),
+        "key_combo" => json!({
+            "type": "object",
+            "description": "仅在进行了快捷键冲突检测时返回",
+            "properties": {
+                "shortcut_check": {
+                    "type": "object",
+                    "properties": {
+                        "conflicts": { "type": "array", "items": { "type": "object" } },
+                        "registry_checked": { "type": "boolean" },
+                        "note": { "type": "string" }
+                    }
+                }
+            }
+        }),
+        "release_all_inputs" => json!({
+            "type": "object",
+            "properties": {
+                "released": { "type": "array", "items": { "type": "string" } },
+                "failed": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["released", "failed"]
+        }),
+        "set_input_profile" => json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "delay_scale": { "type": "number" },
+                "typing_delay_ms": { "type": "integer" },
+                "typing_variance_ms": { "type": "integer" }
+            },
+            "required": ["name", "delay_scale", "typing_delay_ms", "typing_variance_ms"]
+        }),
+        "monitor_screen_events" => json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "description": "截图",
+                    "properties": {
+                        "width": { "type": "integer" },
+                        "height": { "type": "integer" },
+                        "coordinate_mapping": { "type": ["object", "null"] },
+                        "thumbnail": {
+                            "type": ["object", "null"],
+                            "properties": { "width": { "type": "integer" }, "height": { "type": "integer" } }
+                        },
+                        "frame_uri": { "type": ["string", "null"], "description": "缩略图模式下完整截图的资源 URI" }
+                    },
+                    "required": ["width", "height"]
+                },
+                {
+                    "type": "object",
+                    "description": "skip_unchanged 且画面未变化",
+                    "properties": {
+                        "unchanged": { "const": true },
+                        "since_micros": { "type": "integer" },
+                        "since": { "type": "string" },
+                        "difference": { "type": "number" }
+                    },
+                    "required": ["unchanged", "since_micros"]
+                }
+            ]
+        }),
+        "monitor_keyboard_events" | "monitor_mouse_events" => event_batch_schema(),
+        "monitor_gestures" => {
+            let mut schema = event_batch_schema();
+            schema["properties"]
+                .as_object_mut()?
+                .remove("events");
+            schema["properties"]["gestures"] = json!({ "type": "array", "items": { "type": "object" } });
+            schema["required"] = json!(["gestures", "total", "next_cursor", "missed"]);
+            schema
+        }
+        "monitor_ack_events" => json!({
+            "type": "object",
+            "properties": {
+                "consumer": { "type": "string" },
+                "device": { "type": "string", "enum": ["keyboard", "mouse"] },
+                "cursor": { "type": "integer" },
+                "trimmed": { "type": "integer" },
+                "released": { "type": "boolean", "description": "release 为 true 时：消费者是否已登记并被注销" }
+            },
+            "required": ["consumer"]
+        }),
+        "query_event_log" => json!({
+            "type": "object",
+            "properties": {
+                "events": { "type": "array", "items": { "type": "object" }, "description": "事件（带 device 字段）" },
+                "total": { "type": "integer" },
+                "truncated": { "type": "boolean" }
+            },
+            "required": ["events", "total", "truncated"]
+        }),
+        "configure_event_buffers" => json!({
+            "type": "object",
+            "properties": {
+                "keyboard": { "type": "object" },
+                "mouse": { "type": "object" },
+                "dropped_count": { "type": "integer" }
+            },
+            "required": ["keyboard", "mouse", "dropped_count"]
+        }),
+        "monitor_control" => json!({
+            "type": "object",
+            "properties": {
+                "state": { "type": "string", "enum": ["running", "paused", "stopped"] },
+                "cleared": {
+                    "type": "object",
+                    "properties": { "keyboard": { "type": "integer" }, "mouse": { "type": "integer" } }
+                }
+            },
+            "required": ["state"]
+        }),
+        "configure_keyboard_privacy" => json!({
+            "type": "object",
+            "properties": { "mode": { "type": "string", "enum": ["full", "mask", "counts", "disabled"] } },
+            "required": ["mode"]
+        }),
+        "screen_ocr" => json!({
+            "type": "object",
+            "properties": {
+                "language": { "type": ["string", "null"] },
+                "width": { "type": "integer" },
+                "height": { "type": "integer" },
+                "lines": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "text": { "type": "string" },
+                            "confidence": { "type": "number" },
+                            "bbox": { "type": "object" },
+                            "click": { "oneOf": [point(), { "type": "null" }] },
+                            "words": { "type": "array", "items": { "type": "object" } }
+                        }
+                    }
+                }
+            },
+            "required": ["width", "height", "lines"]
+        }),
+        "capture_all_displays" => json!({
+            "type": "object",
+            "properties": {
+                "width": { "type": "integer" },
+                "height": { "type": "integer" },
+                "origin": point(),
+                "points_per_pixel": point(),
+                "displays": { "type": "array", "items": { "type": "object" } },
+                "failed": { "type": "array", "items": { "type": "object" } }
+            },
+            "required": ["width", "height", "origin", "points_per_pixel", "displays"]
+        }),
+        "screen_record_start" => json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "format": { "type": "string" },
+                "fps": { "type": "integer" },
+                "max_duration_secs": { "type": "number" }
+            },
+            "required": ["path", "format", "fps", "max_duration_secs"]
+        }),
+        "screen_record_stop" => json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "format": { "type": "string" },
+                "width": { "type": "integer" },
+                "height": { "type": "integer" },
+                "fps": { "type": "integer" },
+                "captured_frames": { "type": "integer" },
+                "encoded_frames": { "type": "integer" },
+                "duration_ms": { "type": "integer" },
+                "bytes": { "type": "integer" },
+                "stopped_by": { "type": "string" }
+            },
+            "required": ["path", "width", "height", "duration_ms", "bytes"]
+        }),
+        "input_source_info" => json!({
+            "type": "object",
+            "properties": {
+                "source": {
+                    "type": "object",
+                    "properties": {
+                        "signature": { "type": "integer" },
+                        "signature_hex": { "type": "string" },
+                        "events_tagged": { "type": "boolean" },
+                        "field": { "type": ["string", "null"] },
+                        "note": { "type": "string" }
+                    }
+                },
+                "monitor_synthetic_flag": { "type": "string" }
+            },
+            "required": ["source"]
+        }),
+        "monitor_capabilities" => json!({
+            "type": "object",
+            "description": "另含监听后端状态字段（backend、error 等）",
+            "properties": {
+                "running": { "type": "boolean" },
+                "state": { "type": "string" },
+                "mouse_sampling": { "type": "object" },
+                "wayland_session": { "type": "boolean", "description": "仅 Linux" }
+            },
+            "required": ["running", "state"]
+        }),
+        "monitor_health" => json!({
+            "type": "object",
+            "description": "另含监听后端状态字段（backend、error 等）",
+            "properties": {
+                "state": { "type": "string" },
+                "listener_alive": { "type": "boolean" },
+                "collector_alive": { "type": "boolean" },
+                "last_callback_micros": { "type": ["integer", "null"] },
+                "last_callback_age_micros": { "type": ["integer", "null"] },
+                "event_count": { "type": "integer" },
+                "dropped_count": { "type": "integer" },
+                "listener_restarts": { "type": "integer" },
+                "gap_since_micros": { "type": ["integer", "null"] },
+                "keyboard": { "type": "object" },
+                "mouse": { "type": "object" },
+                "lock": { "type": "object" }
+            },
+            "required": ["state", "listener_alive", "collector_alive"]
+        }),
+        "get_keyboard_state" => json!({
+            "type": "object",
+            "properties": {
+                "monitoring": { "type": "boolean" },
+                "shift": { "type": "boolean" },
+                "control": { "type": "boolean" },
+                "alt": { "type": "boolean" },
+                "meta": { "type": "boolean" },
+                "held_keys": { "type": "array", "items": { "type": "string" } },
+                "caps_lock": { "type": ["boolean", "null"] },
+                "num_lock": { "type": ["boolean", "null"] }
+            },
+            "required": ["monitoring", "shift", "control", "alt", "meta", "held_keys"]
+        }),
+        "get_idle_time" => json!({
+            "type": "object",
+            "properties": {
+                "idle_micros": { "type": "integer" },
+                "human_idle_micros": { "type": "integer" },
+                "last_event_micros": { "type": "integer" },
+                "last_human_event_micros": { "type": "integer" }
+            },
+            "required": ["idle_micros", "human_idle_micros"]
+        }),
+        "get_input_snapshot" => json!({
+            "type": "object",
+            "properties": {
+                "keys": { "type": "array", "items": { "type": "string" } },
+                "buttons": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "button": { "type": "string" },
+                            "since_micros": { "type": "integer" },
+                            "held_micros": { "type": "integer" },
+                            "synthetic": { "type": "boolean" },
+                            "press_position": point()
+                        }
+                    }
+                },
+                "timestamp_micros": { "type": "integer" },
+                "cursor": point()
+            },
+            "required": ["keys", "buttons", "timestamp_micros"]
+        }),
+        "monitor_subscribe" => subscription_schema(json!({
+            "notification": { "type": "string" },
+            "filter": { "type": "object" },
+            "display_monitor_error": { "type": ["string", "null"] }
+        })),
+        "screen_subscribe" => subscription_schema(json!({
+            "uri": { "type": "string" },
+            "interval_ms": { "type": "integer" },
+            "only_changes": { "type": "boolean" },
+            "notifications": { "type": "boolean", "description": "当前传输能否推送资源更新通知" },
+            "width": { "type": "integer" },
+            "height": { "type": "integer" }
+        })),
+        "monitor_unsubscribe" | "screen_unsubscribe" => subscription_schema(json!({
+            "unsubscribed": { "type": "boolean" }
+        })),
+        "window_at_point" => json!({
+            "type": "object",
+            "properties": {
+                "point": point(),
+                "window": { "type": "object", "description": "窗口信息，同 list_windows" },
+                "local": point(),
+                "focused": { "type": ["boolean", "null"] }
+            },
+            "required": ["point", "window", "local"]
+        }),
+        "wait_for_window" => json!({
+            "type": "object",
+            "properties": {
+                "state": { "type": "string" },
+                "matched": { "type": "boolean" },
+                "timed_out": { "type": "boolean" },
+                "waited_ms": { "type": "integer" },
+                "windows": { "type": "array", "items": { "type": "object" } }
+            },
+            "required": ["matched", "timed_out", "waited_ms", "windows"]
+        }),
+        "execute_script" | "macro_run" => json!({
+            "type": "object",
+            "properties": {
+                "steps": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "step": { "type": "string" },
+                            "tool": { "type": "string" },
+                            "ok": { "type": "boolean" },
+                            "text": { "type": "string" },
+                            "error": { "type": "string" },
+                            "saved_as": { "type": "string" }
+                        },
+                        "required": ["step", "tool", "ok"]
+                    }
+                },
+                "variables": { "type": "object" }
+            },
+            "required": ["steps", "variables"]
+        }),
+        "macro_list" => json!({
+            "type": "object",
+            "properties": {
+                "macros": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "description": { "type": ["string", "null"] },
+                            "step_count": { "type": "integer" }
+                        }
+                    }
+                }
+            },
+            "required": ["macros"]
+        }),
+        "recording_start" => json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" }, "recording": { "type": "boolean" } },
+            "required": ["name", "recording"]
+        }),
+        "recording_stop" => json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "event_count": { "type": "integer" },
+                "duration_ms": { "type": "integer" },
+                "truncated": { "type": "boolean" }
+            },
+            "required": ["name", "event_count", "duration_ms", "truncated"]
+        }),
+        "recording_replay" => json!({
+            "type": "object",
+            "properties": {
+                "replayed": { "type": "integer" },
+                "skipped": { "type": "integer" },
+                "total": { "type": "integer" },
+                "stopped": { "type": "boolean" }
+            },
+            "required": ["replayed", "skipped", "total", "stopped"]
+        }),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// 根据工具列表生成 OpenRPC 文档：列出服务器处理的全部方法，每个工具另映射为一个 `tools/call` 的具名变体
+pub fn openrpc_document() -> Value {
+    let tools = tools_list::get_tools_list();
+    let tools = tools["tools"].as_array().cloned().unwrap_or_default();
+
+    let tool_methods: Vec<Value> = tools
+        .iter()
+        .map(|tool| {
+            let name = tool["name"].as_str().unwrap_or_default();
+            json!({
+                "name": format!("tools/call#{}", name),
+                "summary": tool["description"],
+                "paramStructure": "by-name",
+                "params": [
+                    { "name": "name", "required": true, "schema": { "const": name } },
+                    { "name": "arguments", "required": true, "schema": tool["inputSchema"] }
+                ],
+                "result": { "name": "result", "schema": tool_output_schema(name) }
+            })
+        })
+        .collect();
+
+    let uri_param = json!({ "name": "uri", "required": true, "schema": { "type": "string" } });
+    let mut methods = vec![
+        json!({
+            "name": "initialize",
+            "params": [],
+            "result": { "name": "result", "schema": { "type": "object" } }
+        }),
+        json!({
+            "name": "initialized",
+            "summary": "客户端初始化完成通知",
+            "params": []
+        }),
+        json!({
+            "name": "tools/list",
+            "params": [],
+            "result": {
+                "name": "result",
+                "schema": {
+                    "type": "object",
+                    "properties": { "tools": { "type": "array" } }
+                }
+            }
+        }),
+        json!({
+            "name": "notifications/cancelled",
+            "summary": "取消进行中的请求（长时间工具在分块或轮询间隙停止）",
+            "paramStructure": "by-name",
+            "params": [
+                { "name": "requestId", "required": true, "schema": { "type": ["string", "integer"] } },
+                { "name": "reason", "schema": { "type": "string" } }
+            ]
+        }),
+        json!({
+            "name": "resources/list",
+            "params": [],
+            "result": {
+                "name": "result",
+                "schema": {
+                    "type": "object",
+                    "properties": {
+                        "resources": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "uri": { "type": "string" },
+                                    "name": { "type": "string" },
+                                    "description": { "type": "string" },
+                                    "mimeType": { "type": "string" }
+                                },
+                                "required": ["uri", "name"]
+                            }
+                        }
+                    },
+                    "required": ["resources"]
+                }
+            }
+        }),
+        json!({
+            "name": "resources/read",
+            "paramStructure": "by-name",
+            "params": [uri_param.clone()],
+            "result": {
+                "name": "result",
+                "schema": {
+                    "type": "object",
+                    "properties": {
+                        "contents": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "uri": { "type": "string" },
+                                    "mimeType": { "type": "string" },
+                                    "blob": { "type": "string", "description": "Base64 编码的 PNG" }
+                                },
+                                "required": ["uri", "blob"]
+                            }
+                        }
+                    },
+                    "required": ["contents"]
+                }
+            }
+        }),
+        json!({
+            "name": "resources/subscribe",
+            "summary": "订阅资源更新，变化时发送 notifications/resources/updated",
+            "paramStructure": "by-name",
+            "params": [uri_param.clone()],
+            "result": { "name": "result", "schema": { "type": "object" } }
+        }),
+        json!({
+            "name": "resources/unsubscribe",
+            "paramStructure": "by-name",
+            "params": [uri_param],
+            "result": { "name": "result", "schema": { "type": "object" } }
+        }),
+    ];
+    methods.extend(tool_methods);
+
+    json!({
+        "openrpc": "1.2.6",
+        "info": {
+            "title": "iris-mcp",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "MCP 输入控制与监控服务器（JSON-RPC 2.0 over stdio）"
+        },
+        "methods": methods
+    })
+}
+
+/// 将每个工具的输入/输出 schema 及 OpenRPC 文档写入 `out_dir`，返回写出的工具数量
+pub fn export(out_dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(out_dir)?;
+
+    let tools = tools_list::get_tools_list();
+    let tools = tools["tools"].as_array().cloned().unwrap_or_default();
+    for tool in &tools {
+        let name = tool["name"].as_str().unwrap_or_default();
+        write_json(&out_dir.join(format!("{}.input.json", name)), &tool["inputSchema"])?;
+        write_json(&out_dir.join(format!("{}.output.json", name)), &tool_output_schema(name))?;
+    }
+    write_json(&out_dir.join("openrpc.json"), &openrpc_document())?;
+
+    Ok(tools.len())
+}
+
+fn write_json(path: &Path, value: &Value) -> io::Result<()> {
+    let text = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    fs::write(path, text + "\n")
+}
@@ -0,0 +1,590 @@
+//! execute_script：在一次调用中顺序执行多个工具步骤。
+//!
+//! 步骤可通过 `save_as` 把结果保存为变量（有 structuredContent 时保存结构化结果，
+//! 否则保存文本），后续步骤的参数中用 `{{name}}` 或 `{{name.path.0}}` 引用。
+//! 整个字符串恰好是一个占位符时按原 JSON 类型替换（数字仍为数字），否则按文本插值。
+//!
+//! 除工具步骤外还支持控制步骤：
+//! - `{"if": {"condition": C, "then": [...], "else": [...]}}`
+//! - `{"repeat": {"times": N, "steps": [...]}}` 或 `{"repeat": {"until": C, "steps": [...]}}`
+//!
+//! 条件 C 可为：`{"locate": target}`（目标可定位，target 同 smart_click）、
+//! `{"pixel": {"x", "y", "color": "#rrggbb", "tolerance"}}`、`{"var": name, "equals": value}`，
+//! 以及组合 `{"not": C}`、`{"all": [C]}`、`{"any": [C]}`。
+//!
+//! 脚本本身不持有执行锁，每个工具步骤单独按其并发类别加锁，循环间隔中其他调用可以执行。
+//! 整个脚本受 `MAX_SCRIPT_DURATION` 墙钟时间限制，`interval_ms` 不超过 `MAX_REPEAT_INTERVAL_MS`。
+
+use super::coords;
+use super::jsonrpc::JsonRpcError;
+use super::mouse;
+use super::redact;
+use crate::monitor::screen;
+use crate::operator::locate;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 单个步骤列表允许的最大步骤数
+const MAX_SCRIPT_STEPS: usize = 100;
+
+/// 一次脚本执行中最多执行的工具步骤数（含循环展开），防止失控循环
+const MAX_EXECUTED_STEPS: usize = 1000;
+
+/// `repeat.until` 未指定 max_iterations 时的迭代上限
+const DEFAULT_MAX_ITERATIONS: u64 = 100;
+
+/// 一次脚本执行的墙钟时间上限（含循环间隔与条件求值）
+const MAX_SCRIPT_DURATION: Duration = Duration::from_secs(300);
+
+/// `repeat.interval_ms` 的上限
+const MAX_REPEAT_INTERVAL_MS: u64 = 60_000;
+
+/// 控制步骤的最大嵌套深度
+const MAX_NESTING: usize = 8;
+
+/// 不能作为脚本步骤调用的工具（避免脚本/宏递归）
+const NESTED_FORBIDDEN: &[&str] = &["execute_script", "macro_run"];
+
+fn invalid(message: String) -> JsonRpcError {
+    JsonRpcError {
+        code: -32602,
+        message,
+        data: None,
+    }
+}
+
+/// 按 `name.key.0` 形式的路径查找变量
+fn lookup<'a>(vars: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut current = vars.get(parts.next()?.trim())?;
+    for part in parts {
+        let part = part.trim();
+        current = match current {
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            other => other.get(part)?,
+        };
+    }
+    Some(current)
+}
+
+fn render_string(s: &str, vars: &Map<String, Value>) -> Result<Value, String> {
+    let trimmed = s.trim();
+    if let Some(inner) = trimmed.strip_prefix("{{").and_then(|r| r.strip_suffix("}}"))
+        && !inner.contains("{{")
+    {
+        return lookup(vars, inner)
+            .cloned()
+            .ok_or_else(|| format!("Undefined variable: {}", inner.trim()));
+    }
+
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let path = &rest[start + 2..start + 2 + len];
+        let value = lookup(vars, path).ok_or_else(|| format!("Undefined variable: {}", path.trim()))?;
+        out.push_str(&rest[..start]);
+        match value {
+            Value::String(text) => out.push_str(text),
+            other => out.push_str(&other.to_string()),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+/// 递归替换参数中的 `{{var}}` 占位符
+fn render(value: &Value, vars: &Map<String, Value>) -> Result<Value, String> {
+    match value {
+        Value::String(s) => render_string(s, vars),
+        Value::Array(items) => items.iter().map(|v| render(v, vars)).collect::<Result<_, _>>().map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), render(v, vars)?)))
+            .collect::<Result<_, String>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+/// 工具结果中可保存为变量的值：优先 structuredContent，其次拼接文本内容
+fn result_value(result: &Value) -> Value {
+    if let Some(structured) = result.get("structuredContent").filter(|v| !v.is_null()) {
+        return structured.clone();
+    }
+    let text: Vec<&str> = result["content"]
+        .as_array()
+        .map(|items| items.iter().filter_map(|c| c["text"].as_str()).collect())
+        .unwrap_or_default();
+    Value::String(text.join("\n"))
+}
+
+fn result_text(result: &Value) -> String {
+    result["content"]
+        .as_array()
+        .and_then(|items| items.iter().find_map(|c| c["text"].as_str()))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 解析 `#rrggbb` 颜色
+pub(crate) fn parse_color(s: &str) -> Option<[u8; 3]> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn step_list<'a>(value: &'a Value, what: &str) -> Result<&'a [Value], JsonRpcError> {
+    let steps = match value {
+        Value::Null => return Ok(&[]),
+        Value::Array(steps) => steps.as_slice(),
+        _ => return Err(invalid(format!("Invalid {}: expected an array of steps", what))),
+    };
+    if steps.len() > MAX_SCRIPT_STEPS {
+        return Err(invalid(format!(
+            "Too many steps in {}: {} (max {})",
+            what,
+            steps.len(),
+            MAX_SCRIPT_STEPS
+        )));
+    }
+    Ok(steps)
+}
+
+/// 不执行，仅检查步骤结构（保存宏时使用）；条件与参数模板在执行时才求值
+pub(crate) fn validate_steps(steps: &Value) -> Result<(), JsonRpcError> {
+    fn check(steps: &Value, what: &str, prefix: &str, depth: usize) -> Result<(), JsonRpcError> {
+        if depth > MAX_NESTING {
+            return Err(invalid(format!("Step {}: nesting deeper than {}", prefix, MAX_NESTING)));
+        }
+        for (i, step) in step_list(steps, what)?.iter().enumerate() {
+            let label = if prefix.is_empty() {
+                (i + 1).to_string()
+            } else {
+                format!("{}.{}", prefix, i + 1)
+            };
+            if let Some(spec) = step.get("if") {
+                check(&spec["then"], "then", &label, depth + 1)?;
+                check(&spec["else"], "else", &label, depth + 1)?;
+            } else if let Some(spec) = step.get("repeat") {
+                check(&spec["steps"], "repeat.steps", &label, depth + 1)?;
+            } else {
+                let tool = step["tool"]
+                    .as_str()
+                    .ok_or_else(|| invalid(format!("Step {}: missing tool, if or repeat", label)))?;
+                if NESTED_FORBIDDEN.contains(&tool) {
+                    return Err(invalid(format!("Step {}: {} cannot be nested", label, tool)));
+                }
+            }
+        }
+        Ok(())
+    }
+    check(steps, "steps", "", 0)
+}
+
+/// 一次脚本执行的状态
+struct Executor {
+    vars: Map<String, Value>,
+    report: Vec<Value>,
+    lines: Vec<String>,
+    executed: usize,
+    stop_on_error: bool,
+    deadline: Instant,
+    /// 被 sensitive 步骤引用的变量，返回与报错时脱敏
+    sensitive_vars: HashSet<String>,
+}
+
+impl Executor {
+    /// 失败时附带已执行步骤与变量，便于调用方定位
+    fn fail(&self, label: &str, e: JsonRpcError) -> JsonRpcError {
+        JsonRpcError {
+            code: e.code,
+            message: format!("Step {} failed: {}", label, e.message),
+            data: Some(json!({ "steps": self.report, "variables": self.visible_vars() })),
+        }
+    }
+
+    /// 可返回给调用方的变量：敏感变量替换为摘要
+    fn visible_vars(&self) -> Map<String, Value> {
+        let mut vars = self.vars.clone();
+        redact::redact_variables(&mut vars, &self.sensitive_vars);
+        vars
+    }
+
+    /// 超过墙钟时间上限时返回错误
+    fn check_deadline(&self, label: &str) -> Result<(), JsonRpcError> {
+        if Instant::now() < self.deadline {
+            return Ok(());
+        }
+        Err(self.fail(
+            label,
+            JsonRpcError {
+                code: -32001,
+                message: format!("script exceeded the {}s time limit", MAX_SCRIPT_DURATION.as_secs()),
+                data: None,
+            },
+        ))
+    }
+
+    fn run(&mut self, steps: &[Value], prefix: &str, depth: usize) -> Result<(), JsonRpcError> {
+        if depth > MAX_NESTING {
+            return Err(self.fail(prefix, invalid(format!("nesting deeper than {}", MAX_NESTING))));
+        }
+        for (i, step) in steps.iter().enumerate() {
+            let label = if prefix.is_empty() {
+                (i + 1).to_string()
+            } else {
+                format!("{}.{}", prefix, i + 1)
+            };
+            if step.get("if").is_some() {
+                self.run_if(&step["if"], &label, depth)?;
+            } else if step.get("repeat").is_some() {
+                self.run_repeat(&step["repeat"], &label, depth)?;
+            } else {
+                self.run_tool(step, &label)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn run_if(&mut self, spec: &Value, label: &str, depth: usize) -> Result<(), JsonRpcError> {
+        let matched = self
+            .condition(&spec["condition"])
+            .map_err(|e| self.fail(label, e))?;
+        self.lines.push(format!("{}. if → {}", label, if matched { "then" } else { "else" }));
+        let branch = if matched { "then" } else { "else" };
+        let steps = step_list(&spec[branch], branch).map_err(|e| self.fail(label, e))?;
+        self.run(steps, label, depth + 1)
+    }
+
+    fn run_repeat(&mut self, spec: &Value, label: &str, depth: usize) -> Result<(), JsonRpcError> {
+        let steps = step_list(&spec["steps"], "repeat.steps").map_err(|e| self.fail(label, e))?;
+        let interval_ms = spec["interval_ms"].as_u64().unwrap_or(0);
+        if interval_ms > MAX_REPEAT_INTERVAL_MS {
+            return Err(self.fail(
+                label,
+                invalid(format!("repeat interval_ms {} exceeds {}", interval_ms, MAX_REPEAT_INTERVAL_MS)),
+            ));
+        }
+        let interval = Duration::from_millis(interval_ms);
+        let until = spec.get("until").filter(|c| !c.is_null());
+        let iterations = match (spec["times"].as_u64(), until) {
+            (Some(times), None) => times,
+            (None, Some(_)) => spec["max_iterations"].as_u64().unwrap_or(DEFAULT_MAX_ITERATIONS),
+            _ => {
+                return Err(self.fail(
+                    label,
+                    invalid("repeat requires exactly one of times or until".to_string()),
+                ));
+            }
+        };
+
+        for n in 0..iterations {
+            self.check_deadline(label)?;
+            // until 在每次迭代前检查：条件已满足时不再执行
+            if let Some(condition) = until
+                && self.condition(condition).map_err(|e| self.fail(label, e))?
+            {
+                self.lines.push(format!("{}. repeat 条件满足，共{}次", label, n));
+                return Ok(());
+            }
+            if n > 0 {
+                thread::sleep(interval.min(self.deadline.saturating_duration_since(Instant::now())));
+                self.check_deadline(label)?;
+            }
+            self.run(steps, &format!("{}[{}]", label, n + 1), depth + 1)?;
+        }
+
+        if let Some(condition) = until
+            && !self.condition(condition).map_err(|e| self.fail(label, e))?
+        {
+            return Err(self.fail(
+                label,
+                JsonRpcError {
+                    code: -32001,
+                    message: format!("until condition not met after {} iterations", iterations),
+                    data: None,
+                },
+            ));
+        }
+        self.lines.push(format!("{}. repeat 完成，共{}次", label, iterations));
+        Ok(())
+    }
+
+    fn run_tool(&mut self, step: &Value, label: &str) -> Result<(), JsonRpcError> {
+        let tool = step["tool"]
+            .as_str()
+            .ok_or_else(|| self.fail(label, invalid("missing tool, if or repeat".to_string())))?;
+        if NESTED_FORBIDDEN.contains(&tool) {
+            return Err(self.fail(label, invalid(format!("{} cannot be nested", tool))));
+        }
+        if self.executed >= MAX_EXECUTED_STEPS {
+            return Err(self.fail(
+                label,
+                invalid(format!("script executed more than {} steps", MAX_EXECUTED_STEPS)),
+            ));
+        }
+        self.check_deadline(label)?;
+        self.executed += 1;
+
+        let outcome = render(&step["arguments"], &self.vars)
+            .map_err(invalid)
+            .and_then(|args| {
+                let args = if args.is_null() { json!({}) } else { args };
+                super::call_tool_locked(tool, &args)
+            });
+
+        match outcome {
+            Ok(result) => {
+                let text = result_text(&result);
+                self.lines.push(format!("{}. {} ✓ {}", label, tool, text.lines().next().unwrap_or_default()));
+                let mut entry = json!({ "step": label, "tool": tool, "ok": true, "text": text });
+                if let Some(name) = step["save_as"].as_str() {
+                    self.vars.insert(name.to_string(), result_value(&result));
+                    entry["saved_as"] = json!(name);
+                }
+                self.report.push(entry);
+                Ok(())
+            }
+            Err(e) => {
+                self.lines.push(format!("{}. {} ✗ {}", label, tool, e.message));
+                self.report.push(json!({ "step": label, "tool": tool, "ok": false, "error": e.message }));
+                if self.stop_on_error {
+                    Err(self.fail(&format!("{} ({})", label, tool), e))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// 求值条件（先展开其中的变量模板）
+    fn condition(&self, condition: &Value) -> Result<bool, JsonRpcError> {
+        let condition = render(condition, &self.vars).map_err(invalid)?;
+        self.evaluate(&condition)
+    }
+
+    fn evaluate(&self, c: &Value) -> Result<bool, JsonRpcError> {
+        if let Some(inner) = c.get("not") {
+            return Ok(!self.evaluate(inner)?);
+        }
+        if let Some(all) = c.get("all") {
+            let items = all.as_array().ok_or_else(|| invalid("Invalid all: expected an array".to_string()))?;
+            for item in items {
+                if !self.evaluate(item)? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        }
+        if let Some(any) = c.get("any") {
+            let items = any.as_array().ok_or_else(|| invalid("Invalid any: expected an array".to_string()))?;
+            for item in items {
+                if self.evaluate(item)? {
+                    return Ok(true);
+                }
+            }
+            return Ok(false);
+        }
+        if let Some(name) = c.get("var") {
+            let name = name.as_str().ok_or_else(|| invalid("Invalid var: expected a name".to_string()))?;
+            let value = lookup(&self.vars, name);
+            return Ok(match c.get("equals") {
+                Some(expected) => value == Some(expected),
+                None => value.is_some_and(|v| !v.is_null()),
+            });
+        }
+        if let Some(target) = c.get("locate") {
+            let ctx = coords::parse_context(c)?;
+            let target = mouse::parse_target(target, &ctx)?;
+            return Ok(locate::locate(&target, true).is_ok());
+        }
+        if let Some(pixel) = c.get("pixel") {
+            let (x, y) = coords::resolve_point(pixel)?;
+            let expected = pixel["color"]
+                .as_str()
+                .and_then(parse_color)
+                .ok_or_else(|| invalid("Invalid pixel.color: expected #rrggbb".to_string()))?;
+            let tolerance = pixel["tolerance"].as_u64().unwrap_or(0).min(255) as u8;
+            let actual = screen::pixel_color(x, y).map_err(|e| JsonRpcError {
+                code: -32001,
+                message: e.to_string(),
+                data: None,
+            })?;
+            return Ok(actual.iter().zip(expected).all(|(a, e)| a.abs_diff(e) <= tolerance));
+        }
+        Err(invalid(format!("Unknown condition: {}", c)))
+    }
+}
+
+pub fn handle_execute_script(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let steps = match &arguments["steps"] {
+        Value::Array(_) => step_list(&arguments["steps"], "steps")?,
+        _ => return Err(invalid("Missing or invalid steps".to_string())),
+    };
+
+    let vars = match &arguments["variables"] {
+        Value::Object(initial) => initial.clone(),
+        Value::Null => Map::new(),
+        _ => return Err(invalid("Invalid variables: expected an object".to_string())),
+    };
+
+    let mut executor = Executor {
+        vars,
+        report: Vec::new(),
+        lines: Vec::new(),
+        executed: 0,
+        stop_on_error: arguments["stop_on_error"].as_bool().unwrap_or(true),
+        deadline: Instant::now() + MAX_SCRIPT_DURATION,
+        sensitive_vars: redact::sensitive_variables(&arguments["steps"]),
+    };
+    executor.run(steps, "", 0)?;
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!("脚本执行完成，共执行{}个工具步骤\n{}", executor.executed, executor.lines.join("\n"))
+        }],
+        "structuredContent": {
+            "steps": executor.report,
+            "variables": executor.visible_vars()
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "hunter2-secret";
+
+    /// 敏感步骤位于不会执行的分支中：变量仍视为敏感，且测试不会真的输入按键
+    fn sensitive_branch() -> Value {
+        json!({
+            "if": {
+                "condition": { "var": "missing" },
+                "then": [{ "tool": "type_text", "arguments": { "text": "{{pw}}", "sensitive": true } }]
+            }
+        })
+    }
+
+    #[test]
+    fn sensitive_variables_are_redacted_from_log_result_and_error() {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "execute_script",
+                "arguments": { "steps": [sensitive_branch()], "variables": { "pw": SECRET } }
+            }
+        });
+        let logged = redact::for_log(&request.to_string());
+        assert!(!logged.contains(SECRET), "{}", logged);
+
+        let result = handle_execute_script(&request["params"]["arguments"]).unwrap();
+        assert_eq!(result["structuredContent"]["variables"]["pw"]["redacted"], true);
+        assert!(!result.to_string().contains(SECRET));
+
+        let failing = json!({
+            "steps": [sensitive_branch(), { "tool": "mouse_move", "arguments": { "x": "{{undefined}}" } }],
+            "variables": { "pw": SECRET, "other": "visible" }
+        });
+        let error = handle_execute_script(&failing).unwrap_err();
+        let data = error.data.unwrap();
+        assert_eq!(data["variables"]["other"], "visible");
+        assert!(!data.to_string().contains(SECRET));
+    }
+
+    fn vars() -> Map<String, Value> {
+        json!({ "x": 120, "name": "iris", "pos": { "x": 5, "list": [10, 20] }, "tpl": "{{x}}" })
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn whole_placeholder_keeps_value_type() {
+        let vars = vars();
+        assert_eq!(render_string("{{x}}", &vars).unwrap(), json!(120));
+        assert_eq!(render_string("  {{ pos.x }} ", &vars).unwrap(), json!(5));
+        assert_eq!(render_string("{{pos.list.1}}", &vars).unwrap(), json!(20));
+        assert_eq!(render_string("{{pos}}", &vars).unwrap(), vars["pos"]);
+    }
+
+    #[test]
+    fn embedded_placeholders_are_stringified() {
+        let vars = vars();
+        assert_eq!(render_string("hi {{name}}!", &vars).unwrap(), json!("hi iris!"));
+        assert_eq!(render_string("{{x}},{{pos.list.0}}", &vars).unwrap(), json!("120,10"));
+        assert_eq!(render_string("{{pos.list}}px", &vars).unwrap(), json!("[10,20]px"));
+    }
+
+    #[test]
+    fn render_recurses_into_arrays_and_objects() {
+        let args = json!({ "x": "{{x}}", "points": [{ "y": "{{pos.x}}" }], "flag": true });
+        assert_eq!(
+            render(&args, &vars()).unwrap(),
+            json!({ "x": 120, "points": [{ "y": 5 }], "flag": true })
+        );
+    }
+
+    #[test]
+    fn undefined_variables_are_errors() {
+        let vars = vars();
+        assert_eq!(render_string("{{ missing }}", &vars).unwrap_err(), "Undefined variable: missing");
+        assert_eq!(render_string("a {{pos.nope}} b", &vars).unwrap_err(), "Undefined variable: pos.nope");
+        assert_eq!(render_string("{{pos.list.9}}", &vars).unwrap_err(), "Undefined variable: pos.list.9");
+        assert!(render(&json!([1, { "k": "{{missing}}" }]), &vars).is_err());
+    }
+
+    #[test]
+    fn unmatched_braces_and_substituted_braces_stay_literal() {
+        let vars = vars();
+        assert_eq!(render_string("{{x", &vars).unwrap(), json!("{{x"));
+        assert_eq!(render_string("{{x}} and {{y", &vars).unwrap(), json!("120 and {{y"));
+        assert_eq!(render_string("a }} b", &vars).unwrap(), json!("a }} b"));
+        // 替换结果不会再次展开
+        assert_eq!(render_string("{{tpl}}", &vars).unwrap(), json!("{{x}}"));
+        assert_eq!(render_string("<{{tpl}}>", &vars).unwrap(), json!("<{{x}}>"));
+    }
+
+    /// 嵌套 `depth` 层控制步骤，最内层为一个普通工具步骤
+    fn nested(depth: usize, wrap: fn(Value) -> Value) -> Value {
+        let mut steps = json!([{ "tool": "get_mouse_position" }]);
+        for _ in 0..depth {
+            steps = json!([wrap(steps)]);
+        }
+        steps
+    }
+
+    #[test]
+    fn nesting_limit_applies_to_repeat_and_if() {
+        let repeat = |steps| json!({ "repeat": { "times": 1, "steps": steps } });
+        let then = |steps| json!({ "if": { "condition": { "var": "x" }, "then": steps } });
+        let otherwise = |steps| json!({ "if": { "condition": { "var": "x" }, "else": steps } });
+        for wrap in [repeat as fn(Value) -> Value, then, otherwise] {
+            assert!(validate_steps(&nested(MAX_NESTING, wrap)).is_ok());
+            let err = validate_steps(&nested(MAX_NESTING + 1, wrap)).unwrap_err();
+            assert_eq!(err.code, -32602);
+            assert!(err.message.contains("nesting deeper than 8"), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn validate_steps_rejects_nested_scripts_and_missing_tools() {
+        let script = json!([{ "repeat": { "times": 2, "steps": [{ "tool": "macro_run" }] } }]);
+        let err = validate_steps(&script).unwrap_err();
+        assert_eq!(err.message, "Step 1.1: macro_run cannot be nested");
+        let err = validate_steps(&json!([{ "tool": "mouse_move" }, { "arguments": {} }])).unwrap_err();
+        assert_eq!(err.message, "Step 2: missing tool, if or repeat");
+    }
+}
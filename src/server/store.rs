@@ -0,0 +1,123 @@
+//! 跨会话保留的 JSON 文件存储（宏、录制）。
+//!
+//! 每个文件形如 `{"<section>": {"<name>": ...}}`，按名称保存条目。
+//! 路径取对应的环境变量，未设置时为用户配置目录下的 `iris-mcp/<file>`
+//! （Linux `~/.config`、macOS `~/Library/Application Support`、Windows `%APPDATA%`）。
+//! Unix 上文件以 0600 权限创建，只有当前用户可读写。
+
+use super::jsonrpc::JsonRpcError;
+use serde_json::{json, Map, Value};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 条目名称的最大长度
+const MAX_NAME_LEN: usize = 64;
+
+/// 一个存储文件
+pub struct Store {
+    /// 覆盖路径的环境变量，如 `IRIS_MACROS_FILE`
+    pub env_var: &'static str,
+    /// 配置目录下的默认文件名，如 `macros.json`
+    pub file_name: &'static str,
+    /// 文件中保存条目的顶层字段，如 `macros`
+    pub section: &'static str,
+}
+
+pub fn invalid(message: String) -> JsonRpcError {
+    JsonRpcError {
+        code: -32602,
+        message,
+        data: None,
+    }
+}
+
+pub fn io_error(message: String) -> JsonRpcError {
+    JsonRpcError {
+        code: -32603,
+        message,
+        data: None,
+    }
+}
+
+/// 读取参数中的条目名称：1–64 个 A-Z、a-z、0-9、`_`、`-`、`.`
+pub fn require_name(arguments: &Value) -> Result<&str, JsonRpcError> {
+    let name = arguments["name"]
+        .as_str()
+        .ok_or_else(|| invalid("Missing name".to_string()))?;
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        return Err(invalid(format!(
+            "Invalid name: {} (1–{} characters of A-Z, a-z, 0-9, _, -, .)",
+            name, MAX_NAME_LEN
+        )));
+    }
+    Ok(name)
+}
+
+impl Store {
+    pub fn path(&self) -> Result<PathBuf, JsonRpcError> {
+        if let Some(path) = env::var_os(self.env_var) {
+            return Ok(PathBuf::from(path));
+        }
+        let var = if cfg!(target_os = "windows") { "APPDATA" } else { "HOME" };
+        let base = env::var_os(var)
+            .map(PathBuf::from)
+            .ok_or_else(|| io_error(format!("{} is not set; set {}", var, self.env_var)))?;
+        let config_dir = if cfg!(target_os = "windows") {
+            base
+        } else if cfg!(target_os = "macos") {
+            base.join("Library/Application Support")
+        } else {
+            env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| base.join(".config"))
+        };
+        Ok(config_dir.join("iris-mcp").join(self.file_name))
+    }
+
+    /// 读取全部条目；文件不存在时为空
+    pub fn load(&self) -> Result<Map<String, Value>, JsonRpcError> {
+        let path = self.path()?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+            Err(e) => return Err(io_error(format!("Failed to read {}: {}", path.display(), e))),
+        };
+        let value: Value = serde_json::from_str(&content)
+            .map_err(|e| io_error(format!("Invalid {} file {}: {}", self.section, path.display(), e)))?;
+        Ok(value[self.section].as_object().cloned().unwrap_or_default())
+    }
+
+    pub fn save(&self, entries: Map<String, Value>) -> Result<(), JsonRpcError> {
+        let path = self.path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| io_error(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let content = serde_json::to_string_pretty(&json!({ self.section: entries }))
+            .map_err(|e| io_error(e.to_string()))?;
+        // 先写临时文件再替换，避免写到一半时损坏已有的条目
+        let tmp = path.with_extension("json.tmp");
+        write_private(&tmp, content.as_bytes())
+            .map_err(|e| io_error(format!("Failed to write {}: {}", tmp.display(), e)))?;
+        fs::rename(&tmp, &path).map_err(|e| io_error(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+/// 新建文件并写入内容；Unix 上权限为 0600。残留的同名文件先删除，保证权限在创建时生效
+fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
@@ -0,0 +1,286 @@
+//! 键鼠与显示器事件订阅：`monitor_subscribe` 注册感兴趣的事件后，收集线程把匹配的事件
+//! 以 `notifications/monitor/event` 通知实时推送给客户端，无需轮询 monitor_*_events。
+//!
+//! 通知需要可推送的传输（stdio）；HTTP 传输下订阅会被拒绝。
+//! 通知参数：`{"subscription_id": 1, "device": "keyboard"|"mouse"|"display", "event": {...}}`，
+//! 键鼠 `event` 与 monitor_keyboard_events / monitor_mouse_events 返回的事件格式相同；
+//! 显示器 `event` 为显示器增减与几何变化（`display_added` / `display_removed` / `geometry_changed`），
+//! 首个订阅显示器的请求启动拓扑监控。
+
+use super::jsonrpc::JsonRpcError;
+use super::monitor::{keyboard_event_to_json, mouse_event_to_json, screen_event_to_json};
+use super::notify;
+use crate::monitor::key_mouse::{self, KeyEventType, MonitorEvent, MouseEventKind};
+use crate::monitor::screen::{self, MonitorHandle, ScreenEvent};
+use crate::operator::keys;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, Once, OnceLock};
+
+pub const MONITOR_EVENT_NOTIFICATION: &str = "notifications/monitor/event";
+
+/// 同时存在的订阅数上限
+const MAX_SUBSCRIPTIONS: usize = 32;
+
+/// 一个订阅的过滤条件
+#[derive(Debug, Clone)]
+struct Filter {
+    keyboard: bool,
+    mouse: bool,
+    display: bool,
+    /// 只推送这些按键（规范名称）；None 表示全部按键
+    keys: Option<Vec<String>>,
+    mouse_move: bool,
+    mouse_button: bool,
+    mouse_scroll: bool,
+    exclude_synthetic: bool,
+}
+
+impl Filter {
+    fn parse(arguments: &Value) -> Result<Self, JsonRpcError> {
+        let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+
+        let (keyboard, mouse, display) = match arguments["devices"].as_array() {
+            None => (true, true, true),
+            Some(devices) => {
+                let (mut keyboard, mut mouse, mut display) = (false, false, false);
+                for device in devices {
+                    match device.as_str() {
+                        Some("keyboard") => keyboard = true,
+                        Some("mouse") => mouse = true,
+                        Some("display") => display = true,
+                        _ => {
+                            return Err(invalid(format!(
+                                "Invalid device: {} (expected keyboard, mouse or display)",
+                                device
+                            )));
+                        }
+                    }
+                }
+                if !keyboard && !mouse && !display {
+                    return Err(invalid("devices must not be empty".to_string()));
+                }
+                (keyboard, mouse, display)
+            }
+        };
+
+        let keys = match arguments["keys"].as_array() {
+            None => None,
+            Some(names) => Some(
+                names
+                    .iter()
+                    .map(|name| {
+                        let name = name.as_str().ok_or_else(|| invalid("keys must be strings".to_string()))?;
+                        Ok(canonical_key(name))
+                    })
+                    .collect::<Result<Vec<_>, JsonRpcError>>()?,
+            ),
+        };
+
+        // 默认不推送移动事件，避免通知洪泛
+        let (mut mouse_move, mut mouse_button, mut mouse_scroll) = (false, true, true);
+        if let Some(kinds) = arguments["mouse_kinds"].as_array() {
+            (mouse_move, mouse_button, mouse_scroll) = (false, false, false);
+            for kind in kinds {
+                match kind.as_str() {
+                    Some("move") => mouse_move = true,
+                    Some("button") => mouse_button = true,
+                    Some("scroll") => mouse_scroll = true,
+                    _ => return Err(invalid(format!("Invalid mouse kind: {} (expected move, button or scroll)", kind))),
+                }
+            }
+        }
+
+        Ok(Filter {
+            keyboard,
+            mouse,
+            display,
+            keys,
+            mouse_move,
+            mouse_button,
+            mouse_scroll,
+            exclude_synthetic: arguments["exclude_synthetic"].as_bool().unwrap_or(false),
+        })
+    }
+
+    fn matches(&self, event: MonitorEvent<'_>) -> bool {
+        match event {
+            MonitorEvent::Keyboard(e) => {
+                self.keyboard
+                    && !(self.exclude_synthetic && e.synthetic)
+                    && (matches!(e.event_type, KeyEventType::Gap { .. })
+                        || self.keys.as_ref().is_none_or(|keys| keys.contains(&canonical_key(&e.key))))
+            }
+            MonitorEvent::Mouse(e) => {
+                self.mouse
+                    && !(self.exclude_synthetic && e.synthetic)
+                    && match e.kind {
+                        MouseEventKind::Move { .. } => self.mouse_move,
+                        MouseEventKind::Button { .. } => self.mouse_button,
+                        MouseEventKind::Scroll { .. } => self.mouse_scroll,
+                        MouseEventKind::Gap { .. } => true,
+                    }
+            }
+        }
+    }
+
+    fn describe(&self) -> Value {
+        let mut devices = Vec::new();
+        if self.keyboard {
+            devices.push("keyboard");
+        }
+        if self.mouse {
+            devices.push("mouse");
+        }
+        if self.display {
+            devices.push("display");
+        }
+        let mut mouse_kinds = Vec::new();
+        for (enabled, kind) in [(self.mouse_move, "move"), (self.mouse_button, "button"), (self.mouse_scroll, "scroll")] {
+            if enabled {
+                mouse_kinds.push(kind);
+            }
+        }
+        json!({
+            "devices": devices,
+            "keys": self.keys,
+            "mouse_kinds": mouse_kinds,
+            "exclude_synthetic": self.exclude_synthetic,
+        })
+    }
+}
+
+/// 按键名称统一为规范名称（组合名称统一为 `Combo` 事件的形式），无法识别时按小写比较
+fn canonical_key(name: &str) -> String {
+    if name.len() > 1
+        && name.contains('+')
+        && let Some(combo) = key_mouse::canonical_combo(name)
+    {
+        return combo.to_lowercase();
+    }
+    keys::canonical_name(name).map_or_else(|| name.to_lowercase(), str::to_lowercase)
+}
+
+fn subscriptions() -> MutexGuard<'static, BTreeMap<u64, Filter>> {
+    static SUBSCRIPTIONS: OnceLock<Mutex<BTreeMap<u64, Filter>>> = OnceLock::new();
+    SUBSCRIPTIONS
+        .get_or_init(|| Mutex::new(BTreeMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 在收集线程上把事件推送给所有匹配的订阅
+fn dispatch(event: MonitorEvent<'_>) {
+    let matched: Vec<u64> = subscriptions()
+        .iter()
+        .filter(|(_, filter)| filter.matches(event))
+        .map(|(id, _)| *id)
+        .collect();
+    if matched.is_empty() {
+        return;
+    }
+    let (device, payload) = match event {
+        MonitorEvent::Keyboard(e) => ("keyboard", keyboard_event_to_json(e)),
+        MonitorEvent::Mouse(e) => ("mouse", mouse_event_to_json(e)),
+    };
+    for id in matched {
+        notify::send(
+            MONITOR_EVENT_NOTIFICATION,
+            json!({ "subscription_id": id, "device": device, "event": payload }),
+        );
+    }
+}
+
+/// 在拓扑监控线程上把显示器事件推送给订阅了显示器的订阅
+fn dispatch_display(event: ScreenEvent) {
+    let matched: Vec<u64> = subscriptions().iter().filter(|(_, f)| f.display).map(|(id, _)| *id).collect();
+    if matched.is_empty() {
+        return;
+    }
+    let payload = screen_event_to_json(&event);
+    for id in matched {
+        notify::send(
+            MONITOR_EVENT_NOTIFICATION,
+            json!({ "subscription_id": id, "device": "display", "event": payload }),
+        );
+    }
+}
+
+/// 启动显示器拓扑监控（进程内只启动一次），返回启动失败的原因
+fn ensure_display_monitor() -> Option<String> {
+    static DISPLAY_MONITOR: OnceLock<Result<MonitorHandle, String>> = OnceLock::new();
+    DISPLAY_MONITOR
+        .get_or_init(|| screen::start_monitor(dispatch_display).map_err(|e| e.to_string()))
+        .as_ref()
+        .err()
+        .cloned()
+}
+
+/// 注册事件订阅，返回订阅 ID
+pub fn handle_monitor_subscribe(arguments: &Value) -> Result<Value, JsonRpcError> {
+    if !notify::is_available() {
+        return Err(JsonRpcError {
+            code: -32001,
+            message: "Event subscriptions require a transport with server notifications (stdio); \
+                      use monitor_keyboard_events / monitor_mouse_events over HTTP"
+                .to_string(),
+            data: None,
+        });
+    }
+    let filter = Filter::parse(arguments)?;
+
+    let mut subscriptions = subscriptions();
+    if subscriptions.len() >= MAX_SUBSCRIPTIONS {
+        return Err(JsonRpcError {
+            code: -32001,
+            message: format!("Too many subscriptions (max {}); unsubscribe first", MAX_SUBSCRIPTIONS),
+            data: None,
+        });
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let description = filter.describe();
+    let display = filter.display;
+    subscriptions.insert(id, filter);
+    drop(subscriptions);
+
+    static LISTENER: Once = Once::new();
+    LISTENER.call_once(|| key_mouse::add_event_listener(dispatch));
+    let display_error = if display { ensure_display_monitor() } else { None };
+
+    let mut text = format!("已创建订阅 {}，匹配的事件将以 {} 通知推送", id, MONITOR_EVENT_NOTIFICATION);
+    if let Some(error) = &display_error {
+        text.push_str(&format!("\n显示器变化监控不可用：{}", error));
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": {
+            "subscription_id": id,
+            "notification": MONITOR_EVENT_NOTIFICATION,
+            "filter": description,
+            "display_monitor_error": display_error
+        }
+    }))
+}
+
+/// 取消事件订阅
+pub fn handle_monitor_unsubscribe(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let id = arguments["subscription_id"].as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing or invalid 'subscription_id' parameter".to_string(),
+        data: None,
+    })?;
+    if subscriptions().remove(&id).is_none() {
+        return Err(JsonRpcError {
+            code: -32602,
+            message: format!("Unknown subscription: {}", id),
+            data: None,
+        });
+    }
+    Ok(json!({
+        "content": [{ "type": "text", "text": format!("已取消订阅 {}", id) }],
+        "structuredContent": { "subscription_id": id, "unsubscribed": true }
+    }))
+}
@@ -1,6 +1,74 @@
 use serde_json::{json, Value};
 
+/// 工具的并发类别，决定调用能否与其他调用并行执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyClass {
+    /// 只观察、不注入输入也不改变系统状态，可与其他只读工具并行
+    ReadOnly,
+    /// 注入输入或改变系统状态，按顺序逐个执行
+    Mutating,
+    /// 编排其他工具的步骤（execute_script / macro_run）：自身不持锁，每个步骤按其类别加锁，
+    /// 步骤之间的等待不阻塞其他调用
+    Scripted,
+    /// 只读但可能长时间等待（轮询窗口、网络请求）：不持锁，等待期间不阻塞改变状态的工具
+    Waiting,
+}
+
+/// 只读工具列表，未列出的工具一律视为 Mutating
+const READ_ONLY_TOOLS: &[&str] = &[
+    "mouse_get_position",
+    "monitor_screen_events",
+    "screen_ocr",
+    "capture_all_displays",
+    "monitor_keyboard_events",
+    "monitor_mouse_events",
+    "monitor_gestures",
+    "list_displays",
+    "input_source_info",
+    "get_keyboard_state",
+    "get_idle_time",
+    "get_input_snapshot",
+    "monitor_capabilities",
+    "monitor_health",
+    "query_event_log",
+    "macro_list",
+    "scroll_metrics",
+    "list_windows",
+    "window_at_point",
+];
+
+/// 只读且可能长时间等待的工具，执行时不持锁
+const WAITING_TOOLS: &[&str] = &["wait_for_window", "check_update"];
+
+/// 按步骤加锁的脚本类工具
+const SCRIPTED_TOOLS: &[&str] = &["execute_script", "macro_run"];
+
+pub fn concurrency_class(name: &str) -> ConcurrencyClass {
+    if READ_ONLY_TOOLS.contains(&name) {
+        ConcurrencyClass::ReadOnly
+    } else if SCRIPTED_TOOLS.contains(&name) {
+        ConcurrencyClass::Scripted
+    } else if WAITING_TOOLS.contains(&name) {
+        ConcurrencyClass::Waiting
+    } else {
+        ConcurrencyClass::Mutating
+    }
+}
+
 pub fn get_tools_list() -> Value {
+    let mut list = tool_definitions();
+    // 通过 MCP 工具注解公开并发类别
+    if let Some(tools) = list["tools"].as_array_mut() {
+        for tool in tools {
+            let name = tool["name"].as_str().unwrap_or_default();
+            let read_only = matches!(concurrency_class(name), ConcurrencyClass::ReadOnly | ConcurrencyClass::Waiting);
+            tool["annotations"] = json!({ "readOnlyHint": read_only });
+        }
+    }
+    list
+}
+
+fn tool_definitions() -> Value {
     json!({
         "tools": [
             {
@@ -9,21 +77,33 @@ pub fn get_tools_list() -> Value {
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "x": { "type": "integer", "description": "X 坐标" },
-                        "y": { "type": "integer", "description": "Y 坐标" }
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
+                        "x": { "type": "number", "description": "X 坐标" },
+                        "y": { "type": "number", "description": "Y 坐标" }
                     },
                     "required": ["x", "y"]
                 }
             },
             {
                 "name": "mouse_click",
-                "description": "在指定坐标点击鼠标按钮",
+                "description": "在指定坐标点击鼠标按钮，支持通过 clicks 实现双击/三击",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "x": { "type": "integer", "description": "X 坐标" },
-                        "y": { "type": "integer", "description": "Y 坐标" },
-                        "button": { "type": "string", "enum": ["left", "right", "middle"], "description": "鼠标按钮" }
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
+                        "x": { "type": "number", "description": "X 坐标" },
+                        "y": { "type": "number", "description": "Y 坐标" },
+                        "button": { "type": "string", "enum": ["left", "right", "middle"], "description": "鼠标按钮" },
+                        "clicks": { "type": "integer", "minimum": 1, "maximum": 3, "description": "点击次数（1-3），默认 1；3 可用于三击选中整行" },
+                        "interval_ms": { "type": "integer", "minimum": 0, "maximum": 2000, "description": "连续点击之间的间隔毫秒数，默认 100" }
                     },
                     "required": ["x", "y", "button"]
                 }
@@ -34,28 +114,46 @@ pub fn get_tools_list() -> Value {
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "x": { "type": "integer", "description": "X 坐标" },
-                        "y": { "type": "integer", "description": "Y 坐标" },
-                        "button": { "type": "string", "enum": ["left", "right", "middle"], "description": "鼠标按钮" }
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
+                        "x": { "type": "number", "description": "X 坐标" },
+                        "y": { "type": "number", "description": "Y 坐标" },
+                        "button": { "type": "string", "enum": ["left", "right", "middle"], "description": "鼠标按钮" },
+                        "interval_ms": { "type": "integer", "minimum": 0, "maximum": 2000, "description": "两次点击之间的间隔毫秒数，默认 100" }
                     },
                     "required": ["x", "y", "button"]
                 }
             },
             {
                 "name": "mouse_scroll",
-                "description": "滚动鼠标滚轮",
+                "description": "滚动鼠标滚轮，支持按行滚动或按像素平滑滚动",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "lines_x": { "type": "integer", "description": "水平滚动行数" },
-                        "lines_y": { "type": "integer", "description": "垂直滚动行数" }
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
+                        "x": { "type": "number", "description": "滚动位置 X 坐标（可选，需与 y 同时提供），先移动光标再滚动" },
+                        "y": { "type": "number", "description": "滚动位置 Y 坐标（可选，需与 x 同时提供）" },
+                        "mode": { "type": "string", "enum": ["lines", "pixels"], "description": "滚动模式：lines按行（默认）/pixels按像素平滑滚动" },
+                        "lines_x": { "type": "integer", "description": "水平滚动行数（lines 模式）" },
+                        "lines_y": { "type": "integer", "description": "垂直滚动行数（lines 模式）" },
+                        "distance_x": { "type": "integer", "description": "水平滚动总像素，正数向右（pixels 模式）" },
+                        "distance_y": { "type": "integer", "description": "垂直滚动总像素，正数向下（pixels 模式）" },
+                        "duration_ms": { "type": "integer", "minimum": 0, "maximum": 10000, "description": "平滑滚动总时长毫秒数（0-10000），默认 0（pixels 模式）" },
+                        "steps": { "type": "integer", "minimum": 1, "maximum": 200, "description": "平滑滚动拆分的步数（1-200），默认 10（pixels 模式）" }
                     },
-                    "required": ["lines_x", "lines_y"]
+                    "required": []
                 }
             },
             {
                 "name": "mouse_get_position",
-                "description": "获取当前鼠标位置",
+                "description": "获取当前鼠标位置，以及光标所在显示器（序号与本地坐标）和光标下像素的 RGB 颜色",
                 "inputSchema": {
                     "type": "object",
                     "properties": {},
@@ -64,25 +162,46 @@ pub fn get_tools_list() -> Value {
             },
             {
                 "name": "type_text",
-                "description": "使用键盘输入文本",
+                "description": "使用键盘输入文本。长文本按块输入，请求携带 _meta.progressToken 时每块发送进度通知，块之间响应 notifications/cancelled",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "text": { "type": "string", "description": "要输入的文本" }
+                        "text": { "type": "string", "description": "要输入的文本" },
+                        "char_delay_ms": { "type": "integer", "minimum": 0, "maximum": 1000, "description": "逐字符输入，字符间隔毫秒数（0-1000）；缺省或 0 时一次性输入；间隔加浮动按字符数累计不超过 120000 毫秒" },
+                        "char_delay_variance_ms": { "type": "integer", "minimum": 0, "maximum": 1000, "description": "字符间隔的随机浮动范围（±毫秒，0-1000），模拟人工输入节奏" },
+                        "chunk_size": { "type": "integer", "minimum": 1, "default": 200, "description": "每块字符数，每块输入后报告进度并检查取消" },
+                        "mode": {
+                            "type": "string",
+                            "enum": ["keys", "clipboard"],
+                            "default": "keys",
+                            "description": "keys 注入按键；clipboard 写入剪贴板后粘贴并恢复原剪贴板，绕过输入法，适合中日韩文本"
+                        },
+                        "sensitive": { "type": "boolean", "description": "敏感文本（如密码）：响应与日志中只记录长度和指纹，不回显内容；不能与 clipboard 模式同用" },
+                        "disable_ime": { "type": "boolean", "description": "输入期间临时切换到 ASCII 键盘布局，结束后恢复原输入法（macOS、Linux fcitx/IBus）；默认 false" },
+                        "astral_fallback": { "type": "boolean", "default": false, "description": "keys 模式下 emoji 等辅助平面字符经剪贴板粘贴（完成后恢复原剪贴板，剪贴板中的图片、文件等非文本内容会丢失），其余字符仍逐键输入；sensitive 时不生效" }
                     },
                     "required": ["text"]
                 }
             },
             {
                 "name": "system_command",
-                "description": "执行系统命令快捷键(复制、粘贴等)",
+                "description": "执行系统命令快捷键(复制、粘贴、查找、标签页、切换应用等)，按当前系统使用对应的修饰键",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "command": {
                             "type": "string",
-                            "enum": ["copy", "paste", "cut", "undo", "save", "select_all"],
+                            "enum": [
+                                "copy", "paste", "cut", "undo", "redo", "save", "select_all",
+                                "find", "find_next", "new_tab", "close_tab", "close_window",
+                                "switch_app", "quit_app", "refresh", "zoom_in", "zoom_out"
+                            ],
                             "description": "要执行的命令"
+                        },
+                        "modifier": {
+                            "type": "string",
+                            "enum": ["ctrl", "cmd", "alt"],
+                            "description": "替换当前系统的主修饰键（macOS 为 Cmd，其他为 Ctrl），用于虚拟机或改键后的键盘；默认取环境变量 IRIS_SYSTEM_COMMAND_MODIFIER"
                         }
                     },
                     "required": ["command"]
@@ -94,12 +213,17 @@ pub fn get_tools_list() -> Value {
                 "inputSchema": {
                     "type": "object",
                     "properties": {
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
                         "target_x": {
-                            "type": "integer",
+                            "type": "number",
                             "description": "目标X坐标"
                         },
                         "target_y": {
-                            "type": "integer",
+                            "type": "number",
                             "description": "目标Y坐标"
                         },
                         "button": {
@@ -133,47 +257,247 @@ pub fn get_tools_list() -> Value {
             },
             {
                 "name": "mouse_move_path",
-                "description": "按指定路径移动鼠标",
+                "description": "按指定路径移动鼠标，支持逐点延迟、缓动曲线与自动插值细分",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
                         "points": {
                             "type": "array",
                             "items": {
                                 "type": "object",
                                 "properties": {
-                                    "x": {"type": "integer"},
-                                    "y": {"type": "integer"}
+                                    "x": {"type": "number"},
+                                    "y": {"type": "number"},
+                                    "delay_ms": {"type": "integer", "minimum": 0, "maximum": 5000, "description": "从上一个点移动到该点所用毫秒数（0-5000），缺省取 speed_ms"}
                                 },
                                 "required": ["x", "y"]
                             },
-                            "description": "路径点数组"
+                            "maxItems": 500,
+                            "description": "路径点数组（最多 500 个点，各点延迟合计不超过 30000 毫秒）"
                         },
                         "speed_ms": {
                             "type": "integer",
-                            "description": "每个点之间的延迟毫秒数"
+                            "minimum": 0,
+                            "maximum": 5000,
+                            "description": "每个点之间的延迟毫秒数（0-5000）"
+                        },
+                        "easing": {
+                            "type": "string",
+                            "enum": ["linear", "ease_in", "ease_out", "ease_in_out"],
+                            "description": "相邻路径点之间的缓动曲线，默认 linear"
+                        },
+                        "max_step_px": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "单步最大移动像素；设置后在路径点之间自动插值细分，缺省时直接跳到下一个点"
                         }
                     },
                     "required": ["points", "speed_ms"]
                 }
             },
+            {
+                "name": "smart_click",
+                "description": "按策略链定位目标并点击（无障碍选择器 → OCR 文本 → 模板图像 → 坐标），返回成功的策略；昂贵策略的定位结果按目标与窗口缓存",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
+                        "target": {
+                            "type": "object",
+                            "properties": {
+                                "selector": { "type": "string", "description": "无障碍选择器（目前仅 macOS），形如 AXButton[title=OK] 或 [description*=save]：角色可省略 AX 前缀，条件属性为 title / description / value / identifier，= 完全相等，*= 包含（不区分大小写）；给出 window 时只在该窗口中查找，否则在前台应用中查找" },
+                                "text": { "type": "string", "description": "主显示器上要查找的文本（tesseract OCR，不区分大小写），点击包含该文本的最短词组中心" },
+                                "template": { "type": "string", "description": "Base64 编码的模板图像（PNG）" },
+                                "x": { "type": "number", "description": "兜底 X 坐标" },
+                                "y": { "type": "number", "description": "兜底 Y 坐标" },
+                                "window": { "type": "string", "description": "目标所在窗口的 ID（list_windows 的 id），用于位置缓存分区；window_snap / window_control 改变该窗口后其缓存失效" }
+                            },
+                            "description": "目标描述，至少提供一种定位方式"
+                        },
+                        "button": { "type": "string", "enum": ["left", "right", "middle"], "description": "鼠标按钮，默认 left" },
+                        "clicks": { "type": "integer", "minimum": 1, "maximum": 3, "description": "点击次数（1-3），默认 1" },
+                        "refresh": { "type": "boolean", "description": "忽略已缓存的位置并重新定位，默认 false" }
+                    },
+                    "required": ["target"]
+                }
+            },
+            {
+                "name": "gesture",
+                "description": "合成触控板手势：捏合缩放、旋转（仅 macOS）与双指滑动，作用于光标下方的视图，用于缩放地图、画布等没有键盘快捷键的场景",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "area": { "type": "string", "enum": ["display", "work_area"], "description": "坐标参考区域：display 整个显示器（默认）/work_area 除去菜单栏、刘海、Dock、任务栏后的可用工作区" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "坐标空间：pixels 逻辑像素（默认）/normalized 0-1 比例，按显示器实际分辨率换算/physical 物理像素（截图中的位置），按显示器缩放比例换算" },
+                        "display": { "type": "integer", "minimum": 0, "description": "坐标所属显示器序号（见 list_displays，0 为主显示器）；缺省时为全局坐标" },
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）：坐标相对于窗口左上角，按调用时的窗口位置换算，窗口移动后坐标仍然有效；不能与 display / area 同时使用" },
+                        "out_of_bounds": { "type": "string", "enum": ["reject", "clamp"], "description": "坐标超出显示器范围时的处理：reject 返回错误，clamp 钳制到最近的有效位置；默认取环境变量 IRIS_OUT_OF_BOUNDS，未设置时为 reject" },
+                        "x": { "type": "number", "description": "手势位置 X 坐标（可选，需与 y 同时提供），先移动光标再执行手势" },
+                        "y": { "type": "number", "description": "手势位置 Y 坐标（可选，需与 x 同时提供）" },
+                        "type": { "type": "string", "enum": ["pinch_in", "pinch_out", "rotate", "swipe"], "description": "手势类型：pinch_in 缩小/pinch_out 放大/rotate 旋转/swipe 双指滑动" },
+                        "magnitude": { "type": "number", "minimum": 0, "description": "捏合幅度，1.0 约为双指完整张开一次，默认 0.5（pinch_in/pinch_out）" },
+                        "degrees": { "type": "number", "description": "旋转角度，正数逆时针，默认 45（rotate）" },
+                        "dx": { "type": "integer", "description": "水平滑动像素，正数向右（swipe）" },
+                        "dy": { "type": "integer", "description": "垂直滑动像素，正数向下（swipe）" },
+                        "steps": { "type": "integer", "minimum": 1, "maximum": 200, "description": "手势拆分的事件数（1-200），默认 20" },
+                        "duration_ms": { "type": "integer", "minimum": 0, "maximum": 10000, "description": "手势总时长毫秒数（0-10000），默认 300" }
+                    },
+                    "required": ["type"]
+                }
+            },
             {
                 "name": "key_control",
-                "description": "控制键盘按键按下或释放",
+                "description": "控制键盘按键按下或释放；指定 hold_ms 时在一次调用内按下、保持并释放",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "key": {
                             "type": "string",
-                            "description": "按键名称，如：a, b, return, shift, control, alt等"
+                            "description": "按键名称（不区分大小写），如：a, enter, shift, left_control, right_alt, f1–f24, home, end, page_up, insert, print_screen, numpad0, numpad_add, caps_lock, volume_up, media_play_pause；与键盘监控输出的名称一致"
                         },
                         "direction": {
                             "type": "string",
                             "enum": ["press", "release", "click"],
-                            "description": "操作方向：press按下/release释放/click点击"
+                            "description": "操作方向：press按下/release释放/click点击；指定 hold_ms 时可省略"
+                        },
+                        "hold_ms": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "maximum": 60000,
+                            "description": "按住时长（毫秒）：按下、等待、释放一次完成，如按住方向键滚动列表"
                         }
                     },
-                    "required": ["key", "direction"]
+                    "required": ["key"]
+                }
+            },
+            {
+                "name": "key_combo",
+                "description": "一次调用按下组合键：依次按住前面的修饰键，点击最后一个键，再按相反顺序释放；中途失败也会释放已按下的键",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "keys": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "minItems": 1,
+                            "description": "按键名称数组（名称同 key_control），如 [\"ctrl\", \"shift\", \"t\"]"
+                        },
+                        "check_conflicts": {
+                            "type": "boolean",
+                            "description": "注入前检查组合键是否已被系统或其他应用注册为全局快捷键（macOS 系统快捷键、GNOME 按键绑定；Windows 仅检查系统保留组合），冲突时在结果中警告；默认 false"
+                        }
+                    },
+                    "required": ["keys"]
+                }
+            },
+            {
+                "name": "key_repeat",
+                "description": "按固定间隔重复点击同一个键（如按 25 次向下方向键），一次调用完成",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "key": { "type": "string", "description": "按键名称（同 key_control）" },
+                        "count": { "type": "integer", "minimum": 1, "maximum": 1000, "description": "点击次数" },
+                        "interval_ms": { "type": "integer", "minimum": 0, "maximum": 10000, "default": 30, "description": "两次点击之间的间隔毫秒数" }
+                    },
+                    "required": ["key", "count"]
+                }
+            },
+            {
+                "name": "paste_text",
+                "description": "将文本写入系统剪贴板并发送粘贴快捷键；适合大段文本或逐键输入容易丢字的 Unicode 文本（中日韩、emoji）",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "text": { "type": "string", "description": "要粘贴的文本" },
+                        "restore_clipboard": { "type": "boolean", "default": true, "description": "粘贴后恢复原剪贴板文本" },
+                        "modifier": {
+                            "type": "string",
+                            "enum": ["ctrl", "cmd", "alt"],
+                            "description": "粘贴快捷键使用的修饰键（同 system_command）"
+                        }
+                    },
+                    "required": ["text"]
+                }
+            },
+            {
+                "name": "release_all_inputs",
+                "description": "释放所有经本服务按下但尚未释放的键与鼠标按键（如拖拽或按键序列中途失败后卡住的修饰键）；工具出错和服务退出时也会自动执行",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "key_sequence",
+                "description": "在一次调用内按顺序执行按键步骤（如按住 Alt 连按 Tab 切换窗口），期间不会穿插其他工具调用；某步失败时释放序列中仍按住的键",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "steps": {
+                            "type": "array",
+                            "minItems": 1,
+                            "maxItems": 200,
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "key": { "type": "string", "description": "按键名称（同 key_control）" },
+                                    "direction": {
+                                        "type": "string",
+                                        "enum": ["press", "release", "click"],
+                                        "default": "click",
+                                        "description": "操作方向"
+                                    },
+                                    "delay_ms": { "type": "integer", "minimum": 0, "maximum": 10000, "description": "本步之后的等待毫秒数" }
+                                },
+                                "required": ["key"]
+                            },
+                            "description": "按顺序执行的按键步骤"
+                        }
+                    },
+                    "required": ["steps"]
+                }
+            },
+            {
+                "name": "focus_next",
+                "description": "按 Tab 将键盘焦点移到下一个控件，并通过无障碍接口返回新的焦点元素",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "times": { "type": "integer", "minimum": 1, "maximum": 100, "description": "按 Tab 的次数（1-100），默认 1" }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "focus_prev",
+                "description": "按 Shift+Tab 将键盘焦点移到上一个控件，并通过无障碍接口返回新的焦点元素",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "times": { "type": "integer", "minimum": 1, "maximum": 100, "description": "按 Shift+Tab 的次数（1-100），默认 1" }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "activate_focused",
+                "description": "激活当前焦点控件：按钮类使用空格，链接、菜单项与输入框使用回车；目前只有 macOS 能读取焦点控件角色，其他平台默认使用空格",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "key": { "type": "string", "enum": ["space", "return"], "description": "强制使用的激活键，缺省时按控件角色自动选择" }
+                    },
+                    "required": []
                 }
             },
             {
@@ -182,40 +506,599 @@ pub fn get_tools_list() -> Value {
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "reason": { "type": "string", "description": "调用原因，便于审计" }
+                        "reason": { "type": "string", "description": "调用原因，便于审计" },
+                        "region": {
+                            "type": "object",
+                            "properties": {
+                                "x": { "type": "integer", "minimum": 0 },
+                                "y": { "type": "integer", "minimum": 0 },
+                                "width": { "type": "integer", "minimum": 1 },
+                                "height": { "type": "integer", "minimum": 1 }
+                            },
+                            "required": ["x", "y", "width", "height"],
+                            "description": "只返回该区域（截图像素坐标）；给出 window_id 时相对于窗口左上角"
+                        },
+                        "window_id": { "type": "integer", "description": "只截取该窗口在主显示器上的可见部分（见 list_windows），按调用时的窗口位置计算" },
+                        "annotations": {
+                            "type": "array",
+                            "maxItems": 32,
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "x": { "type": "number" },
+                                    "y": { "type": "number" },
+                                    "width": { "type": "number", "exclusiveMinimum": 0, "description": "与 height 同时指定时绘制矩形框（x, y 为左上角），否则在 (x, y) 绘制十字准星" },
+                                    "height": { "type": "number", "exclusiveMinimum": 0 },
+                                    "label": { "type": "string", "description": "标签文字，仅支持 ASCII 字母、数字与常用符号（如 \"1\"、\"OK\"），最多 32 个字符" },
+                                    "color": { "type": "string", "description": "颜色 #rrggbb，默认红色" }
+                                },
+                                "required": ["x", "y"]
+                            },
+                            "description": "在截图上绘制十字准星或矩形框及标签，用于向人展示将要点击的位置以便确认；在裁剪与缩放之前绘制"
+                        },
+                        "annotation_coordinates": { "type": "string", "enum": ["global", "pixel"], "default": "global", "description": "annotations 的坐标系：global 为全局逻辑坐标（与鼠标工具一致），pixel 为原始截图像素" },
+                        "grid": { "type": "boolean", "default": false, "description": "叠加带坐标标签的网格，标签为鼠标工具使用的全局逻辑坐标（无法获取显示器几何时为截图像素），没有无障碍信息时可大幅提高估读点击坐标的准确度" },
+                        "grid_spacing": { "type": "integer", "minimum": 10, "maximum": 10000, "default": 100, "description": "网格间距（坐标单位）；指定时即启用网格，线条过密时自动加倍" },
+                        "include_cursor": { "type": "boolean", "default": false, "description": "将当前鼠标指针按其位置合成到截图中（截图默认不含指针），便于确认指针实际所在位置" },
+                        "high_contrast": { "type": "boolean", "description": "高对比度模式：灰度化、对比度拉伸并放大，提升小字号文本的识别率" },
+                        "upscale": { "type": "integer", "minimum": 1, "maximum": 4, "description": "放大倍数，高对比度模式默认 2，否则默认 1；放大后超过 3600 万像素时报错，可先用 region 裁剪" },
+                        "max_width": { "type": "integer", "minimum": 1, "description": "等比缩小到宽度不超过该值（像素），不会放大；视觉模型通常不需要超过约 1500 像素，可减少 token 与延迟" },
+                        "max_height": { "type": "integer", "minimum": 1, "description": "等比缩小到高度不超过该值（像素），不会放大" },
+                        "scale": { "oneOf": [{ "type": "number", "exclusiveMinimum": 0, "maximum": 1 }, { "type": "string", "enum": ["logical", "physical"] }], "description": "按比例缩小（如 0.5），与 max_width / max_height 同时指定时先按比例缩小再限制尺寸；\"logical\" 缩小到显示器的逻辑分辨率（点），Retina 等 Hi-DPI 屏上截图像素即鼠标工具使用的坐标；\"physical\"（默认）保持物理像素。structuredContent.coordinate_mapping 给出截图像素到全局逻辑坐标的换算" },
+                        "color_mode": { "type": "string", "enum": ["color", "grayscale", "palette"], "default": "color", "description": "输出颜色：color 真彩色；grayscale 灰度；palette 量化为 palette_colors 种颜色的索引色 PNG。文本为主的界面用 grayscale / palette 体积小得多，通常足以识别文字" },
+                        "palette_colors": { "type": "integer", "minimum": 2, "maximum": 256, "default": 16, "description": "palette 模式的最大颜色数" },
+                        "thumbnail": { "type": "boolean", "default": false, "description": "缩略图模式：只内联一张小缩略图，完整截图保存为资源（structuredContent.frame_uri，如 screen://frame/3），需要细节时再用 resources/read 读取；只保留最近 8 帧" },
+                        "thumbnail_width": { "type": "integer", "minimum": 16, "maximum": 1024, "default": 320, "description": "缩略图的最大宽度（像素）" },
+                        "skip_unchanged": { "type": "boolean", "default": false, "description": "画面与上一次返回的截图（相同参数）一致时，只返回\"自某时刻起未变化\"的简短结果，不再发送图像，适合轮询等待界面变化" },
+                        "similarity_threshold": { "type": "number", "minimum": 0, "maximum": 1, "default": 0, "description": "配合 skip_unchanged：允许变化的像素比例（如 0.001），低于该值视为未变化，可忽略光标闪烁、时钟等细微变化；0 表示要求完全一致" }
+                    },
+                    "required": ["reason"]
+                }
+            },
+            {
+                "name": "query_event_log",
+                "description": "查询持久化事件日志（IRIS_EVENT_LOG 开启时记录全部键鼠事件，按大小轮转，重启后仍保留）中时间范围内的事件，用于审计与回溯",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "since_micros": { "type": "integer", "minimum": 0, "description": "只返回时间戳不早于该值的事件（Unix 时间，微秒）" },
+                        "until_micros": { "type": "integer", "minimum": 0, "description": "只返回时间戳不晚于该值的事件（Unix 时间，微秒）" },
+                        "device": {
+                            "type": "string",
+                            "enum": ["keyboard", "mouse", "all"],
+                            "default": "all",
+                            "description": "事件来源设备"
+                        },
+                        "limit": { "type": "integer", "minimum": 1, "maximum": 10000, "default": 1000, "description": "最多返回的事件数（从最早的事件开始）" }
+                    }
+                }
+            },
+            {
+                "name": "configure_event_buffers",
+                "description": "查看或调整键鼠监控事件缓冲区的容量，返回各缓冲区的容量与当前占用；缩小容量会丢弃最旧的事件",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "keyboard_capacity": { "type": "integer", "minimum": 1, "maximum": 100000, "description": "键盘事件缓冲区容量" },
+                        "mouse_capacity": { "type": "integer", "minimum": 1, "maximum": 100000, "description": "鼠标事件缓冲区容量" }
+                    }
+                }
+            },
+            {
+                "name": "monitor_control",
+                "description": "控制键鼠监控：start 获取监听器锁并开始采集；pause 暂停采集（保留锁与缓冲区）；resume 恢复暂停的采集；stop 停止采集并释放监听器锁，其他进程可接管；status 只查询状态。clear_buffers=true 时同时清空键盘与鼠标事件缓冲区",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "action": { "type": "string", "enum": ["start", "pause", "resume", "stop", "status"], "default": "status", "description": "控制操作" },
+                        "clear_buffers": { "type": "boolean", "default": false, "description": "清空事件缓冲区（序号继续递增，消费者位置移到末尾）" }
+                    }
+                }
+            },
+            {
+                "name": "configure_keyboard_privacy",
+                "description": "查看或切换键盘监控的隐私策略：full 记录完整按键；mask 将字母与数字键（及其输入字符）记为 *；counts 只保留事件数量与时间；disabled 不采集键盘事件，鼠标监控不受影响。只影响切换之后的事件",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "mode": { "type": "string", "enum": ["full", "mask", "counts", "disabled"], "description": "新的隐私策略；缺省时只返回当前策略" }
+                    }
+                }
+            },
+            {
+                "name": "monitor_capabilities",
+                "description": "报告键鼠监控使用的后端（rdev / evdev）、是否在运行及失败原因，以及是否处于 Wayland 会话",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "monitor_health",
+                "description": "报告键鼠监控各线程的健康状态：监听线程（系统输入钩子）与收集线程是否存活、最近一次系统输入回调及键盘 / 鼠标最近事件距今多久、监听器锁由哪个进程持有、监听后端重启次数与尚未恢复的中断；用于区分“用户没有操作”与“钩子已失效”",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "monitor_ack_events",
+                "description": "确认具名消费者已处理到 cursor（上次读取的 next_cursor）为止的事件；trim=true 时丢弃所有消费者都已确认的事件；release=true 时注销消费者",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "consumer": { "type": "string", "description": "消费者名称（与 monitor_keyboard_events / monitor_mouse_events 的 consumer 相同）" },
+                        "device": { "type": "string", "enum": ["keyboard", "mouse"], "description": "确认的事件缓冲区" },
+                        "cursor": { "type": "integer", "minimum": 0, "description": "已处理到的位置（不含），通常为上次读取的 next_cursor" },
+                        "trim": { "type": "boolean", "default": false, "description": "丢弃所有消费者都已确认的事件" },
+                        "release": { "type": "boolean", "default": false, "description": "注销该消费者（键盘与鼠标缓冲区），此时忽略其他参数" }
+                    },
+                    "required": ["consumer"]
+                }
+            },
+            {
+                "name": "monitor_gestures",
+                "description": "从鼠标监控事件中识别高层手势：click、double_click、drag（起止点）与 swipe（未按住按钮的快速直线移动，方向 left / right / up / down）；只读取不标记为已读",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "cursor": { "type": "integer", "minimum": 0, "description": "只分析序号不小于该游标的事件（使用上次响应的 next_cursor 增量分析）；默认分析整个缓冲区" },
+                        "since_micros": { "type": "integer", "minimum": 0, "description": "只分析时间戳不早于该值的事件（Unix 时间，微秒）" },
+                        "until_micros": { "type": "integer", "minimum": 0, "description": "只分析时间戳不晚于该值的事件（Unix 时间，微秒）" },
+                        "exclude_synthetic": { "type": "boolean", "default": false, "description": "排除 iris-mcp 自身注入的输入产生的手势" }
+                    }
+                }
+            },
+            {
+                "name": "monitor_subscribe",
+                "description": "订阅键鼠与显示器事件：匹配的事件以 notifications/monitor/event 通知实时推送（参数含 subscription_id、device、event），无需轮询 monitor_*_events；显示器事件为 display_added / display_removed / geometry_changed（含 display_id 与新的位置、尺寸、缩放）；需要支持推送的传输（stdio）",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "devices": {
+                            "type": "array",
+                            "items": { "type": "string", "enum": ["keyboard", "mouse", "display"] },
+                            "default": ["keyboard", "mouse", "display"],
+                            "description": "订阅的设备；display 为显示器的增减与分辨率、位置、缩放变化"
+                        },
+                        "keys": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "只推送这些按键的事件（按键名称，如 Enter、Ctrl、a，或组合名称，如 ctrl+c）；默认全部按键"
+                        },
+                        "mouse_kinds": {
+                            "type": "array",
+                            "items": { "type": "string", "enum": ["move", "button", "scroll"] },
+                            "default": ["button", "scroll"],
+                            "description": "推送的鼠标事件类型；移动事件频繁，默认不推送"
+                        },
+                        "exclude_synthetic": { "type": "boolean", "default": false, "description": "不推送 iris-mcp 自身注入的输入" }
+                    }
+                }
+            },
+            {
+                "name": "monitor_unsubscribe",
+                "description": "取消 monitor_subscribe 创建的事件订阅",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "subscription_id": { "type": "integer", "minimum": 1, "description": "monitor_subscribe 返回的订阅 ID" }
+                    },
+                    "required": ["subscription_id"]
+                }
+            },
+            {
+                "name": "screen_ocr",
+                "description": "识别屏幕上的文字（tesseract，PATH 中或 IRIS_TESSERACT 指定），按行返回文字、置信度、截图像素边界框 bbox 与可直接传给鼠标工具的全局坐标 click，无需视觉模型即可点击文字",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "region": {
+                            "type": "object",
+                            "properties": {
+                                "x": { "type": "integer", "minimum": 0 },
+                                "y": { "type": "integer", "minimum": 0 },
+                                "width": { "type": "integer", "minimum": 1 },
+                                "height": { "type": "integer", "minimum": 1 }
+                            },
+                            "required": ["x", "y", "width", "height"],
+                            "description": "只识别该区域（截图像素坐标），返回的坐标仍相对整张截图；给出 window_id 时区域相对于窗口左上角"
+                        },
+                        "window_id": { "type": "integer", "description": "只识别该窗口在主显示器上的可见部分（见 list_windows）" },
+                        "contains": { "type": "string", "description": "只返回包含该文本的行（不区分大小写）" },
+                        "language": { "type": "string", "description": "tesseract 语言代码，多种语言用 + 连接（如 chi_sim+eng），默认 IRIS_OCR_LANG 或 eng" },
+                        "min_confidence": { "type": "number", "minimum": 0, "maximum": 100, "default": 30, "description": "丢弃置信度低于该值的词" },
+                        "upscale": { "type": "integer", "minimum": 1, "maximum": 4, "default": 1, "description": "识别前放大倍数，非 Retina 屏上的小字号文本可用 2 提升识别率" }
+                    }
+                }
+            },
+            {
+                "name": "capture_all_displays",
+                "description": "截取所有显示器并按全局坐标拼接为一张图（Hi-DPI 显示器缩小到逻辑分辨率），structuredContent 给出各显示器的全局位置与在图中的偏移，便于规划跨显示器的拖拽；全局坐标 = origin + 像素坐标 × points_per_pixel",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "reason": { "type": "string", "description": "调用原因，便于审计" },
+                        "max_width": { "type": "integer", "minimum": 1, "description": "等比缩小到不超过该宽度" },
+                        "max_height": { "type": "integer", "minimum": 1, "description": "等比缩小到不超过该高度" }
                     },
                     "required": ["reason"]
                 }
             },
+            {
+                "name": "screen_record_start",
+                "description": "开始录制主显示器（或其中的区域）为 GIF 或 MP4 文件，便于把操作过程交给人回看；调用 screen_record_stop 结束并取得文件路径。MP4 需要 ffmpeg（PATH 中或 IRIS_FFMPEG 指定）",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "format": { "type": "string", "enum": ["gif", "mp4"], "description": "输出格式，默认按 path 扩展名推断，否则为 gif" },
+                        "path": { "type": "string", "description": "输出文件名（不含目录），写入 IRIS_SCREEN_RECORDINGS_DIR（未设置时为系统临时目录下的 iris-mcp）；文件已存在时报错，默认按时间生成" },
+                        "fps": { "type": "integer", "minimum": 1, "maximum": 30, "default": 5, "description": "帧率" },
+                        "max_duration_secs": { "type": "number", "exclusiveMinimum": 0, "maximum": 600, "default": 60, "description": "最长录制时长（秒），到达后自动停止" },
+                        "region": {
+                            "type": "object",
+                            "properties": {
+                                "x": { "type": "integer", "minimum": 0 },
+                                "y": { "type": "integer", "minimum": 0 },
+                                "width": { "type": "integer", "minimum": 1 },
+                                "height": { "type": "integer", "minimum": 1 }
+                            },
+                            "required": ["x", "y", "width", "height"],
+                            "description": "只录制该区域（截图像素坐标）"
+                        },
+                        "max_width": { "type": "integer", "minimum": 1, "description": "等比缩小到宽度不超过该值（像素）；GIF 未指定尺寸限制时默认 1280" },
+                        "max_height": { "type": "integer", "minimum": 1, "description": "等比缩小到高度不超过该值（像素）" }
+                    }
+                }
+            },
+            {
+                "name": "screen_record_stop",
+                "description": "停止屏幕录制并写完文件，返回文件路径、格式、尺寸、时长、帧数与大小",
+                "inputSchema": { "type": "object", "properties": {} }
+            },
+            {
+                "name": "screen_subscribe",
+                "description": "订阅屏幕帧：按间隔或帧率截图，最新帧写入资源 screen://subscription/{id} 并发送 notifications/resources/updated 通知（参数含 uri、subscription_id、sequence），监控面板无需轮询 monitor_screen_events；后处理参数同 monitor_screen_events（high_contrast、upscale、scale、palette_colors 等同样可用）",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "interval_ms": { "type": "integer", "minimum": 100, "description": "截图间隔（毫秒），默认取 IRIS_SCREEN_WATCH_INTERVAL_MS（1000）" },
+                        "fps": { "type": "number", "exclusiveMinimum": 0, "maximum": 10, "description": "帧率，与 interval_ms 二选一" },
+                        "only_changes": { "type": "boolean", "default": true, "description": "只在画面变化时写入新帧并通知" },
+                        "region": {
+                            "type": "object",
+                            "properties": {
+                                "x": { "type": "integer", "minimum": 0 },
+                                "y": { "type": "integer", "minimum": 0 },
+                                "width": { "type": "integer", "minimum": 1 },
+                                "height": { "type": "integer", "minimum": 1 }
+                            },
+                            "required": ["x", "y", "width", "height"],
+                            "description": "只截取该区域（截图像素坐标）"
+                        },
+                        "include_cursor": { "type": "boolean", "default": false, "description": "将鼠标指针合成到帧中" },
+                        "max_width": { "type": "integer", "minimum": 1, "description": "等比缩小到宽度不超过该值（像素）" },
+                        "max_height": { "type": "integer", "minimum": 1, "description": "等比缩小到高度不超过该值（像素）" },
+                        "color_mode": { "type": "string", "enum": ["color", "grayscale", "palette"], "default": "color", "description": "输出颜色，同 monitor_screen_events" }
+                    }
+                }
+            },
+            {
+                "name": "screen_unsubscribe",
+                "description": "停止 screen_subscribe 创建的帧订阅并移除其资源",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "subscription_id": { "type": "integer", "minimum": 1, "description": "screen_subscribe 返回的订阅 ID" }
+                    },
+                    "required": ["subscription_id"]
+                }
+            },
             {
                 "name": "monitor_keyboard_events",
-                "description": "获取已积累的键盘监控事件（服务器启动时自动开始监控）",
+                "description": "获取已积累的键盘监控事件（服务器启动时自动开始监控）。事件保存在环形缓冲区中，读取不会删除；不带 cursor / peek 时返回上次读取后的新事件；event_type 为 press / repeat / release / combo / monitoring_gap，按住键时的自动重复记为 repeat；监听中断恢复后插入 monitoring_gap 事件，since_micros 至其时间戳之间的事件缺失；按住修饰键按下其他键时额外记录一条 combo 事件，key 为组合名称（如 Control+C）；window 为事件发生时的前台窗口（app / title）",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "reason": { "type": "string", "description": "调用原因，便于审计" },
                         "cursor": {
                             "type": "integer",
-                            "description": "从该游标开始读取事件，默认0"
-                        }
+                            "minimum": 0,
+                            "description": "返回序号不小于该游标的事件（使用上次响应的 next_cursor 增量读取），不改变默认读取位置；多个客户端可各自维护游标"
+                        },
+                        "peek": { "type": "boolean", "default": false, "description": "只查看默认读取位置之后的事件，不标记为已读" },
+                        "consumer": { "type": "string", "description": "具名消费者：返回该消费者已确认位置之后的事件，读取不改变位置，处理后用 monitor_ack_events 确认；不能与 cursor / peek 同时使用" },
+                        "since_micros": { "type": "integer", "minimum": 0, "description": "只返回时间戳不早于该值的事件（Unix 时间，微秒）；未指定 cursor 时检索整个缓冲区且不标记为已读" },
+                        "until_micros": { "type": "integer", "minimum": 0, "description": "只返回时间戳不晚于该值的事件（Unix 时间，微秒）" },
+                        "timestamp_format": { "type": "string", "enum": ["micros", "iso8601"], "default": "micros", "description": "iso8601 时每个事件额外附带 timestamp 字段（UTC，如 2024-05-01T08:30:15.123456Z）；monotonic_micros 始终返回，适合计算事件间隔" }
                     },
                     "required": ["reason"]
                 }
             },
             {
                 "name": "monitor_mouse_events",
-                "description": "获取已积累的鼠标监控事件（服务器启动时自动开始监控）",
+                "description": "获取已积累的鼠标监控事件（服务器启动时自动开始监控）。事件保存在环形缓冲区中，读取不会删除；不带 cursor / peek 时返回上次读取后的新事件；window 为事件发生时的前台窗口（app / title）",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "reason": { "type": "string", "description": "调用原因，便于审计" },
                         "cursor": {
                             "type": "integer",
-                            "description": "从该游标开始读取事件，默认0"
-                        }
+                            "minimum": 0,
+                            "description": "返回序号不小于该游标的事件（使用上次响应的 next_cursor 增量读取），不改变默认读取位置；多个客户端可各自维护游标"
+                        },
+                        "peek": { "type": "boolean", "default": false, "description": "只查看默认读取位置之后的事件，不标记为已读" },
+                        "consumer": { "type": "string", "description": "具名消费者：返回该消费者已确认位置之后的事件，读取不改变位置，处理后用 monitor_ack_events 确认；不能与 cursor / peek 同时使用" },
+                        "since_micros": { "type": "integer", "minimum": 0, "description": "只返回时间戳不早于该值的事件（Unix 时间，微秒）；未指定 cursor 时检索整个缓冲区且不标记为已读" },
+                        "until_micros": { "type": "integer", "minimum": 0, "description": "只返回时间戳不晚于该值的事件（Unix 时间，微秒）" },
+                        "coalesce_moves": { "type": "boolean", "default": false, "description": "将连续的移动事件合并为 stroke（start / end / point_count / duration_micros / bounding_box，seq 与 last_seq 为首尾事件序号），大幅减少路径密集时的返回数据量" },
+                        "timestamp_format": { "type": "string", "enum": ["micros", "iso8601"], "default": "micros", "description": "iso8601 时每个事件额外附带 timestamp 字段（UTC，如 2024-05-01T08:30:15.123456Z）；monotonic_micros 始终返回，适合计算事件间隔" }
                     },
                     "required": ["reason"]
                 }
+            },
+            {
+                "name": "list_displays",
+                "description": "列出所有显示器在全局虚拟桌面中的位置、尺寸、缩放比例与可用工作区（work_area），供鼠标工具的 display/area 参数使用",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "get_keyboard_state",
+                "description": "查询 Shift/Ctrl/Alt/Meta 是否按下（来自键鼠监控）以及 CapsLock/NumLock 状态，输入前可确认用户没有按住修饰键",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "get_idle_time",
+                "description": "返回距最近一次键鼠事件的微秒数：human_idle_micros 只计真人输入，idle_micros 含 iris-mcp 注入的输入；尚无事件时从监控启动时算起。可在注入前等待用户停止操作",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "get_input_snapshot",
+                "description": "返回监控记录的当前按下的键（keys）、鼠标按键（buttons，含按下时间与按下位置）及最近的光标位置。可在注入前发现卡住的修饰键或用户正在进行的拖拽；监控启动前已按下的键不会出现",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "input_source_info",
+                "description": "查询 iris-mcp 注入输入的来源签名及当前平台的标记方式（macOS kCGEventSourceUserData / Windows dwExtraInfo），供终端安全软件区分合成输入与真人输入",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "scroll_metrics",
+                "description": "通过无障碍接口获取滚动容器的当前滚动位置（0-1）、可见区域尺寸与内容总尺寸",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "number", "description": "屏幕 X 坐标（可选），取该位置下的元素；缺省时使用焦点元素" },
+                        "y": { "type": "number", "description": "屏幕 Y 坐标（可选）" }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "list_windows",
+                "description": "列出屏幕上的顶层窗口（ID、标题、所属应用、进程号与全局坐标位置尺寸），最前的窗口在先",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "window_at_point",
+                "description": "返回光标（或给定坐标）处最前的顶层窗口及所属应用、点在窗口内的坐标与该窗口是否为前台窗口，便于点击前确认目标",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "number", "description": "X 坐标，缺省时使用当前光标位置" },
+                        "y": { "type": "number", "description": "Y 坐标，缺省时使用当前光标位置" },
+                        "display": { "type": "integer", "minimum": 0, "description": "按该显示器的本地坐标解释 x / y（见 list_displays）" },
+                        "window_id": { "type": "integer", "description": "按该窗口左上角为原点解释 x / y（见 list_windows）" },
+                        "coordinate_space": { "type": "string", "enum": ["pixels", "normalized", "physical"], "description": "x / y 的坐标空间，同鼠标工具" }
+                    }
+                }
+            },
+            {
+                "name": "wait_for_window",
+                "description": "阻塞等待标题（或应用名）匹配的窗口出现或消失，如点击保存后等待对话框弹出，无需反复截图；超时不报错，以 matched / timed_out 表示结果，支持进度通知与取消",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "title": { "type": "string", "description": "窗口标题模式：含 * / ? 时按通配符匹配整个标题，否则按子串匹配，不区分大小写" },
+                        "app": { "type": "string", "description": "所属应用名模式，规则同 title" },
+                        "state": { "type": "string", "enum": ["appear", "disappear"], "default": "appear", "description": "appear 等待匹配的窗口出现；disappear 等待匹配的窗口全部消失" },
+                        "timeout_ms": { "type": "integer", "minimum": 0, "maximum": 120000, "default": 10000, "description": "最长等待时间（毫秒）" }
+                    }
+                }
+            },
+            {
+                "name": "window_snap",
+                "description": "按预设布局移动并缩放窗口，基于显示器可用工作区（排除菜单栏、Dock、任务栏）计算",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows）" },
+                        "position": {
+                            "type": "string",
+                            "enum": ["left-half", "right-half", "maximize-work-area", "center-60"],
+                            "description": "布局预设：左半屏、右半屏、铺满工作区、居中占 60%"
+                        },
+                        "display": { "type": "integer", "minimum": 0, "description": "目标显示器序号（见 list_displays）；缺省时为窗口中心所在显示器" }
+                    },
+                    "required": ["window_id", "position"]
+                }
+            },
+            {
+                "name": "window_control",
+                "description": "通过系统窗口管理接口最小化、最大化、还原或关闭窗口，不依赖各应用不同的快捷键；macOS 没有最大化状态，maximize 铺满窗口所在显示器的可用工作区，restore 还原到最大化之前的位置",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "window_id": { "type": "integer", "description": "窗口 ID（见 list_windows；Windows 为 HWND 数值）" },
+                        "action": {
+                            "type": "string",
+                            "enum": ["minimize", "maximize", "restore", "close"],
+                            "description": "minimize 最小化；maximize 最大化；restore 取消最小化 / 最大化（macOS 同时退出全屏）并置于前台；close 请求关闭，应用可能弹出保存确认"
+                        }
+                    },
+                    "required": ["window_id", "action"]
+                }
+            },
+            {
+                "name": "set_input_profile",
+                "description": "切换全局输入节奏，统一缩放键鼠注入的等待时间（逐字符输入间隔、多击间隔、路径移动耗时、滚动、按键重复与序列延迟）；预设 fast（跳过等待，适合 CI）、normal、human（放慢并默认逐字符输入，适合演示）；不带参数时返回当前配置",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "profile": {
+                            "type": "string",
+                            "enum": ["fast", "normal", "human"],
+                            "description": "预设名称"
+                        },
+                        "delay_scale": { "type": "number", "minimum": 0, "maximum": 10, "description": "覆盖延迟倍率，0 表示跳过等待" },
+                        "typing_delay_ms": { "type": "integer", "minimum": 0, "maximum": 1000, "description": "type_text 未指定 char_delay_ms 时的默认字符间隔（0-1000）" },
+                        "typing_variance_ms": { "type": "integer", "minimum": 0, "maximum": 1000, "description": "type_text 未指定 char_delay_variance_ms 时的默认随机浮动（0-1000）" }
+                    }
+                }
+            },
+            {
+                "name": "execute_script",
+                "description": "在一次调用中顺序执行多个工具步骤，支持 if 条件与 repeat 循环；步骤结果可用 save_as 保存为变量，后续步骤参数中以 {{name}} 或 {{name.path}} 引用",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "steps": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "tool": { "type": "string", "description": "工具名称（不可为 execute_script 或 macro_run）" },
+                                    "arguments": { "type": "object", "description": "工具参数，字符串中可使用 {{var}} 模板" },
+                                    "save_as": { "type": "string", "description": "将结果保存为变量：有结构化结果时保存 structuredContent，否则保存文本" },
+                                    "if": {
+                                        "type": "object",
+                                        "description": "条件步骤：{condition, then: [步骤], else: [步骤]}"
+                                    },
+                                    "repeat": {
+                                        "type": "object",
+                                        "description": "循环步骤：{times: N, steps} 或 {until: 条件, steps, max_iterations（默认 100）}，可选 interval_ms 为迭代间隔（最大 60000）；until 在每次迭代前检查"
+                                    }
+                                },
+                                "description": "工具步骤（tool）或控制步骤（if / repeat）。条件可为 {locate: target}、{pixel: {x, y, color: \"#rrggbb\", tolerance}}、{var: 名称, equals: 值}，及 {not: 条件}、{all: [条件]}、{any: [条件]}"
+                            },
+                            "description": "按顺序执行的步骤（每层最多 100 步，全程最多执行 1000 个工具步骤、最长 300 秒）"
+                        },
+                        "variables": { "type": "object", "description": "初始变量" },
+                        "stop_on_error": { "type": "boolean", "description": "某步失败时停止并返回错误（默认 true）；为 false 时记录错误并继续" }
+                    },
+                    "required": ["steps"]
+                }
+            },
+            {
+                "name": "macro_define",
+                "description": "保存命名宏（步骤格式同 execute_script），跨会话保留，之后用 macro_run 按名称调用；宏以明文保存，不接受 sensitive 步骤",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "pattern": "^[A-Za-z0-9_.-]{1,64}$", "description": "宏名称" },
+                        "description": { "type": "string", "description": "宏的用途说明" },
+                        "steps": { "type": "array", "items": { "type": "object" }, "minItems": 1, "description": "步骤，格式同 execute_script 的 steps" },
+                        "overwrite": { "type": "boolean", "description": "同名宏已存在时替换（默认 false）" }
+                    },
+                    "required": ["name", "steps"]
+                }
+            },
+            {
+                "name": "macro_list",
+                "description": "列出已保存的宏（名称、说明、步骤数）",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "macro_run",
+                "description": "按名称执行已保存的宏，结果同 execute_script",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "宏名称" },
+                        "variables": { "type": "object", "description": "初始变量，供宏步骤中的 {{var}} 模板引用" },
+                        "stop_on_error": { "type": "boolean", "description": "某步失败时停止（默认 true）" }
+                    },
+                    "required": ["name"]
+                }
+            },
+            {
+                "name": "macro_delete",
+                "description": "删除已保存的宏",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "宏名称" }
+                    },
+                    "required": ["name"]
+                }
+            },
+            {
+                "name": "recording_start",
+                "description": "开始录制键鼠输入（连同相对时序），调用 recording_stop 后按名称保存，可用 recording_replay 回放；默认不录制 iris-mcp 自身注入的输入",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "录制名称（A-Z、a-z、0-9、_、-、.，最长 64）" },
+                        "keyboard": { "type": "boolean", "default": true, "description": "录制键盘输入" },
+                        "mouse": { "type": "boolean", "default": true, "description": "录制鼠标输入" },
+                        "mouse_moves": { "type": "boolean", "default": true, "description": "录制鼠标移动；为 false 时回放的点击发生在回放时光标所在位置" },
+                        "include_synthetic": { "type": "boolean", "default": false, "description": "同时录制 iris-mcp 注入的输入" },
+                        "overwrite": { "type": "boolean", "default": false, "description": "替换同名录制" }
+                    },
+                    "required": ["name"]
+                }
+            },
+            {
+                "name": "recording_stop",
+                "description": "停止当前录制并保存，返回事件数与时长",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            {
+                "name": "recording_replay",
+                "description": "按录制时的时序回放已保存的录制；speed 为速度倍数（2 表示两倍速），不受输入节奏配置影响。回放结束或取消时释放仍按住的键与按钮",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "录制名称" },
+                        "speed": { "type": "number", "minimum": 0.1, "maximum": 10, "default": 1, "description": "速度倍数" }
+                    },
+                    "required": ["name"]
+                }
+            },
+            {
+                "name": "check_update",
+                "description": "查询发布源，返回最新版本号与更新日志（只读）；安装新版本需在命令行运行 iris-mcp check-update --install",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
             }
         ]
     })
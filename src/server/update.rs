@@ -0,0 +1,32 @@
+use super::jsonrpc::JsonRpcError;
+use crate::update;
+use serde_json::{json, Value};
+
+/// 只报告版本信息；替换可执行文件只能在命令行执行 `iris-mcp check-update --install`
+pub fn handle_check_update(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let info = update::check_update(false).map_err(|e| JsonRpcError {
+        code: -32001,
+        message: e.to_string(),
+        data: None,
+    })?;
+
+    let summary = if info.update_available {
+        format!(
+            "有新版本 {}（当前 {}），请在命令行运行 `iris-mcp check-update --install` 下载并替换",
+            info.latest_version, info.current_version
+        )
+    } else {
+        format!("已是最新版本 {}", info.current_version)
+    };
+    let result_text = serde_json::to_string_pretty(&info)
+        .unwrap_or_else(|_| json!(info).to_string());
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("{}\n\n{}", summary, result_text)
+            }
+        ]
+    }))
+}
@@ -0,0 +1,265 @@
+use super::coords;
+use super::jsonrpc::JsonRpcError;
+use super::mouse::shared_mouse;
+use super::progress;
+use crate::monitor::display;
+use crate::operator::window::{self, SnapPosition, WindowAction, WindowError, WindowFilter};
+use serde_json::{json, Value};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn window_error(e: WindowError) -> JsonRpcError {
+    let code = match e {
+        WindowError::NotFound(_) => -32602,
+        _ => -32001,
+    };
+    JsonRpcError {
+        code,
+        message: e.to_string(),
+        data: None,
+    }
+}
+
+pub fn handle_list_windows(_arguments: &Value) -> Result<Value, JsonRpcError> {
+    let windows = window::list_windows().map_err(window_error)?;
+
+    let result = json!({
+        "windows": windows,
+        "total": windows.len()
+    });
+    let result_text = serde_json::to_string_pretty(&result)
+        .unwrap_or_else(|_| result.to_string());
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("共{}个窗口（最前在先）\n\n{}", windows.len(), result_text)
+            }
+        ]
+    }))
+}
+
+pub fn handle_window_at_point(arguments: &Value) -> Result<Value, JsonRpcError> {
+    // 未给坐标时取当前光标位置；查询不应受混沌模式扰动，因此不走 resolve_point
+    let (x, y) = match (arguments["x"].as_f64(), arguments["y"].as_f64()) {
+        (Some(x), Some(y)) => coords::parse_context(arguments)?.to_global(x, y),
+        (None, None) => shared_mouse()?.mouse_get_position().map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to get position: {}", e),
+            data: None,
+        })?,
+        _ => {
+            return Err(JsonRpcError {
+                code: -32602,
+                message: "x and y must be given together".to_string(),
+                data: None,
+            });
+        }
+    };
+
+    let info = window::window_at(x, y).map_err(window_error)?;
+    let focused = window::focused_window().ok().map(|w| w.id == info.id);
+    let result = json!({
+        "point": { "x": x, "y": y },
+        "window": info,
+        "local": { "x": x - info.frame.x, "y": y - info.frame.y },
+        "focused": focused,
+    });
+    let result_text = serde_json::to_string_pretty(&result)
+        .unwrap_or_else(|_| result.to_string());
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!(
+                    "({}, {}) 处的窗口: {}「{}」{}\n\n{}",
+                    x,
+                    y,
+                    info.app.as_deref().unwrap_or("未知应用"),
+                    info.title,
+                    match focused {
+                        Some(true) => "（前台窗口）",
+                        Some(false) => "（非前台窗口，点击会先切换焦点）",
+                        None => "",
+                    },
+                    result_text
+                )
+            }
+        ],
+        "structuredContent": result
+    }))
+}
+
+pub fn handle_window_snap(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let id = arguments["window_id"].as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing window_id".to_string(),
+        data: None,
+    })?;
+
+    let position_str = arguments["position"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing position".to_string(),
+        data: None,
+    })?;
+    let position = SnapPosition::parse(position_str).ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: format!("Invalid position: {}", position_str),
+        data: None,
+    })?;
+
+    let info = window::get_window(id).map_err(window_error)?;
+
+    // 未指定显示器时，使用窗口中心所在的显示器（找不到则用主显示器）
+    let target = match coords::parse_display(arguments)? {
+        Some(d) => d,
+        None => {
+            let displays = display::list_displays().map_err(|e| JsonRpcError {
+                code: -32001,
+                message: e.to_string(),
+                data: None,
+            })?;
+            let cx = info.frame.x + (info.frame.width / 2) as i32;
+            let cy = info.frame.y + (info.frame.height / 2) as i32;
+            displays
+                .iter()
+                .find(|d| d.contains(cx, cy))
+                .or_else(|| displays.first())
+                .cloned()
+                .ok_or_else(|| JsonRpcError {
+                    code: -32001,
+                    message: "No displays available".to_string(),
+                    data: None,
+                })?
+        }
+    };
+
+    let area = target.usable_area();
+    let frame = position.frame_in(&area);
+    window::set_frame(id, frame).map_err(window_error)?;
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!(
+                    "已将窗口 {} 贴靠到显示器 {} 的 {}：({}, {}) {}x{}",
+                    id, target.index, position_str, frame.x, frame.y, frame.width, frame.height
+                )
+            }
+        ]
+    }))
+}
+
+pub fn handle_window_control(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let id = arguments["window_id"].as_u64().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing window_id".to_string(),
+        data: None,
+    })?;
+
+    let action_str = arguments["action"].as_str().ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing action".to_string(),
+        data: None,
+    })?;
+    let action = WindowAction::parse(action_str).ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: format!("Invalid action: {}", action_str),
+        data: None,
+    })?;
+
+    window::control(id, action).map_err(window_error)?;
+
+    Ok(json!({
+        "content": [
+            {
+                "type": "text",
+                "text": format!("已请求对窗口 {} 执行 {}", id, action.as_str())
+            }
+        ]
+    }))
+}
+
+/// wait_for_window 的默认与最长等待时间（毫秒）
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 10_000;
+const MAX_WAIT_TIMEOUT_MS: u64 = 120_000;
+/// 轮询窗口列表的间隔
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub fn handle_wait_for_window(arguments: &Value) -> Result<Value, JsonRpcError> {
+    let invalid = |message: String| JsonRpcError { code: -32602, message, data: None };
+    let pattern = |name: &str| match &arguments[name] {
+        Value::Null => Ok(None),
+        value => value
+            .as_str()
+            .filter(|p| !p.is_empty())
+            .map(|p| Some(p.to_string()))
+            .ok_or_else(|| invalid(format!("{} must be a non-empty string", name))),
+    };
+    let filter = WindowFilter { title: pattern("title")?, app: pattern("app")? };
+    if filter.title.is_none() && filter.app.is_none() {
+        return Err(invalid("Missing title or app".to_string()));
+    }
+    let disappear = match arguments["state"].as_str() {
+        None | Some("appear") => false,
+        Some("disappear") => true,
+        Some(other) => return Err(invalid(format!("Invalid state: {}", other))),
+    };
+    let timeout_ms = match &arguments["timeout_ms"] {
+        Value::Null => DEFAULT_WAIT_TIMEOUT_MS,
+        value => value
+            .as_u64()
+            .filter(|t| *t <= MAX_WAIT_TIMEOUT_MS)
+            .ok_or_else(|| invalid(format!("timeout_ms must be an integer between 0 and {}", MAX_WAIT_TIMEOUT_MS)))?,
+    };
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_millis(timeout_ms);
+    let state = if disappear { "disappear" } else { "appear" };
+    let (matched, windows) = loop {
+        let windows: Vec<_> = window::list_windows()
+            .map_err(window_error)?
+            .into_iter()
+            .filter(|w| filter.matches(w))
+            .collect();
+        if windows.is_empty() == disappear {
+            break (true, windows);
+        }
+        let now = Instant::now();
+        if now >= deadline || progress::is_cancelled() {
+            break (false, windows);
+        }
+        let waited = now.duration_since(started).as_millis() as u64;
+        let message = format!("等待窗口{}（{}/{} ms）", if disappear { "消失" } else { "出现" }, waited, timeout_ms);
+        progress::report(waited, Some(timeout_ms), &message);
+        thread::sleep(WAIT_POLL_INTERVAL.min(deadline - now));
+    };
+    let waited_ms = started.elapsed().as_millis() as u64;
+
+    let text = match (matched, disappear) {
+        (true, false) => format!(
+            "窗口已出现（等待 {} ms）: {}「{}」，ID {}",
+            waited_ms,
+            windows[0].app.as_deref().unwrap_or("未知应用"),
+            windows[0].title,
+            windows[0].id
+        ),
+        (true, true) => format!("匹配的窗口已全部消失（等待 {} ms）", waited_ms),
+        (false, _) if waited_ms < timeout_ms => format!("等待已取消（{} ms）", waited_ms),
+        (false, false) => format!("等待 {} ms 后仍没有匹配的窗口", waited_ms),
+        (false, true) => format!("等待 {} ms 后仍有 {} 个匹配的窗口", waited_ms, windows.len()),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "structuredContent": {
+            "state": state,
+            "matched": matched,
+            "timed_out": !matched && waited_ms >= timeout_ms,
+            "waited_ms": waited_ms,
+            "windows": windows
+        }
+    }))
+}
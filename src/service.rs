@@ -0,0 +1,336 @@
+//! 将 iris-mcp 安装为用户级后台服务，使其随登录自动启动并常驻：
+//! macOS 为 LaunchAgent，Linux 为 systemd 用户服务，Windows 为登录时触发的计划任务。
+//!
+//! 后台服务没有可用的 stdin/stdout，因此只支持 HTTP 传输（见 `server::http`）。
+
+use crate::server::http::DEFAULT_LISTEN_ADDR;
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// 服务标识（LaunchAgent label / systemd unit / 计划任务名）
+pub const SERVICE_LABEL: &str = "com.leafliber.iris-mcp";
+
+/// 安装选项
+#[derive(Debug, Clone)]
+pub struct ServiceConfig {
+    /// 服务启动的可执行文件，默认为当前程序
+    pub executable: PathBuf,
+    /// HTTP 监听地址
+    pub listen: String,
+    /// 传递给服务进程的环境变量（如 IRIS_OUT_OF_BOUNDS）
+    pub env: Vec<(String, String)>,
+}
+
+impl ServiceConfig {
+    /// 从 `install-service` 的命令行参数解析：
+    /// `--transport http`、`--listen <addr>`、`--env KEY=VALUE`（可重复）
+    pub fn from_args(args: &[String]) -> io::Result<Self> {
+        let mut config = ServiceConfig {
+            executable: env::current_exe()?,
+            listen: DEFAULT_LISTEN_ADDR.to_string(),
+            env: Vec::new(),
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = |name: &str| {
+                iter.next()
+                    .cloned()
+                    .ok_or_else(|| invalid_input(format!("{} requires a value", name)))
+            };
+            match arg.as_str() {
+                "--transport" => match value("--transport")?.as_str() {
+                    "http" => {}
+                    "stdio" => {
+                        return Err(invalid_input(
+                            "stdio transport cannot run as a background service; use --transport http",
+                        ));
+                    }
+                    other => return Err(invalid_input(format!("unknown transport: {}", other))),
+                },
+                "--listen" => config.listen = value("--listen")?,
+                "--env" => {
+                    let pair = value("--env")?;
+                    let (key, val) = pair
+                        .split_once('=')
+                        .ok_or_else(|| invalid_input(format!("--env expects KEY=VALUE, got {}", pair)))?;
+                    config.env.push((key.to_string(), val.to_string()));
+                }
+                other => return Err(invalid_input(format!("unknown option: {}", other))),
+            }
+        }
+        Ok(config)
+    }
+
+    /// 服务进程的命令行参数
+    fn server_args(&self) -> Vec<String> {
+        vec!["serve".to_string(), "--listen".to_string(), self.listen.clone()]
+    }
+}
+
+fn invalid_input(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg.into())
+}
+
+fn home_dir() -> io::Result<PathBuf> {
+    let var = if cfg!(target_os = "windows") { "USERPROFILE" } else { "HOME" };
+    env::var_os(var)
+        .map(PathBuf::from)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} is not set", var)))
+}
+
+/// 运行外部命令，非零退出码视为错误
+fn run(program: &str, args: &[&str]) -> io::Result<()> {
+    let status = Command::new(program).args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} {} failed: {}", program, args.join(" "), status)))
+    }
+}
+
+/// 安装并启动服务，返回写入的配置文件路径
+pub fn install(config: &ServiceConfig) -> io::Result<PathBuf> {
+    platform::install(config)
+}
+
+/// 停止并移除服务，返回删除的配置文件路径
+pub fn uninstall() -> io::Result<PathBuf> {
+    platform::uninstall()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    fn plist_path() -> io::Result<PathBuf> {
+        Ok(home_dir()?
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", SERVICE_LABEL)))
+    }
+
+    fn xml_escape(s: &str) -> String {
+        s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+
+    fn render_plist(config: &ServiceConfig) -> String {
+        let mut program = vec![config.executable.display().to_string()];
+        program.extend(config.server_args());
+        let program: String = program
+            .iter()
+            .map(|a| format!("        <string>{}</string>\n", xml_escape(a)))
+            .collect();
+        let env: String = config
+            .env
+            .iter()
+            .map(|(k, v)| {
+                format!(
+                    "        <key>{}</key>\n        <string>{}</string>\n",
+                    xml_escape(k),
+                    xml_escape(v)
+                )
+            })
+            .collect();
+        let log = env::temp_dir().join("iris-mcp-service.log");
+        let log = xml_escape(&log.display().to_string());
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{program}    </array>
+    <key>EnvironmentVariables</key>
+    <dict>
+{env}    </dict>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardErrorPath</key>
+    <string>{log}</string>
+</dict>
+</plist>
+"#,
+            label = SERVICE_LABEL,
+        )
+    }
+
+    pub fn install(config: &ServiceConfig) -> io::Result<PathBuf> {
+        let path = plist_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if path.exists() {
+            // 重新安装时先卸载旧配置，忽略未加载的错误
+            let _ = run("launchctl", &["unload", "-w", &path.display().to_string()]);
+        }
+        fs::write(&path, render_plist(config))?;
+        run("launchctl", &["load", "-w", &path.display().to_string()])?;
+        Ok(path)
+    }
+
+    pub fn uninstall() -> io::Result<PathBuf> {
+        let path = plist_path()?;
+        if !path.exists() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} not installed", path.display())));
+        }
+        let _ = run("launchctl", &["unload", "-w", &path.display().to_string()]);
+        fs::remove_file(&path)?;
+        Ok(path)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    const UNIT_NAME: &str = "iris-mcp.service";
+
+    fn unit_path() -> io::Result<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .map_or_else(|| home_dir().map(|h| h.join(".config")), Ok)?;
+        Ok(config_dir.join("systemd/user").join(UNIT_NAME))
+    }
+
+    /// systemd 的参数引用：含空白或引号时加双引号并转义
+    fn quote(arg: &str) -> String {
+        if arg.chars().any(|c| c.is_whitespace() || c == '"' || c == '\\') {
+            format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+        } else {
+            arg.to_string()
+        }
+    }
+
+    fn render_unit(config: &ServiceConfig) -> String {
+        let mut exec = vec![quote(&config.executable.display().to_string())];
+        exec.extend(config.server_args().iter().map(|a| quote(a)));
+        let env: String = config
+            .env
+            .iter()
+            .map(|(k, v)| format!("Environment={}\n", quote(&format!("{}={}", k, v))))
+            .collect();
+
+        format!(
+            "[Unit]\n\
+             Description=iris-mcp desktop control server ({label})\n\
+             After=graphical-session.target\n\
+             PartOf=graphical-session.target\n\
+             \n\
+             [Service]\n\
+             ExecStart={exec}\n\
+             {env}\
+             Restart=on-failure\n\
+             RestartSec=3\n\
+             \n\
+             [Install]\n\
+             WantedBy=graphical-session.target\n",
+            label = SERVICE_LABEL,
+            exec = exec.join(" "),
+        )
+    }
+
+    pub fn install(config: &ServiceConfig) -> io::Result<PathBuf> {
+        let path = unit_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, render_unit(config))?;
+        run("systemctl", &["--user", "daemon-reload"])?;
+        run("systemctl", &["--user", "enable", "--now", UNIT_NAME])?;
+        Ok(path)
+    }
+
+    pub fn uninstall() -> io::Result<PathBuf> {
+        let path = unit_path()?;
+        if !path.exists() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} not installed", path.display())));
+        }
+        let _ = run("systemctl", &["--user", "disable", "--now", UNIT_NAME]);
+        fs::remove_file(&path)?;
+        run("systemctl", &["--user", "daemon-reload"])?;
+        Ok(path)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::fs;
+
+    const TASK_NAME: &str = "iris-mcp";
+
+    /// 计划任务无法直接设置环境变量，通过启动脚本传递
+    fn script_path() -> io::Result<PathBuf> {
+        let base = env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .map_or_else(|| home_dir().map(|h| h.join("AppData").join("Roaming")), Ok)?;
+        Ok(base.join("iris-mcp").join("iris-mcp-service.cmd"))
+    }
+
+    fn render_script(config: &ServiceConfig) -> String {
+        let mut script = String::from("@echo off\r\n");
+        for (k, v) in &config.env {
+            script.push_str(&format!("set \"{}={}\"\r\n", k, v));
+        }
+        script.push_str(&format!(
+            "\"{}\" {}\r\n",
+            config.executable.display(),
+            config.server_args().join(" ")
+        ));
+        script
+    }
+
+    pub fn install(config: &ServiceConfig) -> io::Result<PathBuf> {
+        let path = script_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, render_script(config))?;
+        let action = format!("\"{}\"", path.display());
+        run(
+            "schtasks",
+            &["/Create", "/TN", TASK_NAME, "/TR", &action, "/SC", "ONLOGON", "/RL", "LIMITED", "/F"],
+        )?;
+        run("schtasks", &["/Run", "/TN", TASK_NAME])?;
+        Ok(path)
+    }
+
+    pub fn uninstall() -> io::Result<PathBuf> {
+        let path = script_path()?;
+        let _ = run("schtasks", &["/End", "/TN", TASK_NAME]);
+        run("schtasks", &["/Delete", "/TN", TASK_NAME, "/F"])?;
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    pub fn install(_config: &ServiceConfig) -> io::Result<PathBuf> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("service installation unsupported on {}", env::consts::OS),
+        ))
+    }
+
+    pub fn uninstall() -> io::Result<PathBuf> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("service installation unsupported on {}", env::consts::OS),
+        ))
+    }
+}
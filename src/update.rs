@@ -0,0 +1,323 @@
+//! 版本更新检查：查询发布源获取最新版本与更新日志，并可下载替换当前可执行文件。
+//!
+//! 网络请求通过系统自带的 `curl` 完成（macOS、主流 Linux 发行版与 Windows 10+ 均内置），
+//! 避免为此引入 TLS 依赖。下载的文件必须与发布源给出的 SHA-256 一致才会替换，
+//! 发布源没有提供校验值时拒绝安装。
+
+use serde::Serialize;
+use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 默认发布源（GitHub Releases API）；可通过环境变量 IRIS_UPDATE_FEED 覆盖
+pub const DEFAULT_UPDATE_FEED: &str = "https://api.github.com/repos/leafliber/iris-mcp/releases/latest";
+
+/// 当前版本
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug)]
+pub enum UpdateError {
+    Fetch(String),
+    InvalidFeed(String),
+    NoAsset(String),
+    Checksum(String),
+    Io(String),
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateError::Fetch(msg) => write!(f, "failed to query release feed: {}", msg),
+            UpdateError::InvalidFeed(msg) => write!(f, "invalid release feed: {}", msg),
+            UpdateError::NoAsset(msg) => write!(f, "no release asset for this platform: {}", msg),
+            UpdateError::Checksum(msg) => write!(f, "release checksum: {}", msg),
+            UpdateError::Io(msg) => write!(f, "update io error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub changelog: String,
+    pub release_url: Option<String>,
+    /// 当前平台对应的发布文件名与下载地址
+    pub asset_name: Option<String>,
+    pub asset_url: Option<String>,
+    /// 发布文件的 SHA-256（小写十六进制），来自资源的 `digest` 字段或同名 `.sha256` 文件
+    pub asset_sha256: Option<String>,
+    /// 安装成功后被替换的可执行文件路径
+    pub installed_to: Option<PathBuf>,
+}
+
+fn feed_url() -> String {
+    env::var("IRIS_UPDATE_FEED").unwrap_or_else(|_| DEFAULT_UPDATE_FEED.to_string())
+}
+
+fn curl(args: &[&str]) -> Result<Vec<u8>, UpdateError> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", "60", "-H", "User-Agent: iris-mcp"])
+        .args(args)
+        .output()
+        .map_err(|e| UpdateError::Fetch(format!("failed to run curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(UpdateError::Fetch(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+/// 解析 "v1.2.3" / "1.2.3-beta" 形式的版本号为数字序列（忽略预发布后缀）
+fn parse_version(v: &str) -> Vec<u64> {
+    v.trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    let (mut a, mut b) = (parse_version(latest), parse_version(current));
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a > b
+}
+
+/// 与构建脚本输出一致的发布文件名：iris-mcp-<os>-<arch>[.exe]
+fn asset_candidates() -> Vec<String> {
+    let suffix = if cfg!(target_os = "windows") { ".exe" } else { "" };
+    let os = env::consts::OS;
+    let mut arches = vec![env::consts::ARCH];
+    // macOS 上 `uname -m` 报告 arm64
+    if os == "macos" && env::consts::ARCH == "aarch64" {
+        arches.push("arm64");
+    }
+    arches
+        .into_iter()
+        .map(|arch| format!("iris-mcp-{}-{}{}", os, arch, suffix))
+        .collect()
+}
+
+/// 解析 `sha256:<hex>` 或 `sha256sum` 输出格式（`<hex>  <文件名>`）中的摘要
+fn parse_sha256(text: &str) -> Option<String> {
+    let hex = text.trim().trim_start_matches("sha256:");
+    let hex = hex.split_whitespace().next()?.to_ascii_lowercase();
+    (hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit())).then_some(hex)
+}
+
+/// 发布文件的期望摘要：优先使用 GitHub 资源自带的 `digest`，其次下载同名 `.sha256` 文件
+fn asset_sha256(assets: &[Value], asset: &Value) -> Result<Option<String>, UpdateError> {
+    if let Some(digest) = asset["digest"].as_str().and_then(parse_sha256) {
+        return Ok(Some(digest));
+    }
+    let Some(name) = asset["name"].as_str() else { return Ok(None) };
+    let sidecar = format!("{}.sha256", name);
+    let url = assets
+        .iter()
+        .find(|a| a["name"].as_str() == Some(sidecar.as_str()))
+        .and_then(|a| a["browser_download_url"].as_str());
+    match url {
+        Some(url) => Ok(parse_sha256(&String::from_utf8_lossy(&curl(&[url])?))),
+        None => Ok(None),
+    }
+}
+
+/// 查询发布源；`install` 为 true 且有新版本时下载并替换当前可执行文件
+pub fn check_update(install: bool) -> Result<UpdateInfo, UpdateError> {
+    let body = curl(&["-H", "Accept: application/vnd.github+json", &feed_url()])?;
+    let release: Value = serde_json::from_slice(&body).map_err(|e| UpdateError::InvalidFeed(e.to_string()))?;
+
+    let latest = release["tag_name"]
+        .as_str()
+        .ok_or_else(|| UpdateError::InvalidFeed("missing tag_name".to_string()))?;
+    let candidates = asset_candidates();
+    let assets = release["assets"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let asset = assets.iter().find(|a| {
+        a["name"]
+            .as_str()
+            .is_some_and(|name| candidates.iter().any(|c| c == name))
+    });
+    let sha256 = match asset {
+        Some(asset) => asset_sha256(assets, asset)?,
+        None => None,
+    };
+
+    let mut info = UpdateInfo {
+        current_version: CURRENT_VERSION.to_string(),
+        latest_version: latest.trim_start_matches('v').to_string(),
+        update_available: is_newer(latest, CURRENT_VERSION),
+        changelog: release["body"].as_str().unwrap_or_default().to_string(),
+        release_url: release["html_url"].as_str().map(str::to_string),
+        asset_name: asset.and_then(|a| a["name"].as_str()).map(str::to_string),
+        asset_url: asset
+            .and_then(|a| a["browser_download_url"].as_str())
+            .map(str::to_string),
+        asset_sha256: sha256,
+        installed_to: None,
+    };
+
+    if install && info.update_available {
+        let url = info
+            .asset_url
+            .clone()
+            .ok_or_else(|| UpdateError::NoAsset(candidates.join(" / ")))?;
+        let sha256 = info
+            .asset_sha256
+            .clone()
+            .ok_or_else(|| UpdateError::Checksum("release feed provides no SHA-256 for this asset".to_string()))?;
+        let exe = env::current_exe().map_err(|e| UpdateError::Io(e.to_string()))?;
+        replace_binary(&exe, &url, &sha256)?;
+        info.installed_to = Some(exe);
+    }
+
+    Ok(info)
+}
+
+/// 下载新版本到同目录临时文件，校验 SHA-256 后再替换当前可执行文件（旧文件保留为 .old）
+fn replace_binary(exe: &Path, url: &str, sha256: &str) -> Result<(), UpdateError> {
+    let new_path = exe.with_extension("new");
+    let old_path = exe.with_extension("old");
+    let io_err = |e: std::io::Error| UpdateError::Io(e.to_string());
+
+    curl(&["-o", &new_path.display().to_string(), url])?;
+
+    let data = fs::read(&new_path).map_err(io_err)?;
+    let actual: String = sha256::digest(&data).iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != sha256 {
+        let _ = fs::remove_file(&new_path);
+        return Err(UpdateError::Checksum(format!("expected {}, downloaded file has {}", sha256, actual)));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&new_path, fs::Permissions::from_mode(0o755)).map_err(io_err)?;
+    }
+
+    // 运行中的可执行文件可以改名（Windows 也允许），但不能直接覆盖
+    let _ = fs::remove_file(&old_path);
+    fs::rename(exe, &old_path).map_err(io_err)?;
+    if let Err(e) = fs::rename(&new_path, exe) {
+        let _ = fs::rename(&old_path, exe);
+        return Err(io_err(e));
+    }
+    Ok(())
+}
+
+/// 最小的 SHA-256 实现（FIPS 180-4），仅用于校验下载的发布文件，避免为此引入摘要库
+mod sha256 {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+
+    pub fn digest(data: &[u8]) -> [u8; 32] {
+        let mut h: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+        ];
+
+        // 填充：0x80，补零到 56 mod 64，最后 8 字节为大端位长度
+        let mut msg = data.to_vec();
+        msg.push(0x80);
+        while msg.len() % 64 != 56 {
+            msg.push(0);
+        }
+        msg.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+        for block in msg.chunks_exact(64) {
+            let mut w = [0u32; 64];
+            for (i, word) in block.chunks_exact(4).enumerate() {
+                w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+            }
+            for i in 16..64 {
+                let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+                let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+                w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+            }
+
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+            for i in 0..64 {
+                let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+                let ch = (e & f) ^ (!e & g);
+                let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+                let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+                let maj = (a & b) ^ (a & c) ^ (b & c);
+                let t2 = s0.wrapping_add(maj);
+                hh = g;
+                g = f;
+                f = e;
+                e = d.wrapping_add(t1);
+                d = c;
+                c = b;
+                b = a;
+                a = t1.wrapping_add(t2);
+            }
+            for (state, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+                *state = state.wrapping_add(v);
+            }
+        }
+
+        let mut out = [0u8; 32];
+        for (chunk, v) in out.chunks_exact_mut(4).zip(h) {
+            chunk.copy_from_slice(&v.to_be_bytes());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(data: &[u8]) -> String {
+        sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn sha256_known_answers() {
+        assert_eq!(hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        // NIST 448 位消息：填充后恰好跨越两个块
+        assert_eq!(
+            hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(
+            hex(concat!(
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno",
+                "ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"
+            )
+            .as_bytes()),
+            "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1"
+        );
+        assert_eq!(
+            hex(&vec![b'a'; 1_000_000]),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn parses_digest_formats() {
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(parse_sha256(&format!("sha256:{}", digest)).as_deref(), Some(digest));
+        let sidecar = format!("{}  iris-mcp-linux-x86_64\n", digest.to_uppercase());
+        assert_eq!(parse_sha256(&sidecar).as_deref(), Some(digest));
+        assert_eq!(parse_sha256("sha256:abc"), None);
+    }
+}